@@ -0,0 +1,89 @@
+//! Measures the cost of building the index for a heavily fragmented file.
+//!
+//! As well as the timing benchmark this reports the number of allocations
+//! made per segment while scanning since that dominates on fragmented files.
+use criterion::{black_box, criterion_group, Criterion};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::Cursor;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tedium::{ChannelPath, DataLayout, PropertyPath, PropertyValue, TdmsFile};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const SEGMENT_PAIRS: usize = 5_000;
+
+/// Build a file where every data write is followed by a property update
+/// so we get a large number of small segments.
+fn fragmented_file() -> Vec<u8> {
+    let mut buffer = Cursor::new(Vec::new());
+    let mut file = TdmsFile::new(&mut buffer).unwrap();
+    let mut writer = file.writer().unwrap();
+
+    let channels = (0..4)
+        .map(|index| ChannelPath::new("group", &format!("channel{}", index)))
+        .collect::<Vec<_>>();
+    let data = (0..40).map(|i| i as f64).collect::<Vec<_>>();
+    let group = PropertyPath::group("group");
+
+    for index in 0..SEGMENT_PAIRS {
+        writer
+            .write_channels(&channels, &data[..], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_properties(&group, &[("count", PropertyValue::U64(index as u64))])
+            .unwrap();
+    }
+    drop(writer);
+    drop(file);
+    buffer.into_inner()
+}
+
+fn report_allocations(bytes: &[u8]) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let file = TdmsFile::new(Cursor::new(bytes)).unwrap();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    drop(file);
+
+    let segments = SEGMENT_PAIRS * 2;
+    println!(
+        "Fragmented scan: {allocations} allocations for {segments} segments ({:.2} per segment)",
+        allocations as f64 / segments as f64
+    );
+}
+
+fn fragmented_scan(c: &mut Criterion) {
+    let bytes = fragmented_file();
+    report_allocations(&bytes);
+
+    c.bench_function("Scan Fragmented File", |b| {
+        b.iter(|| black_box(TdmsFile::new(Cursor::new(&bytes[..])).unwrap()))
+    });
+}
+
+criterion_group!(segment_scan, fragmented_scan);
+
+fn main() {
+    segment_scan();
+    Criterion::default().configure_from_args().final_summary();
+}
@@ -1,11 +1,11 @@
 use criterion::{criterion_group, BenchmarkId, Criterion};
 use std::io::Cursor;
-use tedium::{ChannelPath, DataLayout, TdmsFile};
+use tedium::{ChannelPath, DataLayout, TdmsFile, TdmsStreamWriter};
 
 fn setup_file() -> TdmsFile<Cursor<Vec<u8>>> {
     let fake_file = Cursor::new(Vec::with_capacity(32_000_000));
-    let file = TdmsFile::new(fake_file).unwrap();
-    file
+
+    TdmsFile::new(fake_file).unwrap()
 }
 
 fn writer(c: &mut Criterion, layout: DataLayout) {
@@ -113,4 +113,33 @@ fn writer_contiguous(c: &mut Criterion) {
     writer(c, DataLayout::Contigious);
 }
 
-criterion_group!(writers, writer_interleaved, writer_contiguous);
+/// Large writes to memory so the cost of encoding the values dominates.
+fn writer_large(c: &mut Criterion) {
+    // 256 MB of f64 data per write.
+    let elements_per_write = 32_000_000;
+    let write_data = (0..elements_per_write)
+        .map(|i| i as f64)
+        .collect::<Vec<_>>();
+    let channels = vec![ChannelPath::new("group", "channel")];
+
+    let mut group = c.benchmark_group("Writer Large Single Channel");
+    group.sample_size(10);
+    group.throughput(criterion::Throughput::Bytes(
+        elements_per_write as u64 * std::mem::size_of::<f64>() as u64,
+    ));
+    group.bench_function("f64", |b| {
+        b.iter_batched_ref(
+            || Vec::with_capacity(elements_per_write * std::mem::size_of::<f64>() + 4096),
+            |output| {
+                let mut stream = TdmsStreamWriter::new(output);
+                let mut writer = stream.writer();
+                writer
+                    .write_channels(&channels, &write_data[..], DataLayout::Contigious)
+                    .unwrap();
+            },
+            criterion::BatchSize::PerIteration,
+        );
+    });
+}
+
+criterion_group!(writers, writer_interleaved, writer_contiguous, writer_large);
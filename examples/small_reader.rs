@@ -0,0 +1,30 @@
+//! A minimal reader used to audit the size of programs using the library.
+//!
+//! Build it with `scripts/size_check.sh` to compare the default build against
+//! the `small` feature. It prints the number of samples in a channel and the
+//! first value of a floating point channel.
+//!
+//! Usage: `small_reader <file> <group> <channel>`
+
+use std::path::Path;
+
+use tedium::{ChannelPath, TdmsFile};
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let [_, path, group, channel] = &args[..] else {
+        eprintln!("Usage: small_reader <file> <group> <channel>");
+        std::process::exit(2);
+    };
+
+    let mut file = TdmsFile::load(Path::new(path)).unwrap();
+    let channel = ChannelPath::new(group, channel);
+
+    let samples = file.read_all_dyn(&channel).unwrap();
+    println!("{} samples", samples.len());
+
+    let mut first = [0.0f64; 1];
+    if file.read_channel(&channel, &mut first).is_ok() {
+        println!("first value {}", first[0]);
+    }
+}
@@ -0,0 +1,30 @@
+#!/bin/sh
+# Report the size of a minimal reader built with and without the `small` feature.
+#
+# Set MAX_BYTES to fail when the small build is larger than the limit, for
+# example in CI. If cargo-bloat is installed the largest functions of the
+# small build are listed as well.
+set -eu
+
+cd "$(dirname "$0")/.."
+
+build() {
+    cargo build --quiet --profile small --example small_reader --no-default-features "$@"
+    wc -c < target/small/examples/small_reader | tr -d ' '
+}
+
+generic=$(build)
+small=$(build --features small)
+
+echo "small_reader without small: $generic bytes"
+echo "small_reader with small:    $small bytes"
+
+if command -v cargo-bloat > /dev/null 2>&1; then
+    cargo bloat --profile small --example small_reader --no-default-features \
+        --features small -n 20
+fi
+
+if [ -n "${MAX_BYTES:-}" ] && [ "$small" -gt "$MAX_BYTES" ]; then
+    echo "small_reader is larger than MAX_BYTES ($MAX_BYTES bytes)" >&2
+    exit 1
+fi
@@ -1,16 +1,69 @@
 use thiserror::Error;
 
 use crate::io::data_types::DataType;
-use crate::paths::{ChannelPath, ObjectPathOwned};
+use crate::paths::{ChannelPath, GroupPath, RawPathOwned};
+
+/// The broad category of a [`TdmsError`].
+///
+/// This allows applications to handle errors programmatically without
+/// matching every variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// An error from the underlying reader or writer.
+    Io,
+    /// The file structure is invalid or truncated.
+    Corrupt,
+    /// A group, channel or data block could not be found.
+    NotFound,
+    /// The data was requested as a type it is not stored as.
+    TypeMismatch,
+    /// The file uses a feature or type this library does not support.
+    Unsupported,
+    /// The arguments to the call are not valid.
+    InvalidInput,
+}
+
+impl ErrorKind {
+    /// A stable name for the kind, for logs and other programs.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::Io => "io",
+            ErrorKind::Corrupt => "corrupt",
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::TypeMismatch => "type_mismatch",
+            ErrorKind::Unsupported => "unsupported",
+            ErrorKind::InvalidInput => "invalid_input",
+        }
+    }
+}
 
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum TdmsError {
     #[error("Matching datatype not found for code {0:X}")]
     UnknownDataType(u32),
     #[error("Index reader error")]
-    IndexReaderError(#[source] Box<dyn std::error::Error>),
-    #[error("Group or Channel not found in index. {0}")]
-    MissingObject(ObjectPathOwned),
+    IndexReaderError(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Group {group} not found in the file.{}", suggestion_text(.suggestions))]
+    MissingGroup {
+        group: GroupPath,
+        /// The existing groups with the closest names.
+        suggestions: Vec<GroupPath>,
+    },
+    #[error("Channel {channel} not found in its group.{}", suggestion_text(.suggestions))]
+    MissingChannel {
+        channel: ChannelPath,
+        /// The existing channels in the same group with the closest names.
+        suggestions: Vec<ChannelPath>,
+    },
+    #[error("Property {property} not found on {path}.{}", suggestion_text(.suggestions))]
+    MissingProperty {
+        path: RawPathOwned,
+        property: String,
+        /// The existing properties of the object with the closest names.
+        suggestions: Vec<String>,
+    },
     #[error("IO Error")]
     IoError(#[from] std::io::Error),
     #[error("String formatting error")]
@@ -23,10 +76,16 @@ pub enum TdmsError {
     HeaderPatternNotMatched([u8; 4]),
     #[error("Tried to access a datablock that doesn't exist when reading channel: {0}")]
     DataBlockNotFound(ChannelPath, usize),
-    #[error("The data block has length {0} which is not divisible by the number of channels: {1}")]
+    #[error("Expected a multiple of {1} values to write to {1} channels but got {0} values")]
     BadDataBlockLength(usize, usize),
     #[error("Attempting to read a channel or property of type {0} as type {1}")]
     DataTypeMismatch(DataType, DataType),
+    #[error("Channel {channel} stores {stored} data which cannot be read as {requested}")]
+    ChannelTypeMismatch {
+        channel: ChannelPath,
+        stored: DataType,
+        requested: DataType,
+    },
     #[error("Attempted to read past the end of the file")]
     EndOfFile,
     #[error("The start address for the next segment is invalid. The address overflowed. The file is likely corrupt.")]
@@ -37,4 +96,254 @@ pub enum TdmsError {
     InvalidObjectPath(String),
     #[error("Attempted to parse an valid but unsuitable path to a channel. {0}")]
     InvalidChannelPath(String),
+    #[error("Attempted to parse an valid but unsuitable path to a group. {0}")]
+    InvalidGroupPath(String),
+    #[error("Attempted to read {requested} samples from sample {start} of channel {channel} which only has {length} samples")]
+    ReadPastEnd {
+        channel: ChannelPath,
+        start: u64,
+        requested: usize,
+        length: u64,
+    },
+    #[error(
+        "Channel {0} does not have the waveform timing properties wf_start_time and wf_increment"
+    )]
+    MissingWaveformTiming(ChannelPath),
+    #[error("Cannot convert channel {0} from unit {1:?} to {2}")]
+    UnitConversionUnavailable(ChannelPath, Option<String>, String),
+    #[error("The overview file is invalid or from an unsupported version. {0}")]
+    InvalidOverview(String),
+    #[error("The file name template is invalid: {0}")]
+    InvalidFileTemplate(String),
+    #[error("Interleaved data block of {block_bytes} bytes is not a multiple of the {stride} byte record, leaving {residual} bytes")]
+    MisalignedInterleavedBlock {
+        block_bytes: u64,
+        stride: u64,
+        residual: u64,
+    },
+    #[error("Channel {0} appears more than once in a single write")]
+    DuplicateChannel(RawPathOwned),
+    #[error("{0} is too large to address in memory on this platform")]
+    ExceedsAddressSpace(u64),
+    #[error("The string data of channel {0} has invalid offsets. The file is likely corrupt.")]
+    InvalidStringData(ChannelPath),
+    #[error("Sample {sample} of channel {channel} is {value}, which is out of range for the type it is converted to")]
+    ValueOutOfRange {
+        channel: ChannelPath,
+        sample: u64,
+        value: f64,
+    },
+    #[error("Writing {path} would break the TDMS specification: {reason}")]
+    SpecViolation { path: String, reason: String },
+    #[error("The writer's compatibility profile doesn't allow {0}")]
+    IncompatibleWrite(String),
+    #[error("Channel {channel} has {values} values but {quality} quality flags")]
+    QualityLengthMismatch {
+        channel: ChannelPath,
+        values: usize,
+        quality: usize,
+    },
+    #[error("The segment metadata is invalid: {0}. The file is likely corrupt.")]
+    InvalidMetaData(String),
+    #[error("Error in segment {segment} at byte offset {offset}: {source}")]
+    InSegment {
+        segment: u64,
+        offset: u64,
+        #[source]
+        source: Box<TdmsError>,
+    },
+}
+
+impl TdmsError {
+    /// The category of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            TdmsError::IoError(_) => ErrorKind::Io,
+            TdmsError::IndexReaderError(_)
+            | TdmsError::StringFormatError(_)
+            | TdmsError::HeaderPatternNotMatched(_)
+            | TdmsError::EndOfFile
+            | TdmsError::SegmentAddressOverflow
+            | TdmsError::SegmentTocDataBlockWithoutDataChannels
+            | TdmsError::InvalidOverview(_)
+            | TdmsError::MisalignedInterleavedBlock { .. }
+            | TdmsError::InvalidStringData(_)
+            | TdmsError::InvalidMetaData(_) => ErrorKind::Corrupt,
+            TdmsError::MissingGroup { .. }
+            | TdmsError::MissingChannel { .. }
+            | TdmsError::MissingProperty { .. }
+            | TdmsError::DataBlockNotFound(..) => ErrorKind::NotFound,
+            TdmsError::DataTypeMismatch(..) | TdmsError::ChannelTypeMismatch { .. } => {
+                ErrorKind::TypeMismatch
+            }
+            TdmsError::UnknownDataType(_)
+            | TdmsError::UnknownPropertyType(_)
+            | TdmsError::UnsupportedType(_)
+            | TdmsError::UnitConversionUnavailable(..)
+            | TdmsError::ExceedsAddressSpace(_) => ErrorKind::Unsupported,
+            TdmsError::BadDataBlockLength(..)
+            | TdmsError::InvalidObjectPath(_)
+            | TdmsError::InvalidChannelPath(_)
+            | TdmsError::InvalidGroupPath(_)
+            | TdmsError::ReadPastEnd { .. }
+            | TdmsError::MissingWaveformTiming(_)
+            | TdmsError::InvalidFileTemplate(_)
+            | TdmsError::DuplicateChannel(_)
+            | TdmsError::ValueOutOfRange { .. }
+            | TdmsError::SpecViolation { .. }
+            | TdmsError::IncompatibleWrite(_)
+            | TdmsError::QualityLengthMismatch { .. } => ErrorKind::InvalidInput,
+            TdmsError::InSegment { source, .. } => source.kind(),
+        }
+    }
+
+    /// A stable code for the error.
+    ///
+    /// Codes are never reused or changed, so they are safe to store or compare
+    /// across versions. Errors wrapped with segment context report the code of
+    /// the underlying error.
+    pub fn code(&self) -> u32 {
+        match self {
+            TdmsError::UnknownDataType(_) => 1,
+            TdmsError::IndexReaderError(_) => 2,
+            // 3 was the missing object error, which is now split by the type of object.
+            TdmsError::IoError(_) => 4,
+            TdmsError::StringFormatError(_) => 5,
+            TdmsError::UnknownPropertyType(_) => 6,
+            TdmsError::UnsupportedType(_) => 7,
+            TdmsError::HeaderPatternNotMatched(_) => 8,
+            TdmsError::DataBlockNotFound(..) => 9,
+            TdmsError::BadDataBlockLength(..) => 10,
+            TdmsError::DataTypeMismatch(..) => 11,
+            TdmsError::EndOfFile => 12,
+            TdmsError::SegmentAddressOverflow => 13,
+            TdmsError::SegmentTocDataBlockWithoutDataChannels => 14,
+            TdmsError::InvalidObjectPath(_) => 15,
+            TdmsError::InvalidChannelPath(_) => 16,
+            TdmsError::ReadPastEnd { .. } => 17,
+            TdmsError::MissingWaveformTiming(_) => 18,
+            TdmsError::UnitConversionUnavailable(..) => 19,
+            TdmsError::InvalidOverview(_) => 20,
+            TdmsError::ChannelTypeMismatch { .. } => 21,
+            TdmsError::InvalidFileTemplate(_) => 22,
+            TdmsError::MisalignedInterleavedBlock { .. } => 23,
+            TdmsError::InvalidGroupPath(_) => 24,
+            TdmsError::DuplicateChannel(_) => 25,
+            TdmsError::ExceedsAddressSpace(_) => 26,
+            TdmsError::InvalidStringData(_) => 27,
+            TdmsError::ValueOutOfRange { .. } => 28,
+            TdmsError::SpecViolation { .. } => 29,
+            TdmsError::IncompatibleWrite(_) => 30,
+            TdmsError::QualityLengthMismatch { .. } => 31,
+            TdmsError::MissingGroup { .. } => 32,
+            TdmsError::MissingChannel { .. } => 33,
+            TdmsError::MissingProperty { .. } => 34,
+            TdmsError::InvalidMetaData(_) => 35,
+            TdmsError::InSegment { source, .. } => source.code(),
+        }
+    }
+
+    /// True if the error is an IO error that may succeed if the operation is retried.
+    ///
+    /// This covers timeouts, interruptions and dropped connections, as seen on
+    /// network filesystems. Every other error, including IO errors such as a
+    /// missing file or denied permission, is permanent.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            TdmsError::IoError(error) => matches!(
+                error.kind(),
+                std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::WouldBlock
+                    | std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::NotConnected
+                    | std::io::ErrorKind::BrokenPipe
+            ),
+            TdmsError::InSegment { source, .. } => source.is_transient(),
+            _ => false,
+        }
+    }
+
+    /// The object path the error relates to, if known.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            TdmsError::MissingProperty { path, .. }
+            | TdmsError::InvalidObjectPath(path)
+            | TdmsError::InvalidChannelPath(path)
+            | TdmsError::InvalidGroupPath(path)
+            | TdmsError::DuplicateChannel(path)
+            | TdmsError::SpecViolation { path, .. } => Some(path),
+            TdmsError::MissingGroup { group, .. } => Some(group.path()),
+            TdmsError::DataBlockNotFound(channel, _)
+            | TdmsError::MissingChannel { channel, .. }
+            | TdmsError::ChannelTypeMismatch { channel, .. }
+            | TdmsError::ReadPastEnd { channel, .. }
+            | TdmsError::ValueOutOfRange { channel, .. }
+            | TdmsError::QualityLengthMismatch { channel, .. }
+            | TdmsError::MissingWaveformTiming(channel)
+            | TdmsError::InvalidStringData(channel)
+            | TdmsError::UnitConversionUnavailable(channel, ..) => Some(channel.path()),
+            TdmsError::InSegment { source, .. } => source.path(),
+            _ => None,
+        }
+    }
+
+    /// The byte offset in the file of the segment the error occurred in, if known.
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            TdmsError::InSegment { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// The number of the segment the error occurred in, counting from 0, if known.
+    pub fn segment(&self) -> Option<u64> {
+        match self {
+            TdmsError::InSegment { segment, .. } => Some(*segment),
+            _ => None,
+        }
+    }
+
+    /// Add the location of the segment being read to the error.
+    pub(crate) fn in_segment(self, segment: u64, offset: u64) -> Self {
+        match self {
+            // Keep the end of file visible as it marks the end of the segments.
+            TdmsError::EndOfFile | TdmsError::InSegment { .. } => self,
+            source => TdmsError::InSegment {
+                segment,
+                offset,
+                source: Box::new(source),
+            },
+        }
+    }
+
+    /// Add the channel path to a data type mismatch from reading the channel.
+    pub(crate) fn with_channel(self, channel: &ChannelPath) -> Self {
+        match self {
+            TdmsError::DataTypeMismatch(stored, requested) => TdmsError::ChannelTypeMismatch {
+                channel: channel.clone(),
+                stored,
+                requested,
+            },
+            other => other,
+        }
+    }
+}
+
+/// The suggestions for a missing object or property as the end of the error message.
+fn suggestion_text(suggestions: &[impl std::fmt::Display]) -> String {
+    if suggestions.is_empty() {
+        return String::new();
+    }
+    let suggestions: Vec<String> = suggestions.iter().map(ToString::to_string).collect();
+    format!(" Did you mean {}?", suggestions.join(", "))
+}
+
+/// Convert a size or position from the file to a `usize`.
+///
+/// Files can be larger than the address space on 32 bit targets, so this
+/// errors with [`TdmsError::ExceedsAddressSpace`] rather than truncating.
+pub(crate) fn usize_from(value: u64) -> Result<usize, TdmsError> {
+    usize::try_from(value).map_err(|_| TdmsError::ExceedsAddressSpace(value))
 }
@@ -0,0 +1,249 @@
+//! Reading a group as a rectangular block of channels.
+//!
+//! Channels in the same group don't have to be the same length so this
+//! aligns them either by padding or truncating.
+
+use crate::error::{usize_from, TdmsError};
+use crate::io::data_types::TdmsStorageType;
+use crate::paths::{ChannelPath, GroupPath};
+use crate::TdmsFile;
+
+/// How to align channels of different lengths in a group.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupAlignment<D> {
+    /// Extend shorter channels to the length of the longest channel using the value.
+    ///
+    /// For floating point data this is normally `f64::NAN`.
+    PadToLongest(D),
+    /// Cut all channels down to the length of the shortest channel.
+    TruncateToShortest,
+}
+
+/// The channels of a group read to a common length.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlignedGroup<D> {
+    /// The channels in the group in the same order as [`Self::data`].
+    pub channels: Vec<ChannelPath>,
+    /// The common length of every channel.
+    pub length: usize,
+    /// The data for each channel.
+    pub data: Vec<Vec<D>>,
+}
+
+impl<F: std::io::Read + std::io::Seek + std::io::Write + std::fmt::Debug> TdmsFile<F> {
+    /// Read every channel in the group aligned to a common length.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, GroupAlignment, GroupPath, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[ChannelPath::new("group", "a")], &[1.0, 2.0], DataLayout::Contigious).unwrap();
+    /// writer.write_channels(&[ChannelPath::new("group", "b")], &[3.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let aligned = file
+    ///     .read_group_aligned(&GroupPath::new("group"), GroupAlignment::PadToLongest(f64::NAN))
+    ///     .unwrap();
+    /// assert_eq!(aligned.length, 2);
+    /// assert!(aligned.data[1][1].is_nan());
+    /// ```
+    pub fn read_group_aligned<D: TdmsStorageType + Default + Clone>(
+        &mut self,
+        group: &GroupPath,
+        alignment: GroupAlignment<D>,
+    ) -> Result<AlignedGroup<D>, TdmsError> {
+        let channels: Vec<ChannelPath> = self.list_channels_in_group(group).collect();
+        let lengths = channels
+            .iter()
+            .map(|channel| usize_from(self.channel_length(channel).unwrap_or(0)))
+            .collect::<Result<Vec<usize>, TdmsError>>()?;
+
+        let length = match alignment {
+            GroupAlignment::PadToLongest(_) => lengths.iter().copied().max(),
+            GroupAlignment::TruncateToShortest => lengths.iter().copied().min(),
+        }
+        .unwrap_or(0);
+
+        let mut data: Vec<Vec<D>> = lengths
+            .iter()
+            .map(|&channel_length| vec![D::default(); channel_length.min(length)])
+            .collect();
+
+        {
+            let mut outputs: Vec<&mut [D]> = data.iter_mut().map(|v| &mut v[..]).collect();
+            self.read_channels(&channels, &mut outputs[..])?;
+        }
+
+        if let GroupAlignment::PadToLongest(fill) = alignment {
+            for channel_data in data.iter_mut() {
+                channel_data.resize(length, fill.clone());
+            }
+        }
+
+        Ok(AlignedGroup {
+            channels,
+            length,
+            data,
+        })
+    }
+
+    /// The number of channels in the group and the length of the longest.
+    ///
+    /// This comes from the index so a buffer for [`Self::read_group_matrix`]
+    /// can be allocated before reading anything.
+    pub fn group_shape(&self, group: &GroupPath) -> (usize, u64) {
+        self.list_channels_in_group(group)
+            .fold((0, 0), |(channels, longest), channel| {
+                let length = self.channel_length(&channel).unwrap_or(0);
+                (channels + 1, longest.max(length))
+            })
+    }
+
+    /// Read every channel in the group into one buffer, one channel after another.
+    ///
+    /// Each channel takes `output.len() / channels` samples of the buffer.
+    /// Shorter channels are padded with `fill` and longer ones are truncated,
+    /// so size the buffer from [`Self::group_shape`] to read everything.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, GroupPath, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[ChannelPath::new("group", "a")], &[1.0, 2.0], DataLayout::Contigious).unwrap();
+    /// writer.write_channels(&[ChannelPath::new("group", "b")], &[3.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let group = GroupPath::new("group");
+    /// let (channels, samples) = file.group_shape(&group);
+    /// let mut matrix = vec![0.0; channels * samples as usize];
+    /// file.read_group_matrix(&group, &mut matrix, -1.0).unwrap();
+    /// assert_eq!(matrix, [1.0, 2.0, 3.0, -1.0]);
+    /// ```
+    pub fn read_group_matrix<D: TdmsStorageType + Clone>(
+        &mut self,
+        group: &GroupPath,
+        output: &mut [D],
+        fill: D,
+    ) -> Result<(), TdmsError> {
+        let channels: Vec<ChannelPath> = self.list_channels_in_group(group).collect();
+        if channels.is_empty() {
+            return Ok(());
+        }
+        let stride = output.len() / channels.len();
+        if stride == 0 {
+            return Ok(());
+        }
+
+        let mut outputs = Vec::with_capacity(channels.len());
+        for (channel, row) in channels.iter().zip(output.chunks_exact_mut(stride)) {
+            let length = usize_from(self.channel_length(channel).unwrap_or(0))?.min(stride);
+            let (data, padding) = row.split_at_mut(length);
+            padding.fill(fill.clone());
+            outputs.push(data);
+        }
+        self.read_channels(&channels, &mut outputs[..])?;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::DataLayout;
+
+    fn ragged_file() -> TdmsFile<Cursor<Vec<u8>>> {
+        let mut file = TdmsFile::new(Cursor::new(Vec::new())).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(
+                &[ChannelPath::new("group", "long")],
+                &[1.0, 2.0, 3.0],
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        writer
+            .write_channels(
+                &[ChannelPath::new("group", "short")],
+                &[4.0],
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        drop(writer);
+        file
+    }
+
+    #[test]
+    fn test_pad_to_longest() {
+        let mut file = ragged_file();
+        let aligned = file
+            .read_group_aligned(&GroupPath::new("group"), GroupAlignment::PadToLongest(-1.0))
+            .unwrap();
+
+        assert_eq!(
+            aligned.channels,
+            vec![
+                ChannelPath::new("group", "long"),
+                ChannelPath::new("group", "short")
+            ]
+        );
+        assert_eq!(aligned.length, 3);
+        assert_eq!(
+            aligned.data,
+            vec![vec![1.0, 2.0, 3.0], vec![4.0, -1.0, -1.0]]
+        );
+    }
+
+    #[test]
+    fn test_truncate_to_shortest() {
+        let mut file = ragged_file();
+        let aligned = file
+            .read_group_aligned::<f64>(&GroupPath::new("group"), GroupAlignment::TruncateToShortest)
+            .unwrap();
+
+        assert_eq!(aligned.length, 1);
+        assert_eq!(aligned.data, vec![vec![1.0], vec![4.0]]);
+    }
+
+    #[test]
+    fn test_empty_group() {
+        let mut file = ragged_file();
+        let aligned = file
+            .read_group_aligned(
+                &GroupPath::new("missing"),
+                GroupAlignment::PadToLongest(f64::NAN),
+            )
+            .unwrap();
+
+        assert!(aligned.channels.is_empty());
+        assert_eq!(aligned.length, 0);
+    }
+
+    #[test]
+    fn test_group_matrix_sized_from_shape() {
+        let mut file = ragged_file();
+        let group = GroupPath::new("group");
+        assert_eq!(file.group_shape(&group), (2, 3));
+        assert_eq!(file.group_shape(&GroupPath::new("missing")), (0, 0));
+
+        let mut matrix = vec![0.0; 6];
+        file.read_group_matrix(&group, &mut matrix, f64::NAN)
+            .unwrap();
+        assert_eq!(&matrix[..4], &[1.0, 2.0, 3.0, 4.0]);
+        assert!(matrix[4].is_nan() && matrix[5].is_nan());
+
+        // A smaller buffer truncates each channel to its share.
+        let mut matrix = vec![0.0; 4];
+        file.read_group_matrix(&group, &mut matrix, f64::NAN)
+            .unwrap();
+        assert_eq!(&matrix[..3], &[1.0, 2.0, 4.0]);
+        assert!(matrix[3].is_nan());
+    }
+}
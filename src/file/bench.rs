@@ -0,0 +1,356 @@
+//! Instrumented entry points for benchmarking storage.
+//!
+//! Each function opens the file through a wrapper which counts and times every
+//! read and seek, so a report shows how much of the time was spent waiting on
+//! the storage and how many operations the work needed. Comparing reports from
+//! two machines, or two versions of the library, separates slow hardware from
+//! regressions in the parsing.
+
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use labview_interop::types::LVTime;
+
+use super::os_path::os_path;
+use crate::error::TdmsError;
+use crate::io::data_types::{DataType, TdmsStorageType};
+use crate::paths::ChannelPath;
+use crate::TdmsFile;
+
+/// The most samples read at a time by [`read_channel`].
+const CHUNK_SAMPLES: usize = 1024 * 1024;
+
+/// The storage operations performed during a benchmark.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IoMetrics {
+    /// The number of calls to read.
+    pub reads: u64,
+    /// The bytes returned by those reads.
+    pub bytes_read: u64,
+    /// The number of seeks.
+    pub seeks: u64,
+    /// The time spent inside reads and seeks.
+    pub io_time: Duration,
+}
+
+/// The result of [`scan`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScanReport {
+    /// The time to open and index the file.
+    pub elapsed: Duration,
+    pub io: IoMetrics,
+    /// The size of the file in bytes.
+    pub file_bytes: u64,
+    pub segments: u64,
+    pub data_blocks: usize,
+}
+
+impl ScanReport {
+    /// The segments indexed per second.
+    pub fn segments_per_second(&self) -> f64 {
+        self.segments as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+/// The result of [`read_channel`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReadReport {
+    /// Opening and indexing the file before the read.
+    pub open: ScanReport,
+    /// The time to read the whole channel, not including the open.
+    pub elapsed: Duration,
+    pub io: IoMetrics,
+    pub samples: u64,
+    /// The size of the samples read.
+    pub sample_bytes: u64,
+    /// The number of reads of up to a million samples the channel was read in.
+    pub chunks: u64,
+    /// The longest time taken by a single chunk.
+    pub slowest_chunk: Duration,
+}
+
+impl ReadReport {
+    /// The sample bytes read per second, in MB/s.
+    pub fn throughput_mb_per_second(&self) -> f64 {
+        self.sample_bytes as f64 / self.elapsed.as_secs_f64() / 1_000_000.0
+    }
+}
+
+/// A file which records the operations made on it.
+#[derive(Debug)]
+struct Instrumented<F> {
+    inner: F,
+    metrics: IoMetrics,
+}
+
+impl<F> Instrumented<F> {
+    fn new(inner: F) -> Self {
+        Self {
+            inner,
+            metrics: IoMetrics::default(),
+        }
+    }
+
+    /// Return the metrics so far and start counting again.
+    fn take_metrics(&mut self) -> IoMetrics {
+        std::mem::take(&mut self.metrics)
+    }
+}
+
+impl<F: Read> Read for Instrumented<F> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let start = Instant::now();
+        let result = self.inner.read(buf);
+        self.metrics.io_time += start.elapsed();
+        self.metrics.reads += 1;
+        if let Ok(bytes) = result {
+            self.metrics.bytes_read += bytes as u64;
+        }
+        result
+    }
+}
+
+impl<F: Seek> Seek for Instrumented<F> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let start = Instant::now();
+        let result = self.inner.seek(pos);
+        self.metrics.io_time += start.elapsed();
+        self.metrics.seeks += 1;
+        result
+    }
+}
+
+impl<F: Write> Write for Instrumented<F> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn open_file(path: &Path) -> Result<File, TdmsError> {
+    Ok(File::options().read(true).open(os_path(path))?)
+}
+
+/// Open and index the file at the path, measuring the time and IO.
+pub fn scan(path: &Path) -> Result<ScanReport, TdmsError> {
+    scan_reader(open_file(path)?)
+}
+
+/// Open and index a file from any source, measuring the time and IO.
+///
+/// # Example
+#[cfg_attr(feature = "write", doc = "```rust")]
+#[cfg_attr(not(feature = "write"), doc = "```ignore")]
+/// use std::io::Cursor;
+/// use tedium::bench;
+/// use tedium::testgen::TestFileSpec;
+///
+/// let spec = TestFileSpec { segments: 3, ..Default::default() };
+/// let report = bench::scan_reader(Cursor::new(spec.build().unwrap())).unwrap();
+/// assert_eq!(report.segments, 3);
+/// assert!(report.io.reads > 0);
+/// ```
+pub fn scan_reader<F: Read + Seek + Write + Debug>(file: F) -> Result<ScanReport, TdmsError> {
+    Ok(open_instrumented(file)?.1)
+}
+
+fn open_instrumented<F: Read + Seek + Write + Debug>(
+    file: F,
+) -> Result<(TdmsFile<Instrumented<F>>, ScanReport), TdmsError> {
+    let start = Instant::now();
+    let mut file = TdmsFile::new(Instrumented::new(file))?;
+    let elapsed = start.elapsed();
+
+    let io = file.file.take_metrics();
+    let stats = file.file_stats();
+    let report = ScanReport {
+        elapsed,
+        io,
+        file_bytes: file.index.next_segment_start(),
+        segments: stats.segments,
+        data_blocks: file.data_block_count(),
+    };
+    Ok((file, report))
+}
+
+/// Open the file at the path and read the whole channel, measuring the time and IO.
+///
+/// The channel is read in chunks of up to a million samples of its own type.
+/// Errors with [`TdmsError::UnsupportedType`] for channels which aren't
+/// numeric, boolean or timestamps.
+pub fn read_channel(path: &Path, channel: &ChannelPath) -> Result<ReadReport, TdmsError> {
+    read_channel_from(open_file(path)?, channel)
+}
+
+/// Open a file from any source and read the whole channel, measuring the time and IO.
+///
+/// # Example
+#[cfg_attr(feature = "write", doc = "```rust")]
+#[cfg_attr(not(feature = "write"), doc = "```ignore")]
+/// use std::io::Cursor;
+/// use tedium::bench;
+/// use tedium::testgen::TestFileSpec;
+///
+/// let spec = TestFileSpec::default();
+/// let bytes = spec.build().unwrap();
+/// let report = bench::read_channel_from(Cursor::new(bytes), &spec.channel_path(0, 0)).unwrap();
+/// assert_eq!(report.samples, spec.channel_length() as u64);
+/// assert_eq!(report.sample_bytes, report.samples * 8);
+/// ```
+pub fn read_channel_from<F: Read + Seek + Write + Debug>(
+    file: F,
+    channel: &ChannelPath,
+) -> Result<ReadReport, TdmsError> {
+    let (mut file, open) = open_instrumented(file)?;
+    let length = file
+        .channel_length(channel)
+        .ok_or_else(|| file.index.missing_channel(channel))?;
+    let Some(data_type) = file.index.channel_data_type(channel) else {
+        return Ok(ReadReport {
+            open,
+            elapsed: Duration::ZERO,
+            io: IoMetrics::default(),
+            samples: 0,
+            sample_bytes: 0,
+            chunks: 0,
+            slowest_chunk: Duration::ZERO,
+        });
+    };
+
+    let start = Instant::now();
+    let (chunks, slowest_chunk) = match data_type {
+        DataType::I8 => read_chunks(&mut file, channel, length, 0i8),
+        DataType::I16 => read_chunks(&mut file, channel, length, 0i16),
+        DataType::I32 => read_chunks(&mut file, channel, length, 0i32),
+        DataType::I64 => read_chunks(&mut file, channel, length, 0i64),
+        DataType::U8 => read_chunks(&mut file, channel, length, 0u8),
+        DataType::U16 => read_chunks(&mut file, channel, length, 0u16),
+        DataType::U32 => read_chunks(&mut file, channel, length, 0u32),
+        DataType::U64 => read_chunks(&mut file, channel, length, 0u64),
+        DataType::SingleFloat | DataType::SingleFloatWithUnit => {
+            read_chunks(&mut file, channel, length, 0.0f32)
+        }
+        DataType::DoubleFloat | DataType::DoubleFloatWithUnit => {
+            read_chunks(&mut file, channel, length, 0.0f64)
+        }
+        DataType::Boolean => read_chunks(&mut file, channel, length, false),
+        DataType::Timestamp => read_chunks(&mut file, channel, length, LVTime::from_lv_epoch(0.0)),
+        other => Err(TdmsError::UnsupportedType(other)),
+    }?;
+
+    Ok(ReadReport {
+        open,
+        elapsed: start.elapsed(),
+        io: file.file.take_metrics(),
+        samples: length,
+        sample_bytes: length * data_type.size() as u64,
+        chunks,
+        slowest_chunk,
+    })
+}
+
+/// Read the channel in chunks, returning the number of chunks and the slowest.
+///
+/// `initial` is only used to fill the buffer before the first read.
+fn read_chunks<D: TdmsStorageType + Clone, F: Read + Seek + Write + Debug>(
+    file: &mut TdmsFile<Instrumented<F>>,
+    channel: &ChannelPath,
+    length: u64,
+    initial: D,
+) -> Result<(u64, Duration), TdmsError> {
+    let mut buffer = vec![initial; (length as usize).min(CHUNK_SAMPLES)];
+    let mut chunks = 0;
+    let mut slowest = Duration::ZERO;
+    let mut position = 0;
+    while position < length {
+        let samples = ((length - position) as usize).min(buffer.len());
+        let start = Instant::now();
+        file.read_range(channel, position, &mut buffer[..samples])?;
+        slowest = slowest.max(start.elapsed());
+        chunks += 1;
+        position += samples as u64;
+    }
+    Ok((chunks, slowest))
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::testgen::TestFileSpec;
+    use crate::DataLayout;
+
+    #[test]
+    fn test_scan_counts_io() {
+        let spec = TestFileSpec {
+            segments: 4,
+            ..Default::default()
+        };
+        let bytes = spec.build().unwrap();
+        let report = scan_reader(Cursor::new(bytes.clone())).unwrap();
+        assert_eq!(report.segments, 4);
+        assert_eq!(report.data_blocks, 4);
+        assert_eq!(report.file_bytes, bytes.len() as u64);
+        assert!(report.io.seeks > 0);
+        assert!(report.io.bytes_read <= bytes.len() as u64);
+    }
+
+    #[test]
+    fn test_read_counts_only_the_read() {
+        let spec = TestFileSpec {
+            data_type: DataType::I16,
+            segments: 2,
+            layout: DataLayout::Interleaved,
+            ..Default::default()
+        };
+        let report =
+            read_channel_from(Cursor::new(spec.build().unwrap()), &spec.channel_path(0, 1))
+                .unwrap();
+        assert_eq!(report.samples, spec.channel_length() as u64);
+        assert_eq!(report.sample_bytes, report.samples * 2);
+        assert_eq!(report.chunks, 1);
+        assert!(report.io.bytes_read >= report.sample_bytes);
+        assert!(report.open.io.reads > 0);
+    }
+
+    #[test]
+    fn test_unsupported_and_missing_channels() {
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let channel = ChannelPath::new("group", "names");
+        let mut writer = file.writer().unwrap();
+        writer.write_strings(&channel, &["a"]).unwrap();
+        drop(writer);
+        let bytes = file.file.into_inner();
+
+        let result = read_channel_from(Cursor::new(bytes.clone()), &channel);
+        assert!(matches!(
+            result,
+            Err(TdmsError::UnsupportedType(DataType::TdmsString))
+        ));
+        let result = read_channel_from(Cursor::new(bytes), &ChannelPath::new("group", "missing"));
+        assert!(matches!(result, Err(TdmsError::MissingChannel { .. })));
+    }
+
+    #[test]
+    fn test_path_entry_points() {
+        let spec = TestFileSpec::default();
+        let path = std::env::temp_dir().join("tedium_bench_entry_points.tdms");
+        std::fs::write(&path, spec.build().unwrap()).unwrap();
+
+        let scan = scan(&path).unwrap();
+        let read = read_channel(&path, &spec.channel_path(0, 0)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(scan.segments, read.open.segments);
+        assert_eq!(read.samples, spec.channel_length() as u64);
+    }
+}
@@ -0,0 +1,354 @@
+//! Custom decoding of raw data blocks.
+//!
+//! The built in readers convert the raw bytes into typed slices. Specialist
+//! users may want to decode the bytes themselves, for example to upload them
+//! straight to a GPU or use their own SIMD kernels. [`BlockDecoder`] receives
+//! the raw bytes of each block along with the layout of the channels in it so
+//! the index and planning can still be reused.
+
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::error::{usize_from, TdmsError};
+use crate::io::data_types::DataType;
+use crate::paths::ChannelPath;
+use crate::raw_data::{ChunkSize, DataBlock, DataLayout, Endianess};
+use crate::TdmsFile;
+
+/// The position of a channel's data within a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawChannel {
+    pub data_type: DataType,
+    /// The number of samples of this channel in each chunk.
+    pub samples_per_chunk: usize,
+    /// The byte offset of the first sample within a chunk.
+    pub offset: usize,
+}
+
+/// A run of samples for one channel within the block bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SampleSpan {
+    /// The byte offset of the first sample in [`RawBlock::bytes`].
+    pub start: usize,
+    /// The number of samples in the run.
+    pub samples: usize,
+    /// The distance in bytes between the start of each sample.
+    pub stride: usize,
+}
+
+/// A raw data block passed to a [`BlockDecoder`].
+#[derive(Debug, Clone, Copy)]
+pub struct RawBlock<'a> {
+    /// The number of the data block in the file.
+    pub data_block: usize,
+    pub layout: DataLayout,
+    pub big_endian: bool,
+    /// Every channel in the block, in the order they are stored.
+    pub channels: &'a [RawChannel],
+    /// The number of times the channels are repeated in the block.
+    pub chunks: usize,
+    /// The size of each chunk in bytes.
+    pub chunk_bytes: usize,
+    /// The raw bytes of the whole block.
+    pub bytes: &'a [u8],
+}
+
+impl RawBlock<'_> {
+    /// The runs of samples for the channel at `channel_index`, in sample order.
+    ///
+    /// Contiguous blocks give a single run per chunk. Interleaved blocks give a
+    /// run per chunk with a stride of the full record.
+    pub fn spans(&self, channel_index: usize) -> impl Iterator<Item = SampleSpan> + '_ {
+        let channel = self.channels[channel_index];
+        let stride = match self.layout {
+            DataLayout::Contigious => channel.data_type.size() as usize,
+            DataLayout::Interleaved => self
+                .channels
+                .iter()
+                .map(|channel| channel.data_type.size() as usize)
+                .sum(),
+        };
+        (0..self.chunks).map(move |chunk| SampleSpan {
+            start: chunk * self.chunk_bytes + channel.offset,
+            samples: channel.samples_per_chunk,
+            stride,
+        })
+    }
+}
+
+/// A custom decoder for the raw bytes of data blocks.
+///
+/// This is implemented for closures taking the same arguments.
+pub trait BlockDecoder {
+    /// Decode the channel at `channel_index` from the block.
+    fn decode_block(&mut self, block: &RawBlock<'_>, channel_index: usize)
+        -> Result<(), TdmsError>;
+}
+
+impl<F: FnMut(&RawBlock<'_>, usize) -> Result<(), TdmsError>> BlockDecoder for F {
+    fn decode_block(
+        &mut self,
+        block: &RawBlock<'_>,
+        channel_index: usize,
+    ) -> Result<(), TdmsError> {
+        self(block, channel_index)
+    }
+}
+
+impl<F: Read + Seek> TdmsFile<F> {
+    /// Pass the raw bytes of every data block containing the channel to the decoder.
+    ///
+    /// Blocks are visited in file order and the byte buffer is reused between blocks.
+    /// Errors with [`TdmsError::UnsupportedType`] for variable size data such as strings.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, RawBlock, TdmsFile, TdmsError};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[1.0f64, 2.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let mut sum = 0.0;
+    /// file.decode_blocks(&channel, &mut |block: &RawBlock, channel_index| {
+    ///     for span in block.spans(channel_index) {
+    ///         for sample in 0..span.samples {
+    ///             let start = span.start + sample * span.stride;
+    ///             let bytes = block.bytes[start..start + 8].try_into().unwrap();
+    ///             sum += f64::from_le_bytes(bytes);
+    ///         }
+    ///     }
+    ///     Ok::<(), TdmsError>(())
+    /// }).unwrap();
+    /// assert_eq!(sum, 3.0);
+    /// ```
+    pub fn decode_blocks(
+        &mut self,
+        channel: &ChannelPath,
+        decoder: &mut impl BlockDecoder,
+    ) -> Result<(), TdmsError> {
+        self.decode_blocks_in_order(channel, None, decoder)
+    }
+
+    /// Pass the raw bytes of every data block containing the channel to the decoder,
+    /// converted to a single byte order.
+    ///
+    /// Samples in blocks stored in the other byte order are swapped in place
+    /// before the decoder sees them, so files mixing byte orders between
+    /// segments can be decoded with one code path. [`RawBlock::big_endian`]
+    /// always matches `big_endian`. Complex values swap each part on its own.
+    pub fn decode_blocks_normalized(
+        &mut self,
+        channel: &ChannelPath,
+        big_endian: bool,
+        decoder: &mut impl BlockDecoder,
+    ) -> Result<(), TdmsError> {
+        let byte_order = if big_endian {
+            Endianess::Big
+        } else {
+            Endianess::Little
+        };
+        self.decode_blocks_in_order(channel, Some(byte_order), decoder)
+    }
+
+    fn decode_blocks_in_order(
+        &mut self,
+        channel: &ChannelPath,
+        byte_order: Option<Endianess>,
+        decoder: &mut impl BlockDecoder,
+    ) -> Result<(), TdmsError> {
+        let locations = self
+            .index
+            .get_channel_data_positions(channel)
+            .ok_or_else(|| self.index.missing_channel(channel))?;
+
+        let mut bytes = Vec::new();
+        let mut channels = Vec::new();
+
+        for location in locations {
+            let block = self
+                .index
+                .get_data_block(location.data_block)
+                .ok_or_else(|| {
+                    TdmsError::DataBlockNotFound(channel.clone(), location.data_block)
+                })?;
+
+            let chunk_bytes = block_layout(block, &mut channels)?;
+            let block_bytes = usize_from(block.length)?;
+            bytes.resize(block_bytes, 0);
+            self.file.seek(SeekFrom::Start(block.start))?;
+            self.file.read_exact(&mut bytes)?;
+
+            let mut raw_block = RawBlock {
+                data_block: location.data_block,
+                layout: block.layout,
+                big_endian: block.byte_order == Endianess::Big,
+                channels: &channels,
+                chunks: block_bytes.checked_div(chunk_bytes).unwrap_or(0),
+                chunk_bytes,
+                bytes: &[],
+            };
+            if let Some(byte_order) = byte_order.filter(|order| *order != block.byte_order) {
+                for channel_index in 0..channels.len() {
+                    swap_channel_bytes(&raw_block, channel_index, &mut bytes);
+                }
+                raw_block.big_endian = byte_order == Endianess::Big;
+            }
+            raw_block.bytes = &bytes;
+            decoder.decode_block(&raw_block, location.channel_index)?;
+        }
+        Ok(())
+    }
+}
+
+/// Fill `channels` with where each channel is stored in a chunk of the block
+/// and return the size of a chunk in bytes.
+///
+/// Errors with [`TdmsError::UnsupportedType`] for blocks with variable size data.
+pub(super) fn block_layout(
+    block: &DataBlock,
+    channels: &mut Vec<RawChannel>,
+) -> Result<usize, TdmsError> {
+    let chunk_bytes = match block.chunk_size() {
+        ChunkSize::Fixed(size) => usize_from(size)?,
+        ChunkSize::Variable(_) => {
+            let variable = block
+                .channels
+                .iter()
+                .find(|channel| channel.total_size_bytes.is_some())
+                .map_or(DataType::TdmsString, |channel| channel.data_type);
+            return Err(TdmsError::UnsupportedType(variable));
+        }
+    };
+
+    channels.clear();
+    let mut offset = 0;
+    for meta in &block.channels {
+        channels.push(RawChannel {
+            data_type: meta.data_type,
+            samples_per_chunk: meta.number_of_values as usize,
+            offset,
+        });
+        offset += match block.layout {
+            DataLayout::Contigious => {
+                meta.number_of_values as usize * meta.data_type.size() as usize
+            }
+            DataLayout::Interleaved => meta.data_type.size() as usize,
+        };
+    }
+    Ok(chunk_bytes)
+}
+
+/// Reverse the bytes of every sample of a channel in the block.
+fn swap_channel_bytes(block: &RawBlock<'_>, channel_index: usize, bytes: &mut [u8]) {
+    let data_type = block.channels[channel_index].data_type;
+    let word = match data_type {
+        DataType::ComplexSingleFloat | DataType::ComplexDoubleFloat => {
+            data_type.size() as usize / 2
+        }
+        _ => data_type.size() as usize,
+    };
+    if word < 2 {
+        return;
+    }
+    let size = data_type.size() as usize;
+    for span in block.spans(channel_index) {
+        for sample in 0..span.samples {
+            let start = span.start + sample * span.stride;
+            let Some(value) = bytes.get_mut(start..start + size) else {
+                return;
+            };
+            value.chunks_exact_mut(word).for_each(<[u8]>::reverse);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use super::*;
+    use crate::testgen::TestFileSpec;
+
+    /// Decode big or little endian i32 data with the spans.
+    fn decode_i32(block: &RawBlock<'_>, channel_index: usize, output: &mut Vec<i32>) {
+        for span in block.spans(channel_index) {
+            for sample in 0..span.samples {
+                let start = span.start + sample * span.stride;
+                let bytes = block.bytes[start..start + 4].try_into().unwrap();
+                output.push(match block.big_endian {
+                    true => i32::from_be_bytes(bytes),
+                    false => i32::from_le_bytes(bytes),
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn test_decoder_matches_built_in_reader() {
+        for layout in [DataLayout::Contigious, DataLayout::Interleaved] {
+            for big_endian in [false, true] {
+                let spec = TestFileSpec {
+                    channels_per_group: 3,
+                    segments: 3,
+                    samples_per_segment: 5,
+                    layout,
+                    big_endian,
+                    data_type: DataType::I32,
+                    ..Default::default()
+                };
+                let mut file = TdmsFile::new(std::io::Cursor::new(spec.build().unwrap())).unwrap();
+                let channel = spec.channel_path(0, 1);
+
+                let mut decoded = Vec::new();
+                let mut blocks = 0;
+                file.decode_blocks(&channel, &mut |block: &RawBlock<'_>, channel_index| {
+                    blocks += 1;
+                    assert_eq!(block.channels.len(), 3);
+                    decode_i32(block, channel_index, &mut decoded);
+                    Ok(())
+                })
+                .unwrap();
+
+                let expected: Vec<i32> = file.read_all(&channel).unwrap();
+                assert_eq!(blocks, 3);
+                assert_eq!(decoded, expected, "{layout:?} big endian: {big_endian}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_normalized_blocks_use_requested_byte_order() {
+        for layout in [DataLayout::Contigious, DataLayout::Interleaved] {
+            for big_endian in [false, true] {
+                let spec = TestFileSpec {
+                    channels_per_group: 2,
+                    segments: 2,
+                    samples_per_segment: 4,
+                    layout,
+                    big_endian,
+                    data_type: DataType::I32,
+                    ..Default::default()
+                };
+                let mut file = TdmsFile::new(std::io::Cursor::new(spec.build().unwrap())).unwrap();
+                let channel = spec.channel_path(0, 1);
+                let expected: Vec<i32> = file.read_all(&channel).unwrap();
+
+                for target in [false, true] {
+                    let mut decoded = Vec::new();
+                    file.decode_blocks_normalized(
+                        &channel,
+                        target,
+                        &mut |block: &RawBlock<'_>, channel_index| {
+                            assert_eq!(block.big_endian, target);
+                            decode_i32(block, channel_index, &mut decoded);
+                            Ok(())
+                        },
+                    )
+                    .unwrap();
+                    assert_eq!(decoded, expected, "{layout:?} {big_endian} to {target}");
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,13 @@
 use crate::paths::ChannelPath;
-use crate::{error::TdmsError, index::DataLocation, io::data_types::TdmsStorageType, TdmsFile};
+use crate::raw_data::DataBlock;
+use crate::{
+    error::{usize_from, TdmsError},
+    index::{DataLocation, Index},
+    io::data_types::TdmsStorageType,
+    TdmsFile,
+};
+
+use super::RetryPolicy;
 
 #[derive(Eq, PartialEq, Clone, Debug)]
 struct MultiChannelLocation {
@@ -36,6 +44,29 @@ impl ChannelProgress {
     }
 }
 
+/// What to do when a read requests samples beyond the end of the channel.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ReadPastEnd<D> {
+    /// Return [`TdmsError::ReadPastEnd`] without reading anything.
+    Error,
+    /// Read what is available and set the rest of the output to the value.
+    ///
+    /// Use a value of zero for a zero filled output.
+    Fill(D),
+    /// Read what is available and leave the rest of the output untouched.
+    #[default]
+    Truncate,
+}
+
+/// The result of reading a channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadOutcome {
+    /// The number of samples read from the file into the output.
+    pub samples_read: usize,
+    /// True if the output was longer than the data available in the channel.
+    pub truncated: bool,
+}
+
 impl<F: std::io::Read + std::io::Seek + std::io::Write + std::fmt::Debug> TdmsFile<F> {
     /// Get the length of the channel.
     pub fn channel_length(&self, channel: &ChannelPath) -> Option<u64> {
@@ -47,38 +78,271 @@ impl<F: std::io::Read + std::io::Seek + std::io::Write + std::fmt::Debug> TdmsFi
     /// channel should provide a path to the channel and output is a mutable slice for the data to be written into.
     ///
     /// If there is more data in the file than the size of the slice, we will stop reading at the end of the slice.
+    /// If there is less, the rest of the slice is untouched and the outcome is marked as truncated.
     pub fn read_channel<D: TdmsStorageType>(
         &mut self,
         channel: &ChannelPath,
         output: &mut [D],
-    ) -> Result<(), TdmsError> {
-        let data_positions = self
-            .index
-            .get_channel_data_positions(channel)
-            .ok_or_else(|| TdmsError::MissingObject(channel.path().to_owned()))?;
+    ) -> Result<ReadOutcome, TdmsError> {
+        self.read_range(channel, 0, output)
+    }
 
-        let mut samples_read = 0;
+    /// Read the whole of a single channel into a new vector.
+    ///
+    /// The vector is allocated once at the exact length tracked in the index.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[1.0, 2.0, 3.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let data: Vec<f64> = file.read_all(&channel).unwrap();
+    /// assert_eq!(data, vec![1.0, 2.0, 3.0]);
+    /// ```
+    pub fn read_all<D: TdmsStorageType + Default + Clone>(
+        &mut self,
+        channel: &ChannelPath,
+    ) -> Result<Vec<D>, TdmsError> {
+        let mut output = Vec::new();
+        self.read_all_into(channel, &mut output)?;
+        Ok(output)
+    }
 
-        for location in data_positions {
-            let block = self
-                .index
-                .get_data_block(location.data_block)
-                .ok_or_else(|| {
-                    TdmsError::DataBlockNotFound(channel.clone(), location.data_block)
-                })?;
+    /// Read the whole of a single channel into an existing vector.
+    ///
+    /// Any existing contents are replaced. The capacity of the vector is reused so
+    /// repeated reads into the same vector only allocate when it needs to grow.
+    ///
+    /// Errors with [`TdmsError::ExceedsAddressSpace`] if the channel can't fit in
+    /// memory on this platform. Use [`Self::read_channel_bounded`] to stream it instead.
+    pub fn read_all_into<D: TdmsStorageType + Default + Clone>(
+        &mut self,
+        channel: &ChannelPath,
+        output: &mut Vec<D>,
+    ) -> Result<(), TdmsError> {
+        let length = self
+            .channel_length(channel)
+            .ok_or_else(|| self.index.missing_channel(channel))?;
 
-            samples_read += block.read_single(
-                location.channel_index,
-                &mut self.file,
-                &mut output[samples_read..],
-            )?;
+        output.clear();
+        output.resize(usize_from(length)?, D::default());
+        self.read_channel(channel, &mut output[..])?;
+        Ok(())
+    }
 
-            if samples_read >= output.len() {
+    /// Read the whole of a single channel in chunks, passing each chunk to `sink`.
+    ///
+    /// A single buffer of at most `max_bytes_in_flight` bytes is allocated and
+    /// reused for every chunk, so memory use stays within the budget however long
+    /// the channel is. The buffer always holds at least one sample. Returns the
+    /// number of samples passed to the sink.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[1.0, 2.0, 3.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let mut chunks = Vec::new();
+    /// file.read_channel_bounded(&channel, 16, |chunk: &[f64]| {
+    ///     chunks.push(chunk.to_vec());
+    ///     Ok(())
+    /// })
+    /// .unwrap();
+    /// assert_eq!(chunks, vec![vec![1.0, 2.0], vec![3.0]]);
+    /// ```
+    pub fn read_channel_bounded<D: TdmsStorageType + Default + Clone>(
+        &mut self,
+        channel: &ChannelPath,
+        max_bytes_in_flight: usize,
+        mut sink: impl FnMut(&[D]) -> Result<(), TdmsError>,
+    ) -> Result<u64, TdmsError> {
+        let length = self
+            .channel_length(channel)
+            .ok_or_else(|| self.index.missing_channel(channel))?;
+        let chunk_samples = (max_bytes_in_flight / std::mem::size_of::<D>().max(1)).max(1);
+        let mut buffer = vec![D::default(); (chunk_samples as u64).min(length) as usize];
+
+        let mut position = 0;
+        while position < length {
+            let samples_read = self.read_range_inner(channel, position, &mut buffer)?;
+            if samples_read == 0 {
                 break;
             }
+            sink(&buffer[..samples_read])?;
+            position += samples_read as u64;
         }
+        Ok(position)
+    }
 
-        Ok(())
+    /// Read the whole of a single channel in power of two sized chunks for spectral analysis.
+    ///
+    /// `chunk_samples` is rounded up to the next power of two. Every chunk passed
+    /// to `sink` has exactly that length, regardless of where the data blocks
+    /// end, with the last chunk padded with the default (zero) value. The sink
+    /// also receives the number of samples in the chunk which came from the
+    /// channel. Returns the number of samples read from the channel.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[1.0, 2.0, 3.0], DataLayout::Contigious).unwrap();
+    /// writer.write_channels(&[&channel], &[4.0, 5.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let mut chunks = Vec::new();
+    /// file.read_fft_chunks(&channel, 3, |chunk: &[f64], valid| {
+    ///     chunks.push((chunk.to_vec(), valid));
+    ///     Ok(())
+    /// })
+    /// .unwrap();
+    /// assert_eq!(
+    ///     chunks,
+    ///     vec![(vec![1.0, 2.0, 3.0, 4.0], 4), (vec![5.0, 0.0, 0.0, 0.0], 1)]
+    /// );
+    /// ```
+    pub fn read_fft_chunks<D: TdmsStorageType + Default + Clone>(
+        &mut self,
+        channel: &ChannelPath,
+        chunk_samples: usize,
+        mut sink: impl FnMut(&[D], usize) -> Result<(), TdmsError>,
+    ) -> Result<u64, TdmsError> {
+        let length = self
+            .channel_length(channel)
+            .ok_or_else(|| self.index.missing_channel(channel))?;
+        let mut buffer = vec![D::default(); chunk_samples.max(1).next_power_of_two()];
+
+        let mut position = 0;
+        while position < length {
+            let samples_read = self.read_range_inner(channel, position, &mut buffer)?;
+            if samples_read == 0 {
+                break;
+            }
+            buffer[samples_read..].fill(D::default());
+            sink(&buffer, samples_read)?;
+            position += samples_read as u64;
+        }
+        Ok(position)
+    }
+
+    /// Read a single channel from the tdms file starting at the sample `start`.
+    ///
+    /// The block containing `start` is found by a binary search of the index so this
+    /// is efficient even for files with many blocks per channel.
+    ///
+    /// If there is more data in the file than the size of the slice, we will stop reading at the end of the slice.
+    /// If `start` is beyond the end of the channel nothing is read.
+    ///
+    /// Reads past the end of the channel are truncated. Use [`Self::read_range_with_policy`] to change this.
+    pub fn read_range<D: TdmsStorageType>(
+        &mut self,
+        channel: &ChannelPath,
+        start: u64,
+        output: &mut [D],
+    ) -> Result<ReadOutcome, TdmsError> {
+        let samples_read = self.read_range_inner(channel, start, output)?;
+        Ok(ReadOutcome {
+            samples_read,
+            truncated: samples_read < output.len(),
+        })
+    }
+
+    /// Read a single channel from the tdms file starting at the sample `start`, with
+    /// `policy` controlling what happens if the output extends beyond the end of the channel.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, ReadPastEnd, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[1.0, 2.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let mut output = [-1.0; 3];
+    /// let outcome = file
+    ///     .read_range_with_policy(&channel, 0, &mut output, ReadPastEnd::Fill(0.0))
+    ///     .unwrap();
+    /// assert!(outcome.truncated);
+    /// assert_eq!(output, [1.0, 2.0, 0.0]);
+    /// ```
+    pub fn read_range_with_policy<D: TdmsStorageType + Clone>(
+        &mut self,
+        channel: &ChannelPath,
+        start: u64,
+        output: &mut [D],
+        policy: ReadPastEnd<D>,
+    ) -> Result<ReadOutcome, TdmsError> {
+        if let ReadPastEnd::Error = policy {
+            let length = self
+                .channel_length(channel)
+                .ok_or_else(|| self.index.missing_channel(channel))?;
+            if start.saturating_add(output.len() as u64) > length {
+                return Err(TdmsError::ReadPastEnd {
+                    channel: channel.clone(),
+                    start,
+                    requested: output.len(),
+                    length,
+                });
+            }
+        }
+
+        let outcome = self.read_range(channel, start, output)?;
+
+        if let ReadPastEnd::Fill(value) = policy {
+            output[outcome.samples_read..].fill(value);
+        }
+
+        Ok(outcome)
+    }
+
+    /// Read the range and return the number of samples read.
+    ///
+    /// With the `small` feature this goes through the non-generic reader where
+    /// it covers the sample type.
+    fn read_range_inner<D: TdmsStorageType>(
+        &mut self,
+        channel: &ChannelPath,
+        start: u64,
+        output: &mut [D],
+    ) -> Result<usize, TdmsError> {
+        #[cfg(feature = "small")]
+        if let Some(output) = D::sample_buffer(output) {
+            return super::dyn_read::read_range_into(
+                &self.index,
+                &self.retry,
+                &mut self.file,
+                channel,
+                start,
+                output,
+            );
+        }
+        read_range_from(
+            &self.index,
+            &self.retry,
+            &mut self.file,
+            channel,
+            start,
+            output,
+        )
     }
 
     /// Read multiple channels from the tdms file.
@@ -95,7 +359,7 @@ impl<F: std::io::Read + std::io::Seek + std::io::Write + std::fmt::Debug> TdmsFi
             .map(|channel| {
                 self.index
                     .get_channel_data_positions(channel.as_ref())
-                    .ok_or_else(|| TdmsError::MissingObject(channel.as_ref().path().to_owned()))
+                    .ok_or_else(|| self.index.missing_channel(channel.as_ref()))
             })
             .collect::<Result<Vec<&[DataLocation]>, TdmsError>>()?;
 
@@ -117,12 +381,21 @@ impl<F: std::io::Read + std::io::Seek + std::io::Write + std::fmt::Debug> TdmsFi
                     )
                 })?;
 
-            let mut channels_to_read = get_block_read_data(&location, output, &channel_progress);
-
-            let location_samples_read = block.read(&mut self.file, &mut channels_to_read)?;
-
-            let read_complete =
-                update_progress(location, &mut channel_progress, location_samples_read);
+            let file = &mut self.file;
+            self.retry
+                .run(|| {
+                    let mut channels_to_read =
+                        get_block_read_data(&location, output, &channel_progress);
+                    block.read(file, &mut channels_to_read)
+                })
+                .map_err(
+                    |error| match mismatched_channel::<D>(block, &location, channels) {
+                        Some(channel) => error.with_channel(channel),
+                        None => error,
+                    },
+                )?;
+
+            let read_complete = update_progress(location, &mut channel_progress, block);
 
             if read_complete {
                 break;
@@ -133,6 +406,68 @@ impl<F: std::io::Read + std::io::Seek + std::io::Write + std::fmt::Debug> TdmsFi
     }
 }
 
+/// Read the range from `file` using the index and return the number of samples read.
+///
+/// This is separate from [`TdmsFile`] so threads can read with their own file handles.
+pub(super) fn read_range_from<D: TdmsStorageType>(
+    index: &Index,
+    retry: &RetryPolicy,
+    file: &mut (impl std::io::Read + std::io::Seek),
+    channel: &ChannelPath,
+    start: u64,
+    output: &mut [D],
+) -> Result<usize, TdmsError> {
+    let data_positions = index
+        .get_channel_data_positions(channel)
+        .ok_or_else(|| index.missing_channel(channel))?;
+
+    let Some((first_location, mut skip_samples)) = index.find_sample_location(channel, start)
+    else {
+        return Ok(0);
+    };
+
+    let mut samples_read = 0;
+
+    for location in &data_positions[first_location..] {
+        if samples_read >= output.len() {
+            break;
+        }
+
+        let block = index
+            .get_data_block(location.data_block)
+            .ok_or_else(|| TdmsError::DataBlockNotFound(channel.clone(), location.data_block))?;
+
+        samples_read += retry
+            .run(|| {
+                block.read_from(
+                    file,
+                    skip_samples,
+                    &mut [(location.channel_index, &mut output[samples_read..])],
+                )
+            })
+            .map_err(|error| error.with_channel(channel))?;
+        skip_samples = 0;
+    }
+
+    Ok(samples_read.min(output.len()))
+}
+
+/// Find the first channel in the location whose stored type can't be read as `D`.
+fn mismatched_channel<'c, D: TdmsStorageType>(
+    block: &DataBlock,
+    location: &MultiChannelLocation,
+    channels: &'c [impl AsRef<ChannelPath>],
+) -> Option<&'c ChannelPath> {
+    location
+        .channel_indexes
+        .iter()
+        .zip(channels.iter())
+        .find_map(|(channel_index, channel)| {
+            let stored = block.channels.get((*channel_index)?)?.data_type;
+            (!D::SUPPORTED_TYPES.contains(&stored)).then_some(channel.as_ref())
+        })
+}
+
 /// Get the read parameters and output for this particular block.
 fn get_block_read_data<'a, 'b: 'o, 'c: 'o, 'o, D: TdmsStorageType>(
     location: &'a MultiChannelLocation,
@@ -158,17 +493,27 @@ fn get_block_read_data<'a, 'b: 'o, 'c: 'o, 'o, D: TdmsStorageType>(
 
 /// Update the progress of the channels we have read.
 ///
+/// Channels can have a different number of samples in the same block so
+/// each is advanced by its own length in the block.
+///
 /// Returns true if all are complete.
 fn update_progress(
     location: MultiChannelLocation,
     channel_progress: &mut [ChannelProgress],
-    iteration_samples: usize,
+    block: &DataBlock,
 ) -> bool {
     assert!(channel_progress.len() == location.channel_indexes.len());
 
+    let chunks = block.number_of_chunks();
     for (ch_idx, block_idx) in location.channel_indexes.iter().enumerate() {
-        if block_idx.is_some() {
-            channel_progress[ch_idx].add_samples(iteration_samples);
+        if let Some(block_idx) = block_idx {
+            let progress = &mut channel_progress[ch_idx];
+            if progress.is_complete() {
+                continue;
+            }
+            let block_samples = block.channels[*block_idx].number_of_values * chunks;
+            let remaining = progress.samples_target - progress.samples_read;
+            progress.add_samples(block_samples.min(remaining as u64) as usize);
         }
     }
     all_channels_complete(channel_progress)
@@ -216,10 +561,7 @@ fn read_plan(channel_positions: &[&[DataLocation]]) -> Vec<MultiChannelLocation>
             .iter()
             .zip(next_location.iter_mut())
             .map(|(locations, index)| {
-                let next_location = locations.get(*index);
-                let Some(next_location) = next_location else {
-                    return None;
-                };
+                let next_location = locations.get(*index)?;
 
                 if next_location.data_block == next_block {
                     *index += 1;
@@ -237,16 +579,78 @@ fn read_plan(channel_positions: &[&[DataLocation]]) -> Vec<MultiChannelLocation>
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "write"))]
 mod tests {
 
     use crate::index::DataLocation;
+    use crate::io::data_types::DataType;
+    use crate::io::writer::{LittleEndianWriter, TdmsWriter};
+    use crate::meta_data::{MetaData, ObjectMetaData, RawDataIndex, RawDataMeta, ToC};
 
     use super::*;
 
+    /// Build a file with one contigious block where the channels have 3 and 2 values per chunk.
+    ///
+    /// The block holds two chunks so the channels contain 0, 1, 2, 5, 6, 7 and 3, 4, 8, 9.
+    fn differing_lengths_file() -> TdmsFile<std::io::Cursor<Vec<u8>>> {
+        let channel = |name: &str, values: u64| ObjectMetaData {
+            path: ChannelPath::new("group", name).path().to_string(),
+            properties: vec![],
+            raw_data_index: RawDataIndex::RawData(RawDataMeta {
+                data_type: DataType::DoubleFloat,
+                number_of_values: values,
+                total_size_bytes: None,
+            }),
+        };
+        let meta = MetaData {
+            objects: vec![channel("long", 3), channel("short", 2)],
+        };
+        let toc = ToC {
+            contains_new_object_list: true,
+            ..Default::default()
+        };
+        let data: Vec<f64> = (0..10).map(|value| value as f64).collect();
+
+        let mut bytes = vec![];
+        LittleEndianWriter::from_writer(&mut bytes)
+            .write_segment(toc, Some(meta), Some(&data[..]))
+            .unwrap();
+        TdmsFile::new(std::io::Cursor::new(bytes)).unwrap()
+    }
+
+    #[test]
+    fn test_read_channels_with_differing_lengths_in_block() {
+        let mut file = differing_lengths_file();
+        let long = ChannelPath::new("group", "long");
+        let short = ChannelPath::new("group", "short");
+
+        assert_eq!(file.channel_length(&long), Some(6));
+        assert_eq!(file.channel_length(&short), Some(4));
+        let read: Vec<f64> = file.read_all(&long).unwrap();
+        assert_eq!(read, vec![0.0, 1.0, 2.0, 5.0, 6.0, 7.0]);
+        let read: Vec<f64> = file.read_all(&short).unwrap();
+        assert_eq!(read, vec![3.0, 4.0, 8.0, 9.0]);
+
+        let mut output = [0.0; 3];
+        let outcome = file.read_range(&long, 2, &mut output).unwrap();
+        assert_eq!(outcome.samples_read, 3);
+        assert_eq!(output, [2.0, 5.0, 6.0]);
+        let mut output = [0.0; 3];
+        let outcome = file.read_range(&short, 2, &mut output).unwrap();
+        assert_eq!(outcome.samples_read, 2);
+        assert_eq!(output, [8.0, 9.0, 0.0]);
+
+        let mut long_output = [0.0; 6];
+        let mut short_output = [0.0; 4];
+        file.read_channels(&[&long, &short], &mut [&mut long_output, &mut short_output])
+            .unwrap();
+        assert_eq!(long_output, [0.0, 1.0, 2.0, 5.0, 6.0, 7.0]);
+        assert_eq!(short_output, [3.0, 4.0, 8.0, 9.0]);
+    }
+
     #[test]
     fn test_read_plan_single_channel() {
-        let channel_locations = vec![
+        let channel_locations = [
             DataLocation {
                 data_block: 20,
                 channel_index: 1,
@@ -277,7 +681,7 @@ mod tests {
 
     #[test]
     fn test_read_plan_multi_channel_simple() {
-        let channel_location_1 = vec![
+        let channel_location_1 = [
             DataLocation {
                 data_block: 20,
                 channel_index: 1,
@@ -290,7 +694,7 @@ mod tests {
             },
         ];
 
-        let channel_location_2 = vec![
+        let channel_location_2 = [
             DataLocation {
                 data_block: 20,
                 channel_index: 2,
@@ -321,7 +725,7 @@ mod tests {
 
     #[test]
     fn test_read_plan_multi_channel_complex() {
-        let channel_location_1 = vec![
+        let channel_location_1 = [
             DataLocation {
                 data_block: 20,
                 channel_index: 1,
@@ -339,7 +743,7 @@ mod tests {
             },
         ];
 
-        let channel_location_2 = vec![
+        let channel_location_2 = [
             DataLocation {
                 data_block: 20,
                 channel_index: 2,
@@ -398,4 +802,69 @@ mod tests {
 
         assert!(progress.is_complete());
     }
+
+    #[test]
+    fn test_bounded_read_chunks_stay_within_budget() {
+        let spec = crate::testgen::TestFileSpec {
+            segments: 3,
+            samples_per_segment: 7,
+            ..Default::default()
+        };
+        let channel = spec.channel_path(0, 1);
+        let mut file = TdmsFile::new(std::io::Cursor::new(spec.build().unwrap())).unwrap();
+        let expected: Vec<f64> = file.read_all(&channel).unwrap();
+
+        let mut chunks = vec![];
+        let total = file
+            .read_channel_bounded(&channel, 40, |chunk: &[f64]| {
+                chunks.push(chunk.to_vec());
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(total, 21);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 5));
+        assert_eq!(chunks.concat(), expected);
+
+        // A budget smaller than a sample still makes progress.
+        let mut samples = 0;
+        file.read_channel_bounded(&channel, 1, |chunk: &[f64]| {
+            assert_eq!(chunk.len(), 1);
+            samples += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(samples, 21);
+
+        let error = file
+            .read_channel_bounded(&channel, 40, |_: &[f64]| Err(TdmsError::EndOfFile))
+            .unwrap_err();
+        assert!(matches!(error, TdmsError::EndOfFile));
+    }
+
+    #[test]
+    fn test_fft_chunks_cross_blocks_and_pad_the_last() {
+        let spec = crate::testgen::TestFileSpec {
+            segments: 3,
+            samples_per_segment: 7,
+            ..Default::default()
+        };
+        let channel = spec.channel_path(0, 1);
+        let mut file = TdmsFile::new(std::io::Cursor::new(spec.build().unwrap())).unwrap();
+        let expected: Vec<f64> = file.read_all(&channel).unwrap();
+
+        let mut chunks = vec![];
+        let total = file
+            .read_fft_chunks(&channel, 5, |chunk: &[f64], valid| {
+                assert_eq!(chunk.len(), 8);
+                chunks.push((chunk.to_vec(), valid));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(total, 21);
+        let valid: Vec<usize> = chunks.iter().map(|(_, valid)| *valid).collect();
+        assert_eq!(valid, [8, 8, 5]);
+        let data: Vec<f64> = chunks.iter().flat_map(|(chunk, _)| chunk.clone()).collect();
+        assert_eq!(&data[..21], &expected[..]);
+        assert_eq!(&data[21..], &[0.0; 3]);
+    }
 }
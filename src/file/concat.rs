@@ -0,0 +1,205 @@
+//! Reading a channel split across a series of files.
+//!
+//! Long acquisitions are often logged to a new file every hour or every run.
+//! A [`ConcatenatedChannel`] presents the same channel path in an ordered list
+//! of files as one channel, so ranges can be read across the file boundaries
+//! without rewriting the files.
+
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+
+use crate::error::{usize_from, TdmsError};
+use crate::io::data_types::TdmsStorageType;
+use crate::paths::ChannelPath;
+use crate::{ReadOutcome, TdmsFile};
+
+/// One channel read as the concatenation of the same path in several files.
+#[derive(Debug)]
+pub struct ConcatenatedChannel<F: Read + Seek> {
+    channel: ChannelPath,
+    files: Vec<TdmsFile<F>>,
+    /// The sample each file starts at, with the total length at the end.
+    starts: Vec<u64>,
+}
+
+impl ConcatenatedChannel<File> {
+    /// Load the files at the paths, in order, and concatenate the channel.
+    pub fn open(paths: &[impl AsRef<Path>], channel: &ChannelPath) -> Result<Self, TdmsError> {
+        let files = paths
+            .iter()
+            .map(|path| TdmsFile::load(path.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(files, channel)
+    }
+}
+
+impl<F: Read + Seek + Write + Debug> ConcatenatedChannel<F> {
+    /// Concatenate the channel from the files in order.
+    ///
+    /// Errors with [`TdmsError::MissingChannel`] or [`TdmsError::MissingGroup`]
+    /// if any file doesn't have the channel.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use std::io::Cursor;
+    /// use tedium::{ChannelPath, ConcatenatedChannel, DataLayout, TdmsFile};
+    ///
+    /// let channel = ChannelPath::new("run", "speed");
+    /// let files = [[1.0, 2.0], [3.0, 4.0]].map(|data| {
+    ///     let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+    ///     let mut writer = file.writer().unwrap();
+    ///     writer.write_channels(&[&channel], &data, DataLayout::Contigious).unwrap();
+    ///     drop(writer);
+    ///     file
+    /// });
+    ///
+    /// let mut concatenated = ConcatenatedChannel::new(files.into(), &channel).unwrap();
+    /// let mut output = [0.0; 2];
+    /// concatenated.read_range(1, &mut output).unwrap();
+    /// assert_eq!(output, [2.0, 3.0]);
+    /// ```
+    pub fn new(files: Vec<TdmsFile<F>>, channel: &ChannelPath) -> Result<Self, TdmsError> {
+        let mut starts = Vec::with_capacity(files.len() + 1);
+        let mut length = 0u64;
+        starts.push(length);
+        for file in &files {
+            length += file
+                .channel_length(channel)
+                .ok_or_else(|| file.index.missing_channel(channel))?;
+            starts.push(length);
+        }
+        Ok(Self {
+            channel: channel.clone(),
+            files,
+            starts,
+        })
+    }
+
+    /// The channel being read.
+    pub fn channel(&self) -> &ChannelPath {
+        &self.channel
+    }
+
+    /// The total number of samples across all the files.
+    pub fn len(&self) -> u64 {
+        self.starts[self.files.len()]
+    }
+
+    /// True if no file has any samples for the channel.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The sample of the concatenated channel each file starts at.
+    ///
+    /// These are where acquisition restarted, which timestamp checks may need.
+    pub fn file_starts(&self) -> &[u64] {
+        &self.starts[..self.files.len()]
+    }
+
+    /// Read the channel starting at the sample `start` of the concatenated channel.
+    ///
+    /// Reads continue into the following files as needed. Reads past the end
+    /// of the last file are truncated as in [`TdmsFile::read_range`].
+    pub fn read_range<D: TdmsStorageType>(
+        &mut self,
+        start: u64,
+        output: &mut [D],
+    ) -> Result<ReadOutcome, TdmsError> {
+        // The last file starting at or before `start`.
+        let first = self.starts[1..].partition_point(|end| *end <= start);
+        let mut samples_read = 0;
+        let mut position = start;
+        for (file, file_start) in self.files.iter_mut().zip(&self.starts).skip(first) {
+            if samples_read == output.len() {
+                break;
+            }
+            let outcome = file.read_range(
+                &self.channel,
+                position - file_start,
+                &mut output[samples_read..],
+            )?;
+            samples_read += outcome.samples_read;
+            position += outcome.samples_read as u64;
+        }
+        Ok(ReadOutcome {
+            samples_read,
+            truncated: samples_read < output.len(),
+        })
+    }
+
+    /// Read the whole concatenated channel.
+    pub fn read_all<D: TdmsStorageType + Default + Clone>(&mut self) -> Result<Vec<D>, TdmsError> {
+        let mut output = vec![D::default(); usize_from(self.len())?];
+        self.read_range(0, &mut output)?;
+        Ok(output)
+    }
+
+    /// Return the files.
+    pub fn into_files(self) -> Vec<TdmsFile<F>> {
+        self.files
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::DataLayout;
+
+    fn file_with(channel: &ChannelPath, data: &[u32]) -> TdmsFile<Cursor<Vec<u8>>> {
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[channel], data, DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+        file
+    }
+
+    #[test]
+    fn test_read_ranges_across_file_boundaries() {
+        let channel = ChannelPath::new("run", "count");
+        let files = vec![
+            file_with(&channel, &[0, 1, 2]),
+            file_with(&channel, &[]),
+            file_with(&channel, &[3, 4]),
+            file_with(&channel, &[5, 6, 7, 8]),
+        ];
+        let mut concatenated = ConcatenatedChannel::new(files, &channel).unwrap();
+        assert_eq!(concatenated.len(), 9);
+        assert_eq!(concatenated.file_starts(), &[0, 3, 3, 5]);
+        assert_eq!(
+            concatenated.read_all::<u32>().unwrap(),
+            (0..9).collect::<Vec<_>>()
+        );
+
+        for start in 0..9u32 {
+            let mut output = [u32::MAX; 4];
+            let outcome = concatenated.read_range(start as u64, &mut output).unwrap();
+            let expected: Vec<u32> = (start..9).take(4).collect();
+            assert_eq!(outcome.samples_read, expected.len());
+            assert_eq!(&output[..expected.len()], &expected[..]);
+        }
+
+        let mut output = [0u32; 2];
+        let outcome = concatenated.read_range(20, &mut output).unwrap();
+        assert_eq!(outcome.samples_read, 0);
+        assert!(outcome.truncated);
+    }
+
+    #[test]
+    fn test_missing_channel_in_any_file_is_an_error() {
+        let channel = ChannelPath::new("run", "count");
+        let files = vec![
+            file_with(&channel, &[1]),
+            file_with(&ChannelPath::new("run", "other"), &[2]),
+        ];
+        let error = ConcatenatedChannel::new(files, &channel).unwrap_err();
+        assert!(matches!(error, TdmsError::MissingChannel { .. }));
+    }
+}
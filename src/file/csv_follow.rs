@@ -0,0 +1,400 @@
+//! Streaming a growing file out as CSV.
+//!
+//! A [`CsvFollower`] combines [`TdmsFile::refresh`] with a CSV writer. Each
+//! call to [`CsvFollower::poll`] appends the rows of a group which have
+//! arrived since the last call, so a pipeline can consume a LabVIEW logger's
+//! output while the test is still running.
+//!
+//! Columns can be converted to another unit with [`CsvFollower::with_unit`],
+//! using the same [`UnitConversion`] table as [`TdmsFile::read_channel_in_unit`].
+//! The conversion is applied to each chunk of rows as it is read.
+
+use std::fmt::Debug;
+use std::io::{Read, Seek, Write};
+
+use labview_interop::types::LVTime;
+
+use crate::error::{usize_from, TdmsError};
+use crate::io::data_types::DataType;
+use crate::paths::{parse_path, ChannelPath, GroupPath};
+use crate::{PropertyValue, TdmsFile, UnitConversion};
+
+use super::time_sync::read_range_as_f64;
+
+/// The most rows read from the file at once.
+pub(super) const CHUNK_ROWS: u64 = 64 * 1024;
+
+/// Appends the new rows of a group to a CSV sink as the file grows.
+///
+/// Each channel of the group is a column, in the order of the file. A row is
+/// written once every channel has a sample for it. The columns are fixed the
+/// first time the group has channels, so channels added later are ignored.
+#[derive(Debug)]
+pub struct CsvFollower<W: Write> {
+    group: GroupPath,
+    sink: W,
+    /// The unit requested for channels which should be converted.
+    target_units: Vec<(ChannelPath, String)>,
+    channels: Vec<ChannelPath>,
+    /// The conversion for each column, if it has a target unit.
+    conversions: Vec<Option<UnitConversion>>,
+    rows_written: u64,
+}
+
+impl<W: Write> CsvFollower<W> {
+    /// Follow the group, writing the CSV to `sink`.
+    pub fn new(group: &GroupPath, sink: W) -> Self {
+        Self {
+            group: group.clone(),
+            sink,
+            target_units: Vec::new(),
+            channels: Vec::new(),
+            conversions: Vec::new(),
+            rows_written: 0,
+        }
+    }
+
+    /// Convert the channel from its `unit_string` to `unit` as it is written.
+    ///
+    /// The conversion is found when the columns are fixed and [`Self::poll`]
+    /// errors with [`TdmsError::UnitConversionUnavailable`] if the channel has
+    /// no unit or there is no conversion to `unit`.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, CsvFollower, DataLayout, GroupPath, PropertyValue, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let pressure = ChannelPath::new("rig", "pressure");
+    /// let mut writer = file.writer().unwrap();
+    /// writer
+    ///     .write_properties(pressure.as_ref(), &[("unit_string", PropertyValue::from("bar"))])
+    ///     .unwrap();
+    /// writer.write_channels(&[&pressure], &[1.0, 2.5], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let mut follower = CsvFollower::new(&GroupPath::new("rig"), Vec::new())
+    ///     .with_unit(&pressure, "kPa");
+    /// follower.poll(&mut file).unwrap();
+    /// let csv = String::from_utf8(follower.into_inner()).unwrap();
+    /// assert_eq!(csv, "pressure\n100\n250\n");
+    /// ```
+    pub fn with_unit(mut self, channel: &ChannelPath, unit: &str) -> Self {
+        self.target_units
+            .retain(|(existing, _)| existing != channel);
+        self.target_units.push((channel.clone(), unit.to_string()));
+        self
+    }
+
+    /// The number of rows written, not counting the header.
+    pub fn rows_written(&self) -> u64 {
+        self.rows_written
+    }
+
+    /// Return the sink.
+    pub fn into_inner(self) -> W {
+        self.sink
+    }
+
+    /// Refresh the file and write any new complete rows. Returns the rows written.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, CsvFollower, DataLayout, GroupPath, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let mut follower = CsvFollower::new(&GroupPath::new("rig"), Vec::new());
+    ///
+    /// let channels = [ChannelPath::new("rig", "force"), ChannelPath::new("rig", "speed")];
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&channels, &[1.5, 2.0, 10.0, 20.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    /// assert_eq!(follower.poll(&mut file).unwrap(), 2);
+    ///
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&channels, &[3.0, 30.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    /// assert_eq!(follower.poll(&mut file).unwrap(), 1);
+    ///
+    /// let csv = String::from_utf8(follower.into_inner()).unwrap();
+    /// assert_eq!(csv, "force,speed\n1.5,10\n2,20\n3,30\n");
+    /// ```
+    pub fn poll<F: Read + Seek + Write + Debug>(
+        &mut self,
+        file: &mut TdmsFile<F>,
+    ) -> Result<u64, TdmsError> {
+        file.refresh()?;
+        if self.channels.is_empty() {
+            let channels: Vec<ChannelPath> = file.list_channels_in_group(&self.group).collect();
+            if channels.is_empty() {
+                return Ok(0);
+            }
+            self.conversions = channels
+                .iter()
+                .map(|channel| conversion(&self.target_units, file, channel))
+                .collect::<Result<_, TdmsError>>()?;
+            self.channels = channels;
+            self.write_header()?;
+        }
+
+        let complete_rows = self
+            .channels
+            .iter()
+            .map(|channel| file.channel_length(channel).unwrap_or(0))
+            .min()
+            .unwrap_or(0);
+        let start = self.rows_written;
+
+        while self.rows_written < complete_rows {
+            let rows = (complete_rows - self.rows_written).min(CHUNK_ROWS);
+            let columns = self
+                .channels
+                .iter()
+                .zip(&self.conversions)
+                .map(|(channel, conversion)| match conversion {
+                    Some(conversion) => {
+                        read_converted_cells(file, channel, conversion, self.rows_written, rows)
+                    }
+                    None => read_cells(file, channel, self.rows_written, rows),
+                })
+                .collect::<Result<Vec<_>, TdmsError>>()?;
+            for row in 0..usize_from(rows)? {
+                let cells = columns.iter().map(|column| column[row].as_str());
+                write_row(&mut self.sink, cells)?;
+            }
+            self.rows_written += rows;
+        }
+        self.sink.flush()?;
+        Ok(self.rows_written - start)
+    }
+
+    fn write_header(&mut self) -> Result<(), TdmsError> {
+        let names: Vec<String> = self
+            .channels
+            .iter()
+            .map(|channel| {
+                let name = parse_path(channel.path()).ok().and_then(|(_, name)| name);
+                escape(name.unwrap_or_default())
+            })
+            .collect();
+        write_row(&mut self.sink, names.iter().map(String::as_str))
+    }
+}
+
+/// The conversion for the column, if a unit was requested for the channel.
+pub(super) fn conversion<F: Read + Seek + Write + Debug>(
+    target_units: &[(ChannelPath, String)],
+    file: &mut TdmsFile<F>,
+    channel: &ChannelPath,
+) -> Result<Option<UnitConversion>, TdmsError> {
+    let Some((_, unit)) = target_units.iter().find(|(target, _)| target == channel) else {
+        return Ok(None);
+    };
+    let source_unit = file
+        .read_property(channel.as_ref(), "unit_string")?
+        .and_then(PropertyValue::as_str)
+        .map(str::to_owned);
+    source_unit
+        .as_deref()
+        .and_then(|source_unit| UnitConversion::between(source_unit, unit))
+        .map(Some)
+        .ok_or_else(|| {
+            TdmsError::UnitConversionUnavailable(channel.clone(), source_unit, unit.clone())
+        })
+}
+
+/// Write one line of cells which are already escaped.
+fn write_row<'a>(
+    sink: &mut impl Write,
+    cells: impl Iterator<Item = &'a str>,
+) -> Result<(), TdmsError> {
+    for (column, cell) in cells.enumerate() {
+        if column > 0 {
+            sink.write_all(b",")?;
+        }
+        sink.write_all(cell.as_bytes())?;
+    }
+    sink.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Quote the text if it contains a separator, quote or line break.
+fn escape(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Read `rows` samples of the channel from `start` formatted as CSV cells.
+fn read_cells<F: Read + Seek + Write + Debug>(
+    file: &mut TdmsFile<F>,
+    channel: &ChannelPath,
+    start: u64,
+    rows: u64,
+) -> Result<Vec<String>, TdmsError> {
+    let count = usize_from(rows)?;
+    macro_rules! cells {
+        ($initial:expr, $variant:ident) => {{
+            let mut values = vec![$initial; count];
+            file.read_range(channel, start, &mut values)?;
+            values
+                .into_iter()
+                .map(|value| PropertyValue::$variant(value).to_string())
+                .collect()
+        }};
+    }
+
+    let data_type = file
+        .index
+        .channel_data_type(channel)
+        .ok_or_else(|| file.index.missing_channel(channel))?;
+    Ok(match data_type {
+        DataType::I8 => cells!(0i8, I8),
+        DataType::I16 => cells!(0i16, I16),
+        DataType::I32 => cells!(0i32, I32),
+        DataType::I64 => cells!(0i64, I64),
+        DataType::U8 => cells!(0u8, U8),
+        DataType::U16 => cells!(0u16, U16),
+        DataType::U32 => cells!(0u32, U32),
+        DataType::U64 => cells!(0u64, U64),
+        DataType::SingleFloat | DataType::SingleFloatWithUnit => cells!(0f32, SingleFloat),
+        DataType::DoubleFloat | DataType::DoubleFloatWithUnit => cells!(0f64, DoubleFloat),
+        DataType::Boolean => cells!(false, Boolean),
+        DataType::Timestamp => cells!(LVTime::from_parts(0, 0), Timestamp),
+        other => return Err(TdmsError::UnsupportedType(other)),
+    })
+}
+
+/// Read `rows` samples of the channel from `start` converted to another unit.
+fn read_converted_cells<F: Read + Seek + Write + Debug>(
+    file: &mut TdmsFile<F>,
+    channel: &ChannelPath,
+    conversion: &UnitConversion,
+    start: u64,
+    rows: u64,
+) -> Result<Vec<String>, TdmsError> {
+    let mut values = vec![0.0; usize_from(rows)?];
+    read_range_as_f64(file, channel, start, &mut values)?;
+    Ok(values
+        .into_iter()
+        .map(|value| PropertyValue::DoubleFloat(conversion.apply(value)).to_string())
+        .collect())
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::DataLayout;
+
+    #[test]
+    fn test_rows_wait_for_every_channel() {
+        let group = GroupPath::new("log");
+        let time = ChannelPath::new("log", "time, UTC");
+        let valve = ChannelPath::new("log", "valve");
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut follower = CsvFollower::new(&group, Vec::new());
+        assert_eq!(follower.poll(&mut file).unwrap(), 0);
+
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(
+                &[&time],
+                &[LVTime::from_parts(0, 0), LVTime::from_parts(1, 0)],
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        writer
+            .write_channels(&[&valve], &[true], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+        assert_eq!(follower.poll(&mut file).unwrap(), 1);
+
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&valve], &[false], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+        assert_eq!(follower.poll(&mut file).unwrap(), 1);
+        assert_eq!(follower.rows_written(), 2);
+
+        let csv = String::from_utf8(follower.into_inner()).unwrap();
+        assert_eq!(
+            csv,
+            "\"time, UTC\",valve\n1904-01-01T00:00:00Z,true\n1904-01-01T00:00:01Z,false\n"
+        );
+    }
+
+    #[test]
+    fn test_columns_converted_to_target_unit() {
+        let group = GroupPath::new("rig");
+        let pressure = ChannelPath::new("rig", "pressure");
+        let count = ChannelPath::new("rig", "count");
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_properties(
+                pressure.as_ref(),
+                &[("unit_string", PropertyValue::from("bar"))],
+            )
+            .unwrap();
+        writer
+            .write_channels(&[&pressure], &[0.5f32, 2.0], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_channels(&[&count], &[1u8, 2], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+
+        let mut follower = CsvFollower::new(&group, Vec::new()).with_unit(&pressure, "kPa");
+        assert_eq!(follower.poll(&mut file).unwrap(), 2);
+
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&pressure], &[-1f32], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_channels(&[&count], &[3u8], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+        assert_eq!(follower.poll(&mut file).unwrap(), 1);
+
+        let csv = String::from_utf8(follower.into_inner()).unwrap();
+        assert_eq!(csv, "pressure,count\n50,1\n200,2\n-100,3\n");
+    }
+
+    #[test]
+    fn test_missing_unit_errors_until_available() {
+        let group = GroupPath::new("rig");
+        let pressure = ChannelPath::new("rig", "pressure");
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&pressure], &[1.0], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+
+        let mut follower = CsvFollower::new(&group, Vec::new()).with_unit(&pressure, "kPa");
+        assert!(matches!(
+            follower.poll(&mut file),
+            Err(TdmsError::UnitConversionUnavailable(..))
+        ));
+
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_properties(
+                pressure.as_ref(),
+                &[("unit_string", PropertyValue::from("bar"))],
+            )
+            .unwrap();
+        drop(writer);
+        assert_eq!(follower.poll(&mut file).unwrap(), 1);
+        let csv = String::from_utf8(follower.into_inner()).unwrap();
+        assert_eq!(csv, "pressure\n100\n");
+    }
+}
@@ -0,0 +1,90 @@
+//! Channels stored with dead-band compression.
+//!
+//! A dead-band channel only stores a sample when it differs from the last
+//! stored sample by more than the width of the band, as process historians
+//! do for slowly changing signals. [`crate::repack`] writes them with
+//! [`crate::RepackOptions::dead_band`] and [`TdmsFile::read_dead_band`]
+//! expands them again.
+//!
+//! The convention is:
+//!
+//! * The channel holds the stored values as f64.
+//! * A companion channel in the same group, named by the
+//!   `deadband_index_channel` property, holds the u64 sample index of each
+//!   stored value.
+//! * `deadband_width` holds the width of the band and `deadband_samples` the
+//!   number of samples in the original channel.
+//!
+//! Each original sample takes the last stored value at or before it.
+
+use std::fmt::Debug;
+use std::io::{Read, Seek, Write};
+
+use crate::error::{usize_from, TdmsError};
+use crate::paths::{path_group_name, ChannelPath, PropertyPath};
+use crate::{PropertyValue, TdmsFile};
+
+/// The property holding the width of the dead-band.
+#[cfg_attr(not(feature = "write"), allow(dead_code))]
+pub(crate) const WIDTH_PROPERTY: &str = "deadband_width";
+/// The property holding the number of samples in the original channel.
+pub(crate) const SAMPLES_PROPERTY: &str = "deadband_samples";
+/// The property holding the name of the channel with the sample indexes.
+pub(crate) const INDEX_CHANNEL_PROPERTY: &str = "deadband_index_channel";
+
+impl<F: Read + Seek + Write + Debug> TdmsFile<F> {
+    /// Read a channel stored with dead-band compression, expanded to its original samples.
+    ///
+    /// Channels without the dead-band properties are read as plain f64 channels.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use std::io::Cursor;
+    /// use tedium::{repack, ChannelPath, DataLayout, RepackOptions, TdmsFile};
+    ///
+    /// let channel = ChannelPath::new("plant", "pressure");
+    /// let mut source = Cursor::new(vec![]);
+    /// let mut file = TdmsFile::new(&mut source).unwrap();
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[1.0, 1.05, 1.5, 1.52, 1.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    /// drop(file);
+    ///
+    /// let mut packed = Cursor::new(vec![]);
+    /// let options = RepackOptions::new().dead_band(&channel, 0.1);
+    /// repack(&mut source, &mut packed, &options).unwrap();
+    ///
+    /// let mut file = TdmsFile::new(packed).unwrap();
+    /// assert_eq!(file.channel_length(&channel), Some(3));
+    /// let data = file.read_dead_band(&channel).unwrap();
+    /// assert_eq!(data, vec![1.0, 1.0, 1.5, 1.5, 1.0]);
+    /// ```
+    pub fn read_dead_band(&mut self, channel: &ChannelPath) -> Result<Vec<f64>, TdmsError> {
+        let property_path: &PropertyPath = channel.as_ref();
+        let samples = match self.read_property(property_path, SAMPLES_PROPERTY)? {
+            Some(PropertyValue::U64(samples)) => *samples,
+            _ => return self.read_all(channel),
+        };
+        let index_channel = match self.read_property(property_path, INDEX_CHANNEL_PROPERTY)? {
+            Some(PropertyValue::String(name)) => {
+                let group = path_group_name(channel.path()).unwrap_or_default();
+                ChannelPath::new(group, name)
+            }
+            _ => return self.read_all(channel),
+        };
+
+        let values: Vec<f64> = self.read_all(channel)?;
+        let indexes: Vec<u64> = self.read_all(&index_channel)?;
+        let mut output = Vec::with_capacity(usize_from(samples)?);
+        let mut stored = values.iter().zip(&indexes).peekable();
+        let mut current = f64::NAN;
+        for sample in 0..samples {
+            while let Some((value, _)) = stored.next_if(|(_, index)| **index <= sample) {
+                current = *value;
+            }
+            output.push(current);
+        }
+        Ok(output)
+    }
+}
@@ -0,0 +1,246 @@
+//! Properties following DIAdem's conventions.
+//!
+//! DIAdem writes `NI_ChannelLength` and `NI_DataType` on every channel and
+//! shows a set of registered properties, such as `description` and
+//! `minimum`, in its own columns rather than as custom properties. These
+//! helpers read and write them with the types DIAdem uses so files move
+//! between tedium and DIAdem without losing them.
+
+use std::io::{Read, Seek};
+
+use num_traits::FromPrimitive;
+
+use crate::error::TdmsError;
+use crate::io::data_types::DataType;
+use crate::paths::{ChannelPath, PropertyPath};
+use crate::{PropertyValue, TdmsFile};
+
+/// The channel property DIAdem uses for the number of values.
+pub const NI_CHANNEL_LENGTH: &str = "NI_ChannelLength";
+/// The channel property DIAdem uses for the TDMS type code of the values.
+pub const NI_DATA_TYPE: &str = "NI_DataType";
+
+/// The registered channel properties DIAdem shows in its channel view.
+///
+/// Unset fields are neither read from nor written to the file.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DiademChannelProperties {
+    /// `description`.
+    pub description: Option<String>,
+    /// `unit_string`.
+    pub unit: Option<String>,
+    /// `minimum`, the smallest value in the channel.
+    pub minimum: Option<f64>,
+    /// `maximum`, the largest value in the channel.
+    pub maximum: Option<f64>,
+    /// `displaytype`, such as `Numeric` or `Time`.
+    pub display_type: Option<String>,
+}
+
+impl DiademChannelProperties {
+    /// The properties as names and values ready to write.
+    pub fn to_properties(&self) -> Vec<(&'static str, PropertyValue)> {
+        let strings = [
+            ("description", &self.description),
+            ("unit_string", &self.unit),
+            ("displaytype", &self.display_type),
+        ];
+        let numbers = [("minimum", self.minimum), ("maximum", self.maximum)];
+
+        strings
+            .into_iter()
+            .filter_map(|(name, value)| Some((name, PropertyValue::String(value.clone()?))))
+            .chain(
+                numbers
+                    .into_iter()
+                    .filter_map(|(name, value)| Some((name, PropertyValue::DoubleFloat(value?)))),
+            )
+            .collect()
+    }
+}
+
+impl<F: Read + Seek> TdmsFile<F> {
+    /// The number of values DIAdem recorded for the channel in `NI_ChannelLength`.
+    ///
+    /// Returns None if the property isn't set. This can differ from
+    /// [`Self::channel_length`] if the file was appended to after DIAdem wrote it.
+    pub fn diadem_channel_length(&self, channel: &ChannelPath) -> Result<Option<u64>, TdmsError> {
+        let value = self.read_property(channel.as_ref(), NI_CHANNEL_LENGTH)?;
+        Ok(value
+            .and_then(PropertyValue::as_i64)
+            .and_then(|length| u64::try_from(length).ok()))
+    }
+
+    /// The data type DIAdem recorded for the channel in `NI_DataType`.
+    ///
+    /// Returns None if the property isn't set or isn't a known type code.
+    pub fn diadem_data_type(&self, channel: &ChannelPath) -> Result<Option<DataType>, TdmsError> {
+        let value = self.read_property(channel.as_ref(), NI_DATA_TYPE)?;
+        Ok(value
+            .and_then(PropertyValue::as_i64)
+            .and_then(DataType::from_i64))
+    }
+
+    /// Read the registered channel properties DIAdem shows.
+    ///
+    /// Numbers stored with any numeric type are read as f64.
+    pub fn diadem_channel_properties(
+        &self,
+        channel: &ChannelPath,
+    ) -> Result<DiademChannelProperties, TdmsError> {
+        let path: &PropertyPath = channel.as_ref();
+        let string = |name| -> Result<Option<String>, TdmsError> {
+            Ok(self
+                .read_property(path, name)?
+                .and_then(PropertyValue::as_str)
+                .map(str::to_owned))
+        };
+        let number = |name| -> Result<Option<f64>, TdmsError> {
+            Ok(self
+                .read_property(path, name)?
+                .and_then(PropertyValue::as_f64))
+        };
+
+        Ok(DiademChannelProperties {
+            description: string("description")?,
+            unit: string("unit_string")?,
+            minimum: number("minimum")?,
+            maximum: number("maximum")?,
+            display_type: string("displaytype")?,
+        })
+    }
+}
+
+#[cfg(feature = "write")]
+mod write {
+    use std::io::Write;
+
+    use super::*;
+    use crate::io::writer::TdmsWriter;
+    use crate::paths::ObjectPath;
+    use crate::TdmsFileWriter;
+
+    impl<'a, F: Write, W: TdmsWriter<&'a mut F>> TdmsFileWriter<'a, F, W> {
+        /// Write the registered channel properties DIAdem shows.
+        pub fn write_diadem_channel_properties(
+            &mut self,
+            channel: &ChannelPath,
+            properties: &DiademChannelProperties,
+        ) -> Result<(), TdmsError> {
+            self.write_properties(channel.as_ref(), &properties.to_properties())
+        }
+
+        /// Write `NI_ChannelLength` and `NI_DataType` for every channel with data.
+        ///
+        /// Like [`Self::finalize`] this should be called once all data is
+        /// written. The length is written as U64 and the type code as I32.
+        ///
+        /// # Example
+        ///
+        /// ```rust
+        /// use tedium::{ChannelPath, DataLayout, TdmsFile};
+        /// use tedium::types::DataType;
+        ///
+        /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+        /// let channel = ChannelPath::new("group", "channel");
+        /// let mut writer = file.writer().unwrap();
+        /// writer.write_channels(&[&channel], &[1.0, 2.0, 3.0], DataLayout::Contigious).unwrap();
+        /// writer.finalize_diadem().unwrap();
+        /// drop(writer);
+        ///
+        /// assert_eq!(file.diadem_channel_length(&channel).unwrap(), Some(3));
+        /// assert_eq!(file.diadem_data_type(&channel).unwrap(), Some(DataType::DoubleFloat));
+        /// ```
+        pub fn finalize_diadem(&mut self) -> Result<(), TdmsError> {
+            let index = self.index();
+            let objects: Vec<(String, Vec<(String, PropertyValue)>)> = index
+                .data_object_lengths()
+                .filter_map(|(path, length)| {
+                    let ObjectPath::Channel(channel) = ObjectPath::parse(path).ok()? else {
+                        return None;
+                    };
+                    let data_type = index.channel_data_type(&channel)?;
+                    let properties = vec![
+                        (NI_CHANNEL_LENGTH.to_owned(), PropertyValue::U64(length)),
+                        (
+                            NI_DATA_TYPE.to_owned(),
+                            PropertyValue::I32(data_type as u32 as i32),
+                        ),
+                    ];
+                    Some((path.to_owned(), properties))
+                })
+                .collect();
+
+            if objects.is_empty() {
+                return Ok(());
+            }
+            self.write_objects_properties(objects)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use super::*;
+    use crate::DataLayout;
+
+    #[test]
+    fn test_channel_properties_round_trip() {
+        let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+        let channel = ChannelPath::new("group", "channel");
+        let properties = DiademChannelProperties {
+            description: Some("Inlet pressure".into()),
+            unit: Some("bar".into()),
+            minimum: Some(0.5),
+            maximum: None,
+            display_type: Some("Numeric".into()),
+        };
+
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&channel], &[0.5f32, 2.0], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_diadem_channel_properties(&channel, &properties)
+            .unwrap();
+        writer.finalize_diadem().unwrap();
+        drop(writer);
+
+        assert_eq!(
+            file.diadem_channel_properties(&channel).unwrap(),
+            properties
+        );
+        assert_eq!(file.diadem_channel_length(&channel).unwrap(), Some(2));
+        assert_eq!(
+            file.diadem_data_type(&channel).unwrap(),
+            Some(DataType::SingleFloat)
+        );
+        assert_eq!(
+            file.read_property(channel.as_ref(), "maximum").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_lengths_written_by_other_tools_are_read() {
+        let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+        let channel = ChannelPath::new("group", "channel");
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_properties(
+                channel.as_ref(),
+                &[
+                    (NI_CHANNEL_LENGTH, PropertyValue::I32(10)),
+                    (NI_DATA_TYPE, PropertyValue::U16(3)),
+                ],
+            )
+            .unwrap();
+        drop(writer);
+
+        assert_eq!(file.diadem_channel_length(&channel).unwrap(), Some(10));
+        assert_eq!(
+            file.diadem_data_type(&channel).unwrap(),
+            Some(DataType::I32)
+        );
+    }
+}
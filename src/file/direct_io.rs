@@ -0,0 +1,367 @@
+//! A file opened for direct (unbuffered) IO.
+//!
+//! Direct IO bypasses the operating system page cache which gives more
+//! predictable latency when streaming at high rates. It requires every
+//! transfer to be aligned to the device block size, so [`DirectFile`] keeps
+//! an aligned window of the file in memory and only transfers whole blocks.
+//!
+//! The platform flags used are:
+//!
+//! * Linux: `O_DIRECT`.
+//! * Windows: `FILE_FLAG_NO_BUFFERING`.
+//! * macOS: `F_NOCACHE`.
+//!
+//! On other platforms the file is opened normally.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use super::os_path::os_path;
+use crate::error::TdmsError;
+use crate::TdmsFile;
+
+/// The alignment of all transfers. This covers 512 byte and 4K sector devices.
+const ALIGNMENT: usize = 4096;
+
+/// The default size of the in memory window.
+const DEFAULT_WINDOW_BYTES: usize = 1024 * 1024;
+
+#[cfg(windows)]
+const FILE_FLAG_NO_BUFFERING: u32 = 0x2000_0000;
+
+/// A file using direct IO, for use with [`TdmsFile`].
+///
+/// Writes are collected in an aligned window and written to disk as whole
+/// blocks. On flush the final partial block is padded to the alignment and
+/// the file is truncated back to its real length.
+///
+/// Data is only guaranteed to be on disk after [`Write::flush`] which is
+/// called by [`crate::TdmsFileWriter::sync`] and when the file is dropped.
+#[derive(Debug)]
+pub struct DirectFile {
+    file: File,
+    buffer: Vec<u8>,
+    /// The offset of the aligned window in `buffer`.
+    buffer_offset: usize,
+    window_bytes: usize,
+    /// The file position of the start of the window. Always aligned.
+    window_start: u64,
+    /// The number of valid bytes in the window.
+    window_length: usize,
+    dirty: bool,
+    position: u64,
+    length: u64,
+}
+
+impl DirectFile {
+    /// Open an existing file for reading and writing with direct IO.
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        let mut options = OpenOptions::new();
+        options.read(true).write(true);
+        Self::open_with(options, path, DEFAULT_WINDOW_BYTES)
+    }
+
+    /// Create a new file with direct IO. This will replace any existing file at the path.
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        let mut options = OpenOptions::new();
+        options.read(true).write(true).create(true).truncate(true);
+        Self::open_with(options, path, DEFAULT_WINDOW_BYTES)
+    }
+
+    /// Open the file with the options and an in memory window of `window_bytes`.
+    ///
+    /// The window size is rounded up to the alignment. Larger windows mean
+    /// fewer, larger transfers to disk.
+    pub fn open_with(
+        mut options: OpenOptions,
+        path: &Path,
+        window_bytes: usize,
+    ) -> std::io::Result<Self> {
+        set_direct_flags(&mut options);
+        let file = options.open(os_path(path))?;
+        disable_cache(&file)?;
+        let length = file.metadata()?.len();
+
+        let window_bytes = align_up(window_bytes.max(1) as u64) as usize;
+        let buffer = vec![0; window_bytes + ALIGNMENT];
+        let buffer_offset = buffer.as_ptr().align_offset(ALIGNMENT);
+
+        Ok(Self {
+            file,
+            buffer,
+            buffer_offset,
+            window_bytes,
+            window_start: 0,
+            window_length: 0,
+            dirty: false,
+            position: 0,
+            length,
+        })
+    }
+
+    fn window(&mut self) -> &mut [u8] {
+        &mut self.buffer[self.buffer_offset..self.buffer_offset + self.window_bytes]
+    }
+
+    fn window_end(&self) -> u64 {
+        self.window_start + self.window_length as u64
+    }
+
+    /// Write the window to disk as whole blocks.
+    fn flush_window(&mut self) -> std::io::Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let valid = self.window_length;
+        let write_length = align_up(valid as u64) as usize;
+        let window_start = self.window_start;
+        let window = self.window();
+        window[valid..write_length].fill(0);
+
+        self.file.seek(SeekFrom::Start(window_start))?;
+        let window = &self.buffer[self.buffer_offset..self.buffer_offset + write_length];
+        self.file.write_all(window)?;
+
+        // Remove the padding from the end of the file.
+        if window_start + write_length as u64 > self.length {
+            self.file.set_len(self.length)?;
+        }
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Move the window to contain the position, loading any existing data.
+    fn load_window(&mut self, position: u64) -> std::io::Result<()> {
+        self.flush_window()?;
+        self.window_start = align_down(position);
+        self.window_length = 0;
+
+        let available = self.length.saturating_sub(self.window_start);
+        let to_read = available.min(self.window_bytes as u64) as usize;
+        if to_read == 0 {
+            return Ok(());
+        }
+
+        let read_length = align_up(to_read as u64) as usize;
+        self.file.seek(SeekFrom::Start(self.window_start))?;
+        let offset = self.buffer_offset;
+        let mut filled = 0;
+        while filled < to_read {
+            let read = self
+                .file
+                .read(&mut self.buffer[offset + filled..offset + read_length])?;
+            if read == 0 {
+                break;
+            }
+            filled += read;
+        }
+        self.window_length = filled.min(to_read);
+        Ok(())
+    }
+}
+
+impl Read for DirectFile {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.position >= self.length || buf.is_empty() {
+            return Ok(0);
+        }
+        if self.position < self.window_start || self.position >= self.window_end() {
+            self.load_window(self.position)?;
+        }
+        let offset = (self.position - self.window_start) as usize;
+        let count = buf.len().min(self.window_length - offset);
+        let start = self.buffer_offset + offset;
+        buf[..count].copy_from_slice(&self.buffer[start..start + count]);
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+impl Write for DirectFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let window_full_end = self.window_start + self.window_bytes as u64;
+        if self.position < self.window_start
+            || self.position > self.window_end()
+            || self.position >= window_full_end
+        {
+            self.load_window(self.position)?;
+        }
+
+        let offset = (self.position - self.window_start) as usize;
+        if offset > self.window_length {
+            // Writing past the end of the file so fill the gap.
+            let gap_start = self.buffer_offset + self.window_length;
+            self.buffer[gap_start..self.buffer_offset + offset].fill(0);
+        }
+        let count = buf.len().min(self.window_bytes - offset);
+        let start = self.buffer_offset + offset;
+        self.buffer[start..start + count].copy_from_slice(&buf[..count]);
+        self.window_length = self.window_length.max(offset + count);
+        self.dirty = true;
+        self.position += count as u64;
+        self.length = self.length.max(self.position);
+
+        if self.window_length == self.window_bytes {
+            self.flush_window()?;
+        }
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.flush_window()?;
+        self.file.flush()
+    }
+}
+
+impl Seek for DirectFile {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let new_position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.length.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        self.position = new_position.ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.position)
+    }
+}
+
+impl Drop for DirectFile {
+    fn drop(&mut self) {
+        // Errors can't be reported here. Call flush to handle them.
+        let _ = self.flush_window();
+    }
+}
+
+impl TdmsFile<DirectFile> {
+    /// Load the file from the path using direct IO.
+    ///
+    /// See [`DirectFile`] for the platform support.
+    pub fn load_direct(path: &Path) -> Result<Self, TdmsError> {
+        Self::new(DirectFile::open(path)?)
+    }
+
+    /// Create a new file at the path using direct IO. This will replace any existing file at the path.
+    pub fn create_direct(path: &Path) -> Result<Self, TdmsError> {
+        Self::new(DirectFile::create(path)?)
+    }
+}
+
+fn align_up(value: u64) -> u64 {
+    value.div_ceil(ALIGNMENT as u64) * ALIGNMENT as u64
+}
+
+fn align_down(value: u64) -> u64 {
+    value - value % ALIGNMENT as u64
+}
+
+#[cfg(target_os = "linux")]
+fn set_direct_flags(options: &mut OpenOptions) {
+    use std::os::unix::fs::OpenOptionsExt;
+    options.custom_flags(libc::O_DIRECT);
+}
+
+#[cfg(windows)]
+fn set_direct_flags(options: &mut OpenOptions) {
+    use std::os::windows::fs::OpenOptionsExt;
+    options.custom_flags(FILE_FLAG_NO_BUFFERING);
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn set_direct_flags(_options: &mut OpenOptions) {}
+
+#[cfg(target_os = "macos")]
+fn disable_cache(file: &File) -> std::io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+    // SAFETY: The descriptor is valid for the lifetime of the file.
+    let result = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_NOCACHE, 1) };
+    if result == -1 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn disable_cache(_file: &File) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        // Use the target directory as tmpfs may not support direct IO.
+        let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("target");
+        path.push(format!("direct_io_{name}_{}", std::process::id()));
+        path
+    }
+
+    #[test]
+    fn test_unaligned_writes_round_trip() {
+        let path = temp_path("round_trip");
+        let data: Vec<u8> = (0..10_000u32).map(|value| (value % 251) as u8).collect();
+        {
+            let mut options = OpenOptions::new();
+            options.read(true).write(true).create(true).truncate(true);
+            let mut file = DirectFile::open_with(options, &path, 8192).unwrap();
+            for chunk in data.chunks(999) {
+                file.write_all(chunk).unwrap();
+            }
+            file.flush().unwrap();
+            assert_eq!(std::fs::metadata(&path).unwrap().len(), data.len() as u64);
+
+            // Overwrite in the middle and read back through the window.
+            file.seek(SeekFrom::Start(5000)).unwrap();
+            file.write_all(&[0xFF; 10]).unwrap();
+            file.seek(SeekFrom::Start(4995)).unwrap();
+            let mut read = [0; 20];
+            file.read_exact(&mut read).unwrap();
+            assert_eq!(&read[..5], &data[4995..5000]);
+            assert_eq!(&read[5..15], &[0xFF; 10]);
+            assert_eq!(&read[15..], &data[5010..5015]);
+        }
+
+        let mut expected = data;
+        expected[5000..5010].fill(0xFF);
+        assert_eq!(std::fs::read(&path).unwrap(), expected);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "write")]
+    #[test]
+    fn test_tdms_file_with_direct_io() {
+        use crate::{ChannelPath, DataLayout};
+
+        let path = temp_path("tdms");
+        let channel = ChannelPath::new("group", "channel");
+        let data: Vec<f64> = (0..1000).map(|value| value as f64).collect();
+        {
+            let mut file = TdmsFile::create_direct(&path).unwrap();
+            let mut writer = file.writer().unwrap();
+            for chunk in data.chunks(300) {
+                writer
+                    .write_channels(&[&channel], chunk, DataLayout::Contigious)
+                    .unwrap();
+            }
+        }
+
+        let mut file = TdmsFile::load_direct(&path).unwrap();
+        let read: Vec<f64> = file.read_all(&channel).unwrap();
+        assert_eq!(read, data);
+
+        let mut file = TdmsFile::load(&path).unwrap();
+        let read: Vec<f64> = file.read_all(&channel).unwrap();
+        assert_eq!(read, data);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
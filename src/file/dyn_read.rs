@@ -0,0 +1,422 @@
+//! Reads without generic parameters.
+//!
+//! The generic readers are compiled again for every combination of file type
+//! and sample type a program uses, which adds up in firmware sized binaries.
+//! [`TdmsFile::read_range_dyn`], [`TdmsFile::read_channels_dyn`] and
+//! [`TdmsFile::read_all_dyn`] take or return the samples as a [`SampleBuffer`]
+//! or [`SampleVec`] and read through a trait object, so the read code is only
+//! compiled once for each sample type however many file types are read.
+//!
+//! The `small` feature routes the generic single channel reads through the
+//! same code for the sample types these cover.
+
+use std::io::{Read, Seek};
+
+use labview_interop::types::LVTime;
+
+use super::channel_reader::read_range_from;
+use super::RetryPolicy;
+use crate::error::{usize_from, TdmsError};
+use crate::index::Index;
+use crate::io::data_types::DataType;
+use crate::paths::ChannelPath;
+use crate::{ReadOutcome, TdmsFile};
+
+/// An output buffer of one of the sample types.
+#[derive(Debug)]
+pub enum SampleBuffer<'a> {
+    I8(&'a mut [i8]),
+    I16(&'a mut [i16]),
+    I32(&'a mut [i32]),
+    I64(&'a mut [i64]),
+    U8(&'a mut [u8]),
+    U16(&'a mut [u16]),
+    U32(&'a mut [u32]),
+    U64(&'a mut [u64]),
+    F32(&'a mut [f32]),
+    F64(&'a mut [f64]),
+    Boolean(&'a mut [bool]),
+    Timestamp(&'a mut [LVTime]),
+}
+
+impl SampleBuffer<'_> {
+    /// The number of samples the buffer holds.
+    pub fn len(&self) -> usize {
+        match self {
+            SampleBuffer::I8(output) => output.len(),
+            SampleBuffer::I16(output) => output.len(),
+            SampleBuffer::I32(output) => output.len(),
+            SampleBuffer::I64(output) => output.len(),
+            SampleBuffer::U8(output) => output.len(),
+            SampleBuffer::U16(output) => output.len(),
+            SampleBuffer::U32(output) => output.len(),
+            SampleBuffer::U64(output) => output.len(),
+            SampleBuffer::F32(output) => output.len(),
+            SampleBuffer::F64(output) => output.len(),
+            SampleBuffer::Boolean(output) => output.len(),
+            SampleBuffer::Timestamp(output) => output.len(),
+        }
+    }
+
+    /// True if the buffer holds no samples.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Borrow the same samples for a shorter lifetime.
+    fn reborrow(&mut self) -> SampleBuffer<'_> {
+        match self {
+            SampleBuffer::I8(output) => SampleBuffer::I8(output),
+            SampleBuffer::I16(output) => SampleBuffer::I16(output),
+            SampleBuffer::I32(output) => SampleBuffer::I32(output),
+            SampleBuffer::I64(output) => SampleBuffer::I64(output),
+            SampleBuffer::U8(output) => SampleBuffer::U8(output),
+            SampleBuffer::U16(output) => SampleBuffer::U16(output),
+            SampleBuffer::U32(output) => SampleBuffer::U32(output),
+            SampleBuffer::U64(output) => SampleBuffer::U64(output),
+            SampleBuffer::F32(output) => SampleBuffer::F32(output),
+            SampleBuffer::F64(output) => SampleBuffer::F64(output),
+            SampleBuffer::Boolean(output) => SampleBuffer::Boolean(output),
+            SampleBuffer::Timestamp(output) => SampleBuffer::Timestamp(output),
+        }
+    }
+}
+
+/// The samples of a channel, in the type it is stored as.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SampleVec {
+    I8(Vec<i8>),
+    I16(Vec<i16>),
+    I32(Vec<i32>),
+    I64(Vec<i64>),
+    U8(Vec<u8>),
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+    U64(Vec<u64>),
+    F32(Vec<f32>),
+    F64(Vec<f64>),
+    Boolean(Vec<bool>),
+    Timestamp(Vec<LVTime>),
+}
+
+impl SampleVec {
+    /// A vector of `length` default samples for the stored type.
+    fn for_type(data_type: DataType, length: usize) -> Result<Self, TdmsError> {
+        let samples = match data_type {
+            DataType::I8 => SampleVec::I8(vec![0; length]),
+            DataType::I16 => SampleVec::I16(vec![0; length]),
+            DataType::I32 => SampleVec::I32(vec![0; length]),
+            DataType::I64 => SampleVec::I64(vec![0; length]),
+            DataType::U8 => SampleVec::U8(vec![0; length]),
+            DataType::U16 => SampleVec::U16(vec![0; length]),
+            DataType::U32 => SampleVec::U32(vec![0; length]),
+            DataType::U64 => SampleVec::U64(vec![0; length]),
+            DataType::SingleFloat | DataType::SingleFloatWithUnit => {
+                SampleVec::F32(vec![0.0; length])
+            }
+            DataType::DoubleFloat | DataType::DoubleFloatWithUnit => {
+                SampleVec::F64(vec![0.0; length])
+            }
+            DataType::Boolean => SampleVec::Boolean(vec![false; length]),
+            DataType::Timestamp => SampleVec::Timestamp(vec![LVTime::from_parts(0, 0); length]),
+            other => return Err(TdmsError::UnsupportedType(other)),
+        };
+        Ok(samples)
+    }
+
+    /// The number of samples.
+    pub fn len(&self) -> usize {
+        match self {
+            SampleVec::I8(samples) => samples.len(),
+            SampleVec::I16(samples) => samples.len(),
+            SampleVec::I32(samples) => samples.len(),
+            SampleVec::I64(samples) => samples.len(),
+            SampleVec::U8(samples) => samples.len(),
+            SampleVec::U16(samples) => samples.len(),
+            SampleVec::U32(samples) => samples.len(),
+            SampleVec::U64(samples) => samples.len(),
+            SampleVec::F32(samples) => samples.len(),
+            SampleVec::F64(samples) => samples.len(),
+            SampleVec::Boolean(samples) => samples.len(),
+            SampleVec::Timestamp(samples) => samples.len(),
+        }
+    }
+
+    /// True if there are no samples.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Borrow the samples as an output buffer.
+    pub fn as_buffer(&mut self) -> SampleBuffer<'_> {
+        match self {
+            SampleVec::I8(samples) => SampleBuffer::I8(samples),
+            SampleVec::I16(samples) => SampleBuffer::I16(samples),
+            SampleVec::I32(samples) => SampleBuffer::I32(samples),
+            SampleVec::I64(samples) => SampleBuffer::I64(samples),
+            SampleVec::U8(samples) => SampleBuffer::U8(samples),
+            SampleVec::U16(samples) => SampleBuffer::U16(samples),
+            SampleVec::U32(samples) => SampleBuffer::U32(samples),
+            SampleVec::U64(samples) => SampleBuffer::U64(samples),
+            SampleVec::F32(samples) => SampleBuffer::F32(samples),
+            SampleVec::F64(samples) => SampleBuffer::F64(samples),
+            SampleVec::Boolean(samples) => SampleBuffer::Boolean(samples),
+            SampleVec::Timestamp(samples) => SampleBuffer::Timestamp(samples),
+        }
+    }
+}
+
+pub(super) trait ReadSeek: Read + Seek {}
+
+impl<T: Read + Seek> ReadSeek for T {}
+
+/// Read into the buffer. This is only compiled once for all file types.
+pub(super) fn read_range_into(
+    index: &Index,
+    retry: &RetryPolicy,
+    mut file: &mut dyn ReadSeek,
+    channel: &ChannelPath,
+    start: u64,
+    output: SampleBuffer<'_>,
+) -> Result<usize, TdmsError> {
+    let file = &mut file;
+    match output {
+        SampleBuffer::I8(output) => read_range_from(index, retry, file, channel, start, output),
+        SampleBuffer::I16(output) => read_range_from(index, retry, file, channel, start, output),
+        SampleBuffer::I32(output) => read_range_from(index, retry, file, channel, start, output),
+        SampleBuffer::I64(output) => read_range_from(index, retry, file, channel, start, output),
+        SampleBuffer::U8(output) => read_range_from(index, retry, file, channel, start, output),
+        SampleBuffer::U16(output) => read_range_from(index, retry, file, channel, start, output),
+        SampleBuffer::U32(output) => read_range_from(index, retry, file, channel, start, output),
+        SampleBuffer::U64(output) => read_range_from(index, retry, file, channel, start, output),
+        SampleBuffer::F32(output) => read_range_from(index, retry, file, channel, start, output),
+        SampleBuffer::F64(output) => read_range_from(index, retry, file, channel, start, output),
+        SampleBuffer::Boolean(output) => {
+            read_range_from(index, retry, file, channel, start, output)
+        }
+        SampleBuffer::Timestamp(output) => {
+            read_range_from(index, retry, file, channel, start, output)
+        }
+    }
+}
+
+impl<F: Read + Seek> TdmsFile<F> {
+    /// Read a single channel starting at the sample `start` without generics.
+    ///
+    /// This behaves like [`Self::read_range`] but keeps the size of the
+    /// compiled program down, for example on embedded targets.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, SampleBuffer, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[1.0, 2.0, 3.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let mut output = [0.0; 2];
+    /// let outcome = file
+    ///     .read_range_dyn(&channel, 1, SampleBuffer::F64(&mut output))
+    ///     .unwrap();
+    /// assert_eq!(outcome.samples_read, 2);
+    /// assert_eq!(output, [2.0, 3.0]);
+    /// ```
+    pub fn read_range_dyn(
+        &mut self,
+        channel: &ChannelPath,
+        start: u64,
+        output: SampleBuffer<'_>,
+    ) -> Result<ReadOutcome, TdmsError> {
+        let requested = output.len();
+        let samples_read = read_range_into(
+            &self.index,
+            &self.retry,
+            &mut self.file,
+            channel,
+            start,
+            output,
+        )?;
+        Ok(ReadOutcome {
+            samples_read,
+            truncated: samples_read < requested,
+        })
+    }
+
+    /// Read multiple channels without generics.
+    ///
+    /// Each channel is read from its start for the length of its buffer, so
+    /// the channels can have different types. Unlike [`Self::read_channels`]
+    /// the channels are read one after another rather than block by block.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, SampleBuffer, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let values = ChannelPath::new("group", "values");
+    /// let flags = ChannelPath::new("group", "flags");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&values], &[1.0, 2.0], DataLayout::Contigious).unwrap();
+    /// writer.write_channels(&[&flags], &[true, false], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let mut value_output = [0.0; 2];
+    /// let mut flag_output = [false; 2];
+    /// file.read_channels_dyn(
+    ///     &[&values, &flags],
+    ///     &mut [
+    ///         SampleBuffer::F64(&mut value_output),
+    ///         SampleBuffer::Boolean(&mut flag_output),
+    ///     ],
+    /// )
+    /// .unwrap();
+    /// assert_eq!(value_output, [1.0, 2.0]);
+    /// assert_eq!(flag_output, [true, false]);
+    /// ```
+    pub fn read_channels_dyn(
+        &mut self,
+        channels: &[&ChannelPath],
+        output: &mut [SampleBuffer<'_>],
+    ) -> Result<(), TdmsError> {
+        for (channel, output) in channels.iter().zip(output.iter_mut()) {
+            read_range_into(
+                &self.index,
+                &self.retry,
+                &mut self.file,
+                channel,
+                0,
+                output.reborrow(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Read the whole of a single channel, in the type it is stored as, without generics.
+    ///
+    /// Errors with [`TdmsError::UnsupportedType`] if [`SampleVec`] has no
+    /// variant for the stored type.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, SampleVec, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[1u16, 2, 3], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let data = file.read_all_dyn(&channel).unwrap();
+    /// assert_eq!(data, SampleVec::U16(vec![1, 2, 3]));
+    /// ```
+    pub fn read_all_dyn(&mut self, channel: &ChannelPath) -> Result<SampleVec, TdmsError> {
+        let (Some(data_type), Some(length)) = (
+            self.index.channel_data_type(channel),
+            self.index.channel_length(channel),
+        ) else {
+            return Err(self.index.missing_channel(channel));
+        };
+        let mut output = SampleVec::for_type(data_type, usize_from(length)?)?;
+        read_range_into(
+            &self.index,
+            &self.retry,
+            &mut self.file,
+            channel,
+            0,
+            output.as_buffer(),
+        )?;
+        Ok(output)
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::DataLayout;
+
+    #[test]
+    fn test_dyn_reads_match_generic_reads() {
+        let counts = ChannelPath::new("group", "counts");
+        let flags = ChannelPath::new("group", "flags");
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&counts], &[1u16, 2, 3], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_channels(&[&flags], &[true, false], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+
+        let mut output = [0u16; 4];
+        let outcome = file
+            .read_range_dyn(&counts, 0, SampleBuffer::U16(&mut output))
+            .unwrap();
+        assert_eq!(outcome.samples_read, 3);
+        assert!(outcome.truncated);
+        assert_eq!(output, [1, 2, 3, 0]);
+
+        let mut output = [true; 2];
+        file.read_range_dyn(&flags, 0, SampleBuffer::Boolean(&mut output))
+            .unwrap();
+        assert_eq!(output, [true, false]);
+
+        let mut output = [0.0f64; 3];
+        assert!(file
+            .read_range_dyn(&counts, 0, SampleBuffer::F64(&mut output))
+            .is_err());
+    }
+
+    #[test]
+    fn test_dyn_reads_of_whole_channels() {
+        let values = ChannelPath::new("group", "values");
+        let times = ChannelPath::new("group", "times");
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&values], &[1.5f32, 2.5], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_channels(&[&values], &[3.5f32], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_channels(
+                &[&times],
+                &[LVTime::from_parts(1, 0), LVTime::from_parts(2, 0)],
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        drop(writer);
+
+        assert_eq!(
+            file.read_all_dyn(&values).unwrap(),
+            SampleVec::F32(vec![1.5, 2.5, 3.5])
+        );
+        assert!(file
+            .read_all_dyn(&ChannelPath::new("group", "missing"))
+            .is_err());
+
+        let mut value_output = [0.0f32; 2];
+        let mut time_output = [LVTime::from_parts(0, 0); 2];
+        file.read_channels_dyn(
+            &[&values, &times],
+            &mut [
+                SampleBuffer::F32(&mut value_output),
+                SampleBuffer::Timestamp(&mut time_output),
+            ],
+        )
+        .unwrap();
+        assert_eq!(value_output, [1.5, 2.5]);
+        assert_eq!(
+            time_output,
+            [LVTime::from_parts(1, 0), LVTime::from_parts(2, 0)]
+        );
+    }
+}
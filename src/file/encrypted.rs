@@ -0,0 +1,503 @@
+//! Encrypting TDMS files at rest.
+//!
+//! [`EncryptedFile`] wraps the bytes of a normal TDMS file in a non-standard
+//! container encrypted with AES-256-GCM, so sensitive data can be stored
+//! encrypted while still being read and written through the tedium API.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+
+use super::os_path::os_path;
+use crate::error::TdmsError;
+use crate::TdmsFile;
+
+const MAGIC: &[u8; 8] = b"TDMSAES2";
+const HEADER_BYTES: u64 = 12;
+const NONCE_BYTES: usize = 12;
+const TAG_BYTES: usize = 16;
+/// The number of plaintext bytes in each encrypted chunk.
+const CHUNK_BYTES: usize = 64 * 1024;
+/// The size of a full chunk on disk.
+const SLOT_BYTES: u64 = (NONCE_BYTES + CHUNK_BYTES + TAG_BYTES) as u64;
+/// The size of a chunk on disk beyond its plaintext.
+const OVERHEAD_BYTES: u64 = (NONCE_BYTES + TAG_BYTES) as u64;
+
+/// The index of the last chunk of a plaintext of `length` bytes.
+///
+/// There is always at least one chunk, even for an empty plaintext.
+fn last_chunk(length: u64) -> u64 {
+    length.saturating_sub(1) / CHUNK_BYTES as u64
+}
+
+/// The authenticated data for a chunk: its index and whether it is the last.
+fn chunk_aad(index: u64, last: bool) -> [u8; 9] {
+    let mut aad = [0u8; 9];
+    aad[..8].copy_from_slice(&index.to_le_bytes());
+    aad[8] = u8::from(last);
+    aad
+}
+
+/// The plaintext of the chunk currently in memory.
+struct Chunk {
+    index: u64,
+    data: Vec<u8>,
+    dirty: bool,
+}
+
+/// A file encrypted with AES-256-GCM, for use with [`TdmsFile`].
+///
+/// **This is not a standard TDMS format.** The container can only be read by
+/// tedium with the same key. Other TDMS readers will not recognise it.
+///
+/// The file is split into chunks of 64 KiB. Each chunk is encrypted with a
+/// fresh random nonce every time it is written. The chunk number and whether
+/// it is the last chunk are authenticated, so chunks can't be reordered and
+/// the file can't be truncated at a chunk boundary. Chunks are decrypted as
+/// they are read, so reading one channel only decrypts the chunks holding its
+/// data. The last chunk is checked when the container is opened.
+///
+/// The layout on disk is:
+///
+/// * The magic bytes `TDMSAES2` and the chunk size as a little endian u32.
+/// * For each chunk, a 12 byte nonce then the ciphertext and 16 byte tag.
+///   Every chunk is full apart from the last, and an empty file has one
+///   empty chunk.
+///
+/// Changes are kept in memory for the current chunk and are only guaranteed
+/// to be written after [`Write::flush`], which is called by
+/// [`crate::TdmsFileWriter::sync`] and when the file is dropped.
+pub struct EncryptedFile<F: Read + Write + Seek> {
+    inner: F,
+    cipher: Aes256Gcm,
+    chunk: Option<Chunk>,
+    position: u64,
+    /// The length of the plaintext.
+    length: u64,
+    /// The length of the plaintext written to `inner`.
+    stored_length: u64,
+    /// The chunk in `inner` which is authenticated as the last chunk.
+    ///
+    /// This is None between sealing the old last chunk as a full chunk and
+    /// writing the chunk after it.
+    final_chunk: Option<u64>,
+}
+
+impl<F: Read + Write + Seek> std::fmt::Debug for EncryptedFile<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Leave out the cipher and plaintext.
+        f.debug_struct("EncryptedFile")
+            .field("position", &self.position)
+            .field("length", &self.length)
+            .finish_non_exhaustive()
+    }
+}
+
+fn invalid_data(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+impl<F: Read + Write + Seek> EncryptedFile<F> {
+    /// Use `inner` as an encrypted container with the 32 byte key.
+    ///
+    /// An empty `inner` is initialised as a new container. Otherwise the
+    /// header and the last chunk are checked, so a wrong key or a truncated
+    /// file is detected here. Other changes are detected when data is read.
+    pub fn new(mut inner: F, key: &[u8; 32]) -> std::io::Result<Self> {
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+        let inner_length = inner.seek(SeekFrom::End(0))?;
+        if inner_length == 0 {
+            inner.write_all(MAGIC)?;
+            inner.write_all(&(CHUNK_BYTES as u32).to_le_bytes())?;
+            let mut file = Self {
+                inner,
+                cipher,
+                chunk: Some(Chunk {
+                    index: 0,
+                    data: Vec::with_capacity(CHUNK_BYTES),
+                    dirty: true,
+                }),
+                position: 0,
+                length: 0,
+                stored_length: 0,
+                final_chunk: None,
+            };
+            file.write_chunk()?;
+            return Ok(file);
+        }
+
+        let mut header = [0u8; HEADER_BYTES as usize];
+        inner.seek(SeekFrom::Start(0))?;
+        inner.read_exact(&mut header)?;
+        if &header[..8] != MAGIC {
+            return Err(invalid_data(
+                "not an encrypted tedium container".to_string(),
+            ));
+        }
+        let chunk_bytes = u32::from_le_bytes(header[8..].try_into().expect("4 bytes"));
+        if chunk_bytes as usize != CHUNK_BYTES {
+            return Err(invalid_data(format!(
+                "unsupported encrypted chunk size {chunk_bytes}"
+            )));
+        }
+
+        let stored = inner_length.saturating_sub(HEADER_BYTES);
+        let slots = stored.div_ceil(SLOT_BYTES);
+        let last_slot = stored - slots.saturating_sub(1) * SLOT_BYTES;
+        // Only an empty file has an empty last chunk.
+        if last_slot < OVERHEAD_BYTES || (slots > 1 && last_slot == OVERHEAD_BYTES) {
+            return Err(invalid_data(format!(
+                "encrypted container is truncated with {last_slot} bytes in the last chunk"
+            )));
+        }
+        let length = (slots - 1) * CHUNK_BYTES as u64 + last_slot - OVERHEAD_BYTES;
+
+        let mut file = Self {
+            inner,
+            cipher,
+            chunk: None,
+            position: 0,
+            length,
+            stored_length: length,
+            final_chunk: Some(slots - 1),
+        };
+        file.load_chunk(length.saturating_sub(1))?;
+        Ok(file)
+    }
+
+    /// The inner container. Call [`Write::flush`] first to include any changes.
+    pub fn get_ref(&self) -> &F {
+        &self.inner
+    }
+
+    /// Load the chunk holding `position`, writing the current chunk if it changed.
+    fn load_chunk(&mut self, position: u64) -> std::io::Result<&mut Chunk> {
+        let index = position / CHUNK_BYTES as u64;
+        if self.chunk.as_ref().is_none_or(|chunk| chunk.index != index) {
+            self.write_chunk()?;
+            let data = if index > last_chunk(self.stored_length) {
+                Vec::with_capacity(CHUNK_BYTES)
+            } else {
+                self.read_stored_chunk(index, self.final_chunk == Some(index))?
+            };
+            self.chunk = Some(Chunk {
+                index,
+                data,
+                dirty: false,
+            });
+        }
+        Ok(self.chunk.as_mut().expect("chunk was just loaded"))
+    }
+
+    /// Read and decrypt a chunk from `inner`.
+    fn read_stored_chunk(&mut self, index: u64, last: bool) -> std::io::Result<Vec<u8>> {
+        let plain_length =
+            (self.stored_length - index * CHUNK_BYTES as u64).min(CHUNK_BYTES as u64) as usize;
+        let mut stored = vec![0u8; NONCE_BYTES + plain_length + TAG_BYTES];
+        self.inner
+            .seek(SeekFrom::Start(HEADER_BYTES + index * SLOT_BYTES))?;
+        self.inner.read_exact(&mut stored)?;
+        let (nonce, ciphertext) = stored.split_at(NONCE_BYTES);
+        self.cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &chunk_aad(index, last),
+                },
+            )
+            .map_err(|_| {
+                invalid_data(format!(
+                    "failed to decrypt chunk {index}. The key is wrong or the file was modified or truncated"
+                ))
+            })
+    }
+
+    /// Encrypt a chunk and write it to `inner`.
+    fn write_stored_chunk(&mut self, index: u64, data: &[u8], last: bool) -> std::io::Result<()> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: data,
+                    aad: &chunk_aad(index, last),
+                },
+            )
+            .map_err(|_| std::io::Error::other("failed to encrypt chunk"))?;
+        self.inner
+            .seek(SeekFrom::Start(HEADER_BYTES + index * SLOT_BYTES))?;
+        self.inner.write_all(&nonce)?;
+        self.inner.write_all(&ciphertext)?;
+        self.stored_length = self
+            .stored_length
+            .max(index * CHUNK_BYTES as u64 + data.len() as u64);
+        Ok(())
+    }
+
+    /// Encrypt and write the current chunk if it has changed.
+    ///
+    /// If the chunk is now the last, the chunk previously authenticated as
+    /// the last is written again as a full chunk.
+    fn write_chunk(&mut self) -> std::io::Result<()> {
+        let Some(mut chunk) = self.chunk.take_if(|chunk| chunk.dirty) else {
+            return Ok(());
+        };
+        let last = chunk.index == last_chunk(self.length);
+        let result = self.write_stored_chunk(chunk.index, &chunk.data, last);
+        chunk.dirty = result.is_err();
+        let index = chunk.index;
+        self.chunk = Some(chunk);
+        result?;
+
+        match self.final_chunk {
+            Some(previous) if last && previous < index => {
+                let data = self.read_stored_chunk(previous, true)?;
+                self.write_stored_chunk(previous, &data, false)?;
+            }
+            _ => {}
+        }
+        if last {
+            self.final_chunk = Some(index);
+        } else if self.final_chunk == Some(index) {
+            self.final_chunk = None;
+        }
+        Ok(())
+    }
+}
+
+impl<F: Read + Write + Seek> Read for EncryptedFile<F> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.position >= self.length || buf.is_empty() {
+            return Ok(0);
+        }
+        let position = self.position;
+        let chunk = self.load_chunk(position)?;
+        let offset = (position % CHUNK_BYTES as u64) as usize;
+        let count = buf.len().min(chunk.data.len() - offset);
+        buf[..count].copy_from_slice(&chunk.data[offset..offset + count]);
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+impl<F: Read + Write + Seek> Write for EncryptedFile<F> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.position > self.length {
+            // Fill the gap so every chunk before the position is complete.
+            let target = self.position;
+            self.position = self.length;
+            while self.position < target {
+                let gap = (target - self.position).min(CHUNK_BYTES as u64) as usize;
+                self.write_all(&vec![0u8; gap])?;
+            }
+        }
+        let position = self.position;
+        let offset = (position % CHUNK_BYTES as u64) as usize;
+        let count = buf.len().min(CHUNK_BYTES - offset);
+        // Extend the length first so a chunk written while loading this one
+        // isn't authenticated as the last.
+        let previous_length = self.length;
+        self.length = self.length.max(position + count as u64);
+        let chunk = match self.load_chunk(position) {
+            Ok(chunk) => chunk,
+            Err(error) => {
+                self.length = previous_length;
+                return Err(error);
+            }
+        };
+        let end = offset + count;
+        if chunk.data.len() < end {
+            chunk.data.resize(end, 0);
+        }
+        chunk.data[offset..end].copy_from_slice(&buf[..count]);
+        chunk.dirty = true;
+        self.position += count as u64;
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.write_chunk()?;
+        self.inner.flush()
+    }
+}
+
+impl<F: Read + Write + Seek> Seek for EncryptedFile<F> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let new_position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.length.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        self.position = new_position.ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.position)
+    }
+}
+
+impl<F: Read + Write + Seek> Drop for EncryptedFile<F> {
+    fn drop(&mut self) {
+        // Errors can't be reported here. Call flush to handle them.
+        let _ = self.write_chunk();
+    }
+}
+
+impl TdmsFile<EncryptedFile<File>> {
+    /// Load an encrypted file from the path with the 32 byte key.
+    ///
+    /// See [`EncryptedFile`] for the format, which other TDMS readers can't open.
+    pub fn load_encrypted(path: &Path, key: &[u8; 32]) -> Result<Self, TdmsError> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(os_path(path))?;
+        Self::new(EncryptedFile::new(file, key)?)
+    }
+
+    /// Create a new encrypted file at the path with the 32 byte key. This will
+    /// replace any existing file at the path.
+    ///
+    /// See [`EncryptedFile`] for the format, which other TDMS readers can't open.
+    pub fn create_encrypted(path: &Path, key: &[u8; 32]) -> Result<Self, TdmsError> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(os_path(path))?;
+        Self::new(EncryptedFile::new(file, key)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    const KEY: [u8; 32] = [7; 32];
+
+    #[test]
+    fn test_round_trip_across_chunks() {
+        let mut file = EncryptedFile::new(Cursor::new(vec![]), &KEY).unwrap();
+        let data: Vec<u8> = (0..CHUNK_BYTES * 2 + 100).map(|i| i as u8).collect();
+        file.write_all(&data).unwrap();
+        // Overwrite across a chunk boundary, as the writer does when patching.
+        file.seek(SeekFrom::Start(CHUNK_BYTES as u64 - 2)).unwrap();
+        file.write_all(&[0xFF; 4]).unwrap();
+        file.flush().unwrap();
+
+        let stored = file.get_ref().get_ref();
+        assert_eq!(&stored[..8], MAGIC);
+        assert!(!stored.windows(64).any(|window| window == &data[1000..1064]));
+
+        let mut file = EncryptedFile::new(Cursor::new(stored.clone()), &KEY).unwrap();
+        let mut read = vec![];
+        file.read_to_end(&mut read).unwrap();
+        let mut expected = data;
+        expected[CHUNK_BYTES - 2..CHUNK_BYTES + 2].fill(0xFF);
+        assert_eq!(read, expected);
+    }
+
+    #[test]
+    fn test_wrong_key_and_tampering_are_detected() {
+        let mut file = EncryptedFile::new(Cursor::new(vec![]), &KEY).unwrap();
+        file.write_all(&[1u8; CHUNK_BYTES + 4]).unwrap();
+        file.flush().unwrap();
+        let mut stored = file.get_ref().get_ref().clone();
+
+        let error = EncryptedFile::new(Cursor::new(stored.clone()), &[8; 32]).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+        // The first chunk is only checked when it is read.
+        stored[HEADER_BYTES as usize + NONCE_BYTES] ^= 1;
+        let mut tampered = EncryptedFile::new(Cursor::new(stored), &KEY).unwrap();
+        assert!(tampered.read(&mut [0; 4]).is_err());
+
+        let error = EncryptedFile::new(Cursor::new(b"TDSm0000000000".to_vec()), &KEY).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_truncation_at_chunk_boundary_is_detected() {
+        let mut file = EncryptedFile::new(Cursor::new(vec![]), &KEY).unwrap();
+        file.write_all(&vec![1u8; CHUNK_BYTES * 2 + 10]).unwrap();
+        file.flush().unwrap();
+        let stored = file.get_ref().get_ref().clone();
+
+        for slots in [2, 1, 0] {
+            let truncated = stored[..(HEADER_BYTES + slots * SLOT_BYTES) as usize].to_vec();
+            let error = EncryptedFile::new(Cursor::new(truncated), &KEY).unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn test_append_after_flush_at_chunk_boundary() {
+        let mut file = EncryptedFile::new(Cursor::new(vec![]), &KEY).unwrap();
+        file.write_all(&vec![1u8; CHUNK_BYTES]).unwrap();
+        file.flush().unwrap();
+        // The full chunk was the last, so it must be sealed again as a full chunk.
+        file.write_all(&[2u8; 10]).unwrap();
+        file.flush().unwrap();
+        let stored = file.get_ref().get_ref().clone();
+
+        let mut file = EncryptedFile::new(Cursor::new(stored.clone()), &KEY).unwrap();
+        let mut read = vec![];
+        file.read_to_end(&mut read).unwrap();
+        assert_eq!(read.len(), CHUNK_BYTES + 10);
+        assert_eq!(&read[CHUNK_BYTES - 1..CHUNK_BYTES + 1], &[1, 2]);
+
+        let truncated = stored[..(HEADER_BYTES + SLOT_BYTES) as usize].to_vec();
+        assert!(EncryptedFile::new(Cursor::new(truncated), &KEY).is_err());
+    }
+
+    #[test]
+    fn test_empty_container_round_trip() {
+        let mut file = EncryptedFile::new(Cursor::new(vec![]), &KEY).unwrap();
+        file.flush().unwrap();
+        let stored = file.get_ref().get_ref().clone();
+        assert_eq!(stored.len() as u64, HEADER_BYTES + OVERHEAD_BYTES);
+
+        let mut file = EncryptedFile::new(Cursor::new(stored), &KEY).unwrap();
+        let mut read = vec![];
+        file.read_to_end(&mut read).unwrap();
+        assert!(read.is_empty());
+    }
+
+    #[cfg(feature = "write")]
+    #[test]
+    fn test_tdms_file_in_encrypted_container() {
+        use crate::{ChannelPath, DataLayout};
+
+        let channel = ChannelPath::new("group", "channel");
+        let data: Vec<f64> = (0..20_000).map(|i| i as f64).collect();
+
+        let encrypted = EncryptedFile::new(Cursor::new(vec![]), &KEY).unwrap();
+        let mut file = TdmsFile::new(encrypted).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&channel], &data[..10_000], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_channels(&[&channel], &data[10_000..], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+        file.file.flush().unwrap();
+        let stored = Cursor::new(file.file.get_ref().get_ref().clone());
+
+        let mut file = TdmsFile::new(EncryptedFile::new(stored, &KEY).unwrap()).unwrap();
+        assert_eq!(file.read_all::<f64>(&channel).unwrap(), data);
+    }
+}
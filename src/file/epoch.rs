@@ -0,0 +1,159 @@
+//! Consistent views of the index while a file grows.
+//!
+//! [`TdmsFile::refresh`] and the writers change the index as segments are
+//! added. An [`IndexEpoch`] pins the index as it was when it was taken, so a
+//! reader on another thread, with its own handle to the file, always sees one
+//! consistent generation. The index is shared until it next changes, when the
+//! file copies it rather than changing the pinned generation.
+
+use std::io::{Read, Seek};
+use std::sync::Arc;
+
+use super::channel_reader::read_range_from;
+use super::RetryPolicy;
+use crate::error::TdmsError;
+use crate::index::Index;
+use crate::io::data_types::TdmsStorageType;
+use crate::paths::ChannelPath;
+use crate::{ReadOutcome, TdmsFile};
+
+/// An immutable generation of the index of a file.
+///
+/// This is cheap to clone and can be sent to other threads.
+#[derive(Debug, Clone)]
+pub struct IndexEpoch {
+    index: Arc<Index>,
+    generation: u64,
+    retry: RetryPolicy,
+}
+
+impl<F: Read + Seek> TdmsFile<F> {
+    /// Pin the current generation of the index.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[1.0, 2.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let epoch = file.pin_epoch();
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[3.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// assert_eq!(epoch.channel_length(&channel), Some(2));
+    /// assert_eq!(file.channel_length(&channel), Some(3));
+    /// assert!(file.generation() > epoch.generation());
+    /// ```
+    pub fn pin_epoch(&self) -> IndexEpoch {
+        IndexEpoch {
+            index: Arc::clone(&self.index),
+            generation: self.generation,
+            retry: self.retry,
+        }
+    }
+
+    /// The number of times the index has been changed since the file was opened.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+impl IndexEpoch {
+    /// The generation of the index that is pinned.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Take back the pinned index, which is unique once nothing else pins this generation.
+    #[cfg_attr(not(feature = "write"), allow(dead_code))]
+    pub(super) fn into_index(self) -> Arc<Index> {
+        self.index
+    }
+
+    /// The byte offset in the file that reads from this epoch are bounded by.
+    pub fn snapshot_end(&self) -> u64 {
+        self.index.snapshot_end().unwrap_or_default()
+    }
+
+    /// The length of the channel in this epoch. None if the channel does not exist.
+    pub fn channel_length(&self, channel: &ChannelPath) -> Option<u64> {
+        self.index.channel_length(channel)
+    }
+
+    /// Read the channel from `file` starting at the sample `start` using this epoch.
+    ///
+    /// `file` should be a separate handle to the file the epoch was pinned
+    /// from. Reads past the end of the channel in this epoch are truncated,
+    /// even if the file has grown since.
+    pub fn read_range<D: TdmsStorageType>(
+        &self,
+        file: &mut (impl Read + Seek),
+        channel: &ChannelPath,
+        start: u64,
+        output: &mut [D],
+    ) -> Result<ReadOutcome, TdmsError> {
+        let samples_read = read_range_from(&self.index, &self.retry, file, channel, start, output)?;
+        Ok(ReadOutcome {
+            samples_read,
+            truncated: samples_read < output.len(),
+        })
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::DataLayout;
+
+    #[test]
+    fn test_pinned_epoch_is_unchanged_by_refresh() {
+        let channel = ChannelPath::new("group", "channel");
+        let mut source = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = source.writer().unwrap();
+        writer
+            .write_channels(&[&channel], &[1u32, 2], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+        let first_bytes = source.file.get_ref().clone();
+
+        let mut follower = TdmsFile::new(Cursor::new(first_bytes)).unwrap();
+        let epoch = follower.pin_epoch();
+        assert_eq!(follower.refresh().unwrap(), 0);
+        assert_eq!(follower.generation(), epoch.generation());
+
+        let mut writer = source.writer().unwrap();
+        writer
+            .write_channels(&[&channel], &[3u32], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+        *follower.file.get_mut() = source.file.get_ref().clone();
+        assert_eq!(follower.refresh().unwrap(), 1);
+
+        // A reader on another thread keeps the pinned generation.
+        let bytes = follower.file.get_ref().clone();
+        let pinned = std::thread::spawn(move || {
+            let mut output = [0u32; 3];
+            let outcome = epoch
+                .read_range(&mut Cursor::new(bytes), &channel, 0, &mut output)
+                .unwrap();
+            (outcome.samples_read, output)
+        })
+        .join()
+        .unwrap();
+        assert_eq!(pinned, (2, [1, 2, 0]));
+        assert_eq!(
+            follower
+                .read_all::<u32>(&ChannelPath::new("group", "channel"))
+                .unwrap(),
+            vec![1, 2, 3]
+        );
+    }
+}
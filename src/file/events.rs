@@ -0,0 +1,185 @@
+//! String channels and event logs.
+//!
+//! LabVIEW applications commonly log events as a timestamp channel and a
+//! string message channel written side by side. [`TdmsFile::read_events`]
+//! and [`TdmsFileWriter::write_events`] handle the pair as one list of
+//! events rather than two channels to zip together.
+
+use std::fmt::Debug;
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use labview_interop::types::LVTime;
+
+use crate::error::{usize_from, TdmsError};
+use crate::io::data_types::DataType;
+#[cfg(feature = "write")]
+use crate::io::writer::TdmsWriter;
+use crate::paths::ChannelPath;
+use crate::raw_data::{decode_strings, DataLayout, Endianess};
+use crate::TdmsFile;
+#[cfg(feature = "write")]
+use crate::TdmsFileWriter;
+
+impl<F: Read + Seek + Write + Debug> TdmsFile<F> {
+    /// Read all the strings in a string channel.
+    ///
+    /// Errors with [`TdmsError::ChannelTypeMismatch`] if the channel isn't a
+    /// string channel. Invalid UTF-8 is replaced with the replacement character.
+    pub fn read_strings(&mut self, channel: &ChannelPath) -> Result<Vec<String>, TdmsError> {
+        let locations = self
+            .index
+            .get_channel_data_positions(channel)
+            .ok_or_else(|| self.index.missing_channel(channel))?;
+
+        let mut strings = Vec::new();
+        let mut bytes = Vec::new();
+        for location in locations {
+            let block = self
+                .index
+                .get_data_block(location.data_block)
+                .ok_or_else(|| {
+                    TdmsError::DataBlockNotFound(channel.clone(), location.data_block)
+                })?;
+            let meta = &block.channels[location.channel_index];
+            if meta.data_type != DataType::TdmsString {
+                return Err(TdmsError::ChannelTypeMismatch {
+                    channel: channel.clone(),
+                    stored: meta.data_type,
+                    requested: DataType::TdmsString,
+                });
+            }
+            // Strings can't be interleaved as they have no fixed size.
+            if block.layout == DataLayout::Interleaved {
+                return Err(TdmsError::UnsupportedType(DataType::TdmsString));
+            }
+            let offset: u64 = block.channels[..location.channel_index]
+                .iter()
+                .map(|channel| {
+                    channel
+                        .total_size_bytes
+                        .unwrap_or(channel.number_of_values * channel.data_type.size() as u64)
+                })
+                .sum();
+
+            bytes.resize(usize_from(meta.total_size_bytes.unwrap_or(0))?, 0);
+            self.file.seek(SeekFrom::Start(block.start + offset))?;
+            self.file.read_exact(&mut bytes)?;
+            let count = usize_from(meta.number_of_values)?;
+            let big_endian = block.byte_order == Endianess::Big;
+            let block_strings = decode_strings(&bytes, count, big_endian)
+                .ok_or_else(|| TdmsError::InvalidStringData(channel.clone()))?;
+            strings.extend(block_strings);
+        }
+        Ok(strings)
+    }
+
+    /// Read an event log stored as a timestamp channel and a message channel.
+    ///
+    /// Events are paired by position. If one channel is longer, because the
+    /// writer stopped between writing the two, the extra values are ignored.
+    pub fn read_events(
+        &mut self,
+        timestamps: &ChannelPath,
+        messages: &ChannelPath,
+    ) -> Result<Vec<(LVTime, String)>, TdmsError> {
+        let length = self
+            .channel_length(timestamps)
+            .ok_or_else(|| self.index.missing_channel(timestamps))?;
+        let mut times = vec![LVTime::from_parts(0, 0); usize_from(length)?];
+        self.read_channel(timestamps, &mut times)?;
+        let messages = self.read_strings(messages)?;
+        Ok(times.into_iter().zip(messages).collect())
+    }
+}
+
+#[cfg(feature = "write")]
+impl<'a, F: Write, W: TdmsWriter<&'a mut F>> TdmsFileWriter<'a, F, W> {
+    /// Write events to a timestamp channel and a message channel.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use labview_interop::types::LVTime;
+    /// use tedium::types::UnixNanos;
+    /// use tedium::{ChannelPath, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let times = ChannelPath::new("events", "time");
+    /// let messages = ChannelPath::new("events", "message");
+    /// let start = LVTime::from_unix_nanos(1_700_000_000_000_000_000);
+    ///
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_events(&times, &messages, &[(start, "pump on")]).unwrap();
+    /// drop(writer);
+    ///
+    /// let events = file.read_events(&times, &messages).unwrap();
+    /// assert_eq!(events, vec![(start, "pump on".to_string())]);
+    /// ```
+    pub fn write_events(
+        &mut self,
+        timestamps: &ChannelPath,
+        messages: &ChannelPath,
+        events: &[(LVTime, impl AsRef<str>)],
+    ) -> Result<(), TdmsError> {
+        let times: Vec<LVTime> = events.iter().map(|(time, _)| *time).collect();
+        let text: Vec<&str> = events.iter().map(|(_, message)| message.as_ref()).collect();
+        self.write_channels(&[timestamps], &times, DataLayout::Contigious)?;
+        self.write_strings(messages, &text)
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::io::writer::BigEndianWriter;
+    use crate::types::UnixNanos;
+
+    fn time(seconds: i128) -> LVTime {
+        LVTime::from_unix_nanos(seconds * 1_000_000_000)
+    }
+
+    #[test]
+    fn test_events_append_across_writes() {
+        let times = ChannelPath::new("events", "time");
+        let messages = ChannelPath::new("events", "message");
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_events(&times, &messages, &[(time(1), "start"), (time(2), "")])
+            .unwrap();
+        writer
+            .write_events(&times, &messages, &[(time(5), "stop".to_string())])
+            .unwrap();
+        drop(writer);
+
+        let expected = vec![
+            (time(1), "start".to_string()),
+            (time(2), String::new()),
+            (time(5), "stop".to_string()),
+        ];
+        assert_eq!(file.read_events(&times, &messages).unwrap(), expected);
+
+        // The file reads the same when indexed from scratch.
+        let mut reloaded = TdmsFile::new(file.file).unwrap();
+        assert_eq!(reloaded.read_events(&times, &messages).unwrap(), expected);
+        assert!(matches!(
+            reloaded.read_strings(&times),
+            Err(TdmsError::ChannelTypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_big_endian_strings() {
+        let channel = ChannelPath::new("log", "message");
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = TdmsFileWriter::new(
+            std::sync::Arc::make_mut(&mut file.index),
+            BigEndianWriter::from_writer(&mut file.file),
+        );
+        writer.write_strings(&channel, &["a", "bc"]).unwrap();
+        drop(writer);
+        assert_eq!(file.read_strings(&channel).unwrap(), vec!["a", "bc"]);
+    }
+}
@@ -1,17 +1,150 @@
+use std::collections::BTreeSet;
 use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use labview_interop::types::LVTime;
+
+use super::strict::validate_segment;
 use crate::error::TdmsError;
-use crate::index::{DataFormat, Index};
-use crate::io::data_types::TdmsStorageType;
+use crate::index::{DataFormat, Index, ObjectListChange};
+use crate::io::data_types::{DataType, TdmsStorageType, UnixNanos};
 use crate::io::writer::TdmsWriter;
-use crate::meta_data::{MetaData, ObjectMetaData, ToC};
+use crate::meta_data::{MetaData, ObjectMetaData, RawDataIndex, RawDataMeta, Segment, ToC};
 use crate::paths::ChannelPath;
-use crate::raw_data::{MultiChannelSlice, WriteBlock};
-use crate::{DataLayout, PropertyPath, PropertyValue};
+use crate::raw_data::{encode_strings, MultiChannelSlice, WriteBlock};
+use crate::{DataLayout, PropertyPath, PropertyValue, SegmentBuilder};
+
+/// The file property written by [`TdmsFileWriter::write_wall_clock`].
+pub const WALL_CLOCK_PROPERTY: &str = "written_at";
+
+/// Details of a segment which has been written, passed to a [`WriteObserver`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct WrittenSegment<'a> {
+    /// The byte offset of the segment in the file.
+    pub segment_start: u64,
+    /// The table of contents written in the lead in.
+    pub toc: ToC,
+    /// The paths of the objects written. For data this is the channels in order.
+    pub paths: &'a [&'a str],
+    /// The size of the metadata in bytes.
+    pub meta_data_bytes: u64,
+    /// The size of the raw data in bytes.
+    pub data_bytes: u64,
+}
+
+/// Receives a notification for every segment written by a [`TdmsFileWriter`].
+///
+/// This allows applications to maintain external indexes or audit logs.
+/// It is implemented for closures taking a [`WrittenSegment`].
+pub trait WriteObserver {
+    fn segment_written(&mut self, segment: &WrittenSegment);
+}
+
+impl<T: FnMut(&WrittenSegment)> WriteObserver for T {
+    fn segment_written(&mut self, segment: &WrittenSegment) {
+        self(segment)
+    }
+}
+
+/// A limit on the size of the segments created by [`TdmsFileWriter::write_channels`].
+///
+/// Writes larger than the limit are split into several segments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentLimit {
+    /// The maximum samples per channel in each segment.
+    Samples(usize),
+    /// The maximum bytes of raw data in each segment.
+    ///
+    /// At least one sample per channel is always written.
+    Bytes(usize),
+}
+
+/// When a [`TdmsFileWriter`] flushes its buffer to the underlying file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FlushPolicy {
+    /// Flush when the buffer is full, on [`TdmsFileWriter::sync`] and when the writer is dropped.
+    #[default]
+    Threshold,
+    /// Also flush after every segment so readers see each segment as soon as it is written.
+    EverySegment,
+}
+
+/// The buffering between a [`TdmsFileWriter`] and the file.
+///
+/// High rate writers should use a larger buffer than the default so that
+/// small writes don't each become a system call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteBuffer {
+    /// The size of the buffer in bytes.
+    pub capacity: usize,
+    pub flush: FlushPolicy,
+}
+
+impl Default for WriteBuffer {
+    /// An 8 KiB buffer flushed when full, which is what [`crate::TdmsFile::writer`] uses.
+    fn default() -> Self {
+        Self {
+            capacity: 8 * 1024,
+            flush: FlushPolicy::Threshold,
+        }
+    }
+}
+
+/// Constraints on the segments a [`TdmsFileWriter`] writes, for readers which only handle common files.
+///
+/// Every file written is valid either way, but some consumers such as the
+/// Excel TDM importer and older versions of DIAdem have trouble with features
+/// they rarely see. Writes are adapted to the profile where possible and
+/// otherwise fail with [`TdmsError::IncompatibleWrite`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CompatibilityProfile {
+    /// List every channel with its full raw data index in every data segment
+    /// rather than relying on the previous segment.
+    pub always_new_object_list: bool,
+    /// Write interleaved data one channel after another.
+    pub contiguous_only: bool,
+    /// Refuse to write big endian segments.
+    pub little_endian_only: bool,
+}
+
+impl CompatibilityProfile {
+    /// No constraints, which is the default.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// The most conservative output, with every constraint applied.
+    pub fn maximum() -> Self {
+        Self {
+            always_new_object_list: true,
+            contiguous_only: true,
+            little_endian_only: true,
+        }
+    }
+
+    /// For the Excel TDM importer, which expects the channels listed in every segment.
+    pub fn excel_importer() -> Self {
+        Self::maximum()
+    }
+
+    /// For older versions of DIAdem, which expect contiguous little endian data.
+    pub fn legacy_diadem() -> Self {
+        Self {
+            always_new_object_list: false,
+            contiguous_only: true,
+            little_endian_only: true,
+        }
+    }
+}
 
 pub struct TdmsFileWriter<'a, F: Write + 'a, W: TdmsWriter<&'a mut F>> {
     index: &'a mut Index,
     writer: W,
+    observer: Option<Box<dyn WriteObserver + 'a>>,
+    segment_limit: Option<SegmentLimit>,
+    flush: FlushPolicy,
+    strict: bool,
+    profile: CompatibilityProfile,
     _file: std::marker::PhantomData<F>,
 }
 
@@ -25,10 +158,165 @@ impl<'a, F: Write, W: TdmsWriter<&'a mut F>> TdmsFileWriter<'a, F, W> {
         Self {
             index,
             writer,
+            observer: None,
+            segment_limit: None,
+            flush: FlushPolicy::Threshold,
+            strict: false,
+            profile: CompatibilityProfile::default(),
             _file: std::marker::PhantomData,
         }
     }
 
+    /// Set an observer to be notified of every segment written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tedium::{ChannelPath, DataLayout, TdmsFile, WrittenSegment};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let mut log = Vec::new();
+    /// let mut writer = file.writer().unwrap();
+    /// writer.set_observer(|segment: &WrittenSegment| log.push(segment.segment_start));
+    /// writer
+    ///     .write_channels(&[ChannelPath::new("group", "channel")], &[1.0], DataLayout::Contigious)
+    ///     .unwrap();
+    /// drop(writer);
+    /// assert_eq!(log, vec![0]);
+    /// ```
+    pub fn set_observer(&mut self, observer: impl WriteObserver + 'a) {
+        self.observer = Some(Box::new(observer));
+    }
+
+    /// Limit the size of the segments written by [`Self::write_channels`].
+    ///
+    /// By default each call writes a single segment however large the data is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tedium::{ChannelPath, DataLayout, SegmentLimit, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let mut writer = file.writer().unwrap();
+    /// writer.set_segment_limit(Some(SegmentLimit::Samples(1000)));
+    /// let data = vec![0.0; 10_000];
+    /// writer
+    ///     .write_channels(&[ChannelPath::new("group", "channel")], &data, DataLayout::Contigious)
+    ///     .unwrap();
+    /// drop(writer);
+    /// assert_eq!(file.data_block_count(), 10);
+    /// ```
+    pub fn set_segment_limit(&mut self, limit: Option<SegmentLimit>) {
+        self.segment_limit = limit;
+    }
+
+    /// Set when the buffered data is flushed to the file.
+    pub fn set_flush_policy(&mut self, flush: FlushPolicy) {
+        self.flush = flush;
+    }
+
+    /// Validate every segment against the TDMS specification before it is written.
+    ///
+    /// Some third-party readers reject files that NI's readers accept. In strict
+    /// mode object paths must be escaped correctly, property names must be
+    /// unique within a segment, raw data must use a valid type code with an index
+    /// of the right size, and the raw data must be whole chunks. Anything else
+    /// fails with [`TdmsError::SpecViolation`] and nothing is written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tedium::{ErrorKind, PropertyPath, PropertyValue, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let mut writer = file.writer().unwrap();
+    /// writer.set_strict(true);
+    /// let error = writer
+    ///     .write_properties(
+    ///         &PropertyPath::group("group"),
+    ///         &[("name", PropertyValue::I32(1)), ("name", PropertyValue::I32(2))],
+    ///     )
+    ///     .unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    /// drop(writer);
+    /// assert_eq!(file.list_objects().count(), 0);
+    /// ```
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Constrain the segments written so that less capable readers can open the file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tedium::{ChannelPath, CompatibilityProfile, DataLayout, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channels = [ChannelPath::new("group", "a"), ChannelPath::new("group", "b")];
+    /// let mut writer = file.writer().unwrap();
+    /// writer.set_compatibility_profile(CompatibilityProfile::excel_importer());
+    /// writer.write_channels(&channels, &[1.0, 2.0, 3.0, 4.0], DataLayout::Interleaved).unwrap();
+    /// drop(writer);
+    ///
+    /// assert_eq!(file.file_stats().interleaved_blocks, 0);
+    /// assert_eq!(file.read_all::<f64>(&channels[1]).unwrap(), vec![2.0, 4.0]);
+    /// ```
+    pub fn set_compatibility_profile(&mut self, profile: CompatibilityProfile) {
+        self.profile = profile;
+    }
+
+    /// Write the segment, validating it first against the profile and in strict mode.
+    fn write_segment(
+        &mut self,
+        toc: ToC,
+        meta: Option<MetaData>,
+        data: Option<impl WriteBlock>,
+    ) -> Result<Segment, TdmsError> {
+        if self.profile.little_endian_only && W::BIG_ENDIAN_FLAG {
+            return Err(TdmsError::IncompatibleWrite(
+                "big endian segments".to_owned(),
+            ));
+        }
+        if data.is_some() {
+            if self.profile.contiguous_only && toc.data_is_interleaved {
+                return Err(TdmsError::IncompatibleWrite("interleaved data".to_owned()));
+            }
+            if self.profile.always_new_object_list && !toc.contains_new_object_list {
+                return Err(TdmsError::IncompatibleWrite(
+                    "data segments without a new object list".to_owned(),
+                ));
+            }
+        }
+        if self.strict {
+            validate_segment(&toc, meta.as_ref(), data.as_ref().map(WriteBlock::size))?;
+        }
+        self.writer.write_segment(toc, meta, data)
+    }
+
+    /// Add the segment to the index and notify any observer.
+    fn register_segment(&mut self, segment: &Segment, paths: &[&str]) -> Result<(), TdmsError> {
+        let segment_start = self.index.next_segment_start();
+        let segment_end = self.index.add_segment(segment)?;
+        // Segments written here are complete so their data can be read back.
+        self.index.update_snapshot(segment_end);
+        if self.flush == FlushPolicy::EverySegment {
+            self.writer.sync()?;
+        }
+
+        if let Some(observer) = self.observer.as_mut() {
+            observer.segment_written(&WrittenSegment {
+                segment_start,
+                toc: segment.toc,
+                paths,
+                meta_data_bytes: segment.raw_data_offset,
+                data_bytes: segment.next_segment_offset - segment.raw_data_offset,
+            });
+        }
+        Ok(())
+    }
+
     /// Write the data to the given channels.
     ///
     /// If you provide multiple channels then it is assumed tha the values is a 2d array layout.
@@ -36,6 +324,9 @@ impl<'a, F: Write, W: TdmsWriter<&'a mut F>> TdmsFileWriter<'a, F, W> {
     /// If layout is [`DataLayout::Interleaved`] then the data is assumed to be interleaved. i.e. ch1, ch2, ch1, ch2
     ///
     /// If layout is [`DataLayout::Contigious`] then the data is assumed to be contigious. i.e. ch1, ch1, ch1, ch2, ch2, ch2
+    ///
+    /// Repeated writes to the same channels with the same number of values write
+    /// raw data only segments, with no metadata, even if the layout changes.
     pub fn write_channels<D: TdmsStorageType>(
         &mut self,
         channels: &[impl AsRef<ChannelPath>],
@@ -43,20 +334,168 @@ impl<'a, F: Write, W: TdmsWriter<&'a mut F>> TdmsFileWriter<'a, F, W> {
         layout: DataLayout,
     ) -> Result<(), TdmsError> {
         let raw_data = MultiChannelSlice::from_slice(values, channels.len())?;
+        let paths: Vec<&str> = channels.iter().map(|path| path.as_ref().path()).collect();
+
+        let samples_per_channel = raw_data.samples_per_channel();
+        let chunk_samples = self.chunk_samples::<D>(channels.len(), samples_per_channel);
+
+        if layout == DataLayout::Interleaved && self.profile.contiguous_only {
+            let mut start = 0;
+            loop {
+                let length = chunk_samples.min(samples_per_channel - start);
+                let chunk = raw_data.deinterleaved_chunk(start, length);
+                self.write_block(&paths, chunk, DataLayout::Contigious)?;
+                start += length;
+                if start >= samples_per_channel {
+                    return Ok(());
+                }
+            }
+        }
+
+        if samples_per_channel <= chunk_samples {
+            return self.write_block(&paths, raw_data, layout);
+        }
+
+        let mut start = 0;
+        while start < samples_per_channel {
+            let length = chunk_samples.min(samples_per_channel - start);
+            self.write_block(&paths, raw_data.chunk(layout, start, length), layout)?;
+            start += length;
+        }
+        Ok(())
+    }
+
+    /// Write interleaved data where the final record may be incomplete.
+    ///
+    /// [`Self::write_channels`] rejects values which aren't a whole number of
+    /// records. Here the complete records are written and any values left over
+    /// are written to the first channels in a final segment, so those channels
+    /// end up one sample longer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tedium::{ChannelPath, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channels = [ChannelPath::new("group", "a"), ChannelPath::new("group", "b")];
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels_ragged(&channels, &[1.0, 2.0, 3.0]).unwrap();
+    /// drop(writer);
+    ///
+    /// assert_eq!(file.read_all::<f64>(&channels[0]).unwrap(), vec![1.0, 3.0]);
+    /// assert_eq!(file.read_all::<f64>(&channels[1]).unwrap(), vec![2.0]);
+    /// ```
+    pub fn write_channels_ragged<D: TdmsStorageType>(
+        &mut self,
+        channels: &[impl AsRef<ChannelPath>],
+        values: &[D],
+    ) -> Result<(), TdmsError> {
+        let (records, ragged) = MultiChannelSlice::from_slice_ragged(values, channels.len())?;
+        if records.samples_per_channel() > 0 {
+            self.write_channels(channels, records.values(), DataLayout::Interleaved)?;
+        }
+        if !ragged.is_empty() {
+            self.write_channels(&channels[..ragged.len()], ragged, DataLayout::Interleaved)?;
+        }
+        Ok(())
+    }
+
+    /// Write contigious data to the channels using the interleaved layout in the file.
+    ///
+    /// The values are given one channel after another, i.e. ch1, ch1, ch2, ch2, and are
+    /// interleaved as they are written so streaming readers can read the channels together.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tedium::{ChannelPath, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channels = [ChannelPath::new("group", "a"), ChannelPath::new("group", "b")];
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels_transposed(&channels, &[1.0, 2.0, 3.0, 4.0]).unwrap();
+    /// drop(writer);
+    ///
+    /// let b: Vec<f64> = file.read_all(&channels[1]).unwrap();
+    /// assert_eq!(b, vec![3.0, 4.0]);
+    /// ```
+    pub fn write_channels_transposed<D: TdmsStorageType>(
+        &mut self,
+        channels: &[impl AsRef<ChannelPath>],
+        values: &[D],
+    ) -> Result<(), TdmsError> {
+        if self.profile.contiguous_only {
+            return self.write_channels(channels, values, DataLayout::Contigious);
+        }
+        let raw_data = MultiChannelSlice::from_slice(values, channels.len())?;
+        let paths: Vec<&str> = channels.iter().map(|path| path.as_ref().path()).collect();
+
+        let samples_per_channel = raw_data.samples_per_channel();
+        let chunk_samples = self.chunk_samples::<D>(channels.len(), samples_per_channel);
+
+        let mut start = 0;
+        loop {
+            let length = chunk_samples.min(samples_per_channel - start);
+            let chunk = raw_data.transposed_chunk(start, length);
+            self.write_block(&paths, chunk, DataLayout::Interleaved)?;
+            start += length;
+            if start >= samples_per_channel {
+                return Ok(());
+            }
+        }
+    }
+
+    /// The index the writer is keeping up to date.
+    pub(super) fn index(&self) -> &Index {
+        self.index
+    }
+
+    /// The samples per channel to write in each segment under the segment limit.
+    pub(super) fn chunk_samples<D: TdmsStorageType>(
+        &self,
+        channel_count: usize,
+        samples_per_channel: usize,
+    ) -> usize {
+        match self.segment_limit {
+            None => samples_per_channel,
+            Some(SegmentLimit::Samples(samples)) => samples,
+            Some(SegmentLimit::Bytes(bytes)) => bytes / (channel_count * D::SIZE_BYTES),
+        }
+        .max(1)
+    }
+
+    /// Write a single data segment for the channels.
+    ///
+    /// Errors with [`TdmsError::DuplicateChannel`] if a channel is listed twice.
+    pub(super) fn write_block(
+        &mut self,
+        paths: &[&str],
+        raw_data: impl WriteBlock,
+        layout: DataLayout,
+    ) -> Result<(), TdmsError> {
+        let mut seen = BTreeSet::new();
+        if let Some(duplicate) = paths.iter().find(|path| !seen.insert(**path)) {
+            return Err(TdmsError::DuplicateChannel(duplicate.to_string()));
+        }
+
         let data_structures = raw_data
             .data_structure()
             .into_iter()
             .map(DataFormat::RawData);
+        let channels: Vec<_> = paths.iter().copied().zip(data_structures).collect();
 
-        let channels = channels
-            .iter()
-            .map(|path| path.as_ref().path()) //surely a way to avoid this.
-            .zip(data_structures)
-            .collect();
-
-        let (matches_live, channels) = self.index.check_write_values(channels);
+        let (change, channels) = if self.profile.always_new_object_list {
+            let channels = channels
+                .into_iter()
+                .map(|(path, format)| (path, format.into()))
+                .collect();
+            (ObjectListChange::NewList, channels)
+        } else {
+            self.index.check_write_values(channels)
+        };
 
-        let meta = if matches_live {
+        let meta = if channels.is_empty() {
             None
         } else {
             let objects: Vec<ObjectMetaData> = channels
@@ -72,13 +511,56 @@ impl<'a, F: Write, W: TdmsWriter<&'a mut F>> TdmsFileWriter<'a, F, W> {
         };
 
         let toc = ToC {
-            contains_new_object_list: !matches_live,
+            contains_new_object_list: change == ObjectListChange::NewList,
             data_is_interleaved: layout == DataLayout::Interleaved,
             ..Default::default()
         };
-        let segment = self.writer.write_segment(toc, meta, Some(raw_data))?;
-        self.index.add_segment(segment)?;
-        Ok(())
+        let segment = self.write_segment(toc, meta, Some(raw_data))?;
+        self.register_segment(&segment, paths)
+    }
+
+    /// Write strings to a channel.
+    ///
+    /// Strings have a variable size so they are written in a segment of their
+    /// own, which starts a new object list. Read them back with
+    /// [`crate::TdmsFile::read_strings`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tedium::{ChannelPath, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("log", "message");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_strings(&channel, &["started", "stopped"]).unwrap();
+    /// drop(writer);
+    ///
+    /// assert_eq!(file.read_strings(&channel).unwrap(), vec!["started", "stopped"]);
+    /// ```
+    pub fn write_strings(
+        &mut self,
+        channel: &ChannelPath,
+        values: &[impl AsRef<str>],
+    ) -> Result<(), TdmsError> {
+        let bytes = encode_strings(values, W::BIG_ENDIAN_FLAG);
+        let meta = MetaData {
+            objects: vec![ObjectMetaData {
+                path: channel.path().to_string(),
+                properties: vec![],
+                raw_data_index: RawDataIndex::RawData(RawDataMeta {
+                    data_type: DataType::TdmsString,
+                    number_of_values: values.len() as u64,
+                    total_size_bytes: Some(bytes.len() as u64),
+                }),
+            }],
+        };
+        let toc = ToC {
+            contains_new_object_list: true,
+            ..Default::default()
+        };
+        let segment = self.write_segment(toc, Some(meta), Some(&bytes[..]))?;
+        self.register_segment(&segment, &[channel.path()])
     }
 
     /// Write the properties to the given path.
@@ -104,7 +586,53 @@ impl<'a, F: Write, W: TdmsWriter<&'a mut F>> TdmsFileWriter<'a, F, W> {
         path: &PropertyPath,
         properties: &[(&str, PropertyValue)],
     ) -> Result<(), TdmsError> {
-        let path = path.path();
+        let properties = properties
+            .iter()
+            .map(|(name, value)| (name.to_string(), (*value).clone()))
+            .collect();
+        self.write_object_properties(path, properties)
+    }
+
+    /// Declare a group with its properties before any data is written.
+    ///
+    /// This is the same as writing the properties to the group path.
+    pub fn create_group(
+        &mut self,
+        group: &str,
+        properties: &[(&str, PropertyValue)],
+    ) -> Result<(), TdmsError> {
+        self.write_properties(&PropertyPath::group(group), properties)
+    }
+
+    /// Declare a channel with its data type and properties before any data is written.
+    ///
+    /// The channel is written with zero length data so it appears in the file structure
+    /// straight away, for example for viewers attached to a live file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tedium::{ChannelPath, DataLayout, PropertyValue, TdmsFile};
+    /// use tedium::types::DataType;
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.create_group("group", &[]).unwrap();
+    /// writer
+    ///     .create_channel(&channel, DataType::DoubleFloat, &[("unit_string", PropertyValue::String("V".into()))])
+    ///     .unwrap();
+    /// drop(writer);
+    ///
+    /// assert_eq!(file.channel_length(&channel), Some(0));
+    /// ```
+    pub fn create_channel(
+        &mut self,
+        channel: &ChannelPath,
+        data_type: DataType,
+        properties: &[(&str, PropertyValue)],
+    ) -> Result<(), TdmsError> {
+        let path = channel.path();
         let properties = properties
             .iter()
             .map(|(name, value)| (name.to_string(), (*value).clone()))
@@ -113,18 +641,195 @@ impl<'a, F: Write, W: TdmsWriter<&'a mut F>> TdmsFileWriter<'a, F, W> {
         let object = ObjectMetaData {
             path: path.to_string(),
             properties,
-            raw_data_index: crate::meta_data::RawDataIndex::None,
+            raw_data_index: RawDataIndex::RawData(RawDataMeta {
+                data_type,
+                number_of_values: 0,
+                total_size_bytes: None,
+            }),
         };
 
         let meta = MetaData {
             objects: vec![object],
         };
 
-        let segment =
-            self.writer
-                .write_segment(ToC::default(), Some(meta), Option::<&[u8]>::None)?;
-        self.index.add_segment(segment)?;
-        Ok(())
+        let segment = self.write_segment(ToC::default(), Some(meta), Option::<&[u8]>::None)?;
+        self.register_segment(&segment, &[path])
+    }
+
+    /// Update a single property on the object.
+    ///
+    /// This appends a metadata only segment and updates the index immediately so reads
+    /// from this file reflect the new value straight away. The last value written wins.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tedium::{TdmsFile, PropertyPath, PropertyValue};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let path = PropertyPath::group("group");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.update_property(&path, "state", PropertyValue::String("running".into())).unwrap();
+    /// writer.update_property(&path, "state", PropertyValue::String("done".into())).unwrap();
+    /// drop(writer);
+    ///
+    /// assert_eq!(
+    ///     file.read_property(&path, "state").unwrap(),
+    ///     Some(&PropertyValue::String("done".into()))
+    /// );
+    /// ```
+    pub fn update_property(
+        &mut self,
+        path: &PropertyPath,
+        name: &str,
+        value: PropertyValue,
+    ) -> Result<(), TdmsError> {
+        self.write_object_properties(path, vec![(name.to_string(), value)])
+    }
+
+    /// Write the number of samples in every channel to the `wf_samples` property.
+    ///
+    /// This should be called once all data is written so consumers relying on the
+    /// property see the correct totals. See [`Self::finalize_with`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tedium::{ChannelPath, DataLayout, PropertyValue, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[1.0, 2.0], DataLayout::Contigious).unwrap();
+    /// writer.write_channels(&[&channel], &[3.0], DataLayout::Contigious).unwrap();
+    /// writer.finalize().unwrap();
+    /// drop(writer);
+    ///
+    /// assert_eq!(
+    ///     file.read_property(channel.as_ref(), "wf_samples").unwrap(),
+    ///     Some(&PropertyValue::I32(3))
+    /// );
+    /// ```
+    pub fn finalize(&mut self) -> Result<(), TdmsError> {
+        self.finalize_with(&["wf_samples"])
+    }
+
+    /// Write the number of samples in every channel to each of the named properties.
+    ///
+    /// The counts are taken from the index so they include data already in the file
+    /// when appending. They are written as I32 like LabVIEW does unless the count
+    /// is too large, in which case they are written as I64.
+    ///
+    /// All channels are updated in a single metadata only segment.
+    pub fn finalize_with(&mut self, property_names: &[&str]) -> Result<(), TdmsError> {
+        let objects: Vec<(String, Vec<(String, PropertyValue)>)> = self
+            .index
+            .data_object_lengths()
+            .map(|(path, length)| {
+                let count = match i32::try_from(length) {
+                    Ok(length) => PropertyValue::I32(length),
+                    Err(_) => PropertyValue::I64(length as i64),
+                };
+                let properties = property_names
+                    .iter()
+                    .map(|name| (name.to_string(), count.clone()))
+                    .collect();
+                (path.to_string(), properties)
+            })
+            .collect();
+
+        if objects.is_empty() || property_names.is_empty() {
+            return Ok(());
+        }
+        self.write_objects_properties(objects)
+    }
+
+    /// Write a metadata only segment with the properties for a single object.
+    fn write_object_properties(
+        &mut self,
+        path: &PropertyPath,
+        properties: Vec<(String, PropertyValue)>,
+    ) -> Result<(), TdmsError> {
+        self.write_objects_properties(vec![(path.path().to_string(), properties)])
+    }
+
+    /// Write a metadata only segment with the properties for each object.
+    pub(super) fn write_objects_properties(
+        &mut self,
+        objects: Vec<(String, Vec<(String, PropertyValue)>)>,
+    ) -> Result<(), TdmsError> {
+        let objects: Vec<ObjectMetaData> = objects
+            .into_iter()
+            .map(|(path, properties)| {
+                // Channels that are in the current data layout must keep their raw data index,
+                // otherwise readers will drop them from following data only segments.
+                let raw_data_index = if self.index.is_active_data_object(&path) {
+                    RawDataIndex::MatchPrevious
+                } else {
+                    RawDataIndex::None
+                };
+                ObjectMetaData {
+                    path,
+                    properties,
+                    raw_data_index,
+                }
+            })
+            .collect();
+
+        let paths: Vec<String> = objects.iter().map(|object| object.path.clone()).collect();
+        let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+        let meta = MetaData { objects };
+
+        let segment = self.write_segment(ToC::default(), Some(meta), Option::<&[u8]>::None)?;
+        self.register_segment(&segment, &paths)
+    }
+
+    /// Write an empty heartbeat segment.
+    ///
+    /// This has no metadata or data so readers following the file can see the
+    /// writer is still alive. The active channels are unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tedium::TdmsFile;
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_heartbeat().unwrap();
+    /// drop(writer);
+    /// assert_eq!(file.data_block_count(), 0);
+    /// ```
+    pub fn write_heartbeat(&mut self) -> Result<(), TdmsError> {
+        let segment = self.write_segment(ToC::default(), None, Option::<&[u8]>::None)?;
+        self.register_segment(&segment, &[])
+    }
+
+    /// Write the current system time to the [`WALL_CLOCK_PROPERTY`] file property.
+    ///
+    /// Calling this before each flush of data lets readers see when every
+    /// block was written with [`crate::TdmsFile::block_written_at`].
+    pub fn write_wall_clock(&mut self) -> Result<(), TdmsError> {
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let now = LVTime::from_unix_nanos(since_epoch.as_nanos() as i128);
+        self.update_property(
+            &PropertyPath::file(),
+            WALL_CLOCK_PROPERTY,
+            PropertyValue::Timestamp(now),
+        )
+    }
+
+    /// Write a segment assembled with a [`SegmentBuilder`].
+    ///
+    /// The segment is added to the index like any other so it can be read
+    /// back straight away. Errors with [`TdmsError::UnsupportedType`] for
+    /// string data, as the builder can't write the size of the strings.
+    pub fn write_built_segment(&mut self, segment: &SegmentBuilder) -> Result<(), TdmsError> {
+        let parts = segment.segment_parts()?;
+        let written = self.write_segment(parts.toc, parts.meta, parts.payload)?;
+        self.register_segment(&written, &segment.paths())
     }
 
     /// Forces the file to sync to disk by calling the sync method on the writer.
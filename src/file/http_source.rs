@@ -0,0 +1,180 @@
+//! Reading files from web servers with HTTP range requests.
+//!
+//! [`HttpSource`] is a [`RangeSource`] for a file served by any web server
+//! supporting range requests, so a file can be indexed and previewed without
+//! downloading all of it. The size from the first request and the fetches
+//! cached by the [`RangeReader`] mean the metadata scanned while indexing is
+//! only requested once.
+
+use std::io::{Read, Result as IoResult};
+use std::ops::Range;
+
+use super::range_reader::{RangeReader, RangeSource};
+use crate::error::TdmsError;
+use crate::TdmsFile;
+
+/// A file on a web server read with HTTP range requests.
+#[derive(Debug)]
+pub struct HttpSource {
+    agent: ureq::Agent,
+    url: String,
+    size: Option<u64>,
+}
+
+impl HttpSource {
+    /// Read the file at `url` with a default HTTP agent.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::with_agent(ureq::Agent::new(), url)
+    }
+
+    /// Read the file at `url` with an agent configured for timeouts, proxies or TLS.
+    pub fn with_agent(agent: ureq::Agent, url: impl Into<String>) -> Self {
+        Self {
+            agent,
+            url: url.into(),
+            size: None,
+        }
+    }
+}
+
+fn io_error(error: ureq::Error) -> std::io::Error {
+    match error {
+        ureq::Error::Status(404, _) => std::io::Error::new(std::io::ErrorKind::NotFound, error),
+        error => std::io::Error::other(error),
+    }
+}
+
+impl RangeSource for HttpSource {
+    fn size(&mut self) -> IoResult<u64> {
+        if let Some(size) = self.size {
+            return Ok(size);
+        }
+        let response = self.agent.head(&self.url).call().map_err(io_error)?;
+        let size = response
+            .header("Content-Length")
+            .and_then(|length| length.parse().ok())
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("{} has no content length", self.url),
+                )
+            })?;
+        self.size = Some(size);
+        Ok(size)
+    }
+
+    fn fetch(&mut self, range: Range<u64>) -> IoResult<Vec<u8>> {
+        let response = self
+            .agent
+            .get(&self.url)
+            .set("Range", &format!("bytes={}-{}", range.start, range.end - 1))
+            .call()
+            .map_err(io_error)?;
+        // A full response would download the whole file for every read.
+        if response.status() != 206 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!("{} does not support range requests", self.url),
+            ));
+        }
+        let mut bytes = Vec::with_capacity((range.end - range.start) as usize);
+        response.into_reader().read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+impl TdmsFile<RangeReader<HttpSource>> {
+    /// Open the TDMS file at `url` using HTTP range requests.
+    ///
+    /// Each request fetches at least `min_fetch` bytes so the many small reads
+    /// made while indexing, and reads of nearby blocks, are coalesced.
+    pub fn open_http(url: impl Into<String>, min_fetch: u64) -> Result<Self, TdmsError> {
+        let reader = RangeReader::new(HttpSource::new(url))?.with_min_fetch(min_fetch);
+        TdmsFile::new(reader)
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::testgen::TestFileSpec;
+
+    /// Serve the bytes over HTTP on a local port, handling one request per connection.
+    ///
+    /// Range requests are only honoured if `ranges` is true.
+    fn serve(bytes: Vec<u8>, ranges: bool) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { return };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                reader.read_line(&mut request).unwrap();
+                let mut range = None;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("range: bytes=") {
+                        let (start, end) = value.trim().split_once('-').unwrap();
+                        range = Some(
+                            start.parse::<usize>().unwrap()..end.parse::<usize>().unwrap() + 1,
+                        );
+                    }
+                }
+                let (status, body) = match range.filter(|_| ranges) {
+                    Some(range) => ("206 Partial Content", &bytes[range]),
+                    None => ("200 OK", &bytes[..]),
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                )
+                .unwrap();
+                if !request.starts_with("HEAD") {
+                    stream.write_all(body).unwrap();
+                }
+            }
+        });
+        format!("http://{address}/test.tdms")
+    }
+
+    #[test]
+    fn test_read_channel_over_http() {
+        let spec = TestFileSpec {
+            segments: 3,
+            samples_per_segment: 500,
+            ..Default::default()
+        };
+        let bytes = spec.build().unwrap();
+        let mut expected = TdmsFile::new(std::io::Cursor::new(bytes.clone())).unwrap();
+        let url = serve(bytes, true);
+
+        let mut file = TdmsFile::open_http(url, 512).unwrap();
+        let channel = spec.channel_path(0, 1);
+        assert_eq!(
+            file.read_all::<f64>(&channel).unwrap(),
+            expected.read_all::<f64>(&channel).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_servers_without_ranges_are_rejected() {
+        let url = serve(TestFileSpec::default().build().unwrap(), false);
+        let error = TdmsFile::open_http(url, 512).unwrap_err();
+        let TdmsError::InSegment { source, .. } = &error else {
+            panic!("expected the error in the first segment, got {error:?}");
+        };
+        let TdmsError::IoError(error) = source.as_ref() else {
+            panic!("expected an IO error, got {source:?}");
+        };
+        assert_eq!(error.kind(), std::io::ErrorKind::Unsupported);
+    }
+}
@@ -0,0 +1,132 @@
+//! Following a file while another process writes to it.
+//!
+//! [`TdmsFile::refresh`] indexes any segments appended since the file was
+//! opened and a [`ChannelCursor`] tracks how much of a channel has been read
+//! so [`TdmsFile::read_new`] only returns the new samples.
+
+use std::fmt::Debug;
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::sync::Arc;
+
+use crate::error::{usize_from, TdmsError};
+use crate::io::data_types::TdmsStorageType;
+use crate::meta_data::{SegmentScanner, LEAD_IN_BYTES};
+use crate::paths::ChannelPath;
+use crate::TdmsFile;
+
+/// The next segment offset written by LabVIEW while a segment is incomplete.
+const INCOMPLETE_SEGMENT_OFFSET: u64 = u64::MAX;
+
+/// The read position in a channel for reading only the new data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelCursor {
+    channel: ChannelPath,
+    position: u64,
+}
+
+impl ChannelCursor {
+    /// The channel the cursor reads.
+    pub fn channel(&self) -> &ChannelPath {
+        &self.channel
+    }
+
+    /// The number of samples read so far.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+impl<F: Read + Seek> TdmsFile<F> {
+    /// Add any complete segments written to the file since it was indexed.
+    ///
+    /// A segment which is still being written is left for a later refresh.
+    /// This also moves the [`Self::snapshot_end`] to the end of the complete
+    /// segments so their data can be read. Returns the number of segments added.
+    pub fn refresh(&mut self) -> Result<u64, TdmsError> {
+        let file_length = self.file.seek(SeekFrom::End(0))?;
+        let mut position = self.index.next_segment_start();
+        self.file.seek(SeekFrom::Start(position))?;
+
+        let mut scanner = SegmentScanner::new(&mut self.file);
+        let mut segments = Vec::new();
+
+        while position + LEAD_IN_BYTES <= file_length {
+            let segment = match scanner.read_segment() {
+                Ok(segment) => segment,
+                Err(TdmsError::EndOfFile) => break,
+                Err(TdmsError::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e),
+            };
+            if segment.next_segment_offset == INCOMPLETE_SEGMENT_OFFSET
+                || position + segment.total_size_bytes()? > file_length
+            {
+                break;
+            }
+            position += segment.total_size_bytes()?;
+            segments.push(segment.clone());
+            scanner.seek_to(position)?;
+        }
+
+        // Leave the index alone if nothing changed so a pinned epoch isn't copied.
+        let snapshot_end = self.index.snapshot_end_within(file_length);
+        if segments.is_empty() && self.index.snapshot_end() == Some(snapshot_end) {
+            return Ok(0);
+        }
+        self.generation += 1;
+        let index = Arc::make_mut(&mut self.index);
+        for segment in &segments {
+            index.add_segment(segment)?;
+        }
+        index.update_snapshot(file_length);
+        Ok(segments.len() as u64)
+    }
+}
+
+impl<F: Read + Seek + Write + Debug> TdmsFile<F> {
+    /// Create a cursor at the start of the channel for use with [`Self::read_new`].
+    pub fn append_cursor(&self, channel: &ChannelPath) -> ChannelCursor {
+        ChannelCursor {
+            channel: channel.clone(),
+            position: 0,
+        }
+    }
+
+    /// Read the samples added to the channel since the cursor was last used.
+    ///
+    /// Call [`Self::refresh`] first to pick up data written by other processes.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let mut cursor = file.append_cursor(&channel);
+    ///
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[1.0, 2.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    /// assert_eq!(file.read_new::<f64>(&mut cursor).unwrap(), vec![1.0, 2.0]);
+    ///
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[3.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    /// assert_eq!(file.read_new::<f64>(&mut cursor).unwrap(), vec![3.0]);
+    /// assert!(file.read_new::<f64>(&mut cursor).unwrap().is_empty());
+    /// ```
+    pub fn read_new<D: TdmsStorageType + Default + Clone>(
+        &mut self,
+        cursor: &mut ChannelCursor,
+    ) -> Result<Vec<D>, TdmsError> {
+        let length = self.channel_length(&cursor.channel).unwrap_or(0);
+        let new_samples = usize_from(length.saturating_sub(cursor.position))?;
+        let mut output = vec![D::default(); new_samples];
+        if new_samples > 0 {
+            let outcome = self.read_range(&cursor.channel, cursor.position, &mut output)?;
+            output.truncate(outcome.samples_read);
+        }
+        cursor.position += output.len() as u64;
+        Ok(output)
+    }
+}
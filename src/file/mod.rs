@@ -1,22 +1,171 @@
 //! The file module provides the public API for a TDMS file.
 
+mod aligned_read;
+pub(crate) mod bench;
+mod block_decode;
 mod channel_reader;
+mod concat;
+#[cfg(feature = "export")]
+mod csv_follow;
+mod dead_band;
+mod diadem;
+#[cfg(feature = "direct-io")]
+mod direct_io;
+mod dyn_read;
+#[cfg(feature = "encryption")]
+mod encrypted;
+mod epoch;
+mod events;
+#[cfg(feature = "write")]
 mod file_writer;
-
+#[cfg(feature = "http")]
+mod http_source;
+mod live;
+#[cfg(feature = "write")]
+mod naming;
+#[cfg(feature = "object-store")]
+mod object_source;
+mod os_path;
+#[cfg(feature = "analysis")]
+mod overview;
+mod parallelism;
+#[cfg(feature = "parquet")]
+mod parquet_follow;
+mod peek;
+mod plan;
+mod quality;
+mod range_reader;
+#[cfg(feature = "write")]
+mod repack;
+#[cfg(feature = "analysis")]
+mod resample;
+#[cfg(feature = "write")]
+mod retention;
+mod retry;
+#[cfg(feature = "write")]
+mod rewrite;
+#[cfg(feature = "write")]
+mod sample_source;
+mod schema;
+mod scoped_read;
+#[cfg(feature = "write")]
+mod segment_builder;
+#[cfg(feature = "write")]
+mod shared_writer;
+#[cfg(feature = "write")]
+mod spill;
+#[cfg(feature = "analysis")]
+mod stats;
+#[cfg(feature = "write")]
+mod stream_writer;
+#[cfg(feature = "write")]
+mod strict;
+#[cfg(feature = "analysis")]
+mod summary;
+mod template;
+#[cfg(feature = "analysis")]
+mod time_sync;
+#[cfg(feature = "analysis")]
+mod time_track;
+mod uninit;
+#[cfg(feature = "analysis")]
+mod units;
+mod visit;
+#[cfg(feature = "watch")]
+mod watch;
+mod window;
+
+#[cfg(feature = "write")]
+use std::io::Write;
 use std::{
     fs::File,
-    io::{Read, Seek, SeekFrom, Write},
+    io::{Read, Seek, SeekFrom},
     path::Path,
+    sync::Arc,
 };
 
-use crate::meta_data::Segment;
+use labview_interop::types::LVTime;
+
+#[cfg(feature = "write")]
+use crate::io::writer::{LittleEndianWriter, TdmsWriter};
+use crate::meta_data::SegmentScanner;
+#[cfg(feature = "parallel")]
+use crate::meta_data::LEAD_IN_BYTES;
 use crate::{error::TdmsError, PropertyPath, PropertyValue};
-use crate::{index::Index, ChannelPath};
 use crate::{
-    io::writer::{LittleEndianWriter, TdmsWriter},
-    paths::path_group_name,
+    index::{DataBlockDebug, FileStats, Index, SegmentRecord, UnsupportedChannel},
+    ChannelPath, GroupPath, ObjectPath,
+};
+pub use aligned_read::{AlignedGroup, GroupAlignment};
+pub use block_decode::{BlockDecoder, RawBlock, RawChannel, SampleSpan};
+pub use channel_reader::{ReadOutcome, ReadPastEnd};
+pub use concat::ConcatenatedChannel;
+#[cfg(feature = "export")]
+pub use csv_follow::CsvFollower;
+pub use diadem::{DiademChannelProperties, NI_CHANNEL_LENGTH, NI_DATA_TYPE};
+#[cfg(feature = "direct-io")]
+pub use direct_io::DirectFile;
+pub use dyn_read::{SampleBuffer, SampleVec};
+#[cfg(feature = "encryption")]
+pub use encrypted::EncryptedFile;
+pub use epoch::IndexEpoch;
+#[cfg(feature = "write")]
+pub use file_writer::{
+    CompatibilityProfile, FlushPolicy, SegmentLimit, TdmsFileWriter, WriteBuffer, WriteObserver,
+    WrittenSegment, WALL_CLOCK_PROPERTY,
 };
-pub use file_writer::TdmsFileWriter;
+#[cfg(feature = "http")]
+pub use http_source::HttpSource;
+pub use live::ChannelCursor;
+#[cfg(feature = "write")]
+pub use naming::FileNameTemplate;
+#[cfg(feature = "object-store")]
+pub use object_source::ObjectStoreSource;
+use os_path::os_path;
+#[cfg(feature = "analysis")]
+pub use overview::{ChannelOverview, MinMax, Overview, OverviewConfig};
+pub use parallelism::Parallelism;
+#[cfg(feature = "parquet")]
+pub use parquet_follow::{follow_to_parquet, ParquetFollower};
+pub use peek::peek_properties;
+pub use plan::{PlanOutcome, PlannedBlock, ReadBudget, ReadPlan};
+pub use quality::{quality_channel, QUALITY_SUFFIX};
+pub use range_reader::{RangeReader, RangeSource};
+#[cfg(feature = "write")]
+pub use repack::{repack, RangePolicy, RepackOptions};
+#[cfg(feature = "analysis")]
+pub use resample::ResampleMethod;
+#[cfg(feature = "write")]
+pub use retention::RetentionPolicy;
+pub use retry::RetryPolicy;
+#[cfg(feature = "write")]
+pub use rewrite::{extract, redact, Redaction};
+#[cfg(feature = "write")]
+pub use sample_source::SampleSource;
+pub use schema::{compare_schema, ChannelChange, SchemaDiff};
+#[cfg(feature = "write")]
+pub use segment_builder::{SegmentBuilder, SegmentRawIndex};
+#[cfg(feature = "write")]
+pub use shared_writer::{IndexView, OverflowPolicy, SharedTdmsWriter, TdmsProducer, WriterMetrics};
+#[cfg(feature = "write")]
+pub use spill::{SpillBuffer, SpillOutput};
+#[cfg(feature = "analysis")]
+pub use stats::{WindowStats, WindowedStats};
+#[cfg(feature = "write")]
+pub use stream_writer::TdmsStreamWriter;
+#[cfg(feature = "analysis")]
+pub use summary::{ChannelSummary, GroupSummary};
+pub use template::{FileTemplate, TemplateViolation};
+#[cfg(feature = "analysis")]
+pub use time_sync::{SyncPolicy, SyncedSample, TimeSyncedIter};
+#[cfg(feature = "analysis")]
+pub use time_track::TimeTrackSection;
+#[cfg(feature = "analysis")]
+pub use units::UnitConversion;
+pub use visit::BlockInfo;
+#[cfg(feature = "watch")]
+pub use watch::{TdmsWatcher, WatchEvent};
+pub use window::ByteWindow;
 
 /// A TDMS file.
 ///
@@ -29,50 +178,159 @@ pub use file_writer::TdmsFileWriter;
 /// To write to a file use [`Self::writer`]. This will return a writer that can be used to write data to the file.
 #[derive(Debug)]
 pub struct TdmsFile<F: Read + Seek> {
-    index: Index,
+    /// The index is only cloned on change while an [`IndexEpoch`] pins it.
+    index: Arc<Index>,
+    /// Counts the changes to the index.
+    generation: u64,
     file: F,
+    retry: RetryPolicy,
+    parallelism: Parallelism,
 }
 
 impl TdmsFile<File> {
     /// Load the file from the path. This step will load and index the metadata
     /// ready for access.
     pub fn load(path: &Path) -> Result<Self, TdmsError> {
-        let file = File::options().read(true).write(true).open(path)?;
+        let file = File::options().read(true).write(true).open(os_path(path))?;
         Self::new(file)
     }
 
+    /// Load the file from the path, indexing it with the threads set by `parallelism`.
+    ///
+    /// The setting is kept for later parallel operations on the file.
+    pub fn load_with_parallelism(path: &Path, parallelism: Parallelism) -> Result<Self, TdmsError> {
+        let file = File::options().read(true).write(true).open(os_path(path))?;
+        Self::new_with_parallelism(file, parallelism)
+    }
+
     /// Create a new file at the path. This will replace any existing file at the path.
+    #[cfg(feature = "write")]
     pub fn create(path: &Path) -> Result<Self, TdmsError> {
         let file = File::options()
             .write(true)
             .create(true)
+            .truncate(true)
             .read(true)
-            .open(path)?;
+            .open(os_path(path))?;
         Self::new(file)
     }
 }
 
-fn build_index(file: &mut (impl Read + Seek)) -> Result<Index, TdmsError> {
+fn build_index(
+    file: &mut (impl Read + Seek),
+    parallelism: &Parallelism,
+) -> Result<Index, TdmsError> {
+    #[cfg(feature = "parallel")]
+    return build_index_parallel(file, parallelism);
+    #[cfg(not(feature = "parallel"))]
+    let _ = parallelism;
+    #[cfg(not(feature = "parallel"))]
+    return build_index_sequential(file);
+}
+
+/// Build the index by reading each segment in turn.
+#[cfg_attr(feature = "parallel", allow(dead_code))]
+fn build_index_sequential(file: &mut (impl Read + Seek)) -> Result<Index, TdmsError> {
     let mut index = Index::new();
 
     //Make sure we are at the beginning.
     file.seek(SeekFrom::Start(0))?;
 
+    let mut scanner = SegmentScanner::new(file);
+    let mut segment_number = 0;
+    let mut position = 0;
+
     loop {
-        match Segment::read(file) {
+        match scanner.read_segment() {
             Ok(segment) => {
-                let next_segment = index.add_segment(segment)?;
-                if file.seek(SeekFrom::Start(next_segment)).is_err() {
+                let next_segment = index
+                    .add_segment(segment)
+                    .map_err(|e| e.in_segment(segment_number, position))?;
+                segment_number += 1;
+                position = next_segment;
+                if scanner.seek_to(next_segment).is_err() {
                     break;
                 }
             }
             Err(TdmsError::EndOfFile) => break,
-            Err(e) => return Err(e),
+            Err(e) => return Err(e.in_segment(segment_number, position)),
         }
     }
     Ok(index)
 }
 
+/// Build the index in two phases.
+///
+/// 1. Walk the lead ins sequentially to find each segment and copy out its metadata.
+/// 2. Decode the metadata in parallel and merge the segments into the index in file order.
+///
+/// The decoding runs in a dedicated pool unless `parallelism` is the default.
+#[cfg(feature = "parallel")]
+fn build_index_parallel(
+    file: &mut (impl Read + Seek),
+    parallelism: &Parallelism,
+) -> Result<Index, TdmsError> {
+    use rayon::prelude::*;
+
+    //Make sure we are at the beginning.
+    file.seek(SeekFrom::Start(0))?;
+
+    let mut scanner = SegmentScanner::new(file);
+    let mut raw_segments = Vec::new();
+    let mut position = 0u64;
+
+    loop {
+        let segment_number = raw_segments.len() as u64;
+        let lead_in = match scanner.read_lead_in() {
+            Ok(lead_in) => lead_in,
+            Err(TdmsError::EndOfFile) => break,
+            Err(e) => return Err(e.in_segment(segment_number, position)),
+        };
+        let meta_data_bytes = scanner
+            .read_meta_data_bytes(&lead_in)
+            .map_err(|e| e.in_segment(segment_number, position))?;
+        raw_segments.push((position, lead_in, meta_data_bytes));
+
+        // Overflowing addresses are reported when the segment is added to the index.
+        let Some(next_segment) = position
+            .checked_add(LEAD_IN_BYTES)
+            .and_then(|start| start.checked_add(lead_in.next_segment_offset))
+        else {
+            break;
+        };
+        position = next_segment;
+        if scanner.seek_to(position).is_err() {
+            break;
+        }
+    }
+
+    let decode = || {
+        raw_segments
+            .par_iter()
+            .enumerate()
+            .map(|(segment_number, (position, lead_in, meta_data_bytes))| {
+                lead_in
+                    .decode_segment(meta_data_bytes)
+                    .map_err(|e| e.in_segment(segment_number as u64, *position))
+            })
+            .collect::<Result<Vec<_>, _>>()
+    };
+    let segments = match parallelism.thread_pool()? {
+        Some(pool) => pool.install(decode),
+        None => decode(),
+    }?;
+
+    let mut index = Index::new();
+    for (segment_number, (segment, (position, ..))) in
+        segments.iter().zip(&raw_segments).enumerate()
+    {
+        index
+            .add_segment(segment)
+            .map_err(|e| e.in_segment(segment_number as u64, *position))?;
+    }
+    Ok(index)
+}
+
 impl<F: Read + Seek> TdmsFile<F> {
     /// Create a new file from the given stream.
     ///
@@ -82,9 +340,35 @@ impl<F: Read + Seek> TdmsFile<F> {
     /// let mut fake_file = std::io::Cursor::new(vec![]);
     /// let file = TdmsFile::new(fake_file);
     /// ```
-    pub fn new(mut file: F) -> Result<Self, TdmsError> {
-        let index = build_index(&mut file)?;
-        Ok(Self { index, file })
+    pub fn new(file: F) -> Result<Self, TdmsError> {
+        Self::new_with_parallelism(file, Parallelism::default())
+    }
+
+    /// Create a new file from the given stream, indexing it with the threads set by `parallelism`.
+    ///
+    /// The setting is kept for later parallel operations on the file.
+    pub fn new_with_parallelism(mut file: F, parallelism: Parallelism) -> Result<Self, TdmsError> {
+        let mut index = build_index(&mut file, &parallelism)?;
+        index.update_snapshot(file.seek(SeekFrom::End(0))?);
+        Ok(Self {
+            index: Arc::new(index),
+            generation: 0,
+            file,
+            retry: RetryPolicy::default(),
+            parallelism,
+        })
+    }
+
+    /// Set how data block reads are retried after transient IO errors.
+    ///
+    /// By default reads are not retried.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry = policy;
+    }
+
+    /// Set the threads used by later parallel operations such as [`Self::read_channels_parallel`].
+    pub fn set_parallelism(&mut self, parallelism: Parallelism) {
+        self.parallelism = parallelism;
     }
 
     /// Read the property by name from the full object path.
@@ -108,6 +392,39 @@ impl<F: Read + Seek> TdmsFile<F> {
         self.index.get_object_property(object_path, property)
     }
 
+    /// Read a property which must exist.
+    ///
+    /// Errors with [`TdmsError::MissingProperty`] if the object doesn't have the
+    /// property, or [`TdmsError::MissingGroup`] or [`TdmsError::MissingChannel`]
+    /// if the object itself is missing. Each lists the nearest existing names.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{PropertyPath, PropertyValue, TdmsError, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let mut writer = file.writer().unwrap();
+    /// writer
+    ///     .write_properties(&PropertyPath::group("run"), &[("operator", PropertyValue::from("Ada"))])
+    ///     .unwrap();
+    /// drop(writer);
+    ///
+    /// let error = file.require_property(&PropertyPath::group("run"), "Operator").unwrap_err();
+    /// let TdmsError::MissingProperty { suggestions, .. } = error else { panic!() };
+    /// assert_eq!(suggestions, vec!["operator".to_string()]);
+    /// ```
+    pub fn require_property(
+        &self,
+        object_path: &PropertyPath,
+        property: &str,
+    ) -> Result<&PropertyValue, TdmsError> {
+        self.index
+            .get_object_property(object_path, property)?
+            .ok_or_else(|| self.index.missing_property(object_path, property))
+    }
+
     /// Read all properties for the given object path.
     ///
     /// This returns a vector of tuples of the property name and value.
@@ -118,25 +435,169 @@ impl<F: Read + Seek> TdmsFile<F> {
         self.index.get_object_properties(object_path)
     }
 
+    /// Find every object which has the property `name` with a value matching the predicate.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, PropertyPath, PropertyValue, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let mut writer = file.writer().unwrap();
+    /// let voltage = PropertyPath::channel("group", "voltage");
+    /// writer.write_properties(&voltage, &[("unit_string", PropertyValue::from("V"))]).unwrap();
+    /// drop(writer);
+    ///
+    /// let volts = file.find_objects_with_property("unit_string", |unit| unit.as_str() == Some("V"));
+    /// assert_eq!(volts, vec![voltage]);
+    /// ```
+    pub fn find_objects_with_property(
+        &self,
+        name: &str,
+        predicate: impl Fn(&PropertyValue) -> bool,
+    ) -> Vec<PropertyPath> {
+        self.index
+            .find_objects_with_property(name, predicate)
+            .filter_map(|path| PropertyPath::try_from(path).ok())
+            .collect()
+    }
+
+    /// The byte offset in the file that reads are bounded by.
+    ///
+    /// This is the end of the last complete segment when the file was opened or
+    /// last refreshed with [`Self::refresh`], extended by anything written through
+    /// [`Self::writer`]. Data written past it by another process isn't read.
+    pub fn snapshot_end(&self) -> u64 {
+        self.index.snapshot_end().unwrap_or_default()
+    }
+
+    /// Get statistics about the structure of the file.
+    ///
+    /// This includes the segment counts, layouts and data types, and how fragmented the file is.
+    pub fn file_stats(&self) -> FileStats {
+        self.index.file_stats()
+    }
+
+    /// The position, size and channels of every segment in file order.
+    ///
+    /// This shows where the file is fragmented and how much of it is metadata.
+    /// Enable the `serde` feature to export the records.
+    pub fn segment_records(&self) -> &[SegmentRecord] {
+        self.index.segment_records()
+    }
+
+    /// Render the objects and data blocks of the file as a Graphviz DOT graph.
+    ///
+    /// Each object shows its path and number of properties and links to its
+    /// parent. Channels link to every data block holding their data. This is
+    /// meant for attaching to issues about odd files, render it with
+    /// `dot -Tsvg`.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let mut writer = file.writer().unwrap();
+    /// writer
+    ///     .write_channels(&[ChannelPath::new("group", "channel")], &[1.0], DataLayout::Contigious)
+    ///     .unwrap();
+    /// drop(writer);
+    ///
+    /// let dot = file.structure_dot();
+    /// assert!(dot.starts_with("digraph tdms {"));
+    /// assert!(dot.contains("block 0"));
+    /// ```
+    pub fn structure_dot(&self) -> String {
+        self.index.to_dot()
+    }
+
+    /// The number of data blocks in the file.
+    pub fn data_block_count(&self) -> usize {
+        self.index.data_block_count()
+    }
+
+    /// The sample of the channel each of its data blocks starts at.
+    ///
+    /// The first block always starts at 0. A new block usually means a new
+    /// write, so these are where to look for acquisition restarts or
+    /// discontinuities in the timestamps. Returns None if the channel does not exist.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[1.0, 2.0, 3.0], DataLayout::Contigious).unwrap();
+    /// writer.write_channels(&[&channel], &[4.0, 5.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// assert_eq!(file.channel_block_boundaries(&channel), Some(vec![0, 3]));
+    /// ```
+    pub fn channel_block_boundaries(&self, channel: &ChannelPath) -> Option<Vec<u64>> {
+        self.index.channel_block_starts(channel)
+    }
+
+    /// Get the segment lead in details for the data block at `index`.
+    ///
+    /// Data blocks are numbered in file order from 0 to [`Self::data_block_count`].
+    pub fn data_block_debug(&self, index: usize) -> Option<&DataBlockDebug> {
+        self.index.get_data_block_debug(index)
+    }
+
+    /// The latest timestamp property written at or before the data block at `index`.
+    ///
+    /// Loggers often record the time of each flush as a timestamp property, or
+    /// call [`TdmsFileWriter::write_wall_clock`]. The value of the most recent
+    /// timestamp property on any object, up to and including the segment
+    /// holding the block, is kept for every block as an audit trail of when it
+    /// was written. None if no timestamp property comes before the block or
+    /// the block doesn't exist.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use labview_interop::types::LVTime;
+    /// use tedium::{ChannelPath, DataLayout, PropertyPath, PropertyValue, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let flushed = LVTime::from_unix_epoch(1_700_000_000.0);
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[1.0], DataLayout::Contigious).unwrap();
+    /// writer
+    ///     .write_properties(&PropertyPath::file(), &[("flushed", PropertyValue::Timestamp(flushed))])
+    ///     .unwrap();
+    /// writer.write_channels(&[&channel], &[2.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// assert_eq!(file.block_written_at(0), None);
+    /// assert_eq!(file.block_written_at(1), Some(flushed));
+    /// ```
+    pub fn block_written_at(&self, index: usize) -> Option<LVTime> {
+        self.index.get_data_block_written_at(index)
+    }
+
+    /// The channels with raw data that couldn't be decoded, such as DAQmx raw data
+    /// or an unknown data type, with the type code found in the file.
+    ///
+    /// These channels still have their properties but no data can be read.
+    pub fn unsupported_channels(&self) -> impl Iterator<Item = &UnsupportedChannel> {
+        self.index.unsupported_channels()
+    }
+
     /// Read all groups in the file.
     ///
     /// Returns an iterator to the paths for each group.
-    pub fn list_groups<'a>(&'a self) -> impl Iterator<Item = PropertyPath> + 'a {
-        // We cannot guarantee a seperate path for the group has been written
-        // as they are implicitly included in the channel path as well.
-        // Therefore extract all possible group names from all paths and deduplicate.
-        // Use a btreeset to deduplicate the paths.
-        let mut groups = std::collections::BTreeSet::new();
-
-        let paths = self.index.all_paths();
-        for path in paths {
-            let group_name = path_group_name(path);
-            if let Some(group_name) = group_name {
-                groups.insert(group_name);
-            }
-        }
-
-        groups.into_iter().map(PropertyPath::group)
+    pub fn list_groups<'a>(&'a self) -> impl Iterator<Item = GroupPath> + 'a {
+        // Groups are also implied by channel paths so the index tracks these
+        // as well as groups written with their own object.
+        self.index.group_names().map(GroupPath::new)
     }
 
     /// Read all the channels in a group.
@@ -144,13 +605,18 @@ impl<F: Read + Seek> TdmsFile<F> {
     /// Returns an iterator to the paths for each channel.
     pub fn list_channels_in_group<'a: 'c, 'b: 'c, 'c>(
         &'a self,
-        group: &'b PropertyPath,
+        group: &'b GroupPath,
     ) -> impl Iterator<Item = ChannelPath> + 'c {
-        let paths = self.index.paths_starting_with(group.path());
-        paths.filter_map(|path| ChannelPath::try_from(path).ok())
+        self.index.channels_in_group(group)
+    }
+
+    /// Read the paths of every object in the file, including the file itself if it has properties.
+    pub fn list_objects(&self) -> impl Iterator<Item = ObjectPath> + '_ {
+        self.index.objects()
     }
 }
 
+#[cfg(feature = "write")]
 impl<F: Write + Read + Seek> TdmsFile<F> {
     /// Get a writer for the TDMS data so that you can write data.
     ///
@@ -175,17 +641,52 @@ impl<F: Write + Read + Seek> TdmsFile<F> {
     /// drop(writer);
     ///
     /// file.read_channel(&ChannelPath::new("group", "channel"), &mut [0.0f64; 3]).unwrap();
-    pub fn writer(&mut self) -> Result<TdmsFileWriter<F, LittleEndianWriter<&mut F>>, TdmsError> {
+    pub fn writer(
+        &mut self,
+    ) -> Result<TdmsFileWriter<'_, F, LittleEndianWriter<&mut F>>, TdmsError> {
         //make sure we are at the end.
         self.file.seek(SeekFrom::End(0))?;
+        self.generation += 1;
         Ok(TdmsFileWriter::new(
-            &mut self.index,
+            Arc::make_mut(&mut self.index),
             LittleEndianWriter::from_writer(&mut self.file),
         ))
     }
+
+    /// Get a writer using the given buffer size and flush policy.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tedium::{ChannelPath, DataLayout, FlushPolicy, TdmsFile, WriteBuffer};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let mut writer = file
+    ///     .writer_with_buffer(WriteBuffer {
+    ///         capacity: 4 * 1024 * 1024,
+    ///         flush: FlushPolicy::EverySegment,
+    ///     })
+    ///     .unwrap();
+    /// writer
+    ///     .write_channels(&[ChannelPath::new("group", "channel")], &[1.0], DataLayout::Contigious)
+    ///     .unwrap();
+    /// ```
+    pub fn writer_with_buffer(
+        &mut self,
+        buffer: WriteBuffer,
+    ) -> Result<TdmsFileWriter<'_, F, LittleEndianWriter<&mut F>>, TdmsError> {
+        self.file.seek(SeekFrom::End(0))?;
+        self.generation += 1;
+        let mut writer = TdmsFileWriter::new(
+            Arc::make_mut(&mut self.index),
+            LittleEndianWriter::with_capacity(buffer.capacity, &mut self.file),
+        );
+        writer.set_flush_policy(buffer.flush);
+        Ok(writer)
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "write"))]
 mod tests {
 
     use std::io::Cursor;
@@ -200,14 +701,70 @@ mod tests {
         TdmsFile::new(cursor).unwrap()
     }
 
+    #[test]
+    fn test_channel_block_boundaries_skip_other_channels() {
+        let mut file = new_empty_file();
+        let first = ChannelPath::new("group", "first");
+        let second = ChannelPath::new("group", "second");
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&first], &[1u8, 2], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_channels(&[&second], &[1u8], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_channels(&[&first, &second], &[3u8, 4, 2, 3], DataLayout::Interleaved)
+            .unwrap();
+        drop(writer);
+
+        assert_eq!(file.channel_block_boundaries(&first), Some(vec![0, 2]));
+        assert_eq!(file.channel_block_boundaries(&second), Some(vec![0, 1]));
+        assert_eq!(
+            file.channel_block_boundaries(&ChannelPath::new("group", "missing")),
+            None
+        );
+    }
+
     #[test]
     fn test_can_load_empty_buffer() {
         let buffer = Vec::new();
         let mut cursor = Cursor::new(buffer);
-        let result = build_index(&mut cursor);
+        let result = build_index(&mut cursor, &Parallelism::default());
         assert!(result.is_ok());
     }
 
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_index_matches_sequential() {
+        let mut file = new_empty_file();
+        let mut writer = file.writer().unwrap();
+        for index in 0..20 {
+            writer
+                .write_channels(
+                    &[ChannelPath::new("group", "channel")],
+                    &[1.0, 2.0, 3.0],
+                    DataLayout::Contigious,
+                )
+                .unwrap();
+            if index % 3 == 0 {
+                writer
+                    .write_properties(
+                        &PropertyPath::group("group"),
+                        &[("count", PropertyValue::I32(index))],
+                    )
+                    .unwrap();
+            }
+        }
+        drop(writer);
+
+        let sequential = build_index_sequential(&mut file.file).unwrap();
+        let parallel = build_index_parallel(&mut file.file, &Parallelism::default()).unwrap();
+        assert_eq!(format!("{sequential:?}"), format!("{parallel:?}"));
+        let pooled = build_index_parallel(&mut file.file, &Parallelism::threads(2)).unwrap();
+        assert_eq!(format!("{sequential:?}"), format!("{pooled:?}"));
+    }
+
     #[test]
     fn test_list_groups_with_properties_single() {
         let mut file = new_empty_file();
@@ -223,7 +780,7 @@ mod tests {
         drop(writer);
         let groups: Vec<_> = file.list_groups().collect();
         assert_eq!(groups.len(), 1);
-        assert_eq!(groups[0], PropertyPath::group("group"));
+        assert_eq!(groups[0], GroupPath::new("group"));
     }
 
     #[test]
@@ -247,8 +804,8 @@ mod tests {
         drop(writer);
         let groups: Vec<_> = file.list_groups().collect();
         assert_eq!(groups.len(), 2);
-        assert_eq!(groups[0], PropertyPath::group("group"));
-        assert_eq!(groups[1], PropertyPath::group("group2"));
+        assert_eq!(groups[0], GroupPath::new("group"));
+        assert_eq!(groups[1], GroupPath::new("group2"));
     }
 
     #[test]
@@ -267,7 +824,7 @@ mod tests {
         drop(writer);
         let groups: Vec<_> = file.list_groups().collect();
         assert_eq!(groups.len(), 1);
-        assert_eq!(groups[0], PropertyPath::group("group"));
+        assert_eq!(groups[0], GroupPath::new("group"));
     }
 
     #[test]
@@ -285,7 +842,7 @@ mod tests {
 
         drop(writer);
         let channels: Vec<_> = file
-            .list_channels_in_group(&PropertyPath::group("group"))
+            .list_channels_in_group(&GroupPath::new("group"))
             .collect();
         assert_eq!(channels.len(), 1);
         assert_eq!(channels[0], ChannelPath::new("group", "channel"));
@@ -313,7 +870,7 @@ mod tests {
 
         drop(writer);
         let channels: Vec<_> = file
-            .list_channels_in_group(&PropertyPath::group("group"))
+            .list_channels_in_group(&GroupPath::new("group"))
             .collect();
         assert_eq!(channels.len(), 2);
         assert_eq!(channels[0], ChannelPath::new("group", "channel"));
@@ -335,8 +892,72 @@ mod tests {
 
         drop(writer);
         let channels: Vec<_> = file
-            .list_channels_in_group(&PropertyPath::group("group2"))
+            .list_channels_in_group(&GroupPath::new("group2"))
             .collect();
         assert_eq!(channels.len(), 0);
     }
+
+    #[test]
+    fn test_list_channels_in_group_ignores_groups_sharing_prefix() {
+        let mut file = new_empty_file();
+
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(
+                &[
+                    ChannelPath::new("group", "channel"),
+                    ChannelPath::new("group2", "channel"),
+                ],
+                &[1.0, 2.0],
+                DataLayout::Contigious,
+            )
+            .unwrap();
+
+        drop(writer);
+        let channels: Vec<_> = file
+            .list_channels_in_group(&GroupPath::new("group"))
+            .collect();
+        assert_eq!(channels, vec![ChannelPath::new("group", "channel")]);
+
+        let objects: Vec<_> = file.list_objects().collect();
+        assert_eq!(
+            objects,
+            vec![
+                ObjectPath::Channel(ChannelPath::new("group", "channel")),
+                ObjectPath::Channel(ChannelPath::new("group2", "channel")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_block_written_at_follows_wall_clock() {
+        let mut file = new_empty_file();
+        let channel = ChannelPath::new("group", "channel");
+
+        let mut writer = file.writer().unwrap();
+        writer.write_wall_clock().unwrap();
+        writer
+            .write_channels(&[&channel], &[1.0], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_channels(&[&channel], &[2.0], DataLayout::Contigious)
+            .unwrap();
+        writer.write_wall_clock().unwrap();
+        writer
+            .write_channels(&[&channel], &[3.0], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+
+        let stamp = |name| {
+            file.read_property(&PropertyPath::file(), name)
+                .unwrap()
+                .and_then(PropertyValue::as_timestamp)
+        };
+        let latest = stamp(WALL_CLOCK_PROPERTY).unwrap();
+        let first = file.block_written_at(0).unwrap();
+        assert_eq!(file.block_written_at(1), Some(first));
+        assert_eq!(file.block_written_at(2), Some(latest));
+        assert!(first <= latest);
+        assert_eq!(file.block_written_at(3), None);
+    }
 }
@@ -0,0 +1,246 @@
+//! Time based file names for long running loggers.
+//!
+//! A [`FileNameTemplate`] turns a pattern such as `{date}/{hour}/run_{seq}.tdms`
+//! into a path for a given time and sequence number, so each new file can be
+//! placed in a dated folder structure.
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::os_path::os_path;
+use crate::error::TdmsError;
+use crate::TdmsFile;
+
+/// A part of the parsed template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Date,
+    Time,
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    Sequence,
+}
+
+/// A template for the path of each file written by a logger.
+///
+/// The placeholders are replaced using the UTC time:
+///
+/// * `{date}`: `YYYY-MM-DD`.
+/// * `{time}`: `HHMMSS`.
+/// * `{year}`, `{month}`, `{day}`, `{hour}`, `{minute}` and `{second}`: zero padded parts of the time.
+/// * `{seq}`: the sequence number of the file.
+///
+/// Use `/` to separate directories on every platform.
+///
+/// # Example
+/// ```rust
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use tedium::FileNameTemplate;
+///
+/// let template = FileNameTemplate::new("{date}/{hour}/run_{seq}.tdms").unwrap();
+/// let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+/// assert_eq!(
+///     template.render(time, 3),
+///     std::path::PathBuf::from("2023-11-14").join("22").join("run_3.tdms")
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileNameTemplate {
+    parts: Vec<Part>,
+}
+
+impl FileNameTemplate {
+    /// Parse the template.
+    ///
+    /// Errors with [`TdmsError::InvalidFileTemplate`] for unknown or unclosed placeholders.
+    pub fn new(template: &str) -> Result<Self, TdmsError> {
+        let mut parts = Vec::new();
+        let mut rest = template;
+
+        while let Some(open) = rest.find('{') {
+            if open > 0 {
+                parts.push(Part::Text(rest[..open].to_string()));
+            }
+            let close = rest[open..]
+                .find('}')
+                .ok_or_else(|| TdmsError::InvalidFileTemplate(template.to_string()))?
+                + open;
+            let part = match &rest[open + 1..close] {
+                "date" => Part::Date,
+                "time" => Part::Time,
+                "year" => Part::Year,
+                "month" => Part::Month,
+                "day" => Part::Day,
+                "hour" => Part::Hour,
+                "minute" => Part::Minute,
+                "second" => Part::Second,
+                "seq" => Part::Sequence,
+                _ => return Err(TdmsError::InvalidFileTemplate(template.to_string())),
+            };
+            parts.push(part);
+            rest = &rest[close + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Text(rest.to_string()));
+        }
+
+        Ok(Self { parts })
+    }
+
+    /// The relative path for a file started at `time` with sequence number `sequence`.
+    pub fn render(&self, time: SystemTime, sequence: u64) -> PathBuf {
+        let time = UtcTime::from_system_time(time);
+        let mut name = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => name.push_str(text),
+                Part::Date => name.push_str(&format!(
+                    "{:04}-{:02}-{:02}",
+                    time.year, time.month, time.day
+                )),
+                Part::Time => name.push_str(&format!(
+                    "{:02}{:02}{:02}",
+                    time.hour, time.minute, time.second
+                )),
+                Part::Year => name.push_str(&format!("{:04}", time.year)),
+                Part::Month => name.push_str(&format!("{:02}", time.month)),
+                Part::Day => name.push_str(&format!("{:02}", time.day)),
+                Part::Hour => name.push_str(&format!("{:02}", time.hour)),
+                Part::Minute => name.push_str(&format!("{:02}", time.minute)),
+                Part::Second => name.push_str(&format!("{:02}", time.second)),
+                Part::Sequence => name.push_str(&sequence.to_string()),
+            }
+        }
+        name.split('/').filter(|part| !part.is_empty()).collect()
+    }
+
+    /// Create the file for `time` and `sequence` under `root`, creating any directories needed.
+    ///
+    /// This will replace any existing file at the path.
+    pub fn create(
+        &self,
+        root: &Path,
+        time: SystemTime,
+        sequence: u64,
+    ) -> Result<(PathBuf, TdmsFile<File>), TdmsError> {
+        let path = root.join(self.render(time, sequence));
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(os_path(parent))?;
+        }
+        let file = TdmsFile::create(&path)?;
+        Ok((path, file))
+    }
+}
+
+/// The calendar parts of a UTC time.
+struct UtcTime {
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+}
+
+impl UtcTime {
+    fn from_system_time(time: SystemTime) -> Self {
+        let seconds = match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs() as i64,
+            Err(before) => -(before.duration().as_secs_f64().ceil() as i64),
+        };
+        let days = seconds.div_euclid(86_400);
+        let seconds_of_day = seconds.rem_euclid(86_400) as u32;
+
+        // Convert days since the epoch to a civil date in the proleptic Gregorian calendar.
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        Self {
+            year,
+            month,
+            day,
+            hour: seconds_of_day / 3600,
+            minute: seconds_of_day % 3600 / 60,
+            second: seconds_of_day % 60,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn test_calendar_dates() {
+        let template = FileNameTemplate::new("{date}T{hour}:{minute}:{second}").unwrap();
+        let cases = [
+            (0, "1970-01-01T00:00:00"),
+            (951_782_400, "2000-02-29T00:00:00"),
+            (1_709_251_199, "2024-02-29T23:59:59"),
+            (4_102_444_800, "2100-01-01T00:00:00"),
+        ];
+        for (seconds, expected) in cases {
+            assert_eq!(template.render(at(seconds), 0), PathBuf::from(expected));
+        }
+    }
+
+    #[test]
+    fn test_all_placeholders() {
+        let template = FileNameTemplate::new("{year}/{month}/{day}/log_{time}_{seq}.tdms").unwrap();
+        assert_eq!(
+            template.render(at(1_700_000_000), 42),
+            PathBuf::from("2023/11/14/log_221320_42.tdms")
+        );
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        assert!(matches!(
+            FileNameTemplate::new("{date}/{unknown}.tdms"),
+            Err(TdmsError::InvalidFileTemplate(_))
+        ));
+        assert!(matches!(
+            FileNameTemplate::new("{date.tdms"),
+            Err(TdmsError::InvalidFileTemplate(_))
+        ));
+    }
+
+    #[test]
+    fn test_create_makes_directories() {
+        let mut root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        root.push("target");
+        root.push(format!("naming_{}", std::process::id()));
+
+        let template = FileNameTemplate::new("{date}/{hour}/run_{seq}.tdms").unwrap();
+        let (path, file) = template.create(&root, at(1_700_000_000), 1).unwrap();
+        drop(file);
+        assert_eq!(path, root.join("2023-11-14").join("22").join("run_1.tdms"));
+        assert!(path.is_file());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,156 @@
+//! Reading files from object stores such as S3, GCS and Azure.
+//!
+//! [`ObjectStoreSource`] is a [`RangeSource`] using ranged GET requests from
+//! the `object_store` crate, so only the parts of the file needed for the index
+//! and the channels read are downloaded.
+
+use std::io::Result as IoResult;
+use std::ops::Range;
+use std::sync::Arc;
+
+use object_store::path::Path;
+use object_store::ObjectStore;
+use tokio::runtime::Runtime;
+
+use super::range_reader::{RangeReader, RangeSource};
+use crate::error::TdmsError;
+use crate::TdmsFile;
+
+/// A single object in an [`ObjectStore`] read with ranged requests.
+///
+/// The requests run on a private single threaded runtime so this can be used
+/// from synchronous code. It must not be used from within an async task.
+#[derive(Debug)]
+pub struct ObjectStoreSource {
+    store: Arc<dyn ObjectStore>,
+    path: Path,
+    runtime: Runtime,
+}
+
+impl ObjectStoreSource {
+    /// Read the object at `path` in the store.
+    pub fn new(store: Arc<dyn ObjectStore>, path: Path) -> IoResult<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(Self {
+            store,
+            path,
+            runtime,
+        })
+    }
+}
+
+fn io_error(error: object_store::Error) -> std::io::Error {
+    match error {
+        object_store::Error::NotFound { .. } => {
+            std::io::Error::new(std::io::ErrorKind::NotFound, error)
+        }
+        error => std::io::Error::other(error),
+    }
+}
+
+impl RangeSource for ObjectStoreSource {
+    fn size(&mut self) -> IoResult<u64> {
+        let meta = self
+            .runtime
+            .block_on(self.store.head(&self.path))
+            .map_err(io_error)?;
+        Ok(meta.size)
+    }
+
+    fn fetch(&mut self, range: Range<u64>) -> IoResult<Vec<u8>> {
+        let bytes = self
+            .runtime
+            .block_on(self.store.get_range(&self.path, range))
+            .map_err(io_error)?;
+        Ok(bytes.to_vec())
+    }
+}
+
+impl TdmsFile<RangeReader<ObjectStoreSource>> {
+    /// Open the TDMS file at `path` in the object store.
+    ///
+    /// Each request fetches at least `min_fetch` bytes so the many small reads
+    /// made while indexing, and reads of nearby blocks, are coalesced.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use object_store::{memory::InMemory, path::Path, ObjectStore};
+    /// use tedium::testgen::TestFileSpec;
+    /// use tedium::TdmsFile;
+    ///
+    /// let spec = TestFileSpec::default();
+    /// let store = Arc::new(InMemory::new());
+    /// let path = Path::from("data/test.tdms");
+    /// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// runtime
+    ///     .block_on(store.put(&path, spec.build().unwrap().into()))
+    ///     .unwrap();
+    ///
+    /// let mut file = TdmsFile::open_object_store(store, path, 64 * 1024).unwrap();
+    /// let data: Vec<f64> = file.read_all(&spec.channel_path(0, 0)).unwrap();
+    /// assert_eq!(data.len(), spec.channel_length());
+    /// ```
+    pub fn open_object_store(
+        store: Arc<dyn ObjectStore>,
+        path: Path,
+        min_fetch: u64,
+    ) -> Result<Self, TdmsError> {
+        let source = ObjectStoreSource::new(store, path)?;
+        TdmsFile::new(RangeReader::new(source)?.with_min_fetch(min_fetch))
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use object_store::memory::InMemory;
+
+    use super::*;
+    use crate::testgen::TestFileSpec;
+
+    fn store_file(bytes: Vec<u8>) -> (Arc<dyn ObjectStore>, Path) {
+        let store = Arc::new(InMemory::new());
+        let path = Path::from("test.tdms");
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(store.put(&path, bytes.into()))
+            .unwrap();
+        (store, path)
+    }
+
+    #[test]
+    fn test_read_channel_from_object_store() {
+        let spec = TestFileSpec {
+            channels_per_group: 4,
+            segments: 2,
+            samples_per_segment: 1000,
+            ..Default::default()
+        };
+        let bytes = spec.build().unwrap();
+        let length = bytes.len() as u64;
+        let mut expected = TdmsFile::new(std::io::Cursor::new(bytes.clone())).unwrap();
+        let (store, path) = store_file(bytes);
+
+        let mut file = TdmsFile::open_object_store(store, path, 1024).unwrap();
+        let channel = spec.channel_path(0, 2);
+        assert_eq!(
+            file.read_all::<f64>(&channel).unwrap(),
+            expected.read_all::<f64>(&channel).unwrap()
+        );
+        // Only part of the file is needed for one channel.
+        assert!(file.file.bytes_fetched() < length);
+    }
+
+    #[test]
+    fn test_missing_object_is_not_found() {
+        let store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let error =
+            TdmsFile::open_object_store(store, Path::from("missing.tdms"), 1024).unwrap_err();
+        assert!(
+            matches!(error, TdmsError::IoError(ref e) if e.kind() == std::io::ErrorKind::NotFound)
+        );
+    }
+}
@@ -0,0 +1,68 @@
+//! Paths handed to the operating system.
+//!
+//! Windows limits ordinary paths to 260 characters, which deep log
+//! directories on network shares easily exceed. Paths opened by the crate go
+//! through [`os_path`], which makes them absolute and gives them the `\\?\`
+//! extended length prefix on Windows. Elsewhere paths are used unchanged.
+
+use std::borrow::Cow;
+use std::path::Path;
+
+/// The path to open, with the extended length prefix on Windows.
+#[cfg(windows)]
+pub(crate) fn os_path(path: &Path) -> Cow<'_, Path> {
+    let Ok(absolute) = std::path::absolute(path) else {
+        return Cow::Borrowed(path);
+    };
+    match absolute.to_str().and_then(extended_length) {
+        Some(extended) => Cow::Owned(extended.into()),
+        None => Cow::Owned(absolute),
+    }
+}
+
+/// The path to open, which is the path itself on this platform.
+#[cfg(not(windows))]
+pub(crate) fn os_path(path: &Path) -> Cow<'_, Path> {
+    Cow::Borrowed(path)
+}
+
+/// Add the extended length prefix to an absolute Windows path.
+///
+/// UNC shares such as `\\server\share` become `\\?\UNC\server\share`. Returns
+/// None if the path already has a prefix or isn't absolute.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn extended_length(absolute: &str) -> Option<String> {
+    // Extended paths are passed to the file system as is, so only
+    // backslashes separate the components.
+    let absolute = absolute.replace('/', "\\");
+    if absolute.starts_with(r"\\?\") || absolute.starts_with(r"\\.\") {
+        return None;
+    }
+    if let Some(share) = absolute.strip_prefix(r"\\") {
+        return Some(format!(r"\\?\UNC\{share}"));
+    }
+    let bytes = absolute.as_bytes();
+    let has_drive =
+        bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'\\';
+    has_drive.then(|| format!(r"\\?\{absolute}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extended_length_paths() {
+        assert_eq!(
+            extended_length(r"C:\logs\run.tdms").as_deref(),
+            Some(r"\\?\C:\logs\run.tdms")
+        );
+        assert_eq!(
+            extended_length(r"\\server\share\logs/run.tdms").as_deref(),
+            Some(r"\\?\UNC\server\share\logs\run.tdms")
+        );
+        assert_eq!(extended_length(r"\\?\C:\logs\run.tdms"), None);
+        assert_eq!(extended_length(r"\\.\pipe\logger"), None);
+        assert_eq!(extended_length("logs/run.tdms"), None);
+    }
+}
@@ -0,0 +1,639 @@
+//! Overviews are decimated min/max pyramids of the channel data.
+//!
+//! They are designed for viewers which need to render a channel at any zoom
+//! level without touching every sample. Level 0 holds one min/max pair per
+//! `base_bucket` samples and each level above combines `factor` buckets of the
+//! level below.
+//!
+//! Overviews are stored in a sidecar file next to the TDMS file. This is not part
+//! of the TDMS specification so other clients will simply ignore it.
+
+use std::collections::BTreeMap;
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::TdmsError;
+use crate::io::data_types::{DataType, TdmsStorageType};
+use crate::paths::ChannelPath;
+use crate::raw_data::DataBlock;
+use crate::TdmsFile;
+
+use super::os_path::os_path;
+
+/// The tag at the start of an overview file.
+const OVERVIEW_TAG: &[u8; 4] = b"TDOv";
+/// The version of the overview file format.
+const OVERVIEW_VERSION: u32 = 1;
+/// The extension added to the TDMS path to get the sidecar path.
+const OVERVIEW_EXTENSION: &str = "tdms_overview";
+
+/// The minimum and maximum value of a bucket of samples.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MinMax {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl MinMax {
+    fn from_value(value: f64) -> Self {
+        Self {
+            min: value,
+            max: value,
+        }
+    }
+
+    fn merge(&self, other: &MinMax) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+}
+
+/// Configures the shape of the overview pyramids.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OverviewConfig {
+    /// The number of samples covered by each bucket in level 0.
+    pub base_bucket: u64,
+    /// The number of buckets from the level below combined into each bucket of the next level.
+    pub factor: u64,
+}
+
+impl OverviewConfig {
+    /// Check the buckets are non-empty and each level combines at least two buckets.
+    fn validate(&self) -> Result<(), TdmsError> {
+        if self.base_bucket == 0 {
+            return Err(TdmsError::InvalidOverview(
+                "Bucket size cannot be zero".to_string(),
+            ));
+        }
+        if self.factor < 2 {
+            return Err(TdmsError::InvalidOverview(format!(
+                "Level factor must be at least 2 but is {}",
+                self.factor
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for OverviewConfig {
+    fn default() -> Self {
+        Self {
+            base_bucket: 256,
+            factor: 8,
+        }
+    }
+}
+
+/// The min/max pyramid for a single channel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChannelOverview {
+    samples: u64,
+    levels: Vec<Vec<MinMax>>,
+}
+
+impl ChannelOverview {
+    /// The number of samples of the channel covered by the overview.
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// The levels of the pyramid. Level 0 is the most detailed.
+    pub fn levels(&self) -> &[Vec<MinMax>] {
+        &self.levels
+    }
+
+    /// Get the buckets covering the sample range `start..end` from the most detailed
+    /// level that returns no more than `max_points` buckets.
+    ///
+    /// Returns the samples per bucket of the chosen level along with the buckets.
+    /// No buckets are returned if the configuration has a zero bucket size or a
+    /// factor below 2.
+    pub fn query(
+        &self,
+        config: &OverviewConfig,
+        start: u64,
+        end: u64,
+        max_points: usize,
+    ) -> (u64, &[MinMax]) {
+        if config.validate().is_err() {
+            return (config.base_bucket, &[]);
+        }
+        let end = end.min(self.samples);
+        let start = start.min(end);
+        let max_points = max_points.max(1) as u64;
+
+        let mut bucket_size = config.base_bucket;
+        for (level_index, level) in self.levels.iter().enumerate() {
+            let last = end.div_ceil(bucket_size).min(level.len() as u64);
+            // A level read from a sidecar file may be shorter than its sample count.
+            let first = (start / bucket_size).min(last);
+            let is_last_level = level_index == self.levels.len() - 1;
+            if last - first <= max_points || is_last_level {
+                return (bucket_size, &level[first as usize..last as usize]);
+            }
+            bucket_size *= config.factor;
+        }
+        (bucket_size, &[])
+    }
+
+    /// Add new samples to the end of level 0.
+    ///
+    /// The final level 0 bucket may be partial so this will continue filling it.
+    fn extend(&mut self, config: &OverviewConfig, values: impl Iterator<Item = f64>) {
+        if self.levels.is_empty() {
+            self.levels.push(Vec::new());
+        }
+        let base = &mut self.levels[0];
+
+        for value in values {
+            let value = MinMax::from_value(value);
+            if self.samples.is_multiple_of(config.base_bucket) {
+                base.push(value);
+            } else if let Some(last) = base.last_mut() {
+                *last = last.merge(&value);
+            }
+            self.samples += 1;
+        }
+    }
+
+    /// Rebuild the upper levels from level 0.
+    ///
+    /// The configuration must have been validated.
+    fn rebuild_levels(&mut self, config: &OverviewConfig) {
+        self.levels.truncate(1);
+        let factor = config.factor as usize;
+
+        while self.levels.last().is_some_and(|level| level.len() > 1) {
+            let below = self.levels.last().expect("Checked in loop condition");
+            let next = below
+                .chunks(factor)
+                .map(|chunk| {
+                    chunk
+                        .iter()
+                        .skip(1)
+                        .fold(chunk[0], |acc, value| acc.merge(value))
+                })
+                .collect();
+            self.levels.push(next);
+        }
+    }
+}
+
+/// A set of channel overviews for a TDMS file.
+///
+/// Build this with [`TdmsFile::build_overview`] and then keep it in step with
+/// a growing file with [`TdmsFile::update_overview`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Overview {
+    config: OverviewConfig,
+    channels: BTreeMap<String, ChannelOverview>,
+}
+
+impl Overview {
+    /// Create an empty overview with the given configuration.
+    pub fn new(config: OverviewConfig) -> Self {
+        Self {
+            config,
+            channels: BTreeMap::new(),
+        }
+    }
+
+    /// The configuration the overview was built with.
+    pub fn config(&self) -> &OverviewConfig {
+        &self.config
+    }
+
+    /// Get the overview for the channel.
+    ///
+    /// Returns None if the channel is not numeric or was not in the file when the overview was built.
+    pub fn channel(&self, channel: &ChannelPath) -> Option<&ChannelOverview> {
+        self.channels.get(channel.path())
+    }
+
+    /// Query the channel for the buckets covering `start..end`. See [`ChannelOverview::query`].
+    pub fn query(
+        &self,
+        channel: &ChannelPath,
+        start: u64,
+        end: u64,
+        max_points: usize,
+    ) -> Option<(u64, &[MinMax])> {
+        self.channel(channel)
+            .map(|overview| overview.query(&self.config, start, end, max_points))
+    }
+
+    /// Get the sidecar path used for the overview of the TDMS file at `tdms_path`.
+    pub fn sidecar_path(tdms_path: &Path) -> PathBuf {
+        let mut path = tdms_path.as_os_str().to_owned();
+        path.push(".");
+        path.push(OVERVIEW_EXTENSION);
+        PathBuf::from(path)
+    }
+
+    /// Load an overview from a file.
+    pub fn load(path: &Path) -> Result<Self, TdmsError> {
+        let mut file = std::io::BufReader::new(std::fs::File::open(os_path(path))?);
+        Self::read_from(&mut file)
+    }
+
+    /// Save the overview to a file, replacing any existing file.
+    pub fn save(&self, path: &Path) -> Result<(), TdmsError> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(os_path(path))?);
+        self.write_to(&mut file)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Write the overview in the sidecar format.
+    ///
+    /// Only level 0 is stored as the upper levels can be cheaply rebuilt.
+    pub fn write_to(&self, writer: &mut impl Write) -> Result<(), TdmsError> {
+        writer.write_all(OVERVIEW_TAG)?;
+        OVERVIEW_VERSION.write_le(writer)?;
+        self.config.base_bucket.write_le(writer)?;
+        self.config.factor.write_le(writer)?;
+        (self.channels.len() as u64).write_le(writer)?;
+
+        for (path, channel) in self.channels.iter() {
+            path.write_le(writer)?;
+            channel.samples.write_le(writer)?;
+            let base = channel.levels.first().map(|l| &l[..]).unwrap_or(&[]);
+            (base.len() as u64).write_le(writer)?;
+            for bucket in base {
+                bucket.min.write_le(writer)?;
+                bucket.max.write_le(writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read an overview in the sidecar format.
+    pub fn read_from(reader: &mut impl Read) -> Result<Self, TdmsError> {
+        let mut tag = [0u8; 4];
+        reader.read_exact(&mut tag)?;
+        if &tag != OVERVIEW_TAG {
+            return Err(TdmsError::InvalidOverview(format!(
+                "Unexpected tag {tag:X?}"
+            )));
+        }
+        let version = u32::read_le(reader)?;
+        if version != OVERVIEW_VERSION {
+            return Err(TdmsError::InvalidOverview(format!(
+                "Unsupported version {version}"
+            )));
+        }
+
+        let config = OverviewConfig {
+            base_bucket: u64::read_le(reader)?,
+            factor: u64::read_le(reader)?,
+        };
+        config.validate()?;
+
+        let mut overview = Overview::new(config);
+        let channel_count = u64::read_le(reader)?;
+        for _ in 0..channel_count {
+            let path = String::read_le(reader)?;
+            let samples = u64::read_le(reader)?;
+            let bucket_count = u64::read_le(reader)?;
+            let mut base = Vec::new();
+            for _ in 0..bucket_count {
+                let min = f64::read_le(reader)?;
+                let max = f64::read_le(reader)?;
+                base.push(MinMax { min, max });
+            }
+            let mut channel = ChannelOverview {
+                samples,
+                levels: vec![base],
+            };
+            channel.rebuild_levels(&config);
+            overview.channels.insert(path, channel);
+        }
+        Ok(overview)
+    }
+}
+
+/// Read a channel from a single block and convert the values to f64.
+///
+/// Returns None if the channel is not a numeric type.
+fn read_block_as_f64(
+    block: &DataBlock,
+    channel_index: usize,
+    reader: &mut (impl Read + Seek),
+    samples: usize,
+) -> Result<Option<Vec<f64>>, TdmsError> {
+    macro_rules! read_as {
+        ($type:ty) => {{
+            let mut buffer = vec![<$type>::default(); samples];
+            let read = block.read_single(channel_index, reader, &mut buffer[..])?;
+            buffer.truncate(read.min(samples));
+            Some(buffer.into_iter().map(|value| value as f64).collect())
+        }};
+    }
+
+    let data_type = block.channels[channel_index].data_type;
+    let values = match data_type {
+        DataType::I8 => read_as!(i8),
+        DataType::I16 => read_as!(i16),
+        DataType::I32 => read_as!(i32),
+        DataType::I64 => read_as!(i64),
+        DataType::U8 => read_as!(u8),
+        DataType::U16 => read_as!(u16),
+        DataType::U32 => read_as!(u32),
+        DataType::U64 => read_as!(u64),
+        DataType::SingleFloat | DataType::SingleFloatWithUnit => read_as!(f32),
+        DataType::DoubleFloat | DataType::DoubleFloatWithUnit => read_as!(f64),
+        _ => None,
+    };
+    Ok(values)
+}
+
+impl<F: Read + Seek> TdmsFile<F> {
+    /// Build overviews for every numeric channel in the file.
+    ///
+    /// This reads the full file one data block at a time.
+    pub fn build_overview(&mut self, config: OverviewConfig) -> Result<Overview, TdmsError> {
+        let mut overview = Overview::new(config);
+        self.update_overview(&mut overview)?;
+        Ok(overview)
+    }
+
+    /// Update an existing overview with any data added to the file since it was built.
+    ///
+    /// Only the new data (and the final partial bucket) is read from the file.
+    pub fn update_overview(&mut self, overview: &mut Overview) -> Result<(), TdmsError> {
+        let config = overview.config;
+        config.validate()?;
+
+        let channels: Vec<ChannelPath> = self
+            .index
+            .all_paths()
+            .filter_map(|path| ChannelPath::try_from(path).ok())
+            .collect();
+
+        for channel in channels {
+            let existing = overview.channels.remove(channel.path());
+            if let Some(updated) = self.update_channel_overview(&channel, &config, existing)? {
+                overview
+                    .channels
+                    .insert(channel.path().to_string(), updated);
+            }
+        }
+        Ok(())
+    }
+
+    fn update_channel_overview(
+        &mut self,
+        channel: &ChannelPath,
+        config: &OverviewConfig,
+        existing: Option<ChannelOverview>,
+    ) -> Result<Option<ChannelOverview>, TdmsError> {
+        let Some(locations) = self.index.get_channel_data_positions(channel) else {
+            return Ok(existing);
+        };
+
+        let mut overview = existing.unwrap_or(ChannelOverview {
+            samples: 0,
+            levels: vec![],
+        });
+
+        // Drop the partial bucket so it is rebuilt with the new data.
+        let resume_from = (overview.samples / config.base_bucket) * config.base_bucket;
+        if let Some(base) = overview.levels.first_mut() {
+            base.truncate((resume_from / config.base_bucket) as usize);
+        }
+        overview.samples = resume_from;
+
+        let mut block_start = 0u64;
+        for location in locations {
+            let block_end = block_start + location.number_of_samples;
+            if block_end <= resume_from {
+                block_start = block_end;
+                continue;
+            }
+
+            let block = self
+                .index
+                .get_data_block(location.data_block)
+                .ok_or_else(|| {
+                    TdmsError::DataBlockNotFound(channel.clone(), location.data_block)
+                })?;
+            let Some(values) = read_block_as_f64(
+                block,
+                location.channel_index,
+                &mut self.file,
+                location.number_of_samples as usize,
+            )?
+            else {
+                // Not a numeric channel.
+                return Ok(None);
+            };
+
+            let skip = resume_from.saturating_sub(block_start) as usize;
+            overview.extend(config, values.into_iter().skip(skip));
+            block_start = block_end;
+        }
+
+        overview.rebuild_levels(config);
+        Ok(Some(overview))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    #[cfg(feature = "write")]
+    use crate::DataLayout;
+
+    #[cfg(feature = "write")]
+    fn config() -> OverviewConfig {
+        OverviewConfig {
+            base_bucket: 4,
+            factor: 2,
+        }
+    }
+
+    #[cfg(feature = "write")]
+    fn file_with_data(data: &[f64]) -> TdmsFile<Cursor<Vec<u8>>> {
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(
+                &[ChannelPath::new("group", "ch1")],
+                data,
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        drop(writer);
+        file
+    }
+
+    #[cfg(feature = "write")]
+    #[test]
+    fn test_builds_levels() {
+        let data: Vec<f64> = (0..16).map(|i| i as f64).collect();
+        let mut file = file_with_data(&data);
+        let overview = file.build_overview(config()).unwrap();
+        let channel = overview.channel(&ChannelPath::new("group", "ch1")).unwrap();
+
+        assert_eq!(channel.samples(), 16);
+        assert_eq!(channel.levels().len(), 3);
+        assert_eq!(
+            channel.levels()[0],
+            vec![
+                MinMax { min: 0.0, max: 3.0 },
+                MinMax { min: 4.0, max: 7.0 },
+                MinMax {
+                    min: 8.0,
+                    max: 11.0
+                },
+                MinMax {
+                    min: 12.0,
+                    max: 15.0
+                },
+            ]
+        );
+        assert_eq!(
+            channel.levels()[2],
+            vec![MinMax {
+                min: 0.0,
+                max: 15.0
+            }]
+        );
+    }
+
+    #[cfg(feature = "write")]
+    #[test]
+    fn test_update_matches_full_build() {
+        let data: Vec<f64> = (0..10).map(|i| (i as f64).sin()).collect();
+        let more: Vec<f64> = (10..30).map(|i| (i as f64).sin()).collect();
+        let mut file = file_with_data(&data);
+        let mut overview = file.build_overview(config()).unwrap();
+
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(
+                &[ChannelPath::new("group", "ch1")],
+                &more[..],
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        drop(writer);
+
+        file.update_overview(&mut overview).unwrap();
+        let rebuilt = file.build_overview(config()).unwrap();
+        assert_eq!(overview, rebuilt);
+        assert_eq!(
+            overview
+                .channel(&ChannelPath::new("group", "ch1"))
+                .unwrap()
+                .samples(),
+            30
+        );
+    }
+
+    #[cfg(feature = "write")]
+    #[test]
+    fn test_query_selects_level_by_points() {
+        let data: Vec<f64> = (0..64).map(|i| i as f64).collect();
+        let mut file = file_with_data(&data);
+        let overview = file.build_overview(config()).unwrap();
+        let path = ChannelPath::new("group", "ch1");
+
+        let (bucket, points) = overview.query(&path, 0, 64, 16).unwrap();
+        assert_eq!(bucket, 4);
+        assert_eq!(points.len(), 16);
+
+        let (bucket, points) = overview.query(&path, 0, 64, 5).unwrap();
+        assert_eq!(bucket, 16);
+        assert_eq!(points.len(), 4);
+        assert_eq!(
+            points[1],
+            MinMax {
+                min: 16.0,
+                max: 31.0
+            }
+        );
+    }
+
+    #[cfg(feature = "write")]
+    #[test]
+    fn test_round_trip_sidecar_format() {
+        let data: Vec<f64> = (0..37).map(|i| i as f64 * 0.5).collect();
+        let mut file = file_with_data(&data);
+        let overview = file.build_overview(config()).unwrap();
+
+        let mut buffer = vec![];
+        overview.write_to(&mut buffer).unwrap();
+        let read_back = Overview::read_from(&mut Cursor::new(buffer)).unwrap();
+        assert_eq!(read_back, overview);
+    }
+
+    #[test]
+    fn test_rejects_bad_tag() {
+        let result = Overview::read_from(&mut Cursor::new(b"TDSm0000".to_vec()));
+        assert!(matches!(result, Err(TdmsError::InvalidOverview(_))));
+    }
+
+    fn sidecar_with(factor: u64, samples: u64, buckets: &[MinMax]) -> Vec<u8> {
+        let mut buffer = vec![];
+        buffer.extend_from_slice(OVERVIEW_TAG);
+        OVERVIEW_VERSION.write_le(&mut buffer).unwrap();
+        4u64.write_le(&mut buffer).unwrap();
+        factor.write_le(&mut buffer).unwrap();
+        1u64.write_le(&mut buffer).unwrap();
+        ChannelPath::new("group", "ch1")
+            .path()
+            .to_string()
+            .write_le(&mut buffer)
+            .unwrap();
+        samples.write_le(&mut buffer).unwrap();
+        (buckets.len() as u64).write_le(&mut buffer).unwrap();
+        for bucket in buckets {
+            bucket.min.write_le(&mut buffer).unwrap();
+            bucket.max.write_le(&mut buffer).unwrap();
+        }
+        buffer
+    }
+
+    #[test]
+    fn test_rejects_factor_below_two() {
+        for factor in [0, 1] {
+            let result = Overview::read_from(&mut Cursor::new(sidecar_with(factor, 0, &[])));
+            assert!(matches!(result, Err(TdmsError::InvalidOverview(_))));
+        }
+    }
+
+    #[cfg(feature = "write")]
+    #[test]
+    fn test_build_rejects_factor_below_two() {
+        let mut file = file_with_data(&[1.0]);
+        let result = file.build_overview(OverviewConfig {
+            base_bucket: 4,
+            factor: 1,
+        });
+        assert!(matches!(result, Err(TdmsError::InvalidOverview(_))));
+    }
+
+    #[test]
+    fn test_query_with_fewer_buckets_than_samples() {
+        let bucket = MinMax { min: 0.0, max: 1.0 };
+        let buffer = sidecar_with(2, 64, &[bucket; 2]);
+        let overview = Overview::read_from(&mut Cursor::new(buffer)).unwrap();
+        let path = ChannelPath::new("group", "ch1");
+
+        let (_, points) = overview.query(&path, 32, 64, 100).unwrap();
+        assert!(points.is_empty());
+        let (bucket_size, points) = overview.query(&path, 0, 64, 100).unwrap();
+        assert_eq!((bucket_size, points.len()), (4, 2));
+    }
+
+    #[test]
+    fn test_sidecar_path() {
+        let path = Overview::sidecar_path(Path::new("data/run.tdms"));
+        assert_eq!(path, PathBuf::from("data/run.tdms.tdms_overview"));
+    }
+}
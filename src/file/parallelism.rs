@@ -0,0 +1,182 @@
+//! Thread count and core affinity for parallel operations.
+//!
+//! Acquisition machines often reserve cores for real-time tasks, so the
+//! threads tedium starts shouldn't simply take every core. A [`Parallelism`]
+//! limits the number of worker threads and can pin them to chosen cores.
+//!
+//! With the `parallel` feature a non-default setting runs index building in
+//! its own rayon pool rather than the global one, so it doesn't depend on or
+//! change how the rest of the application configures rayon. Pinning needs the
+//! `affinity` feature and is only supported on Linux.
+
+use crate::error::TdmsError;
+
+/// How many threads parallel operations use and which cores they run on.
+///
+/// The default uses one thread per available core with no pinning, and with
+/// the `parallel` feature runs in the global rayon pool.
+///
+/// # Example
+/// ```rust
+/// use tedium::Parallelism;
+///
+/// // Two workers, kept off cores 0 and 1 which run the acquisition loop.
+/// let parallelism = Parallelism::threads(2).with_cores([2, 3]);
+/// assert_eq!(parallelism.thread_count(), 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Parallelism {
+    /// The number of worker threads.
+    ///
+    /// Zero uses one per core in `cores`, or one per available core if no
+    /// cores are set.
+    pub threads: usize,
+    /// The cores worker threads are pinned to, assigned to the workers in
+    /// turn. Empty leaves scheduling to the operating system.
+    pub cores: Vec<usize>,
+}
+
+impl Parallelism {
+    /// Use a fixed number of worker threads.
+    pub fn threads(threads: usize) -> Self {
+        Self {
+            threads,
+            cores: Vec::new(),
+        }
+    }
+
+    /// Pin the worker threads to these cores.
+    pub fn with_cores(mut self, cores: impl IntoIterator<Item = usize>) -> Self {
+        self.cores = cores.into_iter().collect();
+        self
+    }
+
+    /// The number of worker threads that will be used.
+    pub fn thread_count(&self) -> usize {
+        match (self.threads, self.cores.len()) {
+            (0, 0) => std::thread::available_parallelism().map_or(1, |count| count.get()),
+            (0, cores) => cores,
+            (threads, _) => threads,
+        }
+    }
+
+    /// Pin the current thread to the core for the worker, if any cores are set.
+    pub(crate) fn pin_worker(&self, worker: usize) -> Result<(), TdmsError> {
+        if self.cores.is_empty() {
+            return Ok(());
+        }
+        pin_current_thread(self.cores[worker % self.cores.len()])
+    }
+
+    /// Build a dedicated thread pool, or None to use the global pool for the default setting.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn thread_pool(&self) -> Result<Option<rayon::ThreadPool>, TdmsError> {
+        use std::sync::{Arc, Mutex};
+
+        if *self == Self::default() {
+            return Ok(None);
+        }
+
+        let pin_error = Arc::new(Mutex::new(None));
+        let pool = {
+            let parallelism = self.clone();
+            let pin_error = pin_error.clone();
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(self.thread_count())
+                .thread_name(|worker| format!("tedium-worker-{worker}"))
+                .start_handler(move |worker| {
+                    if let Err(error) = parallelism.pin_worker(worker) {
+                        pin_error.lock().unwrap().get_or_insert(error);
+                    }
+                })
+                .build()
+                .map_err(|error| std::io::Error::other(error.to_string()))?
+        };
+        // Every worker has started, and so run its start handler, once this returns.
+        pool.broadcast(|_| ());
+
+        let pin_error = pin_error.lock().unwrap().take();
+        match pin_error {
+            Some(error) => Err(error),
+            None => Ok(Some(pool)),
+        }
+    }
+}
+
+#[cfg(all(feature = "affinity", target_os = "linux"))]
+fn pin_current_thread(core: usize) -> Result<(), TdmsError> {
+    if core >= libc::CPU_SETSIZE as usize {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("core {core} is beyond the largest supported core"),
+        )
+        .into());
+    }
+    // SAFETY: The set is a plain bitmask which is valid when zeroed, and the
+    // core was checked to be inside it.
+    let result = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(core, &mut set);
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set)
+    };
+    if result == -1 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+#[cfg(not(all(feature = "affinity", target_os = "linux")))]
+fn pin_current_thread(_core: usize) -> Result<(), TdmsError> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "pinning threads to cores needs the affinity feature on Linux",
+    )
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_count() {
+        assert!(Parallelism::default().thread_count() >= 1);
+        assert_eq!(Parallelism::threads(3).thread_count(), 3);
+        assert_eq!(Parallelism::default().with_cores([1, 2]).thread_count(), 2);
+        assert_eq!(Parallelism::threads(4).with_cores([1]).thread_count(), 4);
+    }
+
+    #[test]
+    fn test_no_cores_never_pins() {
+        Parallelism::threads(2).pin_worker(5).unwrap();
+    }
+
+    #[cfg(not(all(feature = "affinity", target_os = "linux")))]
+    #[test]
+    fn test_pinning_is_unsupported() {
+        let error = Parallelism::default()
+            .with_cores([0])
+            .pin_worker(0)
+            .unwrap_err();
+        assert!(
+            matches!(error, TdmsError::IoError(error) if error.kind() == std::io::ErrorKind::Unsupported)
+        );
+    }
+
+    #[cfg(all(feature = "affinity", target_os = "linux"))]
+    #[test]
+    fn test_invalid_core_errors() {
+        let result = Parallelism::default()
+            .with_cores([usize::MAX])
+            .pin_worker(0);
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_dedicated_pool_size() {
+        assert!(Parallelism::default().thread_pool().unwrap().is_none());
+        let pool = Parallelism::threads(3).thread_pool().unwrap().unwrap();
+        assert_eq!(pool.current_num_threads(), 3);
+    }
+}
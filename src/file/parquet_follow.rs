@@ -0,0 +1,459 @@
+//! Streaming a growing file out as Parquet.
+//!
+//! A [`ParquetFollower`] works like a [`CsvFollower`](crate::CsvFollower)
+//! but writes each batch of new rows as a Parquet row group. Parquet keeps its
+//! metadata in a footer, so the output only becomes a complete file when
+//! [`ParquetFollower::finish`] writes it.
+//!
+//! Channels are written in the Parquet type closest to the type they are
+//! stored as, with timestamps as nanoseconds since the unix epoch. Columns
+//! converted to another unit are written as doubles.
+
+use std::fmt::Debug;
+use std::io::{Read, Seek, Write};
+use std::sync::Arc;
+
+use labview_interop::types::LVTime;
+use parquet::basic::{LogicalType, Repetition, TimeUnit, Type as PhysicalType};
+use parquet::data_type::{BoolType, DoubleType, FloatType, Int32Type, Int64Type};
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::{SerializedColumnWriter, SerializedFileWriter};
+use parquet::format::NanoSeconds;
+use parquet::schema::types::{Type, TypePtr};
+
+use crate::error::{usize_from, TdmsError};
+use crate::io::data_types::{DataType, UnixNanos};
+use crate::paths::{parse_path, ChannelPath, GroupPath};
+use crate::{TdmsFile, UnitConversion};
+
+use super::csv_follow::{conversion, CHUNK_ROWS};
+use super::time_sync::read_range_as_f64;
+
+/// Appends the new rows of a group to a Parquet sink as the file grows.
+///
+/// Each channel of the group is a column, in the order of the file. A row is
+/// written once every channel has a sample for it. The columns are fixed the
+/// first time the group has channels which all have data, so channels added
+/// later are ignored.
+#[derive(Debug)]
+pub struct ParquetFollower<W: Write + Send> {
+    group: GroupPath,
+    /// The sink until the columns are known.
+    sink: Option<W>,
+    /// The writer, once the columns are known.
+    writer: Option<SerializedFileWriter<W>>,
+    /// The unit requested for channels which should be converted.
+    target_units: Vec<(ChannelPath, String)>,
+    channels: Vec<ChannelPath>,
+    /// The conversion for each column, if it has a target unit.
+    conversions: Vec<Option<UnitConversion>>,
+    rows_written: u64,
+}
+
+/// Start following the group of the file, writing the rows it has so far to `sink`.
+///
+/// Call [`ParquetFollower::poll`] as the file grows to append the new rows and
+/// [`ParquetFollower::finish`] to complete the Parquet file.
+///
+/// # Example
+#[cfg_attr(feature = "write", doc = "```rust")]
+#[cfg_attr(not(feature = "write"), doc = "```ignore")]
+/// use tedium::{export, ChannelPath, DataLayout, GroupPath, TdmsFile};
+///
+/// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+/// let channels = [ChannelPath::new("rig", "force"), ChannelPath::new("rig", "speed")];
+/// let mut writer = file.writer().unwrap();
+/// writer.write_channels(&channels, &[1.5, 2.0, 10.0, 20.0], DataLayout::Contigious).unwrap();
+/// drop(writer);
+///
+/// let mut follower = export::follow_to_parquet(&mut file, &GroupPath::new("rig"), Vec::new()).unwrap();
+/// assert_eq!(follower.rows_written(), 2);
+///
+/// let mut writer = file.writer().unwrap();
+/// writer.write_channels(&channels, &[3.0, 30.0], DataLayout::Contigious).unwrap();
+/// drop(writer);
+/// assert_eq!(follower.poll(&mut file).unwrap(), 1);
+///
+/// let parquet = follower.finish().unwrap();
+/// assert_eq!(&parquet[..4], b"PAR1");
+/// ```
+pub fn follow_to_parquet<F: Read + Seek + Write + Debug, W: Write + Send>(
+    file: &mut TdmsFile<F>,
+    group: &GroupPath,
+    sink: W,
+) -> Result<ParquetFollower<W>, TdmsError> {
+    let mut follower = ParquetFollower::new(group, sink);
+    follower.poll(file)?;
+    Ok(follower)
+}
+
+impl<W: Write + Send> ParquetFollower<W> {
+    /// Follow the group, writing the Parquet file to `sink`.
+    pub fn new(group: &GroupPath, sink: W) -> Self {
+        Self {
+            group: group.clone(),
+            sink: Some(sink),
+            writer: None,
+            target_units: Vec::new(),
+            channels: Vec::new(),
+            conversions: Vec::new(),
+            rows_written: 0,
+        }
+    }
+
+    /// Convert the channel from its `unit_string` to `unit` as it is written.
+    ///
+    /// This works like [`CsvFollower::with_unit`](crate::CsvFollower::with_unit).
+    /// The column is written as doubles.
+    pub fn with_unit(mut self, channel: &ChannelPath, unit: &str) -> Self {
+        self.target_units
+            .retain(|(existing, _)| existing != channel);
+        self.target_units.push((channel.clone(), unit.to_string()));
+        self
+    }
+
+    /// The number of rows written.
+    pub fn rows_written(&self) -> u64 {
+        self.rows_written
+    }
+
+    /// Refresh the file and write any new complete rows as row groups. Returns the rows written.
+    pub fn poll<F: Read + Seek + Write + Debug>(
+        &mut self,
+        file: &mut TdmsFile<F>,
+    ) -> Result<u64, TdmsError> {
+        file.refresh()?;
+        if self.writer.is_none() {
+            self.start(file)?;
+        }
+        let Some(writer) = self.writer.as_mut() else {
+            return Ok(0);
+        };
+
+        let complete_rows = self
+            .channels
+            .iter()
+            .map(|channel| file.channel_length(channel).unwrap_or(0))
+            .min()
+            .unwrap_or(0);
+        let start = self.rows_written;
+
+        while self.rows_written < complete_rows {
+            let rows = (complete_rows - self.rows_written).min(CHUNK_ROWS);
+            let mut row_group = writer.next_row_group().map_err(io_error)?;
+            for (channel, conversion) in self.channels.iter().zip(&self.conversions) {
+                let mut column = row_group
+                    .next_column()
+                    .map_err(io_error)?
+                    .ok_or_else(|| io_error(ParquetError::General("missing column".into())))?;
+                match conversion {
+                    Some(conversion) => write_converted_column(
+                        file,
+                        channel,
+                        conversion,
+                        self.rows_written,
+                        rows,
+                        &mut column,
+                    )?,
+                    None => write_column(file, channel, self.rows_written, rows, &mut column)?,
+                }
+                column.close().map_err(io_error)?;
+            }
+            row_group.close().map_err(io_error)?;
+            self.rows_written += rows;
+        }
+        Ok(self.rows_written - start)
+    }
+
+    /// Write the footer and return the sink.
+    ///
+    /// If the columns were never fixed this writes a file without any columns.
+    pub fn finish(self) -> Result<W, TdmsError> {
+        let writer = match (self.writer, self.sink) {
+            (Some(writer), _) => writer,
+            (None, Some(sink)) => new_writer(sink, Vec::new())?,
+            (None, None) => {
+                return Err(io_error(ParquetError::General(
+                    "the sink was lost when starting the Parquet file failed".into(),
+                ))
+                .into())
+            }
+        };
+        writer.into_inner().map_err(|error| io_error(error).into())
+    }
+
+    /// Fix the columns and start the Parquet file if every channel of the group has data.
+    fn start<F: Read + Seek + Write + Debug>(
+        &mut self,
+        file: &mut TdmsFile<F>,
+    ) -> Result<(), TdmsError> {
+        let channels: Vec<ChannelPath> = file.list_channels_in_group(&self.group).collect();
+        let data_types: Option<Vec<DataType>> = channels
+            .iter()
+            .map(|channel| file.index.channel_data_type(channel))
+            .collect();
+        let Some(data_types) = data_types.filter(|types| !types.is_empty()) else {
+            return Ok(());
+        };
+
+        let conversions = channels
+            .iter()
+            .map(|channel| conversion(&self.target_units, file, channel))
+            .collect::<Result<Vec<_>, TdmsError>>()?;
+        let fields = channels
+            .iter()
+            .zip(data_types)
+            .zip(&conversions)
+            .map(|((channel, data_type), conversion)| {
+                let data_type = match conversion {
+                    Some(_) => DataType::DoubleFloat,
+                    None => data_type,
+                };
+                column_type(channel, data_type)
+            })
+            .collect::<Result<Vec<_>, TdmsError>>()?;
+
+        let Some(sink) = self.sink.take() else {
+            return Ok(());
+        };
+        self.writer = Some(new_writer(sink, fields)?);
+        self.channels = channels;
+        self.conversions = conversions;
+        Ok(())
+    }
+}
+
+/// Start a Parquet file with the columns.
+fn new_writer<W: Write + Send>(
+    sink: W,
+    fields: Vec<TypePtr>,
+) -> Result<SerializedFileWriter<W>, TdmsError> {
+    let schema = Type::group_type_builder("schema")
+        .with_fields(fields)
+        .build()
+        .map_err(io_error)?;
+    let properties = Arc::new(WriterProperties::builder().build());
+    let writer = SerializedFileWriter::new(sink, Arc::new(schema), properties).map_err(io_error)?;
+    Ok(writer)
+}
+
+/// The Parquet column for the channel stored as `data_type`.
+fn column_type(channel: &ChannelPath, data_type: DataType) -> Result<TypePtr, TdmsError> {
+    let integer = |bit_width, is_signed| LogicalType::Integer {
+        bit_width,
+        is_signed,
+    };
+    let (physical, logical) = match data_type {
+        DataType::I8 => (PhysicalType::INT32, Some(integer(8, true))),
+        DataType::I16 => (PhysicalType::INT32, Some(integer(16, true))),
+        DataType::I32 => (PhysicalType::INT32, None),
+        DataType::I64 => (PhysicalType::INT64, None),
+        DataType::U8 => (PhysicalType::INT32, Some(integer(8, false))),
+        DataType::U16 => (PhysicalType::INT32, Some(integer(16, false))),
+        DataType::U32 => (PhysicalType::INT32, Some(integer(32, false))),
+        DataType::U64 => (PhysicalType::INT64, Some(integer(64, false))),
+        DataType::SingleFloat | DataType::SingleFloatWithUnit => (PhysicalType::FLOAT, None),
+        DataType::DoubleFloat | DataType::DoubleFloatWithUnit => (PhysicalType::DOUBLE, None),
+        DataType::Boolean => (PhysicalType::BOOLEAN, None),
+        DataType::Timestamp => (
+            PhysicalType::INT64,
+            Some(LogicalType::Timestamp {
+                is_adjusted_to_u_t_c: true,
+                unit: TimeUnit::NANOS(NanoSeconds::new()),
+            }),
+        ),
+        other => return Err(TdmsError::UnsupportedType(other)),
+    };
+    let name = parse_path(channel.path()).ok().and_then(|(_, name)| name);
+    let column = Type::primitive_type_builder(name.unwrap_or_default(), physical)
+        .with_repetition(Repetition::REQUIRED)
+        .with_logical_type(logical)
+        .build()
+        .map_err(io_error)?;
+    Ok(Arc::new(column))
+}
+
+/// Write `rows` samples of the channel from `start` to the column.
+fn write_column<F: Read + Seek + Write + Debug>(
+    file: &mut TdmsFile<F>,
+    channel: &ChannelPath,
+    start: u64,
+    rows: u64,
+    column: &mut SerializedColumnWriter<'_>,
+) -> Result<(), TdmsError> {
+    let count = usize_from(rows)?;
+    macro_rules! write_as {
+        ($initial:expr, $parquet:ty, $convert:expr) => {{
+            let mut values = vec![$initial; count];
+            file.read_range(channel, start, &mut values)?;
+            let values: Vec<_> = values.into_iter().map($convert).collect();
+            column
+                .typed::<$parquet>()
+                .write_batch(&values, None, None)
+                .map_err(io_error)?;
+        }};
+    }
+
+    let data_type = file
+        .index
+        .channel_data_type(channel)
+        .ok_or_else(|| file.index.missing_channel(channel))?;
+    match data_type {
+        DataType::I8 => write_as!(0i8, Int32Type, i32::from),
+        DataType::I16 => write_as!(0i16, Int32Type, i32::from),
+        DataType::I32 => write_as!(0i32, Int32Type, |value| value),
+        DataType::I64 => write_as!(0i64, Int64Type, |value| value),
+        DataType::U8 => write_as!(0u8, Int32Type, i32::from),
+        DataType::U16 => write_as!(0u16, Int32Type, i32::from),
+        // Parquet stores unsigned integers in the signed type of the same width.
+        DataType::U32 => write_as!(0u32, Int32Type, |value| value as i32),
+        DataType::U64 => write_as!(0u64, Int64Type, |value| value as i64),
+        DataType::SingleFloat | DataType::SingleFloatWithUnit => {
+            write_as!(0f32, FloatType, |value| value)
+        }
+        DataType::DoubleFloat | DataType::DoubleFloatWithUnit => {
+            write_as!(0f64, DoubleType, |value| value)
+        }
+        DataType::Boolean => write_as!(false, BoolType, |value| value),
+        DataType::Timestamp => write_as!(LVTime::from_parts(0, 0), Int64Type, |time: LVTime| {
+            time.as_unix_nanos()
+                .clamp(i64::MIN as i128, i64::MAX as i128) as i64
+        }),
+        other => return Err(TdmsError::UnsupportedType(other)),
+    }
+    Ok(())
+}
+
+/// Write `rows` samples of the channel from `start` converted to another unit.
+fn write_converted_column<F: Read + Seek + Write + Debug>(
+    file: &mut TdmsFile<F>,
+    channel: &ChannelPath,
+    conversion: &UnitConversion,
+    start: u64,
+    rows: u64,
+    column: &mut SerializedColumnWriter<'_>,
+) -> Result<(), TdmsError> {
+    let mut values = vec![0.0; usize_from(rows)?];
+    read_range_as_f64(file, channel, start, &mut values)?;
+    for value in &mut values {
+        *value = conversion.apply(*value);
+    }
+    column
+        .typed::<DoubleType>()
+        .write_batch(&values, None, None)
+        .map_err(io_error)?;
+    Ok(())
+}
+
+fn io_error(error: ParquetError) -> std::io::Error {
+    std::io::Error::other(error)
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::Cursor;
+
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::record::RowAccessor;
+
+    use super::*;
+    use crate::{DataLayout, PropertyValue};
+
+    fn read_rows(path: &std::path::Path) -> (usize, Vec<parquet::record::Row>) {
+        let reader = SerializedFileReader::new(std::fs::File::open(path).unwrap()).unwrap();
+        let row_groups = reader.metadata().num_row_groups();
+        let rows = reader
+            .get_row_iter(None)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        (row_groups, rows)
+    }
+
+    #[test]
+    fn test_rows_appended_as_row_groups() {
+        let group = GroupPath::new("rig");
+        let time = ChannelPath::new("rig", "time");
+        let count = ChannelPath::new("rig", "count");
+        let pressure = ChannelPath::new("rig", "pressure");
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let path =
+            std::env::temp_dir().join(format!("parquet_follow_{}.parquet", std::process::id()));
+        let sink = std::fs::File::create(&path).unwrap();
+        let mut follower = ParquetFollower::new(&group, sink).with_unit(&pressure, "kPa");
+        assert_eq!(follower.poll(&mut file).unwrap(), 0);
+
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_properties(
+                pressure.as_ref(),
+                &[("unit_string", PropertyValue::from("bar"))],
+            )
+            .unwrap();
+        writer
+            .write_channels(
+                &[&time],
+                &[
+                    LVTime::from_unix_nanos(1_000),
+                    LVTime::from_unix_nanos(2_000),
+                ],
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        writer
+            .write_channels(&[&count], &[1u16, 2], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_channels(&[&pressure], &[0.5f32], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+        assert_eq!(follower.poll(&mut file).unwrap(), 1);
+
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&pressure], &[2f32], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+        assert_eq!(follower.poll(&mut file).unwrap(), 1);
+        assert_eq!(follower.rows_written(), 2);
+
+        follower.finish().unwrap();
+        let (row_groups, rows) = read_rows(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(row_groups, 2);
+        assert_eq!(rows.len(), 2);
+        for (row, (nanos, count, pressure)) in
+            rows.iter().zip([(1_000, 1, 50.0), (2_000, 2, 200.0)])
+        {
+            assert_eq!(row.get_double(0).unwrap(), pressure);
+            assert_eq!(row.get_long(1).unwrap(), nanos);
+            assert_eq!(row.get_ushort(2).unwrap(), count);
+        }
+    }
+
+    #[test]
+    fn test_finish_without_columns() {
+        let group = GroupPath::new("rig");
+        let path =
+            std::env::temp_dir().join(format!("parquet_empty_{}.parquet", std::process::id()));
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_properties(
+                ChannelPath::new("rig", "pending").as_ref(),
+                &[("unit_string", PropertyValue::from("bar"))],
+            )
+            .unwrap();
+        drop(writer);
+
+        let sink = std::fs::File::create(&path).unwrap();
+        let follower = follow_to_parquet(&mut file, &group, sink).unwrap();
+        assert_eq!(follower.rows_written(), 0);
+        follower.finish().unwrap();
+        let (row_groups, rows) = read_rows(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(row_groups, 0);
+        assert!(rows.is_empty());
+    }
+}
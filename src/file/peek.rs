@@ -0,0 +1,164 @@
+//! Reading properties without indexing the whole file.
+//!
+//! Tools which only need a few properties, such as a name or serial number,
+//! from thousands of files don't need the data index. [`peek_properties`]
+//! reads just the segment metadata and seeks over all of the raw data.
+
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::path::Path;
+
+use super::os_path::os_path;
+use crate::error::TdmsError;
+use crate::index::missing_object_error;
+use crate::meta_data::{SegmentScanner, LEAD_IN_BYTES};
+use crate::{PropertyPath, PropertyValue};
+
+/// Read the latest value of every property of an object from the file at `path`.
+///
+/// Only the metadata of each segment is read. Properties are returned in the
+/// order they were first written. A final segment that is still being written
+/// is ignored.
+///
+/// Errors with [`TdmsError::MissingGroup`] or [`TdmsError::MissingChannel`] if
+/// the object isn't in the file. The file object always exists, so a file
+/// without any file properties returns an empty list.
+///
+/// # Example
+#[cfg_attr(feature = "write", doc = "```rust")]
+#[cfg_attr(not(feature = "write"), doc = "```ignore")]
+/// use tedium::{peek_properties, PropertyPath, PropertyValue, TdmsFile};
+///
+/// let path = std::env::temp_dir().join(format!("peek_doc_{}.tdms", std::process::id()));
+/// let mut file = TdmsFile::create(&path).unwrap();
+/// let mut writer = file.writer().unwrap();
+/// writer
+///     .write_properties(&PropertyPath::file(), &[("name", PropertyValue::String("run".into()))])
+///     .unwrap();
+/// drop(writer);
+/// drop(file);
+///
+/// let properties = peek_properties(&path, &PropertyPath::file()).unwrap();
+/// assert_eq!(properties, vec![("name".to_string(), PropertyValue::String("run".into()))]);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn peek_properties(
+    path: &Path,
+    object_path: &PropertyPath,
+) -> Result<Vec<(String, PropertyValue)>, TdmsError> {
+    let file = File::open(os_path(path))?;
+    let file_length = file.metadata()?.len();
+    let mut scanner = SegmentScanner::new(file);
+
+    let mut found = false;
+    let mut seen = BTreeSet::new();
+    let mut properties: Vec<(String, PropertyValue)> = Vec::new();
+    let mut position = 0;
+
+    while position + LEAD_IN_BYTES <= file_length {
+        let segment = match scanner.read_segment() {
+            Ok(segment) => segment,
+            Err(TdmsError::EndOfFile) => break,
+            Err(e) => return Err(e),
+        };
+        let Some(next_position) = segment
+            .total_size_bytes()
+            .ok()
+            .and_then(|size| position.checked_add(size))
+            .filter(|next| *next <= file_length)
+        else {
+            // The last segment is incomplete.
+            break;
+        };
+
+        let objects = segment.meta_data.iter().flat_map(|meta| &meta.objects);
+        for object in objects {
+            if object.path != object_path.path() {
+                // Only needed for suggestions if the object is never found.
+                if !found && !seen.contains(&object.path) {
+                    seen.insert(object.path.clone());
+                }
+                continue;
+            }
+            found = true;
+            for (name, value) in &object.properties {
+                match properties.iter_mut().find(|(existing, _)| existing == name) {
+                    Some((_, existing)) => *existing = value.clone(),
+                    None => properties.push((name.clone(), value.clone())),
+                }
+            }
+        }
+
+        position = next_position;
+        scanner.seek_to(position)?;
+    }
+
+    if !found {
+        let existing = seen.iter().map(String::as_str);
+        if let Some(error) = missing_object_error(object_path.path(), existing) {
+            return Err(error);
+        }
+    }
+    Ok(properties)
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::{ChannelPath, DataLayout, TdmsFile};
+
+    #[test]
+    fn test_peek_reads_latest_properties_past_data() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("target");
+        path.push(format!("peek_{}.tdms", std::process::id()));
+
+        let channel = ChannelPath::new("group", "channel");
+        let mut file = TdmsFile::create(&path).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_properties(
+                &PropertyPath::file(),
+                &[
+                    ("name", PropertyValue::String("first".to_string())),
+                    ("serial_number", PropertyValue::I32(7)),
+                ],
+            )
+            .unwrap();
+        writer
+            .write_channels(&[&channel], &[1.0; 1000], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_properties(
+                &PropertyPath::file(),
+                &[("name", PropertyValue::String("second".to_string()))],
+            )
+            .unwrap();
+        writer
+            .write_channels(&[&channel], &[2.0; 1000], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+        drop(file);
+
+        let properties = peek_properties(&path, &PropertyPath::file()).unwrap();
+        assert_eq!(
+            properties,
+            vec![
+                (
+                    "name".to_string(),
+                    PropertyValue::String("second".to_string())
+                ),
+                ("serial_number".to_string(), PropertyValue::I32(7)),
+            ]
+        );
+        assert!(peek_properties(&path, channel.as_ref()).unwrap().is_empty());
+        assert!(matches!(
+            peek_properties(&path, &PropertyPath::group("missing")),
+            Err(TdmsError::MissingGroup { .. })
+        ));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
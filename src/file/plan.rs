@@ -0,0 +1,365 @@
+//! Planning reads before making them.
+//!
+//! [`TdmsFile::plan_read`] works out from the index which data blocks a read
+//! of a channel range touches and how many bytes it will pull from the file,
+//! without reading anything. Comparing the bytes read with the size of the
+//! samples shows the read amplification of interleaved or fragmented files.
+//!
+//! [`TdmsFile::execute_plan`] then makes the read within a [`ReadBudget`],
+//! stopping cleanly with the samples read so far if the budget runs out. This
+//! suits speculative reads from interactive viewers over network storage.
+
+use std::io::{Read, Seek};
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+use crate::error::{usize_from, TdmsError};
+use crate::io::data_types::TdmsStorageType;
+use crate::paths::ChannelPath;
+use crate::raw_data::DataLayout;
+use crate::TdmsFile;
+
+/// The part of one data block a planned read touches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlannedBlock {
+    /// The number of the data block in the file.
+    pub data_block: usize,
+    /// The index of the channel in the block.
+    pub channel_index: usize,
+    pub layout: DataLayout,
+    /// The byte offset of the block in the file.
+    pub block_start: u64,
+    /// The samples of the channel in the block skipped before reading.
+    pub skip_samples: u64,
+    /// The samples of the channel read from the block.
+    pub samples: u64,
+    /// The bytes read from the file for those samples.
+    ///
+    /// Interleaved blocks read every channel of each record.
+    pub bytes: u64,
+}
+
+/// A description of the IO a read of a channel range will make.
+///
+/// Created by [`TdmsFile::plan_read`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadPlan {
+    pub channel: ChannelPath,
+    /// The first sample read.
+    pub start: u64,
+    /// The samples read, which is less than requested if the range passes the end of the channel.
+    pub samples: u64,
+    /// The size of one sample of the channel in bytes, or 0 for variable size types.
+    pub sample_bytes: u64,
+    /// The blocks read in order.
+    pub blocks: Vec<PlannedBlock>,
+}
+
+impl ReadPlan {
+    /// The total bytes read from the file.
+    pub fn bytes(&self) -> u64 {
+        self.blocks.iter().map(|block| block.bytes).sum()
+    }
+
+    /// The bytes read per byte of the samples requested.
+    ///
+    /// 1.0 means only the samples are read. Returns 1.0 for an empty plan
+    /// or a variable size type.
+    pub fn amplification(&self) -> f64 {
+        let useful = self.samples * self.sample_bytes;
+        match useful {
+            0 => 1.0,
+            useful => self.bytes() as f64 / useful as f64,
+        }
+    }
+}
+
+/// Limits on the IO of [`TdmsFile::execute_plan`].
+///
+/// The default has no limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ReadBudget {
+    /// The most bytes to read from the file.
+    pub max_bytes: Option<u64>,
+    /// The longest to spend reading.
+    ///
+    /// This is checked before each block so a slow block can overrun it.
+    pub max_time: Option<Duration>,
+}
+
+/// The result of executing a [`ReadPlan`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlanOutcome {
+    /// The number of samples read into the start of the output.
+    pub samples_read: usize,
+    /// The bytes read from the file.
+    pub bytes_read: u64,
+    /// True if the budget stopped the read before the end of the plan.
+    pub aborted: bool,
+}
+
+impl<F: Read + Seek> TdmsFile<F> {
+    /// Plan a read of the samples in `range` of the channel without reading them.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channels = [ChannelPath::new("group", "a"), ChannelPath::new("group", "b")];
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&channels, &[1.0, 10.0, 2.0, 20.0], DataLayout::Interleaved).unwrap();
+    /// writer.write_channels(&channels, &[3.0, 30.0, 4.0, 40.0], DataLayout::Interleaved).unwrap();
+    /// drop(writer);
+    ///
+    /// let plan = file.plan_read(&channels[0], 1..3).unwrap();
+    /// assert_eq!(plan.blocks.len(), 2);
+    /// assert_eq!(plan.bytes(), 32);
+    /// assert_eq!(plan.amplification(), 2.0);
+    /// ```
+    pub fn plan_read(
+        &self,
+        channel: &ChannelPath,
+        range: Range<u64>,
+    ) -> Result<ReadPlan, TdmsError> {
+        let locations = self
+            .index
+            .get_channel_data_positions(channel)
+            .ok_or_else(|| self.index.missing_channel(channel))?;
+        let sample_bytes = self
+            .index
+            .channel_data_type(channel)
+            .map_or(0, |data_type| data_type.size() as u64);
+
+        let mut plan = ReadPlan {
+            channel: channel.clone(),
+            start: range.start,
+            samples: 0,
+            sample_bytes,
+            blocks: Vec::new(),
+        };
+        let Some((first_location, mut skip_samples)) =
+            self.index.find_sample_location(channel, range.start)
+        else {
+            return Ok(plan);
+        };
+
+        let mut remaining = range.end.saturating_sub(range.start);
+        for location in &locations[first_location..] {
+            if remaining == 0 {
+                break;
+            }
+            let block = self
+                .index
+                .get_data_block(location.data_block)
+                .ok_or_else(|| {
+                    TdmsError::DataBlockNotFound(channel.clone(), location.data_block)
+                })?;
+            let samples = location
+                .number_of_samples
+                .saturating_sub(skip_samples)
+                .min(remaining);
+            let meta = &block.channels[location.channel_index];
+            let bytes = match (meta.total_size_bytes, block.layout) {
+                (Some(total), _) => total,
+                (None, DataLayout::Contigious) => samples * meta.data_type.size() as u64,
+                (None, DataLayout::Interleaved) => {
+                    let record: u64 = block
+                        .channels
+                        .iter()
+                        .map(|channel| channel.data_type.size() as u64)
+                        .sum();
+                    samples * record
+                }
+            };
+            plan.blocks.push(PlannedBlock {
+                data_block: location.data_block,
+                channel_index: location.channel_index,
+                layout: block.layout,
+                block_start: block.start,
+                skip_samples,
+                samples,
+                bytes,
+            });
+            plan.samples += samples;
+            remaining -= samples;
+            skip_samples = 0;
+        }
+        Ok(plan)
+    }
+
+    /// Read the samples of the plan into `output` within the budget.
+    ///
+    /// Blocks are read whole or not at all. Before each block the read stops,
+    /// setting [`PlanOutcome::aborted`], if the block would take the bytes
+    /// read past the budget or the time has run out. Reading stops at the end
+    /// of `output` if it is shorter than the plan.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, ReadBudget, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[1.0, 2.0], DataLayout::Contigious).unwrap();
+    /// writer.write_channels(&[&channel], &[3.0, 4.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let plan = file.plan_read(&channel, 0..4).unwrap();
+    /// let budget = ReadBudget { max_bytes: Some(20), ..Default::default() };
+    /// let mut output = [0.0; 4];
+    /// let outcome = file.execute_plan(&plan, &mut output, budget).unwrap();
+    /// assert!(outcome.aborted);
+    /// assert_eq!(&output[..outcome.samples_read], &[1.0, 2.0]);
+    /// ```
+    pub fn execute_plan<D: TdmsStorageType>(
+        &mut self,
+        plan: &ReadPlan,
+        output: &mut [D],
+        budget: ReadBudget,
+    ) -> Result<PlanOutcome, TdmsError> {
+        let started = Instant::now();
+        let mut outcome = PlanOutcome {
+            samples_read: 0,
+            bytes_read: 0,
+            aborted: false,
+        };
+
+        for planned in &plan.blocks {
+            if outcome.samples_read >= output.len() {
+                break;
+            }
+            let over_bytes = budget
+                .max_bytes
+                .is_some_and(|max| outcome.bytes_read + planned.bytes > max);
+            let over_time = budget.max_time.is_some_and(|max| started.elapsed() >= max);
+            if over_bytes || over_time {
+                outcome.aborted = true;
+                break;
+            }
+
+            let block = self
+                .index
+                .get_data_block(planned.data_block)
+                .ok_or_else(|| {
+                    TdmsError::DataBlockNotFound(plan.channel.clone(), planned.data_block)
+                })?;
+            let end = outcome
+                .samples_read
+                .saturating_add(usize_from(planned.samples)?)
+                .min(output.len());
+            let read = self
+                .retry
+                .run(|| {
+                    block.read_from(
+                        &mut self.file,
+                        planned.skip_samples,
+                        &mut [(
+                            planned.channel_index,
+                            &mut output[outcome.samples_read..end],
+                        )],
+                    )
+                })
+                .map_err(|error| error.with_channel(&plan.channel))?;
+            outcome.samples_read += read.min(end - outcome.samples_read);
+            outcome.bytes_read += planned.bytes;
+        }
+        Ok(outcome)
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use super::*;
+    use crate::io::data_types::DataType;
+    use crate::testgen::TestFileSpec;
+
+    #[test]
+    fn test_plan_matches_the_read() {
+        for layout in [DataLayout::Contigious, DataLayout::Interleaved] {
+            let spec = TestFileSpec {
+                channels_per_group: 4,
+                segments: 5,
+                samples_per_segment: 10,
+                layout,
+                data_type: DataType::I32,
+                ..Default::default()
+            };
+            let mut file = TdmsFile::new(std::io::Cursor::new(spec.build().unwrap())).unwrap();
+            let channel = spec.channel_path(0, 2);
+
+            let plan = file.plan_read(&channel, 15..45).unwrap();
+            let skips: Vec<u64> = plan.blocks.iter().map(|block| block.skip_samples).collect();
+            let samples: Vec<u64> = plan.blocks.iter().map(|block| block.samples).collect();
+            assert_eq!(skips, [5, 0, 0, 0]);
+            assert_eq!(samples, [5, 10, 10, 5]);
+            let expected_amplification = match layout {
+                DataLayout::Contigious => 1.0,
+                DataLayout::Interleaved => 4.0,
+            };
+            assert_eq!(plan.amplification(), expected_amplification);
+
+            let mut output = vec![0i32; 30];
+            let outcome = file.read_range(&channel, 15, &mut output).unwrap();
+            assert_eq!(outcome.samples_read as u64, plan.samples);
+
+            let past_end = file.plan_read(&channel, 45..100).unwrap();
+            assert_eq!(past_end.samples, 5);
+            assert!(file.plan_read(&channel, 60..70).unwrap().blocks.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_budgets_stop_between_blocks() {
+        let spec = TestFileSpec {
+            segments: 4,
+            samples_per_segment: 10,
+            data_type: DataType::I32,
+            ..Default::default()
+        };
+        let mut file = TdmsFile::new(std::io::Cursor::new(spec.build().unwrap())).unwrap();
+        let channel = spec.channel_path(0, 0);
+        let expected: Vec<i32> = file.read_all(&channel).unwrap();
+        let plan = file.plan_read(&channel, 5..40).unwrap();
+
+        let mut output = vec![0i32; 35];
+        let outcome = file
+            .execute_plan(&plan, &mut output, ReadBudget::default())
+            .unwrap();
+        assert_eq!(outcome.samples_read, 35);
+        assert_eq!(outcome.bytes_read, plan.bytes());
+        assert!(!outcome.aborted);
+        assert_eq!(output, expected[5..]);
+
+        // The first block of 20 bytes and the second of 40 fit, the third doesn't.
+        let budget = ReadBudget {
+            max_bytes: Some(99),
+            ..Default::default()
+        };
+        let mut output = vec![0i32; 35];
+        let outcome = file.execute_plan(&plan, &mut output, budget).unwrap();
+        assert_eq!((outcome.samples_read, outcome.bytes_read), (15, 60));
+        assert!(outcome.aborted);
+        assert_eq!(output[..15], expected[5..20]);
+
+        let budget = ReadBudget {
+            max_time: Some(Duration::ZERO),
+            ..Default::default()
+        };
+        let outcome = file.execute_plan(&plan, &mut output, budget).unwrap();
+        assert_eq!(outcome.samples_read, 0);
+        assert!(outcome.aborted);
+
+        // A short output stops the read without counting as an abort.
+        let mut short = vec![0i32; 8];
+        let outcome = file
+            .execute_plan(&plan, &mut short, ReadBudget::default())
+            .unwrap();
+        assert_eq!(outcome.samples_read, 8);
+        assert!(!outcome.aborted);
+        assert_eq!(short, expected[5..13]);
+    }
+}
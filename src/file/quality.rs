@@ -0,0 +1,193 @@
+//! Channels paired with a quality flag channel.
+//!
+//! SCADA systems and historians log a quality code alongside every value to
+//! mark it as good, uncertain or bad. The convention here is:
+//!
+//! * The quality channel is in the same group as the value channel and is
+//!   named after it with [`QUALITY_SUFFIX`], so `pressure` has
+//!   `pressure_quality`.
+//! * The quality channel holds one U8 flag per value. The meaning of the
+//!   flags is left to the application.
+//!
+//! [`TdmsFile::read_with_quality`] reads the pairs back as tuples and
+//! [`crate::TdmsFileWriter::write_with_quality`] writes both channels in a
+//! single segment.
+
+use std::fmt::Debug;
+use std::io::{Read, Seek, Write};
+
+use crate::error::TdmsError;
+use crate::io::data_types::TdmsStorageType;
+use crate::paths::{parse_path, ChannelPath};
+use crate::TdmsFile;
+
+/// The suffix added to a channel name for its quality channel.
+pub const QUALITY_SUFFIX: &str = "_quality";
+
+/// The path of the quality channel paired with the channel.
+pub fn quality_channel(channel: &ChannelPath) -> ChannelPath {
+    match parse_path(channel.path()) {
+        Ok((Some(group), Some(name))) => {
+            ChannelPath::new(group, &format!("{name}{QUALITY_SUFFIX}"))
+        }
+        // Channel paths always have a group and channel name.
+        _ => unreachable!("channel path without a group and channel name"),
+    }
+}
+
+impl<F: Read + Seek + Write + Debug> TdmsFile<F> {
+    /// Read a channel and its quality channel as pairs of value and quality flag.
+    ///
+    /// Errors with [`TdmsError::QualityLengthMismatch`] if the quality channel
+    /// has a different length to the channel.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("plant", "pressure");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_with_quality(&channel, &[1.5, 1.6], &[192, 0]).unwrap();
+    /// drop(writer);
+    ///
+    /// let pairs = file.read_with_quality::<f64>(&channel).unwrap();
+    /// assert_eq!(pairs, vec![(1.5, 192), (1.6, 0)]);
+    /// ```
+    pub fn read_with_quality<D: TdmsStorageType + Default + Clone>(
+        &mut self,
+        channel: &ChannelPath,
+    ) -> Result<Vec<(D, u8)>, TdmsError> {
+        let values: Vec<D> = self.read_all(channel)?;
+        let quality: Vec<u8> = self.read_all(&quality_channel(channel))?;
+        if values.len() != quality.len() {
+            return Err(TdmsError::QualityLengthMismatch {
+                channel: channel.clone(),
+                values: values.len(),
+                quality: quality.len(),
+            });
+        }
+        Ok(values.into_iter().zip(quality).collect())
+    }
+}
+
+#[cfg(feature = "write")]
+mod write {
+    use std::io::Write;
+
+    use super::*;
+    use crate::io::writer::TdmsWriter;
+    use crate::meta_data::RawDataMeta;
+    use crate::raw_data::WriteBlock;
+    use crate::{DataLayout, TdmsFileWriter};
+
+    /// The values and quality flags written contiguously in one block.
+    struct QualityBlock<'a, D: TdmsStorageType> {
+        values: &'a [D],
+        quality: &'a [u8],
+    }
+
+    impl<D: TdmsStorageType> WriteBlock for QualityBlock<'_, D> {
+        fn data_structure(&self) -> Vec<RawDataMeta> {
+            let mut structure = self.values.data_structure();
+            structure.extend(self.quality.data_structure());
+            structure
+        }
+
+        fn write<W: Write, T: TdmsWriter<W>>(&self, writer: &mut T) -> Result<(), TdmsError> {
+            self.values.write(writer)?;
+            self.quality.write(writer)
+        }
+
+        fn size(&self) -> usize {
+            self.values.size() + self.quality.size()
+        }
+    }
+
+    impl<'a, F: Write, W: TdmsWriter<&'a mut F>> TdmsFileWriter<'a, F, W> {
+        /// Write values to the channel and their flags to its quality channel in a single segment.
+        ///
+        /// Repeated writes to the same channel only write raw data, like
+        /// [`Self::write_channels`]. The segment limit is not applied. Errors
+        /// with [`TdmsError::QualityLengthMismatch`] if there isn't one flag
+        /// per value.
+        pub fn write_with_quality<D: TdmsStorageType>(
+            &mut self,
+            channel: &ChannelPath,
+            values: &[D],
+            quality: &[u8],
+        ) -> Result<(), TdmsError> {
+            if values.len() != quality.len() {
+                return Err(TdmsError::QualityLengthMismatch {
+                    channel: channel.clone(),
+                    values: values.len(),
+                    quality: quality.len(),
+                });
+            }
+            let quality_channel = quality_channel(channel);
+            let paths = [channel.path(), quality_channel.path()];
+            self.write_block(
+                &paths,
+                QualityBlock { values, quality },
+                DataLayout::Contigious,
+            )
+        }
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quality_channel_name() {
+        let channel = ChannelPath::new("group", "it's");
+        assert_eq!(
+            quality_channel(&channel),
+            ChannelPath::new("group", "it's_quality")
+        );
+    }
+
+    #[test]
+    fn test_repeated_writes_round_trip() {
+        let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+        let channel = ChannelPath::new("plant", "flow");
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_with_quality(&channel, &[1i32, 2], &[0, 1])
+            .unwrap();
+        writer
+            .write_with_quality(&channel, &[3i32, 4], &[2, 3])
+            .unwrap();
+        drop(writer);
+
+        assert_eq!(file.data_block_count(), 2);
+        assert!(!file.data_block_debug(1).unwrap().toc.contains_meta_data);
+        let pairs = file.read_with_quality::<i32>(&channel).unwrap();
+        assert_eq!(pairs, vec![(1, 0), (2, 1), (3, 2), (4, 3)]);
+    }
+
+    #[test]
+    fn test_mismatched_lengths_error() {
+        let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+        let channel = ChannelPath::new("plant", "flow");
+        let mut writer = file.writer().unwrap();
+        let result = writer.write_with_quality(&channel, &[1.0, 2.0], &[0]);
+        assert!(matches!(
+            result,
+            Err(TdmsError::QualityLengthMismatch {
+                values: 2,
+                quality: 1,
+                ..
+            })
+        ));
+        writer
+            .write_channels(&[&channel], &[1.0], crate::DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+
+        let result = file.read_with_quality::<f64>(&channel);
+        assert!(matches!(result, Err(TdmsError::MissingChannel { .. })));
+    }
+}
@@ -0,0 +1,296 @@
+//! Reading TDMS files from remote storage with ranged requests.
+//!
+//! Remote stores such as object stores and web servers can return any byte
+//! range of a file but each request has a high latency. A [`RangeReader`]
+//! turns a [`RangeSource`] into a reader which fetches at least a minimum
+//! number of bytes per request and keeps recent fetches in a cache. Small
+//! reads of the lead ins and metadata, and nearby data blocks, are then
+//! served from one request rather than one each.
+
+use std::collections::VecDeque;
+use std::io::{Read, Result as IoResult, Seek, SeekFrom, Write};
+use std::ops::Range;
+
+use crate::error::TdmsError;
+use crate::TdmsFile;
+
+/// A store which can return byte ranges of a single file.
+pub trait RangeSource {
+    /// The total size of the file in bytes.
+    fn size(&mut self) -> IoResult<u64>;
+    /// Fetch the bytes in the range. The range is always within the file.
+    fn fetch(&mut self, range: Range<u64>) -> IoResult<Vec<u8>>;
+}
+
+/// A read only reader over a [`RangeSource`] which coalesces small reads.
+#[derive(Debug)]
+pub struct RangeReader<S> {
+    source: S,
+    length: u64,
+    position: u64,
+    min_fetch: u64,
+    cache_windows: usize,
+    /// The cached fetches with their start offsets, most recently used first.
+    windows: VecDeque<(u64, Vec<u8>)>,
+    fetches: u64,
+    bytes_fetched: u64,
+}
+
+impl<S: RangeSource> RangeReader<S> {
+    /// The default minimum number of bytes fetched per request.
+    pub const DEFAULT_MIN_FETCH: u64 = 256 * 1024;
+    /// The default number of fetches kept in the cache.
+    pub const DEFAULT_CACHE_WINDOWS: usize = 16;
+
+    /// Create the reader, requesting the size of the file from the source.
+    pub fn new(mut source: S) -> IoResult<Self> {
+        let length = source.size()?;
+        Ok(Self {
+            source,
+            length,
+            position: 0,
+            min_fetch: Self::DEFAULT_MIN_FETCH,
+            cache_windows: Self::DEFAULT_CACHE_WINDOWS,
+            windows: VecDeque::new(),
+            fetches: 0,
+            bytes_fetched: 0,
+        })
+    }
+
+    /// Set the minimum number of bytes fetched per request.
+    ///
+    /// Larger values make fewer requests at the cost of fetching bytes which
+    /// may not be needed.
+    pub fn with_min_fetch(mut self, bytes: u64) -> Self {
+        self.min_fetch = bytes.max(1);
+        self
+    }
+
+    /// Set the number of fetches kept in the cache. At least one is always kept.
+    pub fn with_cache_windows(mut self, windows: usize) -> Self {
+        self.cache_windows = windows.max(1);
+        self.windows.truncate(self.cache_windows);
+        self
+    }
+
+    /// Make sure the bytes at the current position are in the cache and
+    /// return the cached window.
+    fn window(&mut self, wanted: usize) -> IoResult<&[u8]> {
+        let position = self.position;
+        let cached = self
+            .windows
+            .iter()
+            .position(|(start, bytes)| *start <= position && position < start + bytes.len() as u64);
+        match cached {
+            Some(0) => {}
+            Some(found) => {
+                let window = self.windows.remove(found).expect("index is in range");
+                self.windows.push_front(window);
+            }
+            None => {
+                let end = position
+                    .saturating_add((wanted as u64).max(self.min_fetch))
+                    .min(self.length);
+                let bytes = self.source.fetch(position..end)?;
+                if bytes.len() as u64 != end - position {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        format!(
+                            "range source returned {} bytes for a {} byte range",
+                            bytes.len(),
+                            end - position
+                        ),
+                    ));
+                }
+                self.fetches += 1;
+                self.bytes_fetched += bytes.len() as u64;
+                self.windows.push_front((position, bytes));
+                self.windows.truncate(self.cache_windows);
+            }
+        }
+        let (start, bytes) = &self.windows[0];
+        Ok(&bytes[(position - start) as usize..])
+    }
+}
+
+impl<S> RangeReader<S> {
+    /// The size of the file in bytes.
+    pub fn len(&self) -> u64 {
+        self.length
+    }
+
+    /// True if the file has no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// The number of requests made to the source.
+    pub fn fetches(&self) -> u64 {
+        self.fetches
+    }
+
+    /// The total number of bytes fetched from the source.
+    pub fn bytes_fetched(&self) -> u64 {
+        self.bytes_fetched
+    }
+
+    /// Return the source.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S: RangeSource> Read for RangeReader<S> {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        if buf.is_empty() || self.position >= self.length {
+            return Ok(0);
+        }
+        let window = self.window(buf.len())?;
+        let read = window.len().min(buf.len());
+        buf[..read].copy_from_slice(&window[..read]);
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl<S> Seek for RangeReader<S> {
+    fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+        let target = match pos {
+            SeekFrom::Start(position) => Some(position),
+            SeekFrom::End(delta) => self.length.checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.position.checked_add_signed(delta),
+        };
+        let Some(target) = target else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "seek to a negative position in the range reader",
+            ));
+        };
+        self.position = target;
+        Ok(target)
+    }
+}
+
+/// Writing is not supported as remote files are read only.
+///
+/// This exists so the read API, which is shared with writable files, is available.
+impl<S> Write for RangeReader<S> {
+    fn write(&mut self, _buf: &[u8]) -> IoResult<usize> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "range readers are read only",
+        ))
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+impl<S: RangeSource> TdmsFile<RangeReader<S>> {
+    /// Open a TDMS file from a source of byte ranges with the default fetch settings.
+    ///
+    /// Use [`TdmsFile::new`] with a configured [`RangeReader`] to change them.
+    pub fn open_ranges(source: S) -> Result<Self, TdmsError> {
+        TdmsFile::new(RangeReader::new(source)?)
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use super::*;
+    use crate::testgen::TestFileSpec;
+
+    /// A source over bytes in memory which records each fetch.
+    #[derive(Debug)]
+    struct MemorySource {
+        bytes: Vec<u8>,
+        fetched: Vec<Range<u64>>,
+    }
+
+    impl RangeSource for MemorySource {
+        fn size(&mut self) -> IoResult<u64> {
+            Ok(self.bytes.len() as u64)
+        }
+
+        fn fetch(&mut self, range: Range<u64>) -> IoResult<Vec<u8>> {
+            self.fetched.push(range.clone());
+            Ok(self.bytes[range.start as usize..range.end as usize].to_vec())
+        }
+    }
+
+    fn source(bytes: Vec<u8>) -> MemorySource {
+        MemorySource {
+            bytes,
+            fetched: vec![],
+        }
+    }
+
+    #[test]
+    fn test_small_reads_are_coalesced() {
+        let mut reader = RangeReader::new(source((0u8..100).collect()))
+            .unwrap()
+            .with_min_fetch(40);
+
+        let mut buf = [0u8; 4];
+        for expected in [[0, 1, 2, 3], [4, 5, 6, 7]] {
+            reader.read_exact(&mut buf).unwrap();
+            assert_eq!(buf, expected);
+        }
+        reader.seek(SeekFrom::Start(38)).unwrap();
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [38, 39, 40, 41]);
+        // Going back to the first window is served from the cache.
+        reader.seek(SeekFrom::Start(10)).unwrap();
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [10, 11, 12, 13]);
+
+        assert_eq!(reader.into_inner().fetched, vec![0..40, 40..80]);
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let mut reader = RangeReader::new(source((0u8..100).collect()))
+            .unwrap()
+            .with_min_fetch(10)
+            .with_cache_windows(2);
+        let mut buf = [0u8; 1];
+        for position in [0, 50, 0, 90, 0, 50] {
+            reader.seek(SeekFrom::Start(position)).unwrap();
+            reader.read_exact(&mut buf).unwrap();
+            assert_eq!(buf[0] as u64, position);
+        }
+        assert_eq!(reader.fetches(), 4);
+        assert_eq!(
+            reader.into_inner().fetched,
+            vec![0..10, 50..60, 90..100, 50..60]
+        );
+    }
+
+    #[test]
+    fn test_read_file_from_range_source() {
+        let spec = TestFileSpec {
+            segments: 4,
+            ..Default::default()
+        };
+        let bytes = spec.build().unwrap();
+        let length = bytes.len() as u64;
+        let reader = RangeReader::new(source(bytes.clone()))
+            .unwrap()
+            .with_min_fetch(64);
+        let mut file = TdmsFile::new(reader).unwrap();
+        let mut expected = TdmsFile::new(std::io::Cursor::new(bytes)).unwrap();
+
+        let channel = spec.channel_path(0, 1);
+        let read: Vec<f64> = file.read_all(&channel).unwrap();
+        assert_eq!(read, expected.read_all::<f64>(&channel).unwrap());
+
+        // A fetch as large as the file means everything comes from one request.
+        let reader = RangeReader::new(source(expected.file.get_ref().clone()))
+            .unwrap()
+            .with_min_fetch(length);
+        let mut file = TdmsFile::new(reader).unwrap();
+        assert_eq!(file.read_all::<f64>(&channel).unwrap(), read);
+        assert_eq!(file.file.fetches(), 1);
+    }
+}
@@ -0,0 +1,667 @@
+//! Rewriting a file with each channel stored contiguously.
+//!
+//! Loggers write a segment per acquisition, so long runs end up with
+//! thousands of small data blocks. [`repack`] reads each channel and writes
+//! it back in large single channel segments, which makes the file smaller
+//! and faster to read. Channels can be converted on the way, such as storing
+//! double precision data as single precision for archiving or dropping
+//! samples within a dead-band.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use labview_interop::types::LVTime;
+
+use super::channel_reader::read_range_from;
+use super::dead_band::{INDEX_CHANNEL_PROPERTY, SAMPLES_PROPERTY, WIDTH_PROPERTY};
+use super::rewrite::{slice_block, write_raw_segment, Redaction};
+use crate::error::{usize_from, TdmsError};
+use crate::io::data_types::{DataType, TdmsStorageType};
+use crate::io::writer::{LittleEndianWriter, TdmsWriter};
+use crate::meta_data::{MetaData, ObjectMetaData, RawDataIndex, RawDataMeta};
+use crate::paths::{parse_path, ChannelPath};
+use crate::raw_data::{DataLayout, Endianess};
+use crate::{PropertyPath, PropertyValue, TdmsFile, ToC};
+
+/// The most samples of a channel written in one segment.
+const SEGMENT_SAMPLES: u64 = 1 << 20;
+
+/// What to do with a value outside the range of the type it is converted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RangePolicy {
+    /// Stop the repack with [`TdmsError::ValueOutOfRange`].
+    #[default]
+    Error,
+    /// Store the nearest value the type can hold.
+    Clamp,
+}
+
+/// The changes made to the channels of a file by [`repack`].
+///
+/// The changes are all made in the one pass over the file.
+#[derive(Debug, Clone, Default)]
+pub struct RepackOptions {
+    f64_to_f32: Option<RangePolicy>,
+    /// The dead-band width by channel path.
+    dead_bands: BTreeMap<String, f64>,
+    redaction: Redaction,
+    excluded: BTreeSet<String>,
+}
+
+impl RepackOptions {
+    /// Repack without changing any values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store double precision channels as single precision, halving their size.
+    ///
+    /// Values beyond the range of f32 are handled by `policy`. Infinities and
+    /// NaN are kept and other values are rounded to the nearest f32.
+    pub fn f64_to_f32(mut self, policy: RangePolicy) -> Self {
+        self.f64_to_f32 = Some(policy);
+        self
+    }
+
+    /// Only store samples of the channel which differ from the last stored
+    /// sample by more than `width`.
+    ///
+    /// The stored values are written as f64 with their sample indexes in a
+    /// companion channel named `<channel>_index`. See
+    /// [`TdmsFile::read_dead_band`] for the convention and reading them back.
+    pub fn dead_band(mut self, channel: &ChannelPath, width: f64) -> Self {
+        self.dead_bands.insert(channel.path().to_string(), width);
+        self
+    }
+
+    /// Edit the properties and rename objects as [`crate::redact`] does.
+    ///
+    /// Channels are still named by their path in the source for the other options.
+    pub fn redaction(mut self, redaction: Redaction) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    /// Leave the channel out of the new file.
+    pub fn exclude_channel(mut self, channel: &ChannelPath) -> Self {
+        self.excluded.insert(channel.path().to_string());
+        self
+    }
+}
+
+/// Copy the TDMS file in `source` to `dest` with each channel stored contiguously.
+///
+/// The new file starts with the properties of every object, followed by the
+/// data of each channel in turn in segments of up to a million samples. The
+/// data is written little endian. Channels of variable size types, such as
+/// strings, are copied a data block at a time.
+///
+/// Errors with [`TdmsError::UnsupportedType`] for DAQmx raw data or a
+/// dead-band on a channel which isn't numeric, and with
+/// [`TdmsError::DuplicateChannel`] if the renames or the companion channel of
+/// a dead-band channel give two objects the same path.
+///
+/// # Example
+/// ```rust
+/// use std::io::Cursor;
+/// use tedium::{repack, ChannelPath, DataLayout, RangePolicy, RepackOptions, TdmsFile};
+///
+/// let channel = ChannelPath::new("group", "channel");
+/// let mut source = Cursor::new(vec![]);
+/// let mut file = TdmsFile::new(&mut source).unwrap();
+/// let mut writer = file.writer().unwrap();
+/// for value in 0..100 {
+///     writer.write_channels(&[&channel], &[value as f64 * 0.5], DataLayout::Contigious).unwrap();
+/// }
+/// drop(writer);
+/// drop(file);
+///
+/// let mut packed = Cursor::new(vec![]);
+/// let options = RepackOptions::new().f64_to_f32(RangePolicy::Error);
+/// repack(&mut source, &mut packed, &options).unwrap();
+///
+/// let mut file = TdmsFile::new(packed).unwrap();
+/// assert_eq!(file.data_block_count(), 1);
+/// let data: Vec<f32> = file.read_all(&channel).unwrap();
+/// assert_eq!(data[99], 49.5);
+/// ```
+pub fn repack(
+    source: &mut (impl Read + Seek),
+    dest: &mut impl Write,
+    options: &RepackOptions,
+) -> Result<(), TdmsError> {
+    let mut file = TdmsFile::new(source)?;
+
+    let mut objects = Vec::new();
+    // The path of each channel in the source and the new file.
+    let mut channels = Vec::new();
+    for path in file.index.all_paths() {
+        if options.excluded.contains(path) {
+            continue;
+        }
+        let properties = PropertyPath::try_from(path)
+            .ok()
+            .and_then(|object| file.index.get_object_properties(&object))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        let mut object = options.redaction.redact_object(&ObjectMetaData {
+            path: path.to_string(),
+            properties,
+            raw_data_index: RawDataIndex::None,
+        });
+        let Ok(channel) = ChannelPath::try_from(path) else {
+            objects.push(object);
+            continue;
+        };
+        let target = ChannelPath::try_from(object.path.as_str())?;
+        let companion = options.dead_bands.get(path).map(|width| {
+            let index_channel = index_channel(&target);
+            let samples = file.index.channel_length(&channel).unwrap_or(0);
+            let index_name = parse_path(index_channel.path())
+                .ok()
+                .and_then(|(_, name)| name)
+                .unwrap_or_default();
+            object.properties.extend([
+                (
+                    WIDTH_PROPERTY.to_string(),
+                    PropertyValue::DoubleFloat(*width),
+                ),
+                (SAMPLES_PROPERTY.to_string(), PropertyValue::U64(samples)),
+                (
+                    INDEX_CHANNEL_PROPERTY.to_string(),
+                    PropertyValue::String(index_name.to_string()),
+                ),
+            ]);
+            ObjectMetaData {
+                path: index_channel.path().to_string(),
+                properties: vec![],
+                raw_data_index: RawDataIndex::None,
+            }
+        });
+        objects.push(object);
+        objects.extend(companion);
+        channels.push((channel, target));
+    }
+    let mut paths = BTreeSet::new();
+    if let Some(duplicate) = objects.iter().find(|object| !paths.insert(&object.path)) {
+        return Err(TdmsError::DuplicateChannel(duplicate.path.clone()));
+    }
+    let toc = ToC {
+        contains_new_object_list: true,
+        ..Default::default()
+    };
+    write_raw_segment(dest, toc, Some(MetaData { objects }), &[])?;
+
+    for (channel, target) in channels {
+        repack_channel(&mut file, &channel, &target, dest, options)?;
+    }
+    Ok(())
+}
+
+/// Copy the channel to `target` in the new file.
+fn repack_channel<S: Read + Seek>(
+    file: &mut TdmsFile<&mut S>,
+    channel: &ChannelPath,
+    target: &ChannelPath,
+    dest: &mut impl Write,
+    options: &RepackOptions,
+) -> Result<(), TdmsError> {
+    let Some(data_type) = file.index.channel_data_type(channel) else {
+        return Ok(());
+    };
+    if let Some(width) = options.dead_bands.get(channel.path()) {
+        return dead_band_channel(file, channel, target, *width, dest);
+    }
+    macro_rules! copy {
+        ($initial:expr) => {
+            copy_samples(file, channel, target, dest, $initial, Ok)
+        };
+    }
+
+    match data_type {
+        DataType::I8 => copy!(0i8),
+        DataType::I16 => copy!(0i16),
+        DataType::I32 => copy!(0i32),
+        DataType::I64 => copy!(0i64),
+        DataType::U8 => copy!(0u8),
+        DataType::U16 => copy!(0u16),
+        DataType::U32 => copy!(0u32),
+        DataType::U64 => copy!(0u64),
+        DataType::SingleFloat | DataType::SingleFloatWithUnit => copy!(0f32),
+        DataType::DoubleFloat | DataType::DoubleFloatWithUnit => match options.f64_to_f32 {
+            Some(policy) => {
+                let mut sample = 0;
+                copy_samples(file, channel, target, dest, 0f64, |value| {
+                    sample += 1;
+                    to_f32(value, policy).ok_or_else(|| TdmsError::ValueOutOfRange {
+                        channel: channel.clone(),
+                        sample: sample - 1,
+                        value,
+                    })
+                })
+            }
+            None => copy!(0f64),
+        },
+        DataType::Boolean => copy!(false),
+        DataType::Timestamp => copy!(LVTime::from_parts(0, 0)),
+        _ => copy_blocks(file, channel, target, dest),
+    }
+}
+
+/// Convert to f32, returning None if the value is out of range under the error policy.
+fn to_f32(value: f64, policy: RangePolicy) -> Option<f32> {
+    let limit = f32::MAX as f64;
+    if value.is_finite() && value.abs() > limit {
+        return match policy {
+            RangePolicy::Error => None,
+            RangePolicy::Clamp => Some(value.clamp(-limit, limit) as f32),
+        };
+    }
+    Some(value as f32)
+}
+
+/// Read the channel in large chunks and write each as a segment, converting every value.
+fn copy_samples<S: Read + Seek, D: TdmsStorageType + Clone, O: TdmsStorageType>(
+    file: &mut TdmsFile<&mut S>,
+    channel: &ChannelPath,
+    target: &ChannelPath,
+    dest: &mut impl Write,
+    initial: D,
+    mut convert: impl FnMut(D) -> Result<O, TdmsError>,
+) -> Result<(), TdmsError> {
+    let length = file.index.channel_length(channel).unwrap_or(0);
+    let mut buffer = vec![initial; usize_from(length.min(SEGMENT_SAMPLES))?];
+    let mut converted = Vec::with_capacity(buffer.len());
+
+    let mut position = 0;
+    while position < length {
+        let read = read_range_from(
+            &file.index,
+            &file.retry,
+            &mut file.file,
+            channel,
+            position,
+            &mut buffer,
+        )?;
+        if read == 0 {
+            break;
+        }
+        converted.clear();
+        for value in &buffer[..read] {
+            converted.push(convert(value.clone())?);
+        }
+        write_channel_segment(dest, target, &converted)?;
+        position += read as u64;
+    }
+    Ok(())
+}
+
+/// The companion channel holding the sample indexes of a dead-band channel.
+fn index_channel(channel: &ChannelPath) -> ChannelPath {
+    let (group, name) = parse_path(channel.path()).unwrap_or_default();
+    ChannelPath::new(
+        group.unwrap_or_default(),
+        &format!("{}_index", name.unwrap_or_default()),
+    )
+}
+
+/// Write the samples which leave the dead-band along with their indexes.
+fn dead_band_channel<S: Read + Seek>(
+    file: &mut TdmsFile<&mut S>,
+    channel: &ChannelPath,
+    target: &ChannelPath,
+    width: f64,
+    dest: &mut impl Write,
+) -> Result<(), TdmsError> {
+    let index_channel = index_channel(target);
+    let length = file.index.channel_length(channel).unwrap_or(0);
+    let mut buffer = vec![0.0; usize_from(length.min(SEGMENT_SAMPLES))?];
+    let mut values = Vec::new();
+    let mut indexes = Vec::new();
+    let mut last: Option<f64> = None;
+
+    let mut position = 0;
+    while position < length {
+        let read = read_as_f64(file, channel, position, &mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        values.clear();
+        indexes.clear();
+        for (offset, value) in buffer[..read].iter().enumerate() {
+            let changed = last
+                .is_none_or(|last| value.is_nan() != last.is_nan() || (value - last).abs() > width);
+            if changed {
+                values.push(*value);
+                indexes.push(position + offset as u64);
+                last = Some(*value);
+            }
+        }
+        if !values.is_empty() {
+            write_channel_segment(dest, target, &values)?;
+            write_channel_segment(dest, &index_channel, &indexes)?;
+        }
+        position += read as u64;
+    }
+    Ok(())
+}
+
+/// Read a numeric channel from `start` as f64, returning the samples read.
+fn read_as_f64<S: Read + Seek>(
+    file: &mut TdmsFile<&mut S>,
+    channel: &ChannelPath,
+    start: u64,
+    output: &mut [f64],
+) -> Result<usize, TdmsError> {
+    macro_rules! read_as {
+        ($type:ty) => {{
+            let mut buffer = vec![<$type>::default(); output.len()];
+            let read = read_range_from(
+                &file.index,
+                &file.retry,
+                &mut file.file,
+                channel,
+                start,
+                &mut buffer,
+            )?;
+            for (out, value) in output.iter_mut().zip(&buffer[..read]) {
+                *out = *value as f64;
+            }
+            read
+        }};
+    }
+
+    let data_type = file
+        .index
+        .channel_data_type(channel)
+        .ok_or_else(|| file.index.missing_channel(channel))?;
+    Ok(match data_type {
+        DataType::I8 => read_as!(i8),
+        DataType::I16 => read_as!(i16),
+        DataType::I32 => read_as!(i32),
+        DataType::I64 => read_as!(i64),
+        DataType::U8 => read_as!(u8),
+        DataType::U16 => read_as!(u16),
+        DataType::U32 => read_as!(u32),
+        DataType::U64 => read_as!(u64),
+        DataType::SingleFloat | DataType::SingleFloatWithUnit => read_as!(f32),
+        DataType::DoubleFloat | DataType::DoubleFloatWithUnit => read_range_from(
+            &file.index,
+            &file.retry,
+            &mut file.file,
+            channel,
+            start,
+            output,
+        )?,
+        other => return Err(TdmsError::UnsupportedType(other)),
+    })
+}
+
+/// Write the samples as a little endian segment holding only the channel.
+fn write_channel_segment<O: TdmsStorageType>(
+    dest: &mut impl Write,
+    channel: &ChannelPath,
+    data: &[O],
+) -> Result<(), TdmsError> {
+    let meta = MetaData {
+        objects: vec![ObjectMetaData {
+            path: channel.path().to_string(),
+            properties: vec![],
+            raw_data_index: RawDataIndex::RawData(RawDataMeta {
+                data_type: O::NATURAL_TYPE,
+                number_of_values: data.len() as u64,
+                total_size_bytes: None,
+            }),
+        }],
+    };
+    let toc = ToC {
+        contains_new_object_list: true,
+        contains_raw_data: true,
+        ..Default::default()
+    };
+    let mut writer = LittleEndianWriter::from_writer(dest);
+    writer.write_segment(toc, Some(meta), Some(data))?;
+    writer.sync()?;
+    Ok(())
+}
+
+/// Copy the bytes of the channel out of each data block without decoding them.
+fn copy_blocks<S: Read + Seek>(
+    file: &mut TdmsFile<&mut S>,
+    channel: &ChannelPath,
+    target: &ChannelPath,
+    dest: &mut impl Write,
+) -> Result<(), TdmsError> {
+    let locations = file
+        .index
+        .get_channel_data_positions(channel)
+        .ok_or_else(|| file.index.missing_channel(channel))?;
+
+    let mut bytes = Vec::new();
+    for location in locations {
+        let block = file
+            .index
+            .get_data_block(location.data_block)
+            .ok_or_else(|| TdmsError::DataBlockNotFound(channel.clone(), location.data_block))?;
+        bytes.resize(usize_from(block.length)?, 0);
+        file.file.seek(SeekFrom::Start(block.start))?;
+        file.file.read_exact(&mut bytes)?;
+
+        let data = slice_block(block, &[location.channel_index], &bytes)?;
+        let meta = MetaData {
+            objects: vec![ObjectMetaData {
+                path: target.path().to_string(),
+                properties: vec![],
+                raw_data_index: RawDataIndex::RawData(
+                    block.channels[location.channel_index].clone(),
+                ),
+            }],
+        };
+        let toc = ToC {
+            contains_new_object_list: true,
+            contains_raw_data: true,
+            data_is_interleaved: block.layout == DataLayout::Interleaved,
+            big_endian: block.byte_order == Endianess::Big,
+            ..Default::default()
+        };
+        write_raw_segment(dest, toc, Some(meta), &data)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{PropertyValue, TdmsFile};
+
+    fn fragmented_file(values: &[f64]) -> Cursor<Vec<u8>> {
+        let mut source = Cursor::new(vec![]);
+        let mut file = TdmsFile::new(&mut source).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_properties(
+                &PropertyPath::channel("rig", "load"),
+                &[("unit_string", PropertyValue::from("N"))],
+            )
+            .unwrap();
+        let channels = [
+            ChannelPath::new("rig", "load"),
+            ChannelPath::new("rig", "count"),
+        ];
+        for (index, value) in values.iter().enumerate() {
+            writer
+                .write_channels(&channels[..1], &[*value], DataLayout::Contigious)
+                .unwrap();
+            writer
+                .write_channels(&channels[1..], &[index as u32], DataLayout::Contigious)
+                .unwrap();
+        }
+        writer
+            .write_strings(&ChannelPath::new("rig", "notes"), &["a", "bc"])
+            .unwrap();
+        drop(writer);
+        drop(file);
+        source
+    }
+
+    #[test]
+    fn test_repack_keeps_values_and_properties() {
+        let values = [1.0, -2.5, 1e300, 4.0];
+        let mut source = fragmented_file(&values);
+        let mut dest = Cursor::new(vec![]);
+        repack(&mut source, &mut dest, &RepackOptions::new()).unwrap();
+
+        let mut file = TdmsFile::new(dest).unwrap();
+        let load = ChannelPath::new("rig", "load");
+        assert_eq!(file.read_all::<f64>(&load).unwrap(), values);
+        assert_eq!(
+            file.read_all::<u32>(&ChannelPath::new("rig", "count"))
+                .unwrap(),
+            [0, 1, 2, 3]
+        );
+        assert_eq!(
+            file.read_strings(&ChannelPath::new("rig", "notes"))
+                .unwrap(),
+            ["a", "bc"]
+        );
+        assert_eq!(
+            file.read_property(load.as_ref(), "unit_string").unwrap(),
+            Some(&PropertyValue::from("N"))
+        );
+        assert_eq!(file.data_block_count(), 3);
+    }
+
+    #[test]
+    fn test_f32_conversion_range_policy() {
+        let values = [1.0, -2.5, -1e300, f64::INFINITY];
+        let load = ChannelPath::new("rig", "load");
+
+        let mut source = fragmented_file(&values);
+        let options = RepackOptions::new().f64_to_f32(RangePolicy::Error);
+        let error = repack(&mut source, &mut Cursor::new(vec![]), &options).unwrap_err();
+        assert!(matches!(
+            error,
+            TdmsError::ValueOutOfRange { sample: 2, value, .. } if value == -1e300
+        ));
+        assert_eq!(error.path(), Some(load.path()));
+
+        let options = RepackOptions::new().f64_to_f32(RangePolicy::Clamp);
+        let mut dest = Cursor::new(vec![]);
+        repack(&mut source, &mut dest, &options).unwrap();
+        let mut file = TdmsFile::new(dest).unwrap();
+        assert_eq!(
+            file.read_all::<f32>(&load).unwrap(),
+            [1.0, -2.5, f32::MIN, f32::INFINITY]
+        );
+    }
+
+    #[test]
+    fn test_dead_band_round_trip() {
+        let values = [0.0, 0.5, 2.0, 2.9, f64::NAN, f64::NAN, 3.0, 3.0];
+        let load = ChannelPath::new("rig", "load");
+        let count = ChannelPath::new("rig", "count");
+        let mut source = fragmented_file(&values);
+        let options = RepackOptions::new()
+            .dead_band(&load, 1.0)
+            .dead_band(&count, 1.5);
+        let mut dest = Cursor::new(vec![]);
+        repack(&mut source, &mut dest, &options).unwrap();
+
+        let mut file = TdmsFile::new(dest).unwrap();
+        assert_eq!(
+            file.read_all::<u64>(&ChannelPath::new("rig", "load_index"))
+                .unwrap(),
+            [0, 2, 4, 6]
+        );
+        let expanded = file.read_dead_band(&load).unwrap();
+        assert_eq!(expanded.len(), values.len());
+        for (expanded, expected) in
+            expanded
+                .iter()
+                .zip([0.0, 0.0, 2.0, 2.0, f64::NAN, f64::NAN, 3.0, 3.0])
+        {
+            assert!(*expanded == expected || (expanded.is_nan() && expected.is_nan()));
+        }
+        assert_eq!(
+            file.read_dead_band(&count).unwrap(),
+            [0.0, 0.0, 2.0, 2.0, 4.0, 4.0, 6.0, 6.0]
+        );
+        assert_eq!(
+            file.read_property(load.as_ref(), "unit_string").unwrap(),
+            Some(&PropertyValue::from("N"))
+        );
+
+        let notes = ChannelPath::new("rig", "notes");
+        let options = RepackOptions::new().dead_band(&notes, 1.0);
+        let error = repack(&mut source, &mut Cursor::new(vec![]), &options).unwrap_err();
+        assert!(matches!(
+            error,
+            TdmsError::UnsupportedType(DataType::TdmsString)
+        ));
+    }
+
+    #[test]
+    fn test_dead_band_companion_must_not_exist() {
+        let mut source = fragmented_file(&[1.0]);
+        let mut file = TdmsFile::new(&mut source).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(
+                &[ChannelPath::new("rig", "load_index")],
+                &[0u64],
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        drop(writer);
+        drop(file);
+
+        let options = RepackOptions::new().dead_band(&ChannelPath::new("rig", "load"), 1.0);
+        let error = repack(&mut source, &mut Cursor::new(vec![]), &options).unwrap_err();
+        assert!(
+            matches!(error, TdmsError::DuplicateChannel(path) if path == "/'rig'/'load_index'")
+        );
+    }
+
+    #[test]
+    fn test_edits_renames_and_exclusions_in_one_pass() {
+        let mut source = fragmented_file(&[1.0, 2.0, 3.0]);
+        let load = ChannelPath::new("rig", "load");
+        let force = ChannelPath::new("test", "force");
+        let redaction = Redaction::new()
+            .replace_property("unit_string", PropertyValue::from("kN"))
+            .rename_channel(&load, &force)
+            .rename_group("rig", "test");
+        let options = RepackOptions::new()
+            .redaction(redaction)
+            .exclude_channel(&ChannelPath::new("rig", "count"))
+            .dead_band(&load, 1.5);
+        let mut dest = Cursor::new(vec![]);
+        repack(&mut source, &mut dest, &options).unwrap();
+
+        let mut file = TdmsFile::new(dest).unwrap();
+        assert_eq!(file.read_dead_band(&force).unwrap(), [1.0, 1.0, 3.0]);
+        assert_eq!(
+            file.read_property(force.as_ref(), "unit_string").unwrap(),
+            Some(&PropertyValue::from("kN"))
+        );
+        assert_eq!(
+            file.read_strings(&ChannelPath::new("test", "notes"))
+                .unwrap(),
+            ["a", "bc"]
+        );
+        let channels: Vec<_> = file
+            .list_channels_in_group(&crate::GroupPath::new("test"))
+            .collect();
+        assert_eq!(channels.len(), 3);
+        assert!(file.channel_length(&load).is_none());
+
+        let clash = Redaction::new().rename_channel(&load, &ChannelPath::new("rig", "count"));
+        let options = RepackOptions::new().redaction(clash);
+        let error = repack(&mut source, &mut Cursor::new(vec![]), &options).unwrap_err();
+        assert!(matches!(error, TdmsError::DuplicateChannel(_)));
+    }
+}
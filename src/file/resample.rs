@@ -0,0 +1,187 @@
+//! Resampling a waveform channel to a new rate as it is read.
+
+use std::fmt::Debug;
+use std::io::{Read, Seek, Write};
+
+use crate::error::TdmsError;
+use crate::paths::{ChannelPath, PropertyPath};
+use crate::{PropertyValue, TdmsFile};
+
+use super::time_sync::read_range_as_f64;
+
+/// The number of source samples read from the file at a time.
+const CHUNK_SAMPLES: u64 = 4096;
+
+/// How values between the source samples are calculated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResampleMethod {
+    /// Linearly interpolate between the source samples either side.
+    #[default]
+    Linear,
+    /// Hold the value of the previous source sample.
+    ZeroOrderHold,
+}
+
+impl<F: Read + Seek + Write + Debug> TdmsFile<F> {
+    /// Read a waveform channel resampled to `new_rate` samples per second.
+    ///
+    /// The source rate comes from the `wf_increment` property. The output starts at the
+    /// first source sample and continues while it is within the source data.
+    /// The channel is read in chunks so only the output is held in memory.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, PropertyPath, PropertyValue, ResampleMethod, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_properties(
+    ///     &PropertyPath::channel("group", "channel"),
+    ///     &[("wf_increment", PropertyValue::DoubleFloat(1.0))],
+    /// ).unwrap();
+    /// writer.write_channels(&[&channel], &[0.0, 10.0, 20.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let resampled = file.read_resampled(&channel, 2.0, ResampleMethod::Linear).unwrap();
+    /// assert_eq!(resampled, vec![0.0, 5.0, 10.0, 15.0, 20.0]);
+    /// ```
+    pub fn read_resampled(
+        &mut self,
+        channel: &ChannelPath,
+        new_rate: f64,
+        method: ResampleMethod,
+    ) -> Result<Vec<f64>, TdmsError> {
+        let property_path: &PropertyPath = channel.as_ref();
+        let increment = match self.read_property(property_path, "wf_increment")? {
+            Some(PropertyValue::DoubleFloat(increment)) if *increment > 0.0 => *increment,
+            _ => return Err(TdmsError::MissingWaveformTiming(channel.clone())),
+        };
+        let length = self
+            .channel_length(channel)
+            .ok_or_else(|| self.index.missing_channel(channel))?;
+
+        if length == 0 || new_rate <= 0.0 {
+            return Ok(Vec::new());
+        }
+
+        // The ratio of source samples per output sample.
+        let step = 1.0 / (new_rate * increment);
+        let last_position = (length - 1) as f64;
+        let output_length = (last_position / step + 1e-9).floor() as usize + 1;
+        let mut output = Vec::with_capacity(output_length);
+
+        let mut buffer: Vec<f64> = Vec::new();
+        let mut buffer_start = 0u64;
+
+        for index in 0..output_length {
+            let position = (index as f64 * step).min(last_position);
+            let before = position.floor() as u64;
+            let after = (before + 1).min(length - 1);
+
+            if before < buffer_start || after >= buffer_start + buffer.len() as u64 {
+                buffer_start = before;
+                let end = (before + CHUNK_SAMPLES).min(length);
+                buffer.clear();
+                buffer.resize((end - before) as usize, f64::NAN);
+                read_range_as_f64(self, channel, buffer_start, &mut buffer)?;
+            }
+
+            let before_value = buffer[(before - buffer_start) as usize];
+            let value = match method {
+                ResampleMethod::ZeroOrderHold => before_value,
+                ResampleMethod::Linear => {
+                    let fraction = position - before as f64;
+                    if fraction < 1e-9 {
+                        before_value
+                    } else {
+                        let after_value = buffer[(after - buffer_start) as usize];
+                        before_value + (after_value - before_value) * fraction
+                    }
+                }
+            };
+            output.push(value);
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::DataLayout;
+
+    fn waveform_file(increment: f64, data: &[f64]) -> TdmsFile<Cursor<Vec<u8>>> {
+        let mut file = TdmsFile::new(Cursor::new(Vec::new())).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_properties(
+                &PropertyPath::channel("group", "channel"),
+                &[("wf_increment", PropertyValue::DoubleFloat(increment))],
+            )
+            .unwrap();
+        writer
+            .write_channels(
+                &[ChannelPath::new("group", "channel")],
+                data,
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        drop(writer);
+        file
+    }
+
+    #[test]
+    fn test_zero_order_hold_upsample() {
+        let mut file = waveform_file(1.0, &[1.0, 2.0, 3.0]);
+        let output = file
+            .read_resampled(
+                &ChannelPath::new("group", "channel"),
+                3.0,
+                ResampleMethod::ZeroOrderHold,
+            )
+            .unwrap();
+        assert_eq!(output, vec![1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_downsample_across_chunks() {
+        let length = CHUNK_SAMPLES as usize * 3 + 5;
+        let data: Vec<f64> = (0..length).map(|i| i as f64).collect();
+        let mut file = waveform_file(0.001, &data);
+        let output = file
+            .read_resampled(
+                &ChannelPath::new("group", "channel"),
+                300.0,
+                ResampleMethod::Linear,
+            )
+            .unwrap();
+
+        let step = 1000.0 / 300.0;
+        assert_eq!(output.len(), ((length - 1) as f64 / step) as usize + 1);
+        for (index, value) in output.iter().enumerate() {
+            assert!(
+                (value - index as f64 * step).abs() < 1e-6,
+                "{index}: {value}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_missing_increment() {
+        let mut file = TdmsFile::new(Cursor::new(Vec::new())).unwrap();
+        let channel = ChannelPath::new("group", "channel");
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&channel], &[1.0], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+
+        let result = file.read_resampled(&channel, 10.0, ResampleMethod::Linear);
+        assert!(matches!(result, Err(TdmsError::MissingWaveformTiming(_))));
+    }
+}
@@ -0,0 +1,249 @@
+//! Retention of the files written by long running loggers.
+//!
+//! A [`RetentionPolicy`] deletes the oldest files in a log directory once it
+//! holds too many files, too many bytes or files older than a maximum age,
+//! so embedded loggers don't fill their disks.
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::os_path::os_path;
+use crate::error::TdmsError;
+use crate::TdmsFile;
+
+/// Limits on the files kept in a log directory.
+///
+/// Any limit left as None is not applied. The newest file is never deleted
+/// as it may still be being written.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Keep at most this many files.
+    pub max_files: Option<usize>,
+    /// Keep at most this many bytes of files.
+    pub max_bytes: Option<u64>,
+    /// Delete files last modified longer ago than this.
+    pub max_age: Option<Duration>,
+}
+
+/// A TDMS file found in the log directory.
+struct LogFile {
+    path: PathBuf,
+    modified: SystemTime,
+    bytes: u64,
+}
+
+impl RetentionPolicy {
+    /// Delete the oldest TDMS files under `root` until the limits are met.
+    ///
+    /// Files are searched for recursively and ordered by their modified time.
+    /// Only complete files, where every segment is fully written, are deleted.
+    /// Any `.tdms_index` file alongside a deleted file is removed too, as are
+    /// directories left empty.
+    ///
+    /// Returns the paths of the deleted files.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use tedium::RetentionPolicy;
+    ///
+    /// let policy = RetentionPolicy {
+    ///     max_files: Some(100),
+    ///     max_bytes: Some(10 * 1024 * 1024 * 1024),
+    ///     ..Default::default()
+    /// };
+    /// let deleted = policy.apply("logs".as_ref()).unwrap();
+    /// ```
+    pub fn apply(&self, root: &Path) -> Result<Vec<PathBuf>, TdmsError> {
+        self.apply_at(root, SystemTime::now())
+    }
+
+    fn apply_at(&self, root: &Path, now: SystemTime) -> Result<Vec<PathBuf>, TdmsError> {
+        let mut files = Vec::new();
+        find_log_files(root, &mut files)?;
+        files.sort_by(|a, b| a.modified.cmp(&b.modified).then(a.path.cmp(&b.path)));
+
+        let mut remaining_files = files.len();
+        let mut remaining_bytes: u64 = files.iter().map(|file| file.bytes).sum();
+        let mut deleted = Vec::new();
+
+        // Never consider the newest file.
+        let candidates = files.len().saturating_sub(1);
+        for file in files.into_iter().take(candidates) {
+            let too_many = self.max_files.is_some_and(|max| remaining_files > max);
+            let too_large = self.max_bytes.is_some_and(|max| remaining_bytes > max);
+            let too_old = self.max_age.is_some_and(|max_age| {
+                now.duration_since(file.modified)
+                    .is_ok_and(|age| age > max_age)
+            });
+            if !(too_many || too_large || too_old) {
+                continue;
+            }
+            if !is_complete(&file.path) {
+                continue;
+            }
+
+            std::fs::remove_file(os_path(&file.path))?;
+            let index_path = os_path(&file.path).with_extension("tdms_index");
+            if index_path.is_file() {
+                std::fs::remove_file(&index_path)?;
+            }
+            remove_empty_directories(root, &file.path);
+
+            remaining_files -= 1;
+            remaining_bytes -= file.bytes;
+            deleted.push(file.path);
+        }
+
+        Ok(deleted)
+    }
+}
+
+fn find_log_files(directory: &Path, files: &mut Vec<LogFile>) -> Result<(), TdmsError> {
+    for entry in std::fs::read_dir(os_path(directory))? {
+        let entry = entry?;
+        let path = entry.path();
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            find_log_files(&path, files)?;
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("tdms"))
+        {
+            files.push(LogFile {
+                path,
+                modified: metadata.modified()?,
+                bytes: metadata.len(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Check the file can be indexed and ends on a segment boundary.
+fn is_complete(path: &Path) -> bool {
+    let Ok(file) = File::open(os_path(path)) else {
+        return false;
+    };
+    let Ok(length) = file.metadata().map(|metadata| metadata.len()) else {
+        return false;
+    };
+    TdmsFile::new(file).is_ok_and(|file| file.index.next_segment_start() == length)
+}
+
+/// Remove the parents of the path up to the root while they are empty.
+///
+/// The path comes from reading the directory through [`os_path`], so it is
+/// compared with the root in the same form.
+fn remove_empty_directories(root: &Path, path: &Path) {
+    let root = os_path(root);
+    let mut directory = path.parent();
+    while let Some(current) = directory {
+        if current == root.as_ref() || !current.starts_with(&root) {
+            break;
+        }
+        // Fails if the directory isn't empty, which is where we stop.
+        if std::fs::remove_dir(os_path(current)).is_err() {
+            break;
+        }
+        directory = current.parent();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testgen::TestFileSpec;
+
+    fn temp_directory(name: &str) -> PathBuf {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("target");
+        path.push(format!("retention_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    /// Write a complete file and set its modified time to `age` before now.
+    fn write_file(path: &Path, now: SystemTime, age: Duration) -> u64 {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let bytes = TestFileSpec::default().build().unwrap();
+        std::fs::write(path, &bytes).unwrap();
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(now - age)
+            .unwrap();
+        bytes.len() as u64
+    }
+
+    #[test]
+    fn test_keeps_newest_files() {
+        let root = temp_directory("count");
+        let now = SystemTime::now();
+        let hour = Duration::from_secs(3600);
+        let oldest = root.join("day1").join("run_0.tdms");
+        let middle = root.join("day2").join("run_1.tdms");
+        let newest = root.join("day2").join("run_2.tdms");
+        write_file(&oldest, now, hour * 3);
+        write_file(&middle, now, hour * 2);
+        write_file(&newest, now, hour);
+        std::fs::write(oldest.with_extension("tdms_index"), b"index").unwrap();
+
+        let policy = RetentionPolicy {
+            max_files: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(policy.apply_at(&root, now).unwrap(), vec![oldest.clone()]);
+        assert!(!oldest.with_extension("tdms_index").exists());
+        assert!(!root.join("day1").exists());
+        assert!(middle.exists() && newest.exists());
+
+        // The newest file is kept even if it breaks the limits.
+        let policy = RetentionPolicy {
+            max_bytes: Some(0),
+            max_age: Some(Duration::from_secs(1)),
+            ..Default::default()
+        };
+        assert_eq!(policy.apply_at(&root, now).unwrap(), vec![middle]);
+        assert!(newest.exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_age_and_size_limits_skip_incomplete_files() {
+        let root = temp_directory("limits");
+        let now = SystemTime::now();
+        let hour = Duration::from_secs(3600);
+        let incomplete = root.join("run_0.tdms");
+        let old = root.join("run_1.tdms");
+        let recent = root.join("run_2.tdms");
+        let newest = root.join("run_3.tdms");
+        write_file(&incomplete, now, hour * 4);
+        let size = write_file(&old, now, hour * 3);
+        write_file(&recent, now, hour * 2);
+        write_file(&newest, now, hour);
+
+        // Cut the file part way through a segment.
+        let file = File::options().write(true).open(&incomplete).unwrap();
+        file.set_len(size - 10).unwrap();
+        file.set_modified(now - hour * 4).unwrap();
+
+        let policy = RetentionPolicy {
+            max_age: Some(hour * 2 + Duration::from_secs(60)),
+            ..Default::default()
+        };
+        assert_eq!(policy.apply_at(&root, now).unwrap(), vec![old]);
+
+        let policy = RetentionPolicy {
+            max_bytes: Some(size * 2),
+            ..Default::default()
+        };
+        assert_eq!(policy.apply_at(&root, now).unwrap(), vec![recent]);
+        assert!(incomplete.exists() && newest.exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,147 @@
+//! Retrying block reads after transient IO errors.
+//!
+//! On network filesystems a single timeout would otherwise fail a read of a
+//! whole channel. A [`RetryPolicy`] set on the file retries each data block
+//! read that fails with a transient error, see [`TdmsError::is_transient`].
+
+use std::time::Duration;
+
+use crate::error::TdmsError;
+
+/// How failed block reads are retried.
+///
+/// The default makes no retries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The number of times a block read is retried before the error is returned.
+    pub retries: u32,
+    /// The wait before the first retry. This doubles after each attempt.
+    pub backoff: Duration,
+    /// The longest wait between attempts.
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Retry up to `retries` times, waiting `backoff` and doubling the wait each time.
+    pub fn new(retries: u32, backoff: Duration) -> Self {
+        Self {
+            retries,
+            backoff,
+            max_backoff: Duration::MAX,
+        }
+    }
+
+    /// Run the operation, retrying it while it fails with a transient error.
+    ///
+    /// The operation must be safe to repeat, so it should seek to the data
+    /// before reading it.
+    pub(crate) fn run<T>(
+        &self,
+        mut operation: impl FnMut() -> Result<T, TdmsError>,
+    ) -> Result<T, TdmsError> {
+        let mut wait = self.backoff;
+        let mut attempt = 0;
+        loop {
+            match operation() {
+                Err(error) if error.is_transient() && attempt < self.retries => {
+                    attempt += 1;
+                    std::thread::sleep(wait.min(self.max_backoff));
+                    wait = wait.saturating_mul(2);
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+
+    use super::*;
+    use crate::testgen::TestFileSpec;
+    use crate::TdmsFile;
+
+    /// A file which fails a number of reads once indexing is done.
+    #[derive(Debug)]
+    struct FlakyFile {
+        inner: Cursor<Vec<u8>>,
+        failures: u32,
+        kind: std::io::ErrorKind,
+    }
+
+    impl Read for FlakyFile {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                return Err(std::io::Error::new(self.kind, "flaky"));
+            }
+            self.inner.read(buf)
+        }
+    }
+
+    impl Seek for FlakyFile {
+        fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    impl Write for FlakyFile {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.inner.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn flaky_file(spec: &TestFileSpec) -> TdmsFile<FlakyFile> {
+        TdmsFile::new(FlakyFile {
+            inner: Cursor::new(spec.build().unwrap()),
+            failures: 0,
+            kind: std::io::ErrorKind::TimedOut,
+        })
+        .unwrap()
+    }
+
+    fn fail_next_reads(file: &mut TdmsFile<FlakyFile>, failures: u32, kind: std::io::ErrorKind) {
+        file.file.failures = failures;
+        file.file.kind = kind;
+    }
+
+    #[test]
+    fn test_transient_errors_are_retried() {
+        let spec = TestFileSpec::default();
+        let channel = spec.channel_path(0, 1);
+        let mut file = flaky_file(&spec);
+        let expected: Vec<f64> = file.read_all(&channel).unwrap();
+
+        fail_next_reads(&mut file, 2, std::io::ErrorKind::TimedOut);
+        assert!(file.read_all::<f64>(&channel).unwrap_err().is_transient());
+
+        file.set_retry_policy(RetryPolicy::new(2, Duration::ZERO));
+        fail_next_reads(&mut file, 2, std::io::ErrorKind::TimedOut);
+        assert_eq!(file.read_all::<f64>(&channel).unwrap(), expected);
+
+        let mut output = vec![vec![0.0; spec.channel_length()]; 2];
+        let mut slices: Vec<&mut [f64]> = output.iter_mut().map(|v| &mut v[..]).collect();
+        fail_next_reads(&mut file, 1, std::io::ErrorKind::ConnectionReset);
+        file.read_channels(&[spec.channel_path(0, 0), channel.clone()], &mut slices)
+            .unwrap();
+        assert_eq!(output[1], expected);
+    }
+
+    #[test]
+    fn test_permanent_errors_are_not_retried() {
+        let spec = TestFileSpec::default();
+        let channel = spec.channel_path(0, 0);
+        let mut file = flaky_file(&spec);
+        file.set_retry_policy(RetryPolicy::new(5, Duration::ZERO));
+
+        fail_next_reads(&mut file, 1, std::io::ErrorKind::PermissionDenied);
+        let error = file.read_all::<f64>(&channel).unwrap_err();
+        assert!(!error.is_transient());
+        assert_eq!(file.file.failures, 0);
+    }
+}
@@ -0,0 +1,694 @@
+//! Rewriting files segment by segment.
+//!
+//! A rewrite walks the segments of the source file and writes a changed copy
+//! of each one to the destination. The raw data of each segment is copied as
+//! bytes without being decoded, so rewriting is fast and the values are
+//! unchanged bit for bit.
+//!
+//! [`redact`] uses this to strip or replace properties and rename objects so
+//! test data can be shared outside the organisation that recorded it.
+//!
+//! [`TdmsFile::append_file`] uses it to stitch the segments of another file
+//! onto the end of an open file.
+//!
+//! [`extract`] works from the index instead, slicing the bytes of the chosen
+//! channels out of each data block to build a smaller file.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use super::os_path::os_path;
+use crate::error::{usize_from, TdmsError};
+use crate::io::data_types::DataType;
+use crate::io::writer::{BigEndianWriter, LittleEndianWriter, TdmsWriter};
+use crate::meta_data::{
+    MetaData, ObjectMetaData, RawDataIndex, Segment, SegmentScanner, LEAD_IN_BYTES,
+};
+use crate::paths::{parse_path, path_group_name, ChannelPath, GroupPath};
+use crate::raw_data::{DataBlock, DataLayout, Endianess};
+use crate::{PropertyPath, PropertyValue, TdmsFile, ToC};
+
+/// Visit every segment in the file with its raw data bytes.
+///
+/// The final segment of a file which was not closed cleanly has an unknown
+/// length, so its raw data runs to the end of the file.
+pub(crate) fn for_each_segment(
+    source: &mut (impl Read + Seek),
+    mut visit: impl FnMut(&Segment, &[u8]) -> Result<(), TdmsError>,
+) -> Result<(), TdmsError> {
+    let file_length = source.seek(SeekFrom::End(0))?;
+    source.seek(SeekFrom::Start(0))?;
+    let mut scanner = SegmentScanner::new(source);
+    let mut data = Vec::new();
+    let mut position = 0;
+    let mut segment_number = 0;
+
+    while position < file_length {
+        let in_segment = |error: TdmsError| error.in_segment(segment_number, position);
+        let segment = match scanner.read_segment() {
+            Ok(segment) => segment.clone(),
+            Err(TdmsError::EndOfFile) => break,
+            Err(error) => return Err(in_segment(error)),
+        };
+        let data_start = position + LEAD_IN_BYTES + segment.raw_data_offset;
+        let next_segment = segment
+            .total_size_bytes()
+            .ok()
+            .and_then(|size| position.checked_add(size))
+            .map_or(file_length, |next| next.min(file_length));
+
+        data.resize(usize_from(next_segment.saturating_sub(data_start))?, 0);
+        scanner
+            .read_bytes_at(data_start, &mut data)
+            .map_err(in_segment)?;
+        visit(&segment, &data).map_err(in_segment)?;
+
+        position = next_segment;
+        segment_number += 1;
+        if position < file_length {
+            scanner.seek_to(position)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write a segment with the byte order set in the ToC, returning it for indexing.
+///
+/// The metadata and raw data flags follow `meta` and `toc.contains_raw_data`.
+/// `data` must already be in the byte order of the segment.
+pub(crate) fn write_raw_segment(
+    dest: &mut impl Write,
+    toc: ToC,
+    meta: Option<MetaData>,
+    data: &[u8],
+) -> Result<Segment, TdmsError> {
+    let data = toc.contains_raw_data.then_some(data);
+    let segment = if toc.big_endian {
+        let mut writer = BigEndianWriter::from_writer(dest);
+        let segment = writer.write_segment(toc, meta, data)?;
+        writer.sync()?;
+        segment
+    } else {
+        let mut writer = LittleEndianWriter::from_writer(dest);
+        let segment = writer.write_segment(toc, meta, data)?;
+        writer.sync()?;
+        segment
+    };
+    Ok(segment)
+}
+
+/// The changes made to the objects of a file by [`redact`].
+///
+/// Property changes apply to every object, including the file and groups.
+#[derive(Debug, Clone, Default)]
+pub struct Redaction {
+    remove_properties: BTreeSet<String>,
+    replace_properties: BTreeMap<String, PropertyValue>,
+    /// New groups by the path of the old group.
+    groups: BTreeMap<String, GroupPath>,
+    /// New channel paths by the path of the old channel.
+    channels: BTreeMap<String, ChannelPath>,
+}
+
+impl Redaction {
+    /// Start with no changes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove the property from every object.
+    pub fn remove_property(mut self, name: &str) -> Self {
+        self.remove_properties.insert(name.to_string());
+        self
+    }
+
+    /// Replace the value of the property on every object which has it.
+    pub fn replace_property(mut self, name: &str, value: PropertyValue) -> Self {
+        self.replace_properties.insert(name.to_string(), value);
+        self
+    }
+
+    /// Rename a group. Its channels move with it unless they are renamed themselves.
+    pub fn rename_group(mut self, from: &str, to: &str) -> Self {
+        self.groups
+            .insert(GroupPath::new(from).path().to_string(), GroupPath::new(to));
+        self
+    }
+
+    /// Rename a channel, which may also move it to another group.
+    pub fn rename_channel(mut self, from: &ChannelPath, to: &ChannelPath) -> Self {
+        self.channels.insert(from.path().to_string(), to.clone());
+        self
+    }
+
+    /// The path of the object after any renames.
+    fn object_path(&self, path: &str) -> String {
+        if let Some(channel) = self.channels.get(path) {
+            return channel.path().to_string();
+        }
+        let Ok((Some(group), channel)) = parse_path(path) else {
+            return path.to_string();
+        };
+        match (self.groups.get(&format!("/'{group}'")), channel) {
+            (Some(new_group), Some(channel)) => new_group.channel(channel).path().to_string(),
+            (Some(new_group), None) => new_group.path().to_string(),
+            (None, _) => path.to_string(),
+        }
+    }
+
+    pub(super) fn redact_object(&self, object: &ObjectMetaData) -> ObjectMetaData {
+        let properties = object
+            .properties
+            .iter()
+            .filter(|(name, _)| !self.remove_properties.contains(name))
+            .map(|(name, value)| {
+                let value = self.replace_properties.get(name).unwrap_or(value);
+                (name.clone(), value.clone())
+            })
+            .collect();
+        ObjectMetaData {
+            path: self.object_path(&object.path),
+            properties,
+            raw_data_index: object.raw_data_index.clone(),
+        }
+    }
+}
+
+/// Copy the TDMS file in `source` to `dest` with the properties and object
+/// names changed by the redaction.
+///
+/// The raw data is copied without being decoded. Errors with
+/// [`TdmsError::DuplicateChannel`] if the renames give two objects in a
+/// segment the same path.
+///
+/// # Example
+/// ```rust
+/// use std::io::Cursor;
+/// use tedium::{redact, ChannelPath, DataLayout, PropertyPath, PropertyValue, Redaction, TdmsFile};
+///
+/// let channel = ChannelPath::new("Rig 7", "load");
+/// let mut source = Cursor::new(vec![]);
+/// let mut file = TdmsFile::new(&mut source).unwrap();
+/// let mut writer = file.writer().unwrap();
+/// writer.write_properties(&PropertyPath::file(), &[("operator", PropertyValue::from("A. Smith"))]).unwrap();
+/// writer.write_channels(&[&channel], &[1.0, 2.0], DataLayout::Contigious).unwrap();
+/// drop(writer);
+/// drop(file);
+///
+/// let redaction = Redaction::new()
+///     .remove_property("operator")
+///     .rename_group("Rig 7", "Rig");
+/// let mut shared = Cursor::new(vec![]);
+/// redact(&mut source, &mut shared, &redaction).unwrap();
+///
+/// let mut file = TdmsFile::new(shared).unwrap();
+/// assert_eq!(file.read_property(&PropertyPath::file(), "operator").unwrap(), None);
+/// let data: Vec<f64> = file.read_all(&ChannelPath::new("Rig", "load")).unwrap();
+/// assert_eq!(data, vec![1.0, 2.0]);
+/// ```
+pub fn redact(
+    source: &mut (impl Read + Seek),
+    dest: &mut impl Write,
+    redaction: &Redaction,
+) -> Result<(), TdmsError> {
+    for_each_segment(source, |segment, data| {
+        let meta = match (&segment.meta_data, segment.toc.contains_meta_data) {
+            (Some(meta), true) => {
+                let objects: Vec<ObjectMetaData> = meta
+                    .objects
+                    .iter()
+                    .map(|object| redaction.redact_object(object))
+                    .collect();
+                let mut paths = BTreeSet::new();
+                if let Some(duplicate) = objects.iter().find(|object| !paths.insert(&object.path)) {
+                    return Err(TdmsError::DuplicateChannel(duplicate.path.clone()));
+                }
+                Some(MetaData { objects })
+            }
+            _ => None,
+        };
+        write_raw_segment(dest, segment.toc, meta, data)?;
+        Ok(())
+    })
+}
+
+impl<F: Read + Write + Seek> TdmsFile<F> {
+    /// Append every segment of the TDMS file at `path` to the end of this file.
+    ///
+    /// See [`Self::append_from`].
+    pub fn append_file(&mut self, path: &Path) -> Result<(), TdmsError> {
+        self.append_from(&mut File::open(os_path(path))?)
+    }
+
+    /// Append every segment of the TDMS file in `source` to the end of this file.
+    ///
+    /// This is a fast way to join sequential runs as the raw data is copied
+    /// without being decoded. The first segment of the source starts a new
+    /// object list so channels of this file which aren't in the source don't
+    /// pick up its data. The lead in of each segment is rewritten for its
+    /// new position and a segment left unfinished in the source is closed.
+    ///
+    /// The index is updated as each segment is written, so if an error stops
+    /// the append the segments already copied are still readable.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use tedium::{ChannelPath, DataLayout, TdmsFile};
+    ///
+    /// let channel = ChannelPath::new("run", "speed");
+    /// let mut second_run = Cursor::new(vec![]);
+    /// let mut second = TdmsFile::new(&mut second_run).unwrap();
+    /// let mut writer = second.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[3.0, 4.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    /// drop(second);
+    ///
+    /// let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[1.0, 2.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// file.append_from(&mut second_run).unwrap();
+    /// let data: Vec<f64> = file.read_all(&channel).unwrap();
+    /// assert_eq!(data, vec![1.0, 2.0, 3.0, 4.0]);
+    /// ```
+    pub fn append_from(&mut self, source: &mut (impl Read + Seek)) -> Result<(), TdmsError> {
+        self.file.seek(SeekFrom::End(0))?;
+        self.generation += 1;
+        let index = Arc::make_mut(&mut self.index);
+        let file = &mut self.file;
+        let mut first = true;
+        for_each_segment(source, |segment, data| {
+            let mut toc = segment.toc;
+            let mut meta = segment.meta_data.clone().filter(|_| toc.contains_meta_data);
+            if std::mem::take(&mut first) {
+                // Without metadata the data would belong to the active
+                // channels of this file.
+                if toc.contains_raw_data && meta.is_none() {
+                    return Err(TdmsError::SegmentTocDataBlockWithoutDataChannels);
+                }
+                toc.contains_new_object_list = true;
+                meta.get_or_insert_with(|| MetaData { objects: vec![] });
+            }
+            let written = write_raw_segment(file, toc, meta, data)?;
+            let end = index.add_segment(&written)?;
+            index.update_snapshot(end);
+            Ok(())
+        })
+    }
+}
+
+/// Copy only the named channels from the TDMS file in `source` to `dest`.
+///
+/// The new file has the properties of the file, the groups of the channels
+/// and the channels themselves, followed by a segment for each data block
+/// holding any of the channels. The bytes of the channels are sliced out of
+/// each block without decoding the values, and the layout and byte order of
+/// the block are kept.
+///
+/// Errors with [`TdmsError::MissingChannel`] or [`TdmsError::MissingGroup`] if
+/// a channel isn't in the file and [`TdmsError::UnsupportedType`] for DAQmx
+/// raw data.
+///
+/// # Example
+/// ```rust
+/// use std::io::Cursor;
+/// use tedium::testgen::TestFileSpec;
+/// use tedium::{extract, TdmsFile};
+///
+/// let spec = TestFileSpec { channels_per_group: 8, ..Default::default() };
+/// let mut source = Cursor::new(spec.build().unwrap());
+/// let channel = spec.channel_path(0, 3);
+///
+/// let mut subset = Cursor::new(vec![]);
+/// extract(&mut source, &[&channel], &mut subset).unwrap();
+///
+/// let mut file = TdmsFile::new(subset).unwrap();
+/// let groups: Vec<_> = file.list_groups().collect();
+/// assert_eq!(groups.len(), 1);
+/// assert_eq!(file.list_channels_in_group(&groups[0]).count(), 1);
+/// let data: Vec<f64> = file.read_all(&channel).unwrap();
+/// assert_eq!(data.len(), spec.channel_length());
+/// ```
+pub fn extract(
+    source: &mut (impl Read + Seek),
+    channels: &[impl AsRef<ChannelPath>],
+    dest: &mut impl Write,
+) -> Result<(), TdmsError> {
+    let file = TdmsFile::new(source)?;
+
+    let properties = |path: &PropertyPath| {
+        file.index
+            .get_object_properties(path)
+            .map(|properties| ObjectMetaData {
+                path: path.path().to_string(),
+                properties: properties
+                    .into_iter()
+                    .map(|(name, value)| (name.clone(), value.clone()))
+                    .collect(),
+                raw_data_index: RawDataIndex::None,
+            })
+    };
+    let mut objects: Vec<ObjectMetaData> = properties(&PropertyPath::file()).into_iter().collect();
+    let mut groups = BTreeSet::new();
+    // The selected channels in each data block by the channel index in the block.
+    let mut blocks: BTreeMap<usize, Vec<(usize, &ChannelPath)>> = BTreeMap::new();
+    for channel in channels {
+        let channel = channel.as_ref();
+        let locations = file
+            .index
+            .get_channel_data_positions(channel)
+            .ok_or_else(|| file.index.missing_channel(channel))?;
+        if let Some(group) = path_group_name(channel.path()) {
+            if groups.insert(group) {
+                objects.extend(properties(GroupPath::new(group).as_ref()));
+            }
+        }
+        objects.extend(properties(channel.as_ref()));
+        for location in locations {
+            blocks
+                .entry(location.data_block)
+                .or_default()
+                .push((location.channel_index, channel));
+        }
+    }
+    let toc = ToC {
+        contains_new_object_list: true,
+        ..Default::default()
+    };
+    write_raw_segment(dest, toc, Some(MetaData { objects }), &[])?;
+
+    let mut bytes = Vec::new();
+    for (data_block, mut selected) in blocks {
+        selected.sort_by_key(|(channel_index, _)| *channel_index);
+        let block = file
+            .index
+            .get_data_block(data_block)
+            .ok_or_else(|| TdmsError::DataBlockNotFound(selected[0].1.clone(), data_block))?;
+        bytes.resize(usize_from(block.length)?, 0);
+        file.file.seek(SeekFrom::Start(block.start))?;
+        file.file.read_exact(&mut bytes)?;
+
+        let indexes: Vec<usize> = selected.iter().map(|(index, _)| *index).collect();
+        let data = slice_block(block, &indexes, &bytes)?;
+        let objects = selected
+            .iter()
+            .map(|(channel_index, channel)| ObjectMetaData {
+                path: channel.path().to_string(),
+                properties: vec![],
+                raw_data_index: RawDataIndex::RawData(block.channels[*channel_index].clone()),
+            })
+            .collect();
+        let toc = ToC {
+            contains_new_object_list: true,
+            contains_raw_data: true,
+            data_is_interleaved: block.layout == DataLayout::Interleaved,
+            big_endian: block.byte_order == Endianess::Big,
+            ..Default::default()
+        };
+        write_raw_segment(dest, toc, Some(MetaData { objects }), &data)?;
+    }
+    Ok(())
+}
+
+/// Copy the bytes of the channels at `selected`, in ascending order, out of the block.
+pub(super) fn slice_block(
+    block: &DataBlock,
+    selected: &[usize],
+    bytes: &[u8],
+) -> Result<Vec<u8>, TdmsError> {
+    let mut sizes = Vec::with_capacity(block.channels.len());
+    for channel in &block.channels {
+        if channel.data_type == DataType::DAQmxRawData
+            || (block.layout == DataLayout::Interleaved && channel.total_size_bytes.is_some())
+        {
+            return Err(TdmsError::UnsupportedType(channel.data_type));
+        }
+        // Interleaved blocks repeat one value of each channel, contiguous
+        // blocks repeat every value of each channel.
+        let size = match block.layout {
+            DataLayout::Contigious => channel
+                .total_size_bytes
+                .unwrap_or(channel.number_of_values * channel.data_type.size() as u64),
+            DataLayout::Interleaved => channel.data_type.size() as u64,
+        };
+        sizes.push(usize_from(size)?);
+    }
+    let offsets: Vec<usize> = sizes
+        .iter()
+        .scan(0, |offset, size| {
+            let start = *offset;
+            *offset += size;
+            Some(start)
+        })
+        .collect();
+    let repeat_bytes: usize = sizes.iter().sum();
+    let repeats = bytes.len().checked_div(repeat_bytes).unwrap_or(0);
+
+    let selected_bytes: usize = selected.iter().map(|index| sizes[*index]).sum();
+    let mut data = Vec::with_capacity(selected_bytes * repeats);
+    for repeat in bytes.chunks_exact(repeat_bytes.max(1)).take(repeats) {
+        for index in selected {
+            data.extend_from_slice(&repeat[offsets[*index]..offsets[*index] + sizes[*index]]);
+        }
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{DataLayout, PropertyPath, TdmsFile};
+
+    fn source_file() -> Cursor<Vec<u8>> {
+        let mut bytes = Cursor::new(vec![]);
+        let mut file = TdmsFile::new(&mut bytes).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_properties(
+                &PropertyPath::file(),
+                &[
+                    ("operator", PropertyValue::from("A. Smith")),
+                    ("site", PropertyValue::from("Plant 3")),
+                ],
+            )
+            .unwrap();
+        writer
+            .write_properties(
+                &PropertyPath::channel("rig", "load"),
+                &[("serial", PropertyValue::from("SN-1234"))],
+            )
+            .unwrap();
+        let channels = [
+            ChannelPath::new("rig", "load"),
+            ChannelPath::new("rig", "temp"),
+        ];
+        writer
+            .write_channels(&channels, &[1.0, 2.0, 10.0, 20.0], DataLayout::Interleaved)
+            .unwrap();
+        writer
+            .write_channels(&channels, &[3.0, 4.0], DataLayout::Interleaved)
+            .unwrap();
+        drop(writer);
+        drop(file);
+        bytes
+    }
+
+    #[test]
+    fn test_redact_properties_and_names() {
+        let redaction = Redaction::new()
+            .remove_property("operator")
+            .replace_property("serial", PropertyValue::from("redacted"))
+            .rename_group("rig", "anonymous")
+            .rename_channel(
+                &ChannelPath::new("rig", "temp"),
+                &ChannelPath::new("other", "t"),
+            );
+        let mut dest = Cursor::new(vec![]);
+        redact(&mut source_file(), &mut dest, &redaction).unwrap();
+
+        let mut file = TdmsFile::new(dest).unwrap();
+        let root = PropertyPath::file();
+        assert_eq!(file.read_property(&root, "operator").unwrap(), None);
+        assert_eq!(
+            file.read_property(&root, "site").unwrap(),
+            Some(&PropertyValue::from("Plant 3"))
+        );
+        let load = ChannelPath::new("anonymous", "load");
+        assert_eq!(
+            file.read_property(load.as_ref(), "serial").unwrap(),
+            Some(&PropertyValue::from("redacted"))
+        );
+        assert_eq!(file.read_all::<f64>(&load).unwrap(), vec![1.0, 10.0, 3.0]);
+        assert_eq!(
+            file.read_all::<f64>(&ChannelPath::new("other", "t"))
+                .unwrap(),
+            vec![2.0, 20.0, 4.0]
+        );
+        assert!(file
+            .read_all::<f64>(&ChannelPath::new("rig", "load"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_empty_redaction_copies_file_exactly() {
+        let source = source_file();
+        let mut dest = Cursor::new(vec![]);
+        redact(&mut source.clone(), &mut dest, &Redaction::new()).unwrap();
+        assert_eq!(dest.into_inner(), source.into_inner());
+    }
+
+    #[test]
+    fn test_renames_to_the_same_path_are_rejected() {
+        let redaction = Redaction::new().rename_channel(
+            &ChannelPath::new("rig", "temp"),
+            &ChannelPath::new("rig", "load"),
+        );
+        let error = redact(&mut source_file(), &mut Cursor::new(vec![]), &redaction).unwrap_err();
+        assert_eq!(error.code(), 25);
+        assert_eq!(error.path(), Some("/'rig'/'load'"));
+    }
+
+    #[test]
+    fn test_extract_channels_from_mixed_layouts() {
+        let mut source = Cursor::new(vec![]);
+        let mut file = TdmsFile::new(&mut source).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_properties(
+                &PropertyPath::group("rig"),
+                &[("site", PropertyValue::from("Plant 3"))],
+            )
+            .unwrap();
+        let channels = [
+            ChannelPath::new("rig", "a"),
+            ChannelPath::new("rig", "b"),
+            ChannelPath::new("rig", "c"),
+        ];
+        writer
+            .write_properties(channels[2].as_ref(), &[("unit", PropertyValue::from("N"))])
+            .unwrap();
+        writer
+            .write_channels(
+                &channels,
+                &[1u32, 2, 10, 20, 100, 200],
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        writer
+            .write_channels(
+                &channels,
+                &[3u32, 30, 300, 4, 40, 400],
+                DataLayout::Interleaved,
+            )
+            .unwrap();
+        drop(writer);
+        drop(file);
+
+        let selected = [&channels[2], &channels[0]];
+        let mut dest = Cursor::new(vec![]);
+        extract(&mut source, &selected, &mut dest).unwrap();
+
+        let mut file = TdmsFile::new(dest).unwrap();
+        assert_eq!(
+            file.read_all::<u32>(&channels[0]).unwrap(),
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(
+            file.read_all::<u32>(&channels[2]).unwrap(),
+            vec![100, 200, 300, 400]
+        );
+        assert!(file.read_all::<u32>(&channels[1]).is_err());
+        assert_eq!(
+            file.read_property(channels[2].as_ref(), "unit").unwrap(),
+            Some(&PropertyValue::from("N"))
+        );
+        assert_eq!(
+            file.read_property(&PropertyPath::group("rig"), "site")
+                .unwrap(),
+            Some(&PropertyValue::from("Plant 3"))
+        );
+
+        let missing = [ChannelPath::new("rig", "missing")];
+        let error = extract(&mut source, &missing, &mut Cursor::new(vec![])).unwrap_err();
+        assert!(matches!(error, TdmsError::MissingChannel { .. }));
+    }
+
+    #[test]
+    fn test_append_starts_a_new_object_list() {
+        let first_only = ChannelPath::new("run", "first");
+        let shared = ChannelPath::new("run", "shared");
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(
+                &[&first_only, &shared],
+                &[1u32, 2, 10, 20],
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        drop(writer);
+
+        // The second run only has raw data for the shared channel and a
+        // segment reusing its object list, written big endian.
+        let mut second = Cursor::new(vec![]);
+        let meta = MetaData {
+            objects: vec![ObjectMetaData {
+                path: shared.path().to_string(),
+                properties: vec![("run".to_string(), PropertyValue::from(2i32))],
+                raw_data_index: RawDataIndex::RawData(crate::meta_data::RawDataMeta {
+                    data_type: DataType::U32,
+                    number_of_values: 1,
+                    total_size_bytes: None,
+                }),
+            }],
+        };
+        let toc = ToC {
+            contains_raw_data: true,
+            big_endian: true,
+            ..Default::default()
+        };
+        write_raw_segment(&mut second, toc, Some(meta), &30u32.to_be_bytes()).unwrap();
+        write_raw_segment(&mut second, toc, None, &40u32.to_be_bytes()).unwrap();
+
+        second.set_position(0);
+        file.append_from(&mut second).unwrap();
+        assert_eq!(file.read_all::<u32>(&shared).unwrap(), vec![10, 20, 30, 40]);
+        assert_eq!(file.read_all::<u32>(&first_only).unwrap(), vec![1, 2]);
+        assert_eq!(
+            file.read_property(shared.as_ref(), "run").unwrap(),
+            Some(&PropertyValue::from(2i32))
+        );
+
+        // The appended file indexes the same from scratch.
+        let mut reloaded = TdmsFile::new(file.file).unwrap();
+        assert_eq!(
+            reloaded.read_all::<u32>(&shared).unwrap(),
+            vec![10, 20, 30, 40]
+        );
+        assert_eq!(reloaded.read_all::<u32>(&first_only).unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_append_rejects_data_without_objects() {
+        let mut source = Cursor::new(vec![]);
+        let toc = ToC {
+            contains_raw_data: true,
+            ..Default::default()
+        };
+        write_raw_segment(&mut source, toc, None, &[0; 4]).unwrap();
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let error = file.append_from(&mut source).unwrap_err();
+        assert!(matches!(
+            error,
+            TdmsError::InSegment { ref source, .. }
+                if matches!(**source, TdmsError::SegmentTocDataBlockWithoutDataChannels)
+        ));
+    }
+}
@@ -0,0 +1,213 @@
+//! Writing samples straight from where they are produced.
+//!
+//! [`TdmsFileWriter::write_channels`] needs the samples in a slice. A
+//! [`SampleSource`] is drained into the file through a small buffer instead,
+//! so ring buffers, DMA buffers and generators don't have to be copied into a
+//! `Vec` first.
+
+use std::cell::RefCell;
+use std::io::Write;
+use std::marker::PhantomData;
+
+use crate::error::TdmsError;
+use crate::io::data_types::TdmsStorageType;
+use crate::io::writer::TdmsWriter;
+use crate::meta_data::RawDataMeta;
+use crate::paths::ChannelPath;
+use crate::raw_data::WriteBlock;
+use crate::{DataLayout, TdmsFileWriter};
+
+/// The most samples copied through the buffer at once.
+const FILL_SAMPLES: usize = 4096;
+
+/// Samples which can be written by [`TdmsFileWriter::write_channels_from_source`].
+///
+/// This is implemented for exact size iterators, which covers generators such
+/// as `(0..n).map(f)` and draining ring buffers such as a `VecDeque`.
+pub trait SampleSource<D> {
+    /// The number of samples left in the source.
+    fn len(&self) -> usize;
+
+    /// Fill `output` with the next samples.
+    ///
+    /// The writer never asks for more than [`Self::len`] samples.
+    fn fill(&mut self, output: &mut [D]);
+
+    /// True if the source has no samples left.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<D, I: ExactSizeIterator<Item = D>> SampleSource<D> for I {
+    fn len(&self) -> usize {
+        ExactSizeIterator::len(self)
+    }
+
+    fn fill(&mut self, output: &mut [D]) {
+        for (slot, value) in output.iter_mut().zip(self) {
+            *slot = value;
+        }
+    }
+}
+
+/// A block of raw data drawn from a source as it is written.
+struct SourceBlock<'s, D, S> {
+    // The writer only has shared access to the block while it writes.
+    source: RefCell<&'s mut S>,
+    channel_count: usize,
+    samples: usize,
+    _type: PhantomData<D>,
+}
+
+impl<D: TdmsStorageType + Default + Clone, S: SampleSource<D>> WriteBlock
+    for SourceBlock<'_, D, S>
+{
+    fn data_structure(&self) -> Vec<RawDataMeta> {
+        let meta = RawDataMeta {
+            data_type: D::NATURAL_TYPE,
+            number_of_values: (self.samples / self.channel_count) as u64,
+            total_size_bytes: None,
+        };
+        vec![meta; self.channel_count]
+    }
+
+    fn write<W: Write, T: TdmsWriter<W>>(&self, writer: &mut T) -> Result<(), TdmsError> {
+        let mut source = self.source.borrow_mut();
+        let mut buffer = vec![D::default(); self.samples.min(FILL_SAMPLES)];
+        let mut remaining = self.samples;
+        while remaining > 0 {
+            let length = remaining.min(buffer.len());
+            source.fill(&mut buffer[..length]);
+            writer.write_values(&buffer[..length])?;
+            remaining -= length;
+        }
+        Ok(())
+    }
+
+    fn size(&self) -> usize {
+        self.samples * D::SIZE_BYTES
+    }
+}
+
+impl<'a, F: Write, W: TdmsWriter<&'a mut F>> TdmsFileWriter<'a, F, W> {
+    /// Write all the samples in the source to the channels.
+    ///
+    /// The samples are ordered as for [`Self::write_channels`] with the same
+    /// layout. Interleaved sources are split by the segment limit. Contigious
+    /// sources are always written as one segment, as each channel must be
+    /// complete before the next starts.
+    ///
+    /// Errors with [`TdmsError::BadDataBlockLength`] if the source isn't a
+    /// whole number of samples for each channel.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tedium::{ChannelPath, DataLayout, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "ramp");
+    /// let mut writer = file.writer().unwrap();
+    /// let mut ramp = (0..1000).map(|sample| sample as f64 * 0.5);
+    /// writer
+    ///     .write_channels_from_source(&[&channel], &mut ramp, DataLayout::Contigious)
+    ///     .unwrap();
+    /// drop(writer);
+    ///
+    /// assert_eq!(file.channel_length(&channel), Some(1000));
+    /// ```
+    pub fn write_channels_from_source<D: TdmsStorageType + Default + Clone>(
+        &mut self,
+        channels: &[impl AsRef<ChannelPath>],
+        source: &mut impl SampleSource<D>,
+        layout: DataLayout,
+    ) -> Result<(), TdmsError> {
+        let total = source.len();
+        if channels.is_empty() || !total.is_multiple_of(channels.len()) {
+            return Err(TdmsError::BadDataBlockLength(total, channels.len()));
+        }
+        let paths: Vec<&str> = channels.iter().map(|path| path.as_ref().path()).collect();
+
+        let samples_per_channel = total / channels.len();
+        let chunk_samples = match layout {
+            DataLayout::Interleaved => self.chunk_samples::<D>(channels.len(), samples_per_channel),
+            DataLayout::Contigious => samples_per_channel.max(1),
+        };
+
+        let mut start = 0;
+        loop {
+            let length = chunk_samples.min(samples_per_channel - start);
+            let block = SourceBlock {
+                source: RefCell::new(&mut *source),
+                channel_count: channels.len(),
+                samples: length * channels.len(),
+                _type: PhantomData,
+            };
+            self.write_block(&paths, block, layout)?;
+            start += length;
+            if start >= samples_per_channel {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{SegmentLimit, TdmsFile};
+
+    #[test]
+    fn test_ring_buffer_source_is_split_by_limit() {
+        let channels = [ChannelPath::new("daq", "a"), ChannelPath::new("daq", "b")];
+        let mut ring: VecDeque<i32> = (0..10_000).collect();
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer.set_segment_limit(Some(SegmentLimit::Samples(1500)));
+        writer
+            .write_channels_from_source(&channels, &mut ring.drain(..), DataLayout::Interleaved)
+            .unwrap();
+        drop(writer);
+
+        assert!(ring.is_empty());
+        assert_eq!(file.data_block_count(), 4);
+        let a: Vec<i32> = file.read_all(&channels[0]).unwrap();
+        let b: Vec<i32> = file.read_all(&channels[1]).unwrap();
+        assert_eq!(a, (0..10_000).step_by(2).collect::<Vec<_>>());
+        assert_eq!(b, (1..10_000).step_by(2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_contigious_source_matches_slice_write() {
+        let channels = [ChannelPath::new("daq", "a"), ChannelPath::new("daq", "b")];
+        let values: Vec<u16> = (0..9000).collect();
+
+        let mut from_slice = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = from_slice.writer().unwrap();
+        writer
+            .write_channels(&channels, &values, DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+
+        let mut from_source = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = from_source.writer().unwrap();
+        writer
+            .write_channels_from_source(
+                &channels,
+                &mut values.iter().copied(),
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        assert!(matches!(
+            writer.write_channels_from_source(&channels, &mut (0..3u16), DataLayout::Contigious),
+            Err(TdmsError::BadDataBlockLength(3, 2))
+        ));
+        drop(writer);
+
+        assert_eq!(from_source.file.get_ref(), from_slice.file.get_ref());
+    }
+}
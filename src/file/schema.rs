@@ -0,0 +1,174 @@
+//! Comparing the channels of two files.
+//!
+//! Long test campaigns write many files with what should be the same
+//! configuration. [`compare_schema`] reports the channels added or removed
+//! between two files and those whose data type or unit changed, so drift in
+//! the configuration is caught between runs.
+
+use std::collections::BTreeMap;
+use std::io::{Read, Seek};
+
+use crate::io::data_types::DataType;
+use crate::paths::{ChannelPath, ObjectPath};
+use crate::{PropertyValue, TdmsFile};
+
+/// A channel in both files with a different data type or unit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelChange {
+    pub channel: ChannelPath,
+    /// The data type in the first and second files. None if it has no data.
+    pub data_type: (Option<DataType>, Option<DataType>),
+    /// The `unit_string` property in the first and second files.
+    pub unit: (Option<String>, Option<String>),
+}
+
+/// The differences in the channels of two files found by [`compare_schema`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaDiff {
+    /// Channels only in the second file.
+    pub added: Vec<ChannelPath>,
+    /// Channels only in the first file.
+    pub removed: Vec<ChannelPath>,
+    /// Channels in both files whose data type or unit changed.
+    pub changed: Vec<ChannelChange>,
+}
+
+impl SchemaDiff {
+    /// True if the files have the same channels with the same types and units.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// The data type and unit of every channel, by path.
+fn channel_schema<F: Read + Seek>(
+    file: &TdmsFile<F>,
+) -> BTreeMap<String, (ChannelPath, Option<DataType>, Option<String>)> {
+    file.list_objects()
+        .filter_map(|object| match object {
+            ObjectPath::Channel(channel) => Some(channel),
+            _ => None,
+        })
+        .map(|channel| {
+            let data_type = file.index.channel_data_type(&channel);
+            let unit = match file
+                .index
+                .get_object_property(channel.as_ref(), "unit_string")
+            {
+                Ok(Some(PropertyValue::String(unit))) => Some(unit.clone()),
+                _ => None,
+            };
+            (channel.path().to_string(), (channel, data_type, unit))
+        })
+        .collect()
+}
+
+/// Compare the channels of two files.
+///
+/// Channels are matched by path. Groups, other properties and the amount of
+/// data are not compared.
+///
+/// # Example
+#[cfg_attr(feature = "write", doc = "```rust")]
+#[cfg_attr(not(feature = "write"), doc = "```ignore")]
+/// use tedium::{compare_schema, ChannelPath, DataLayout, TdmsFile};
+///
+/// let write = |channels: &[ChannelPath]| {
+///     let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+///     let mut writer = file.writer().unwrap();
+///     let data = vec![0.0; channels.len()];
+///     writer.write_channels(channels, &data, DataLayout::Contigious).unwrap();
+///     drop(writer);
+///     file
+/// };
+/// let first = write(&[ChannelPath::new("rig", "a"), ChannelPath::new("rig", "b")]);
+/// let second = write(&[ChannelPath::new("rig", "a"), ChannelPath::new("rig", "c")]);
+///
+/// let diff = compare_schema(&first, &second);
+/// assert_eq!(diff.added, vec![ChannelPath::new("rig", "c")]);
+/// assert_eq!(diff.removed, vec![ChannelPath::new("rig", "b")]);
+/// assert!(diff.changed.is_empty());
+/// ```
+pub fn compare_schema<A: Read + Seek, B: Read + Seek>(
+    first: &TdmsFile<A>,
+    second: &TdmsFile<B>,
+) -> SchemaDiff {
+    let first = channel_schema(first);
+    let mut second = channel_schema(second);
+    let mut diff = SchemaDiff::default();
+
+    for (path, (channel, data_type, unit)) in first {
+        let Some((_, new_type, new_unit)) = second.remove(&path) else {
+            diff.removed.push(channel);
+            continue;
+        };
+        if data_type != new_type || unit != new_unit {
+            diff.changed.push(ChannelChange {
+                channel,
+                data_type: (data_type, new_type),
+                unit: (unit, new_unit),
+            });
+        }
+    }
+    diff.added = second.into_values().map(|(channel, ..)| channel).collect();
+    diff
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::DataLayout;
+
+    #[test]
+    fn test_changed_types_and_units() {
+        let force = ChannelPath::new("rig", "force");
+        let speed = ChannelPath::new("rig", "speed");
+
+        let mut first = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = first.writer().unwrap();
+        writer
+            .write_properties(force.as_ref(), &[("unit_string", PropertyValue::from("N"))])
+            .unwrap();
+        writer
+            .write_channels(&[&force, &speed], &[1.0, 2.0], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+
+        let mut second = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = second.writer().unwrap();
+        writer
+            .write_properties(
+                force.as_ref(),
+                &[("unit_string", PropertyValue::from("kN"))],
+            )
+            .unwrap();
+        writer
+            .write_channels(&[&force], &[1.0], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_channels(&[&speed], &[2.0f32], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+
+        assert!(compare_schema(&first, &first).is_empty());
+        let diff = compare_schema(&first, &second);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(
+            diff.changed,
+            vec![
+                ChannelChange {
+                    channel: force,
+                    data_type: (Some(DataType::DoubleFloat), Some(DataType::DoubleFloat)),
+                    unit: (Some("N".to_string()), Some("kN".to_string())),
+                },
+                ChannelChange {
+                    channel: speed,
+                    data_type: (Some(DataType::DoubleFloat), Some(DataType::SingleFloat)),
+                    unit: (None, None),
+                },
+            ]
+        );
+    }
+}
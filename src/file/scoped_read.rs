@@ -0,0 +1,230 @@
+//! Reading channels on multiple threads without a thread pool.
+//!
+//! [`TdmsFile::read_channels_scoped`] spreads the channels over scoped
+//! threads from the standard library. Each thread opens its own handle to the
+//! file so the seeks of one thread never move the position of another, and the
+//! index is shared between them. [`TdmsFile::read_channels_parallel`] does the
+//! same with the thread count and core pinning set on the file.
+
+use std::io::{Read, Seek};
+
+use super::channel_reader::read_range_from;
+use super::Parallelism;
+use crate::error::TdmsError;
+use crate::io::data_types::TdmsStorageType;
+use crate::paths::ChannelPath;
+use crate::TdmsFile;
+
+impl<F: Read + Seek> TdmsFile<F> {
+    /// Read multiple channels using up to `thread_count` threads.
+    ///
+    /// Each channel is read for the length of its corresponding output slice,
+    /// as in [`Self::read_channels`]. The channels are shared between the
+    /// threads and each thread calls `open` once for its own file handle, for
+    /// example by opening the file path again.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use std::io::Cursor;
+    /// use tedium::testgen::TestFileSpec;
+    /// use tedium::{TdmsError, TdmsFile};
+    ///
+    /// let spec = TestFileSpec { channels_per_group: 4, ..Default::default() };
+    /// let bytes = spec.build().unwrap();
+    /// let file = TdmsFile::new(Cursor::new(bytes.clone())).unwrap();
+    ///
+    /// let channels: Vec<_> = (0..4).map(|channel| spec.channel_path(0, channel)).collect();
+    /// let mut data = vec![vec![0.0f64; spec.channel_length()]; 4];
+    /// let mut outputs: Vec<&mut [f64]> = data.iter_mut().map(|v| &mut v[..]).collect();
+    /// file.read_channels_scoped(&channels, &mut outputs, 2, || {
+    ///     Ok::<_, TdmsError>(Cursor::new(bytes.as_slice()))
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn read_channels_scoped<D, R>(
+        &self,
+        channels: &[impl AsRef<ChannelPath> + Sync],
+        output: &mut [&mut [D]],
+        thread_count: usize,
+        open: impl Fn() -> Result<R, TdmsError> + Sync,
+    ) -> Result<(), TdmsError>
+    where
+        D: TdmsStorageType + Send,
+        R: Read + Seek,
+    {
+        self.read_channels_on_threads(
+            channels,
+            output,
+            &Parallelism::threads(thread_count.max(1)),
+            open,
+        )
+    }
+
+    /// Read multiple channels on the threads set by [`Self::set_parallelism`].
+    ///
+    /// This is [`Self::read_channels_scoped`] with the thread count taken from
+    /// the file's [`Parallelism`] and each thread pinned to its core, if cores
+    /// are set. Errors if a thread can't be pinned.
+    pub fn read_channels_parallel<D, R>(
+        &self,
+        channels: &[impl AsRef<ChannelPath> + Sync],
+        output: &mut [&mut [D]],
+        open: impl Fn() -> Result<R, TdmsError> + Sync,
+    ) -> Result<(), TdmsError>
+    where
+        D: TdmsStorageType + Send,
+        R: Read + Seek,
+    {
+        self.read_channels_on_threads(channels, output, &self.parallelism, open)
+    }
+
+    fn read_channels_on_threads<D, R>(
+        &self,
+        channels: &[impl AsRef<ChannelPath> + Sync],
+        output: &mut [&mut [D]],
+        parallelism: &Parallelism,
+        open: impl Fn() -> Result<R, TdmsError> + Sync,
+    ) -> Result<(), TdmsError>
+    where
+        D: TdmsStorageType + Send,
+        R: Read + Seek,
+    {
+        let thread_count = parallelism.thread_count().clamp(1, channels.len().max(1));
+        let mut jobs: Vec<Vec<(&ChannelPath, &mut [D])>> =
+            (0..thread_count).map(|_| Vec::new()).collect();
+        for (job, (channel, output)) in channels.iter().zip(output.iter_mut()).enumerate() {
+            jobs[job % thread_count].push((channel.as_ref(), &mut output[..]));
+        }
+
+        let index = &self.index;
+        let retry = &self.retry;
+        let open = &open;
+        std::thread::scope(|scope| {
+            let threads: Vec<_> = jobs
+                .into_iter()
+                .filter(|job| !job.is_empty())
+                .enumerate()
+                .map(|(worker, job)| {
+                    scope.spawn(move || -> Result<(), TdmsError> {
+                        parallelism.pin_worker(worker)?;
+                        let mut file = open()?;
+                        for (channel, output) in job {
+                            read_range_from(index, retry, &mut file, channel, 0, output)?;
+                        }
+                        Ok(())
+                    })
+                })
+                .collect();
+
+            threads.into_iter().try_for_each(|thread| {
+                thread
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+        })
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::testgen::TestFileSpec;
+    use crate::DataLayout;
+
+    #[test]
+    fn test_scoped_read_matches_sequential_read() {
+        let spec = TestFileSpec {
+            channels_per_group: 5,
+            segments: 3,
+            layout: DataLayout::Interleaved,
+            ..Default::default()
+        };
+        let bytes = spec.build().unwrap();
+        let mut file = TdmsFile::new(Cursor::new(bytes.clone())).unwrap();
+        let channels: Vec<ChannelPath> = (0..5)
+            .map(|channel| spec.channel_path(0, channel))
+            .collect();
+
+        for thread_count in [0, 1, 2, 8] {
+            let mut data = vec![vec![0.0f64; spec.channel_length()]; 5];
+            let mut outputs: Vec<&mut [f64]> = data.iter_mut().map(|v| &mut v[..]).collect();
+            file.read_channels_scoped(&channels, &mut outputs, thread_count, || {
+                Ok(Cursor::new(bytes.as_slice()))
+            })
+            .unwrap();
+            for (channel, read) in channels.iter().zip(&data) {
+                assert_eq!(read, &file.read_all::<f64>(channel).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_scoped_read_returns_errors() {
+        let spec = TestFileSpec::default();
+        let bytes = spec.build().unwrap();
+        let file = TdmsFile::new(Cursor::new(bytes.clone())).unwrap();
+        let channels = [
+            spec.channel_path(0, 0),
+            ChannelPath::new("group0", "missing"),
+        ];
+        let mut data = vec![vec![0.0f64; spec.channel_length()]; 2];
+        let mut outputs: Vec<&mut [f64]> = data.iter_mut().map(|v| &mut v[..]).collect();
+
+        let error = file
+            .read_channels_scoped(&channels, &mut outputs, 2, || {
+                Ok(Cursor::new(bytes.as_slice()))
+            })
+            .unwrap_err();
+        assert!(matches!(error, TdmsError::MissingChannel { .. }));
+
+        let error = file
+            .read_channels_scoped(&channels, &mut outputs, 2, || {
+                Err::<Cursor<Vec<u8>>, _>(TdmsError::EndOfFile)
+            })
+            .unwrap_err();
+        assert!(matches!(error, TdmsError::EndOfFile));
+    }
+
+    #[test]
+    fn test_parallel_read_uses_file_setting() {
+        let spec = TestFileSpec {
+            channels_per_group: 3,
+            ..Default::default()
+        };
+        let bytes = spec.build().unwrap();
+        let mut file = TdmsFile::new(Cursor::new(bytes.clone())).unwrap();
+        file.set_parallelism(Parallelism::threads(2));
+        let channels: Vec<ChannelPath> = (0..3)
+            .map(|channel| spec.channel_path(0, channel))
+            .collect();
+        let mut data = vec![vec![0.0f64; spec.channel_length()]; 3];
+        let mut outputs: Vec<&mut [f64]> = data.iter_mut().map(|v| &mut v[..]).collect();
+
+        file.read_channels_parallel(&channels, &mut outputs, || {
+            Ok(Cursor::new(bytes.as_slice()))
+        })
+        .unwrap();
+        for (channel, read) in channels.iter().zip(&data) {
+            assert_eq!(read, &file.read_all::<f64>(channel).unwrap());
+        }
+    }
+
+    #[cfg(not(all(feature = "affinity", target_os = "linux")))]
+    #[test]
+    fn test_parallel_read_fails_when_pinning_is_unsupported() {
+        let spec = TestFileSpec::default();
+        let bytes = spec.build().unwrap();
+        let mut file = TdmsFile::new(Cursor::new(bytes.clone())).unwrap();
+        file.set_parallelism(Parallelism::threads(1).with_cores([0]));
+        let mut data = vec![0.0f64; spec.channel_length()];
+
+        let result =
+            file.read_channels_parallel(&[spec.channel_path(0, 0)], &mut [&mut data[..]], || {
+                Ok(Cursor::new(bytes.as_slice()))
+            });
+        assert!(matches!(result, Err(TdmsError::IoError(_))));
+    }
+}
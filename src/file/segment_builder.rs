@@ -0,0 +1,250 @@
+//! Assembling segments by hand.
+//!
+//! The normal writer API chooses the table of contents, object list and raw
+//! data index itself. [`SegmentBuilder`] lets advanced users set each part of
+//! a segment explicitly, for example to reproduce the output of another writer
+//! or to test how readers handle unusual files.
+
+use crate::error::TdmsError;
+use crate::io::data_types::DataType;
+use crate::meta_data::{MetaData, ObjectMetaData, RawDataIndex, RawDataMeta};
+use crate::{PropertyPath, PropertyValue, ToC};
+
+/// The raw data index written for an object in a [`SegmentBuilder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentRawIndex {
+    /// The object has no data in this segment.
+    NoData,
+    /// The object has the same data layout as in the previous segment.
+    MatchPrevious,
+    /// The object has `number_of_values` values of the data type in each chunk of this segment.
+    Data {
+        data_type: DataType,
+        number_of_values: u64,
+    },
+}
+
+/// A segment with every part set explicitly.
+///
+/// The raw payload is written exactly as given so it must already be in the
+/// byte order of the file and laid out to match the table of contents and
+/// raw data indexes.
+///
+/// # Example
+/// ```rust
+/// use tedium::types::DataType;
+/// use tedium::{ChannelPath, SegmentBuilder, SegmentRawIndex, TdmsFile, ToC};
+///
+/// let channel = ChannelPath::new("group", "channel");
+/// let payload: Vec<u8> = [1u16, 2, 3].iter().flat_map(|v| v.to_le_bytes()).collect();
+/// let segment = SegmentBuilder::new()
+///     .toc(ToC {
+///         contains_new_object_list: true,
+///         ..Default::default()
+///     })
+///     .object(
+///         channel.as_ref(),
+///         SegmentRawIndex::Data { data_type: DataType::U16, number_of_values: 3 },
+///         &[],
+///     )
+///     .payload(payload);
+///
+/// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+/// let mut writer = file.writer().unwrap();
+/// writer.write_built_segment(&segment).unwrap();
+/// drop(writer);
+///
+/// let data: Vec<u16> = file.read_all(&channel).unwrap();
+/// assert_eq!(data, vec![1, 2, 3]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SegmentBuilder {
+    toc: ToC,
+    objects: Vec<ObjectMetaData>,
+    payload: Vec<u8>,
+}
+
+/// The parts of a built segment ready to pass to the writer.
+pub(crate) struct SegmentParts<'a> {
+    pub toc: ToC,
+    pub meta: Option<MetaData>,
+    pub payload: Option<&'a [u8]>,
+}
+
+impl SegmentBuilder {
+    /// Start an empty segment with no flags set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the table of contents.
+    ///
+    /// The metadata and raw data flags are also set when the segment has
+    /// objects or a payload, and the byte order flag always matches the writer.
+    pub fn toc(mut self, toc: ToC) -> Self {
+        self.toc = toc;
+        self
+    }
+
+    /// Add an object to the metadata with its raw data index and properties.
+    ///
+    /// Objects are written in the order they are added.
+    pub fn object(
+        mut self,
+        path: &PropertyPath,
+        raw_index: SegmentRawIndex,
+        properties: &[(&str, PropertyValue)],
+    ) -> Self {
+        let raw_data_index = match raw_index {
+            SegmentRawIndex::NoData => RawDataIndex::None,
+            SegmentRawIndex::MatchPrevious => RawDataIndex::MatchPrevious,
+            SegmentRawIndex::Data {
+                data_type,
+                number_of_values,
+            } => RawDataIndex::RawData(RawDataMeta {
+                data_type,
+                number_of_values,
+                total_size_bytes: None,
+            }),
+        };
+        self.objects.push(ObjectMetaData {
+            path: path.path().to_string(),
+            properties: properties
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+            raw_data_index,
+        });
+        self
+    }
+
+    /// Set the raw data written after the metadata.
+    pub fn payload(mut self, payload: Vec<u8>) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    /// The table of contents, metadata and payload to write.
+    ///
+    /// Errors with [`TdmsError::UnsupportedType`] for string data, as the
+    /// builder can't write the size of the strings.
+    pub(crate) fn segment_parts(&self) -> Result<SegmentParts<'_>, TdmsError> {
+        let unsupported = self
+            .objects
+            .iter()
+            .find_map(|object| match &object.raw_data_index {
+                RawDataIndex::RawData(meta) if meta.data_type == DataType::TdmsString => {
+                    Some(meta.data_type)
+                }
+                _ => None,
+            });
+        if let Some(data_type) = unsupported {
+            return Err(TdmsError::UnsupportedType(data_type));
+        }
+
+        let meta = (self.toc.contains_meta_data || !self.objects.is_empty()).then(|| MetaData {
+            objects: self.objects.clone(),
+        });
+        let payload =
+            (self.toc.contains_raw_data || !self.payload.is_empty()).then_some(&self.payload[..]);
+        Ok(SegmentParts {
+            toc: self.toc,
+            meta,
+            payload,
+        })
+    }
+
+    /// The paths of the objects in the segment in order.
+    pub(crate) fn paths(&self) -> Vec<&str> {
+        self.objects
+            .iter()
+            .map(|object| object.path.as_str())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{ChannelPath, TdmsFile};
+
+    #[test]
+    fn test_built_interleaved_segment_then_match_previous() {
+        let channels = [
+            ChannelPath::new("group", "a"),
+            ChannelPath::new("group", "b"),
+        ];
+        let data = |values: &[i32]| values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let first = SegmentBuilder::new()
+            .toc(ToC {
+                contains_new_object_list: true,
+                data_is_interleaved: true,
+                ..Default::default()
+            })
+            .object(
+                &PropertyPath::group("group"),
+                SegmentRawIndex::NoData,
+                &[("vendor", PropertyValue::from("x"))],
+            )
+            .object(
+                channels[0].as_ref(),
+                SegmentRawIndex::Data {
+                    data_type: DataType::I32,
+                    number_of_values: 2,
+                },
+                &[],
+            )
+            .object(
+                channels[1].as_ref(),
+                SegmentRawIndex::Data {
+                    data_type: DataType::I32,
+                    number_of_values: 2,
+                },
+                &[],
+            )
+            .payload(data(&[1, 10, 2, 20]));
+        // No metadata, so the previous layout is reused.
+        let second = SegmentBuilder::new()
+            .toc(ToC {
+                data_is_interleaved: true,
+                ..Default::default()
+            })
+            .payload(data(&[3, 30, 4, 40]));
+
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer.write_built_segment(&first).unwrap();
+        writer.write_built_segment(&second).unwrap();
+        drop(writer);
+
+        let a: Vec<i32> = file.read_all(&channels[0]).unwrap();
+        let b: Vec<i32> = file.read_all(&channels[1]).unwrap();
+        assert_eq!(a, vec![1, 2, 3, 4]);
+        assert_eq!(b, vec![10, 20, 30, 40]);
+        assert_eq!(
+            file.read_property(&PropertyPath::group("group"), "vendor")
+                .unwrap(),
+            Some(&PropertyValue::from("x"))
+        );
+    }
+
+    #[test]
+    fn test_built_segment_rejects_strings() {
+        let segment = SegmentBuilder::new().object(
+            ChannelPath::new("group", "text").as_ref(),
+            SegmentRawIndex::Data {
+                data_type: DataType::TdmsString,
+                number_of_values: 1,
+            },
+            &[],
+        );
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = file.writer().unwrap();
+        assert!(matches!(
+            writer.write_built_segment(&segment),
+            Err(TdmsError::UnsupportedType(DataType::TdmsString))
+        ));
+    }
+}
@@ -0,0 +1,743 @@
+//! Writing to one file from several threads.
+//!
+//! [`SharedTdmsWriter`] owns the file on a background thread. Each producer
+//! thread gets a [`TdmsProducer`] which adds its writes to a shared queue and
+//! the background thread writes them in the order they arrive, batching
+//! whatever is queued into a single writer session.
+//!
+//! The queue can be bounded with an [`OverflowPolicy`] deciding what happens
+//! when the disk can't keep up, so acquisition threads need never stall.
+//!
+//! An [`IndexView`] lets a live display read what has been written so far
+//! through its own handle to the file, while the writer thread carries on.
+//! This is how to log and display a file within one process, as a
+//! [`TdmsFileWriter`] borrows its [`TdmsFile`] for as long as it is writing.
+
+use std::collections::VecDeque;
+use std::io::{Read, Seek, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, RwLock};
+use std::thread::JoinHandle;
+
+use crate::error::TdmsError;
+use crate::io::data_types::TdmsStorageType;
+use crate::io::writer::LittleEndianWriter;
+use crate::paths::{ChannelPath, PropertyPath};
+use crate::{DataLayout, IndexEpoch, PropertyValue, TdmsFile, TdmsFileWriter};
+
+/// A write queued by a producer to run on the writer thread.
+type WriteJob<F> = Box<
+    dyn for<'a> FnOnce(
+            &mut TdmsFileWriter<'a, F, LittleEndianWriter<&'a mut F>>,
+        ) -> Result<(), TdmsError>
+        + Send,
+>;
+
+/// What a producer does when the queue of a [`SharedTdmsWriter`] is full.
+///
+/// Property writes are never dropped, they are always queued even if this
+/// takes the queue over its capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Wait for the writer to make space.
+    #[default]
+    Block,
+    /// Drop the oldest queued data write to make space.
+    DropOldest,
+    /// Drop the data write being queued.
+    DropNewest,
+}
+
+/// Counters for the queue of a [`SharedTdmsWriter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WriterMetrics {
+    /// The number of writes waiting for the writer thread.
+    pub queued_writes: usize,
+    /// The number of data writes dropped by the [`OverflowPolicy`].
+    pub dropped_writes: u64,
+    /// The number of values in the dropped writes, across all channels.
+    pub dropped_samples: u64,
+}
+
+struct QueuedJob<F: Write> {
+    job: WriteJob<F>,
+    /// The number of values for data writes, None for property writes.
+    samples: Option<usize>,
+}
+
+struct QueueState<F: Write> {
+    jobs: VecDeque<QueuedJob<F>>,
+    /// The number of live producers, including the one held by the writer.
+    producers: usize,
+    /// Set when the writer thread has stopped, on an error or a panic.
+    stopped: bool,
+    dropped_writes: u64,
+    dropped_samples: u64,
+}
+
+struct Queue<F: Write> {
+    state: Mutex<QueueState<F>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize,
+    policy: OverflowPolicy,
+}
+
+impl<F: Write> Queue<F> {
+    fn lock(&self) -> MutexGuard<'_, QueueState<F>> {
+        // A panicking job can't leave the queue itself inconsistent.
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn push(&self, job: QueuedJob<F>) -> Result<(), TdmsError> {
+        let mut state = self.lock();
+        if state.jobs.len() >= self.capacity {
+            if let Some(samples) = job.samples {
+                match self.policy {
+                    OverflowPolicy::Block => {
+                        while state.jobs.len() >= self.capacity && !state.stopped {
+                            state = self
+                                .not_full
+                                .wait(state)
+                                .unwrap_or_else(|poisoned| poisoned.into_inner());
+                        }
+                    }
+                    OverflowPolicy::DropNewest => {
+                        state.dropped_writes += 1;
+                        state.dropped_samples += samples as u64;
+                        return Ok(());
+                    }
+                    OverflowPolicy::DropOldest => {
+                        let oldest = state.jobs.iter().position(|job| job.samples.is_some());
+                        if let Some(dropped) = oldest.and_then(|index| state.jobs.remove(index)) {
+                            state.dropped_writes += 1;
+                            state.dropped_samples += dropped.samples.unwrap_or(0) as u64;
+                        }
+                    }
+                }
+            }
+        }
+        if state.stopped {
+            return Err(TdmsError::IoError(std::io::Error::other(
+                "shared writer stopped, the error is returned by finish",
+            )));
+        }
+        state.jobs.push_back(job);
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Wait for queued jobs, returning None once every producer has gone.
+    fn take_all(&self) -> Option<VecDeque<QueuedJob<F>>> {
+        let mut state = self.lock();
+        while state.jobs.is_empty() && state.producers > 0 {
+            state = self
+                .not_empty
+                .wait(state)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+        if state.jobs.is_empty() {
+            return None;
+        }
+        self.not_full.notify_all();
+        Some(std::mem::take(&mut state.jobs))
+    }
+
+    fn stop(&self) {
+        let mut state = self.lock();
+        state.stopped = true;
+        state.jobs.clear();
+        self.not_full.notify_all();
+    }
+
+    fn metrics(&self) -> WriterMetrics {
+        let state = self.lock();
+        WriterMetrics {
+            queued_writes: state.jobs.len(),
+            dropped_writes: state.dropped_writes,
+            dropped_samples: state.dropped_samples,
+        }
+    }
+}
+
+/// The latest index published by the writer thread for [`IndexView`]s.
+#[derive(Debug)]
+struct Published {
+    epoch: RwLock<IndexEpoch>,
+    /// Set once a view exists. Until then nothing is published.
+    enabled: AtomicBool,
+}
+
+impl Published {
+    /// Publish the index of the file and take back the previous generation to write to.
+    ///
+    /// Writing to the index the views now share would copy it, which costs
+    /// more as the file grows. Instead the file continues with the previous
+    /// generation, brought up to date by indexing just the segments written
+    /// since. That is only possible once no view pins the previous generation,
+    /// otherwise the next write copies the index.
+    fn publish<F: Read + Seek>(&self, file: &mut TdmsFile<F>) -> Result<(), TdmsError> {
+        if !self.enabled.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        let previous = std::mem::replace(
+            &mut *self
+                .epoch
+                .write()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+            file.pin_epoch(),
+        );
+        let mut previous = previous.into_index();
+        if Arc::get_mut(&mut previous).is_some() {
+            file.index = previous;
+            file.refresh()?;
+        }
+        Ok(())
+    }
+}
+
+/// A view of what a [`SharedTdmsWriter`] has written, for reading while it writes.
+///
+/// The writer thread publishes a new [`IndexEpoch`] after each batch of writes
+/// has been synced, so reads through a separate handle to the file only see
+/// complete data.
+///
+/// Publishing re-reads the metadata of the segments in the batch, so it
+/// costs the same however large the file is. Holding an epoch from
+/// [`Self::current`] while the writer publishes twice makes it copy the whole
+/// index, so a display should take a fresh epoch for each update rather than
+/// keeping old ones.
+#[derive(Debug, Clone)]
+pub struct IndexView {
+    published: Arc<Published>,
+}
+
+impl IndexView {
+    /// The latest epoch published by the writer.
+    pub fn current(&self) -> IndexEpoch {
+        self.published
+            .epoch
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+/// Writes to a single file from multiple producer threads.
+///
+/// # Example
+/// ```rust
+/// use tedium::{ChannelPath, DataLayout, SharedTdmsWriter, TdmsFile};
+///
+/// let file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+/// let shared = SharedTdmsWriter::new(file);
+///
+/// let threads: Vec<_> = ["group_a", "group_b"]
+///     .into_iter()
+///     .map(|group| {
+///         let producer = shared.producer();
+///         std::thread::spawn(move || {
+///             let channel = ChannelPath::new(group, "channel");
+///             producer
+///                 .write_channels(&[channel], vec![1.0, 2.0, 3.0], DataLayout::Contigious)
+///                 .unwrap();
+///         })
+///     })
+///     .collect();
+/// for thread in threads {
+///     thread.join().unwrap();
+/// }
+///
+/// let mut file = shared.finish().unwrap();
+/// let data: Vec<f64> = file.read_all(&ChannelPath::new("group_b", "channel")).unwrap();
+/// assert_eq!(data, vec![1.0, 2.0, 3.0]);
+/// ```
+pub struct SharedTdmsWriter<F: Read + Write + Seek> {
+    producer: TdmsProducer<F>,
+    published: Arc<Published>,
+    thread: JoinHandle<Result<TdmsFile<F>, TdmsError>>,
+}
+
+impl<F: Read + Write + Seek + Send + 'static> SharedTdmsWriter<F> {
+    /// Start the writer thread for the file with an unbounded queue.
+    pub fn new(file: TdmsFile<F>) -> Self {
+        Self::with_queue(file, usize::MAX, OverflowPolicy::Block)
+    }
+
+    /// Start the writer thread with a queue of at most `capacity` writes.
+    ///
+    /// # Example
+    /// ```rust
+    /// use tedium::{ChannelPath, DataLayout, OverflowPolicy, SharedTdmsWriter, TdmsFile};
+    ///
+    /// let file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let shared = SharedTdmsWriter::with_queue(file, 16, OverflowPolicy::DropOldest);
+    /// let producer = shared.producer();
+    /// producer
+    ///     .write_channels(&[ChannelPath::new("group", "channel")], vec![1.0], DataLayout::Contigious)
+    ///     .unwrap();
+    /// println!("dropped {} samples", producer.metrics().dropped_samples);
+    /// ```
+    pub fn with_queue(file: TdmsFile<F>, capacity: usize, policy: OverflowPolicy) -> Self {
+        let queue = Arc::new(Queue {
+            state: Mutex::new(QueueState {
+                jobs: VecDeque::new(),
+                producers: 1,
+                stopped: false,
+                dropped_writes: 0,
+                dropped_samples: 0,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            capacity: capacity.max(1),
+            policy,
+        });
+        let published = Arc::new(Published {
+            epoch: RwLock::new(file.pin_epoch()),
+            enabled: AtomicBool::new(false),
+        });
+        let thread_queue = queue.clone();
+        let thread_published = published.clone();
+        let thread = std::thread::spawn(move || {
+            // Stop the queue however the thread ends so producers waiting for
+            // space, or still queuing, get an error rather than hanging.
+            let _stop = StopOnExit(&thread_queue);
+            write_jobs(file, &thread_queue, &thread_published)
+        });
+        Self {
+            producer: TdmsProducer { queue },
+            published,
+            thread,
+        }
+    }
+
+    /// Create a handle for a producer thread to write with.
+    pub fn producer(&self) -> TdmsProducer<F> {
+        self.producer.clone()
+    }
+
+    /// The current queue length and dropped write counters.
+    pub fn metrics(&self) -> WriterMetrics {
+        self.producer.metrics()
+    }
+
+    /// Create a view of the index for reading the file while it is written.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::fs::File;
+    /// use tedium::{ChannelPath, DataLayout, SharedTdmsWriter, TdmsFile};
+    ///
+    /// let path = std::env::temp_dir().join(format!("view_doc_{}.tdms", std::process::id()));
+    /// let shared = SharedTdmsWriter::new(TdmsFile::create(&path).unwrap());
+    /// let view = shared.index_view();
+    ///
+    /// let channel = ChannelPath::new("log", "value");
+    /// let producer = shared.producer();
+    /// producer.write_channels(std::slice::from_ref(&channel), vec![1.0, 2.0], DataLayout::Contigious).unwrap();
+    /// drop(producer);
+    /// let file = shared.finish().unwrap();
+    ///
+    /// // A display thread reads with its own handle.
+    /// let mut output = [0.0; 2];
+    /// let mut reader = File::open(&path).unwrap();
+    /// view.current().read_range(&mut reader, &channel, 0, &mut output).unwrap();
+    /// assert_eq!(output, [1.0, 2.0]);
+    /// # drop(file);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn index_view(&self) -> IndexView {
+        self.published.enabled.store(true, Ordering::Release);
+        IndexView {
+            published: self.published.clone(),
+        }
+    }
+
+    /// Wait for every producer to be dropped and all queued writes to finish.
+    ///
+    /// Returns the file, or the first error from the writer thread. The writer
+    /// stops at the first error and later writes from producers fail.
+    pub fn finish(self) -> Result<TdmsFile<F>, TdmsError> {
+        drop(self.producer);
+        self.thread
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    }
+}
+
+/// Queues writes to a [`SharedTdmsWriter`] from one producer thread.
+///
+/// Writes from the same producer are written in the order they are made.
+pub struct TdmsProducer<F: Write> {
+    queue: Arc<Queue<F>>,
+}
+
+impl<F: Write> Clone for TdmsProducer<F> {
+    fn clone(&self) -> Self {
+        self.queue.lock().producers += 1;
+        Self {
+            queue: self.queue.clone(),
+        }
+    }
+}
+
+impl<F: Write> Drop for TdmsProducer<F> {
+    fn drop(&mut self) {
+        self.queue.lock().producers -= 1;
+        self.queue.not_empty.notify_one();
+    }
+}
+
+impl<F: Write + 'static> TdmsProducer<F> {
+    /// Queue data for the channels. See [`TdmsFileWriter::write_channels`] for the layout.
+    ///
+    /// If the queue is full this follows the [`OverflowPolicy`] of the writer.
+    pub fn write_channels<D: TdmsStorageType + Send + 'static>(
+        &self,
+        channels: &[impl AsRef<ChannelPath>],
+        values: Vec<D>,
+        layout: DataLayout,
+    ) -> Result<(), TdmsError> {
+        let channels: Vec<ChannelPath> = channels
+            .iter()
+            .map(|channel| channel.as_ref().clone())
+            .collect();
+        let samples = values.len();
+        self.queue.push(QueuedJob {
+            job: Box::new(move |writer| writer.write_channels(&channels, &values, layout)),
+            samples: Some(samples),
+        })
+    }
+
+    /// Queue properties for an object. See [`TdmsFileWriter::write_properties`].
+    pub fn write_properties(
+        &self,
+        path: &PropertyPath,
+        properties: &[(&str, PropertyValue)],
+    ) -> Result<(), TdmsError> {
+        let path = path.clone();
+        let properties: Vec<(String, PropertyValue)> = properties
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+        self.queue.push(QueuedJob {
+            job: Box::new(move |writer| {
+                let properties: Vec<(&str, PropertyValue)> = properties
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.clone()))
+                    .collect();
+                writer.write_properties(&path, &properties)
+            }),
+            samples: None,
+        })
+    }
+
+    /// The current queue length and dropped write counters.
+    pub fn metrics(&self) -> WriterMetrics {
+        self.queue.metrics()
+    }
+}
+
+/// Stops the queue when dropped, including while a job unwinds from a panic.
+struct StopOnExit<'q, F: Write>(&'q Queue<F>);
+
+impl<F: Write> Drop for StopOnExit<'_, F> {
+    fn drop(&mut self) {
+        self.0.stop();
+    }
+}
+
+/// Run the queued writes until every producer is dropped.
+fn write_jobs<F: Read + Write + Seek>(
+    mut file: TdmsFile<F>,
+    queue: &Queue<F>,
+    published: &Published,
+) -> Result<TdmsFile<F>, TdmsError> {
+    while let Some(jobs) = queue.take_all() {
+        let mut writer = file.writer()?;
+        for queued in jobs {
+            (queued.job)(&mut writer)?;
+        }
+        writer.sync()?;
+        drop(writer);
+        published.publish(&mut file)?;
+    }
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_producers_write_their_own_groups() {
+        let file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+        let shared = SharedTdmsWriter::new(file);
+
+        let threads: Vec<_> = (0..4)
+            .map(|producer_index| {
+                let producer = shared.producer();
+                std::thread::spawn(move || {
+                    let group = format!("group_{producer_index}");
+                    producer
+                        .write_properties(
+                            &PropertyPath::group(&group),
+                            &[("producer", PropertyValue::I32(producer_index))],
+                        )
+                        .unwrap();
+                    let channels = [
+                        ChannelPath::new(&group, "first"),
+                        ChannelPath::new(&group, "second"),
+                    ];
+                    for block in 0..20 {
+                        let values: Vec<i32> = (0..10).map(|value| block * 10 + value).collect();
+                        producer
+                            .write_channels(&channels, values, DataLayout::Contigious)
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let mut file = shared.finish().unwrap();
+        for producer_index in 0..4 {
+            let group = format!("group_{producer_index}");
+            assert_eq!(
+                file.read_property(&PropertyPath::group(&group), "producer")
+                    .unwrap(),
+                Some(&PropertyValue::I32(producer_index))
+            );
+            let first: Vec<i32> = file.read_all(&ChannelPath::new(&group, "first")).unwrap();
+            let second: Vec<i32> = file.read_all(&ChannelPath::new(&group, "second")).unwrap();
+            let expected_first: Vec<i32> = (0..20)
+                .flat_map(|block| (0..5).map(move |value| block * 10 + value))
+                .collect();
+            let expected_second: Vec<i32> = expected_first.iter().map(|value| value + 5).collect();
+            assert_eq!(first, expected_first);
+            assert_eq!(second, expected_second);
+        }
+    }
+
+    #[test]
+    fn test_writer_errors_are_returned_by_finish() {
+        let file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+        let shared = SharedTdmsWriter::new(file);
+        let producer = shared.producer();
+        let channels = [
+            ChannelPath::new("group", "first"),
+            ChannelPath::new("group", "second"),
+        ];
+        // Three values can't be split across two channels.
+        producer
+            .write_channels(&channels, vec![1.0, 2.0, 3.0], DataLayout::Contigious)
+            .unwrap();
+        drop(producer);
+        assert!(shared.finish().is_err());
+    }
+
+    #[test]
+    fn test_panicking_job_stops_blocked_producers() {
+        let file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+        let shared = SharedTdmsWriter::with_queue(file, 1, OverflowPolicy::Block);
+        let producer = shared.producer();
+        // Stands in for user code, such as an observer, panicking on the writer thread.
+        producer
+            .queue
+            .push(QueuedJob {
+                job: Box::new(|_| panic!("job panicked")),
+                samples: None,
+            })
+            .unwrap();
+
+        // Once the queue is full these wait for space, so they would hang if
+        // the writer thread died without stopping the queue.
+        let channel = ChannelPath::new("group", "channel");
+        let failed = (0..100).any(|_| {
+            producer
+                .write_channels(&[&channel], vec![1.0], DataLayout::Contigious)
+                .is_err()
+        });
+        assert!(failed);
+
+        drop(producer);
+        let finished =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || shared.finish()));
+        assert!(finished.is_err());
+    }
+
+    /// A file whose writes wait until the gate is opened.
+    #[derive(Debug)]
+    struct GatedFile {
+        inner: std::io::Cursor<Vec<u8>>,
+        gate: Arc<(Mutex<bool>, Condvar)>,
+    }
+
+    impl Read for GatedFile {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.inner.read(buf)
+        }
+    }
+
+    impl Seek for GatedFile {
+        fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    impl Write for GatedFile {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let (open, opened) = &*self.gate;
+            let mut open = open.lock().unwrap();
+            while !*open {
+                open = opened.wait(open).unwrap();
+            }
+            self.inner.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.inner.flush()
+        }
+    }
+
+    #[test]
+    fn test_overflow_policies_drop_data() {
+        let cases = [
+            (OverflowPolicy::DropOldest, vec![0.0, 2.0, 3.0]),
+            (OverflowPolicy::DropNewest, vec![0.0, 1.0, 2.0]),
+        ];
+        for (policy, expected) in cases {
+            let gate = Arc::new((Mutex::new(false), Condvar::new()));
+            let file = TdmsFile::new(GatedFile {
+                inner: std::io::Cursor::new(vec![]),
+                gate: gate.clone(),
+            })
+            .unwrap();
+            let shared = SharedTdmsWriter::with_queue(file, 2, policy);
+            let producer = shared.producer();
+            let channel = ChannelPath::new("group", "channel");
+
+            // The first write is taken by the writer thread, which then waits on the gate.
+            producer
+                .write_channels(&[&channel], vec![0.0], DataLayout::Contigious)
+                .unwrap();
+            while shared.metrics().queued_writes > 0 {
+                std::thread::yield_now();
+            }
+            for value in 1..4 {
+                producer
+                    .write_channels(&[&channel], vec![value as f64], DataLayout::Contigious)
+                    .unwrap();
+            }
+            assert_eq!(
+                producer.metrics(),
+                WriterMetrics {
+                    queued_writes: 2,
+                    dropped_writes: 1,
+                    dropped_samples: 1,
+                }
+            );
+
+            let (open, opened) = &*gate;
+            *open.lock().unwrap() = true;
+            opened.notify_all();
+            drop(producer);
+            let mut file = shared.finish().unwrap();
+            let data: Vec<f64> = file.read_all(&channel).unwrap();
+            assert_eq!(data, expected, "{policy:?}");
+        }
+    }
+
+    #[test]
+    fn test_publishing_takes_back_the_previous_generation() {
+        let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+        let published = Published {
+            epoch: RwLock::new(file.pin_epoch()),
+            enabled: AtomicBool::new(true),
+        };
+        let view = IndexView {
+            published: Arc::new(published),
+        };
+        let channel = ChannelPath::new("log", "value");
+
+        for batch in 1..=3u32 {
+            let before = Arc::as_ptr(&file.index);
+            let mut writer = file.writer().unwrap();
+            writer
+                .write_channels(&[&channel], &[batch], DataLayout::Contigious)
+                .unwrap();
+            writer.sync().unwrap();
+            drop(writer);
+            // Only the first write copies, as the view shares the initial index.
+            assert_eq!(Arc::as_ptr(&file.index) == before, batch > 1);
+            view.published.publish(&mut file).unwrap();
+
+            let current = view.current().into_index();
+            assert!(!Arc::ptr_eq(&current, &file.index));
+            assert_eq!(current.channel_length(&channel), Some(batch as u64));
+            assert_eq!(file.channel_length(&channel), Some(batch as u64));
+        }
+        let data: Vec<u32> = file.read_all(&channel).unwrap();
+        assert_eq!(data, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_index_view_reads_while_writing() {
+        let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("target")
+            .join(format!("shared_view_{}.tdms", std::process::id()));
+        let shared = SharedTdmsWriter::new(TdmsFile::create(&path).unwrap());
+        let view = shared.index_view();
+        let producer = shared.producer();
+        let channel = ChannelPath::new("log", "value");
+        let mut reader = std::fs::File::open(&path).unwrap();
+
+        let wait_for_length = |length: u64| {
+            let start = std::time::Instant::now();
+            loop {
+                let epoch = view.current();
+                if epoch.channel_length(&channel) == Some(length) {
+                    return epoch;
+                }
+                assert!(start.elapsed() < std::time::Duration::from_secs(10));
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+        };
+
+        producer
+            .write_channels(
+                std::slice::from_ref(&channel),
+                vec![1u32, 2, 3],
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        let first = wait_for_length(3);
+        producer
+            .write_channels(
+                std::slice::from_ref(&channel),
+                vec![4u32],
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        let second = wait_for_length(4);
+
+        let mut output = [0u32; 4];
+        let outcome = first
+            .read_range(&mut reader, &channel, 0, &mut output)
+            .unwrap();
+        assert_eq!(outcome.samples_read, 3);
+        second
+            .read_range(&mut reader, &channel, 0, &mut output)
+            .unwrap();
+        assert_eq!(output, [1, 2, 3, 4]);
+        assert!(second.generation() > first.generation());
+
+        drop(producer);
+        shared.finish().unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,250 @@
+//! Building files in memory without unbounded growth.
+//!
+//! A [`SpillBuffer`] keeps the file in memory until it passes a threshold and
+//! then moves it to a temporary file, so servers building files for download
+//! only hold small files in memory. [`TdmsFile::into_spill_output`] returns
+//! the bytes or the path of the temporary file at the end.
+
+use std::fs::File;
+use std::io::{Cursor, Read, Result as IoResult, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::os_path::os_path;
+use crate::error::TdmsError;
+use crate::TdmsFile;
+
+/// Numbers the temporary files created by this process.
+static SPILL_COUNT: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug)]
+enum Storage {
+    Memory(Cursor<Vec<u8>>),
+    Spilled { file: File, path: PathBuf },
+}
+
+/// A file held in memory until it is larger than a threshold, then in a temporary file.
+///
+/// The temporary file is deleted when the buffer is dropped unless it is
+/// taken with [`Self::into_output`].
+#[derive(Debug)]
+pub struct SpillBuffer {
+    storage: Storage,
+    threshold: u64,
+    directory: PathBuf,
+}
+
+/// The contents of a [`SpillBuffer`] once writing has finished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpillOutput {
+    /// The file never passed the threshold.
+    Bytes(Vec<u8>),
+    /// The path of the temporary file, which the caller now owns.
+    File(PathBuf),
+}
+
+impl SpillBuffer {
+    /// Spill to the system temporary directory past `threshold` bytes.
+    pub fn new(threshold: u64) -> Self {
+        Self::in_directory(threshold, &std::env::temp_dir())
+    }
+
+    /// Spill to a temporary file in `directory` past `threshold` bytes.
+    pub fn in_directory(threshold: u64, directory: &Path) -> Self {
+        Self {
+            storage: Storage::Memory(Cursor::new(Vec::new())),
+            threshold,
+            directory: directory.to_path_buf(),
+        }
+    }
+
+    /// True once the contents have moved to a temporary file.
+    pub fn is_spilled(&self) -> bool {
+        matches!(self.storage, Storage::Spilled { .. })
+    }
+
+    /// Finish writing and return the bytes or the temporary file.
+    pub fn into_output(mut self) -> IoResult<SpillOutput> {
+        let storage =
+            std::mem::replace(&mut self.storage, Storage::Memory(Cursor::new(Vec::new())));
+        match storage {
+            Storage::Memory(cursor) => Ok(SpillOutput::Bytes(cursor.into_inner())),
+            Storage::Spilled { mut file, path } => {
+                file.flush()?;
+                Ok(SpillOutput::File(path))
+            }
+        }
+    }
+
+    /// Move the contents to a new temporary file, keeping the position.
+    fn spill(&mut self) -> IoResult<()> {
+        let Storage::Memory(cursor) = &self.storage else {
+            return Ok(());
+        };
+        let path = self.directory.join(format!(
+            "tedium-spill-{}-{}.tdms",
+            std::process::id(),
+            SPILL_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut file = File::options()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(os_path(&path))?;
+        file.write_all(cursor.get_ref())?;
+        file.seek(SeekFrom::Start(cursor.position()))?;
+        self.storage = Storage::Spilled { file, path };
+        Ok(())
+    }
+}
+
+impl Read for SpillBuffer {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        match &mut self.storage {
+            Storage::Memory(cursor) => cursor.read(buf),
+            Storage::Spilled { file, .. } => file.read(buf),
+        }
+    }
+}
+
+impl Write for SpillBuffer {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        if let Storage::Memory(cursor) = &self.storage {
+            let end = cursor.position() + buf.len() as u64;
+            if end > self.threshold {
+                self.spill()?;
+            }
+        }
+        match &mut self.storage {
+            Storage::Memory(cursor) => cursor.write(buf),
+            Storage::Spilled { file, .. } => file.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        match &mut self.storage {
+            Storage::Memory(_) => Ok(()),
+            Storage::Spilled { file, .. } => file.flush(),
+        }
+    }
+}
+
+impl Seek for SpillBuffer {
+    fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+        match &mut self.storage {
+            Storage::Memory(cursor) => cursor.seek(pos),
+            Storage::Spilled { file, .. } => file.seek(pos),
+        }
+    }
+}
+
+impl Drop for SpillBuffer {
+    fn drop(&mut self) {
+        if let Storage::Spilled { path, .. } = &self.storage {
+            let _ = std::fs::remove_file(os_path(path));
+        }
+    }
+}
+
+impl TdmsFile<SpillBuffer> {
+    /// Create a file in memory which moves to a temporary file past `threshold` bytes.
+    ///
+    /// # Example
+    /// ```rust
+    /// use tedium::{ChannelPath, DataLayout, SpillOutput, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::create_spilling(1024).unwrap();
+    /// let mut writer = file.writer().unwrap();
+    /// writer
+    ///     .write_channels(&[ChannelPath::new("group", "channel")], &[0.0; 1000], DataLayout::Contigious)
+    ///     .unwrap();
+    /// drop(writer);
+    ///
+    /// match file.into_spill_output().unwrap() {
+    ///     SpillOutput::Bytes(_) => unreachable!("8000 bytes of data is over the threshold"),
+    ///     SpillOutput::File(path) => std::fs::remove_file(path).unwrap(),
+    /// }
+    /// ```
+    pub fn create_spilling(threshold: u64) -> Result<Self, TdmsError> {
+        Self::new(SpillBuffer::new(threshold))
+    }
+
+    /// Finish the file and return its bytes or the path of its temporary file.
+    pub fn into_spill_output(mut self) -> Result<SpillOutput, TdmsError> {
+        self.file.flush()?;
+        Ok(self.file.into_output()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChannelPath, DataLayout};
+
+    fn temp_directory() -> PathBuf {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("target")
+            .join("spill_tests");
+        std::fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    fn write_file(threshold: u64, samples: usize) -> TdmsFile<SpillBuffer> {
+        let buffer = SpillBuffer::in_directory(threshold, &temp_directory());
+        let mut file = TdmsFile::new(buffer).unwrap();
+        let channel = ChannelPath::new("group", "channel");
+        let mut writer = file.writer().unwrap();
+        for block in 0..4 {
+            let data: Vec<u32> = (0..samples as u32).map(|value| value + block).collect();
+            writer
+                .write_channels(&[&channel], &data, DataLayout::Contigious)
+                .unwrap();
+        }
+        drop(writer);
+        file
+    }
+
+    #[test]
+    fn test_small_files_stay_in_memory() {
+        let mut file = write_file(1 << 20, 10);
+        assert!(!file.file.is_spilled());
+        let data: Vec<u32> = file
+            .read_all(&ChannelPath::new("group", "channel"))
+            .unwrap();
+        assert_eq!(data.len(), 40);
+        assert!(matches!(
+            file.into_spill_output().unwrap(),
+            SpillOutput::Bytes(_)
+        ));
+    }
+
+    #[test]
+    fn test_large_files_spill_and_match_memory() {
+        let in_memory = write_file(u64::MAX, 1000);
+        let SpillOutput::Bytes(expected) = in_memory.into_spill_output().unwrap() else {
+            panic!("expected the file in memory");
+        };
+
+        let spilled = write_file(5000, 1000);
+        assert!(spilled.file.is_spilled());
+        let channel = ChannelPath::new("group", "channel");
+        assert_eq!(spilled.channel_length(&channel), Some(4000));
+        let SpillOutput::File(path) = spilled.into_spill_output().unwrap() else {
+            panic!("expected a temporary file");
+        };
+        assert_eq!(std::fs::read(&path).unwrap(), expected);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_dropped_buffer_removes_temporary_file() {
+        let spilled = write_file(100, 100);
+        let Storage::Spilled { path, .. } = &spilled.file.storage else {
+            panic!("expected a temporary file");
+        };
+        let path = path.clone();
+        assert!(path.exists());
+        drop(spilled);
+        assert!(!path.exists());
+    }
+}
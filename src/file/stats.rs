@@ -0,0 +1,187 @@
+//! Statistics over fixed size windows of a channel.
+//!
+//! [`TdmsFile::windowed_stats`] reads a numeric channel in a single pass and
+//! produces the minimum, maximum, mean and RMS of each window, so long
+//! channels can be summarised without holding them in memory.
+
+use std::fmt::Debug;
+use std::io::{Read, Seek, Write};
+
+use crate::error::TdmsError;
+use crate::paths::ChannelPath;
+use crate::TdmsFile;
+
+use super::time_sync::read_range_as_f64;
+
+/// The most samples read from the file at a time.
+const CHUNK_SAMPLES: u64 = 64 * 1024;
+
+/// The statistics of one window of samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowStats {
+    /// The index of the first sample in the window.
+    pub start: u64,
+    /// The number of samples in the window. Only the last window can be short.
+    pub samples: u64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// The root mean square of the samples.
+    pub rms: f64,
+}
+
+/// An iterator over the statistics of consecutive windows of a channel.
+///
+/// Created by [`TdmsFile::windowed_stats`].
+pub struct WindowedStats<'f, F: Read + Seek + Write + Debug> {
+    file: &'f mut TdmsFile<F>,
+    channel: ChannelPath,
+    window_samples: u64,
+    length: u64,
+    next_sample: u64,
+    buffer: Vec<f64>,
+    failed: bool,
+}
+
+impl<F: Read + Seek + Write + Debug> WindowedStats<'_, F> {
+    fn read_window(&mut self) -> Result<WindowStats, TdmsError> {
+        let start = self.next_sample;
+        let end = (start + self.window_samples).min(self.length);
+        let mut stats = WindowStats {
+            start,
+            samples: end - start,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            mean: 0.0,
+            rms: 0.0,
+        };
+        let mut sum = 0.0;
+        let mut sum_of_squares = 0.0;
+
+        let mut position = start;
+        while position < end {
+            let chunk = (end - position).min(CHUNK_SAMPLES);
+            self.buffer.clear();
+            self.buffer.resize(chunk as usize, f64::NAN);
+            read_range_as_f64(self.file, &self.channel, position, &mut self.buffer)?;
+            for &value in &self.buffer {
+                stats.min = stats.min.min(value);
+                stats.max = stats.max.max(value);
+                sum += value;
+                sum_of_squares += value * value;
+            }
+            position += chunk;
+        }
+
+        let count = stats.samples as f64;
+        stats.mean = sum / count;
+        stats.rms = (sum_of_squares / count).sqrt();
+        self.next_sample = end;
+        Ok(stats)
+    }
+}
+
+impl<F: Read + Seek + Write + Debug> Iterator for WindowedStats<'_, F> {
+    type Item = Result<WindowStats, TdmsError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.next_sample >= self.length {
+            return None;
+        }
+        let stats = self.read_window();
+        self.failed = stats.is_err();
+        Some(stats)
+    }
+}
+
+impl<F: Read + Seek + Write + Debug> TdmsFile<F> {
+    /// Iterate over the min, max, mean and RMS of each `window_samples` samples of the channel.
+    ///
+    /// The last window holds whatever samples remain. A window size of 0 is
+    /// treated as 1. Windows of channels which aren't numeric give
+    /// [`TdmsError::UnsupportedType`].
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[1.0, -1.0, 3.0, 4.0, 10.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let windows: Vec<_> = file
+    ///     .windowed_stats(&channel, 2)
+    ///     .unwrap()
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    /// assert_eq!(windows.len(), 3);
+    /// assert_eq!((windows[0].min, windows[0].max, windows[0].mean), (-1.0, 1.0, 0.0));
+    /// assert_eq!(windows[1].rms, 12.5f64.sqrt());
+    /// assert_eq!((windows[2].start, windows[2].samples), (4, 1));
+    /// ```
+    pub fn windowed_stats(
+        &mut self,
+        channel: &ChannelPath,
+        window_samples: u64,
+    ) -> Result<WindowedStats<'_, F>, TdmsError> {
+        let length = self
+            .channel_length(channel)
+            .ok_or_else(|| self.index.missing_channel(channel))?;
+        Ok(WindowedStats {
+            file: self,
+            channel: channel.clone(),
+            window_samples: window_samples.max(1),
+            length,
+            next_sample: 0,
+            buffer: Vec::new(),
+            failed: false,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::DataLayout;
+
+    #[test]
+    fn test_windows_span_blocks_and_match_direct_calculation() {
+        let channel = ChannelPath::new("group", "channel");
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = file.writer().unwrap();
+        for block in 0..3 {
+            let data: Vec<i16> = (0..7).map(|sample| block * 7 + sample - 10).collect();
+            writer
+                .write_channels(&[&channel], &data, DataLayout::Contigious)
+                .unwrap();
+        }
+        drop(writer);
+
+        let all: Vec<f64> = (-10..11).map(f64::from).collect();
+        let windows: Vec<WindowStats> = file
+            .windowed_stats(&channel, 5)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(windows.len(), 5);
+        for (window, expected) in windows.iter().zip(all.chunks(5)) {
+            let count = expected.len() as f64;
+            assert_eq!(window.samples, expected.len() as u64);
+            assert_eq!(window.min, expected[0]);
+            assert_eq!(window.max, expected[expected.len() - 1]);
+            assert_eq!(window.mean, expected.iter().sum::<f64>() / count);
+            let rms = (expected.iter().map(|v| v * v).sum::<f64>() / count).sqrt();
+            assert_eq!(window.rms, rms);
+        }
+
+        assert!(matches!(
+            file.windowed_stats(&ChannelPath::new("group", "missing"), 5),
+            Err(TdmsError::MissingChannel { .. })
+        ));
+    }
+}
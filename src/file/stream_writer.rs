@@ -0,0 +1,73 @@
+//! Writing TDMS data to outputs which cannot seek, such as pipes and sockets.
+
+use std::io::Write;
+
+use crate::index::Index;
+use crate::io::writer::{LittleEndianWriter, TdmsWriter};
+
+use super::{TdmsFileWriter, WriteBuffer};
+
+/// Writes a TDMS stream to an output which only supports [`Write`].
+///
+/// Each segment is sized before it is written, so the `next_segment_offset` in the lead in
+/// is always known up front and nothing needs to be patched afterwards. This means the output
+/// never needs to seek and the stream can be written straight to a pipe or socket.
+///
+/// The writer keeps its own index of what has been written so channels that continue with
+/// the same layout still produce data only segments.
+///
+/// # Example
+/// ```rust
+/// use tedium::{ChannelPath, DataLayout, TdmsStreamWriter};
+///
+/// let mut stream = TdmsStreamWriter::new(std::io::sink());
+/// let mut writer = stream.writer();
+/// writer.write_channels(
+///     &[ChannelPath::new("group", "channel")],
+///     &[1.0, 2.0, 3.0],
+///     DataLayout::Contigious,
+/// ).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct TdmsStreamWriter<W: Write> {
+    index: Index,
+    output: W,
+}
+
+impl<W: Write> TdmsStreamWriter<W> {
+    /// Start a new TDMS stream on the output.
+    pub fn new(output: W) -> Self {
+        Self {
+            index: Index::new(),
+            output,
+        }
+    }
+
+    /// Get a writer to add data and properties to the stream.
+    ///
+    /// Data is buffered by the writer and is flushed to the output when it is dropped.
+    pub fn writer(&mut self) -> TdmsFileWriter<'_, W, LittleEndianWriter<&mut W>> {
+        TdmsFileWriter::new(
+            &mut self.index,
+            LittleEndianWriter::from_writer(&mut self.output),
+        )
+    }
+
+    /// Get a writer using the given buffer size and flush policy.
+    pub fn writer_with_buffer(
+        &mut self,
+        buffer: WriteBuffer,
+    ) -> TdmsFileWriter<'_, W, LittleEndianWriter<&mut W>> {
+        let mut writer = TdmsFileWriter::new(
+            &mut self.index,
+            LittleEndianWriter::with_capacity(buffer.capacity, &mut self.output),
+        );
+        writer.set_flush_policy(buffer.flush);
+        writer
+    }
+
+    /// Finish the stream and return the output.
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+}
@@ -0,0 +1,294 @@
+//! Validation of segments against the TDMS specification before they are written.
+//!
+//! The normal writer trusts its inputs and relies on readers being tolerant,
+//! as NI's own readers are. Some third-party tools are not, so
+//! [`crate::TdmsFileWriter::set_strict`] checks each segment first and fails
+//! with [`TdmsError::SpecViolation`] before anything reaches the file.
+
+use std::collections::BTreeSet;
+
+use crate::error::TdmsError;
+use crate::io::data_types::DataType;
+use crate::meta_data::{MetaData, ObjectMetaData, RawDataIndex, RawDataMeta, ToC};
+
+/// Check the segment that is about to be written.
+///
+/// `data_bytes` is the size of the raw data, if the segment has any.
+pub(super) fn validate_segment(
+    toc: &ToC,
+    meta: Option<&MetaData>,
+    data_bytes: Option<usize>,
+) -> Result<(), TdmsError> {
+    let objects = meta.map_or(&[][..], |meta| &meta.objects[..]);
+
+    let mut seen = BTreeSet::new();
+    for object in objects {
+        if !seen.insert(object.path.as_str()) {
+            return Err(violation(
+                object,
+                "the object is listed twice in the segment",
+            ));
+        }
+        validate_path(object)?;
+        validate_properties(object)?;
+        if let RawDataIndex::RawData(raw) = &object.raw_data_index {
+            validate_raw_data_index(object, raw)?;
+        }
+    }
+
+    if toc.contains_new_object_list {
+        if let Some(data_bytes) = data_bytes {
+            validate_chunk_size(objects, data_bytes as u64)?;
+        }
+    }
+    Ok(())
+}
+
+fn violation(object: &ObjectMetaData, reason: impl Into<String>) -> TdmsError {
+    TdmsError::SpecViolation {
+        path: object.path.clone(),
+        reason: reason.into(),
+    }
+}
+
+/// Paths must be `/`, `/'group'` or `/'group'/'channel'` with single quotes in names doubled.
+///
+/// Names may contain `/`, so this walks the quotes rather than splitting on it.
+fn validate_path(object: &ObjectMetaData) -> Result<(), TdmsError> {
+    let malformed = || violation(object, "the path is not of the form /'group'/'channel'");
+    if object.path == "/" {
+        return Ok(());
+    }
+
+    let mut chars = object.path.chars().peekable();
+    let mut names = 0;
+    while let Some(separator) = chars.next() {
+        if separator != '/' || chars.next() != Some('\'') {
+            return Err(malformed());
+        }
+        loop {
+            match chars.next() {
+                Some('\'') if chars.peek() == Some(&'\'') => {
+                    chars.next();
+                }
+                Some('\'') => break,
+                Some(_) => {}
+                None => return Err(malformed()),
+            }
+        }
+        if chars.peek().is_some_and(|next| *next != '/') {
+            return Err(violation(
+                object,
+                "a name has a single quote which is not escaped as ''",
+            ));
+        }
+        names += 1;
+    }
+    if names == 0 || names > 2 {
+        return Err(malformed());
+    }
+    Ok(())
+}
+
+fn validate_properties(object: &ObjectMetaData) -> Result<(), TdmsError> {
+    let mut names = BTreeSet::new();
+    for (name, _) in &object.properties {
+        if name.is_empty() {
+            return Err(violation(object, "a property has an empty name"));
+        }
+        if !names.insert(name.as_str()) {
+            return Err(violation(
+                object,
+                format!("the property {name} is written twice in the segment"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_raw_data_index(object: &ObjectMetaData, raw: &RawDataMeta) -> Result<(), TdmsError> {
+    match (raw.data_type, raw.total_size_bytes) {
+        (DataType::Void | DataType::DAQmxRawData, _) => Err(violation(
+            object,
+            format!("{} is not a valid type code for raw data", raw.data_type),
+        )),
+        (DataType::TdmsString, None) => Err(violation(
+            object,
+            "the string raw data index has no total size",
+        )),
+        (DataType::TdmsString, Some(total)) if total < raw.number_of_values * 4 => {
+            Err(violation(
+                object,
+                format!(
+                    "the total size of {total} bytes can't hold the offsets of {} strings",
+                    raw.number_of_values
+                ),
+            ))
+        }
+        (DataType::TdmsString, Some(_)) => Ok(()),
+        (data_type, Some(_)) => Err(violation(
+            object,
+            format!("the raw data index for {data_type} data has a total size, which is only for strings"),
+        )),
+        (_, None) => Ok(()),
+    }
+}
+
+/// The raw data must be a whole number of chunks of the channels listed.
+fn validate_chunk_size(objects: &[ObjectMetaData], data_bytes: u64) -> Result<(), TdmsError> {
+    let mut chunk_bytes = 0;
+    let mut last = None;
+    for object in objects {
+        match &object.raw_data_index {
+            RawDataIndex::RawData(raw) => {
+                chunk_bytes += match raw.total_size_bytes {
+                    Some(total) => total,
+                    None => raw.number_of_values * raw.data_type.size() as u64,
+                };
+                last = Some(object);
+            }
+            RawDataIndex::None => {}
+            _ => return Ok(()),
+        }
+    }
+
+    let Some(last) = last else {
+        return Err(TdmsError::SpecViolation {
+            path: "/".to_owned(),
+            reason: "the segment has raw data but no channels in its new object list".to_owned(),
+        });
+    };
+    if !data_bytes.is_multiple_of(chunk_bytes) {
+        return Err(violation(
+            last,
+            format!(
+                "{data_bytes} bytes of raw data is not a whole number of {chunk_bytes} byte chunks"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PropertyValue;
+
+    fn object(path: &str, raw_data_index: RawDataIndex) -> ObjectMetaData {
+        ObjectMetaData {
+            path: path.to_owned(),
+            properties: vec![],
+            raw_data_index,
+        }
+    }
+
+    fn raw(data_type: DataType, number_of_values: u64, total: Option<u64>) -> RawDataIndex {
+        RawDataIndex::RawData(RawDataMeta {
+            data_type,
+            number_of_values,
+            total_size_bytes: total,
+        })
+    }
+
+    fn check(objects: Vec<ObjectMetaData>, data_bytes: Option<usize>) -> Result<(), TdmsError> {
+        let toc = ToC {
+            contains_new_object_list: true,
+            ..Default::default()
+        };
+        validate_segment(&toc, Some(&MetaData { objects }), data_bytes)
+    }
+
+    #[test]
+    fn test_valid_segment_passes() {
+        let objects = vec![
+            object("/'group'", RawDataIndex::None),
+            object("/'group'/'it''s'", raw(DataType::DoubleFloat, 2, None)),
+            object("/'group'/'names'", raw(DataType::TdmsString, 1, Some(9))),
+        ];
+        check(objects, Some(50)).unwrap();
+    }
+
+    #[test]
+    fn test_paths() {
+        for path in ["/", "/'group'", "/'a/b'/'c''d'", "/''/''"] {
+            check(vec![object(path, RawDataIndex::None)], None).unwrap();
+        }
+        for path in ["", "//", "/group", "/'group", "/'a'/'b'/'c'", "/'a'b'"] {
+            let result = check(vec![object(path, RawDataIndex::None)], None);
+            assert!(result.is_err(), "{path}");
+        }
+    }
+
+    #[test]
+    fn test_unescaped_quote_is_rejected() {
+        let error = check(vec![object("/'it's'", RawDataIndex::None)], None).unwrap_err();
+        assert_eq!(error.path(), Some("/'it's'"));
+        assert!(error.to_string().contains("not escaped"));
+    }
+
+    #[test]
+    fn test_duplicate_property_is_rejected() {
+        let mut group = object("/'group'", RawDataIndex::None);
+        group.properties = vec![
+            ("name".into(), PropertyValue::I32(1)),
+            ("name".into(), PropertyValue::I32(2)),
+        ];
+        let error = check(vec![group], None).unwrap_err();
+        assert!(error.to_string().contains("property name is written twice"));
+    }
+
+    #[test]
+    fn test_invalid_raw_data_indexes_are_rejected() {
+        for index in [
+            raw(DataType::Void, 1, None),
+            raw(DataType::TdmsString, 1, None),
+            raw(DataType::TdmsString, 2, Some(4)),
+            raw(DataType::I32, 1, Some(4)),
+        ] {
+            let result = check(vec![object("/'group'/'channel'", index)], None);
+            assert!(matches!(result, Err(TdmsError::SpecViolation { .. })));
+        }
+    }
+
+    #[test]
+    fn test_partial_chunk_is_rejected() {
+        let objects = vec![object("/'group'/'channel'", raw(DataType::I32, 2, None))];
+        check(objects.clone(), Some(16)).unwrap();
+        let error = check(objects, Some(12)).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("not a whole number of 8 byte chunks"));
+    }
+
+    #[test]
+    fn test_strict_writer_accepts_its_own_segments() {
+        use crate::{ChannelPath, DataLayout, PropertyPath, TdmsFile};
+
+        let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+        let channels = [
+            ChannelPath::new("group", "it's"),
+            ChannelPath::new("a/b", "c"),
+        ];
+        let mut writer = file.writer().unwrap();
+        writer.set_strict(true);
+        writer
+            .write_properties(&PropertyPath::group("group"), &[("name", "x".into())])
+            .unwrap();
+        writer
+            .write_channels(&channels, &[1.0, 2.0, 3.0, 4.0], DataLayout::Interleaved)
+            .unwrap();
+        writer
+            .write_channels(&channels, &[5.0, 6.0], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_strings(&ChannelPath::new("log", "message"), &["started"])
+            .unwrap();
+        writer.finalize().unwrap();
+        drop(writer);
+
+        assert_eq!(
+            file.read_all::<f64>(&channels[1]).unwrap(),
+            vec![2.0, 4.0, 6.0]
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Summaries of every channel in a group.
+//!
+//! [`TdmsFile::summarize`] collects what a test report usually lists for each
+//! channel: the type, length, range, mean, unit and properties. Statistics are
+//! calculated in a single streamed pass so long channels aren't held in
+//! memory. With the `serde` feature the summary can be serialized straight
+//! into a report template.
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::io::{Read, Seek, Write};
+
+use crate::error::TdmsError;
+use crate::paths::{parse_path, ChannelPath, GroupPath};
+use crate::{PropertyValue, TdmsFile};
+
+use super::time_sync::read_range_as_f64;
+
+/// The most samples read from the file at a time.
+const CHUNK_SAMPLES: u64 = 64 * 1024;
+
+/// The summary of a group produced by [`TdmsFile::summarize`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GroupSummary {
+    pub name: String,
+    /// The properties of the group formatted as text.
+    pub properties: BTreeMap<String, String>,
+    /// The channels in path order.
+    pub channels: Vec<ChannelSummary>,
+}
+
+/// The summary of one channel in a [`GroupSummary`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelSummary {
+    pub name: String,
+    /// The name of the data type, or None if the channel has never had data.
+    pub data_type: Option<String>,
+    pub length: u64,
+    /// The statistics are None for empty or non-numeric channels.
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub mean: Option<f64>,
+    /// The `unit_string` property.
+    pub unit: Option<String>,
+    /// The other properties of the channel formatted as text.
+    pub properties: BTreeMap<String, String>,
+}
+
+/// The properties of an object as text, leaving out the unit.
+fn text_properties(properties: Option<Vec<(&String, &PropertyValue)>>) -> BTreeMap<String, String> {
+    properties
+        .into_iter()
+        .flatten()
+        .filter(|(name, _)| *name != "unit_string")
+        .map(|(name, value)| (name.clone(), value.to_string()))
+        .collect()
+}
+
+impl<F: Read + Seek + Write + Debug> TdmsFile<F> {
+    /// Summarize every channel in the group.
+    ///
+    /// Errors with [`TdmsError::MissingGroup`] if the group has no channels
+    /// and no properties.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, GroupPath, PropertyValue, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("run 1", "pressure");
+    /// let mut writer = file.writer().unwrap();
+    /// writer
+    ///     .write_properties(channel.as_ref(), &[("unit_string", PropertyValue::from("bar"))])
+    ///     .unwrap();
+    /// writer.write_channels(&[&channel], &[1.0, 2.0, 6.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let summary = file.summarize(&GroupPath::new("run 1")).unwrap();
+    /// let pressure = &summary.channels[0];
+    /// assert_eq!(pressure.name, "pressure");
+    /// assert_eq!(pressure.unit.as_deref(), Some("bar"));
+    /// assert_eq!((pressure.min, pressure.max, pressure.mean), (Some(1.0), Some(6.0), Some(3.0)));
+    /// ```
+    pub fn summarize(&mut self, group: &GroupPath) -> Result<GroupSummary, TdmsError> {
+        let channels: Vec<ChannelPath> = self.list_channels_in_group(group).collect();
+        let group_properties = self.read_all_properties(group.as_ref());
+        if channels.is_empty() && group_properties.is_none() {
+            return Err(self.index.missing_group(group));
+        }
+
+        let mut summary = GroupSummary {
+            name: parse_path(group.path())?.0.unwrap_or_default().to_owned(),
+            properties: text_properties(group_properties),
+            channels: Vec::with_capacity(channels.len()),
+        };
+        for channel in &channels {
+            summary.channels.push(self.summarize_channel(channel)?);
+        }
+        Ok(summary)
+    }
+
+    fn summarize_channel(&mut self, channel: &ChannelPath) -> Result<ChannelSummary, TdmsError> {
+        let data_type = self.index.channel_data_type(channel);
+        let length = self.channel_length(channel).unwrap_or(0);
+        let unit = self
+            .read_property(channel.as_ref(), "unit_string")?
+            .and_then(PropertyValue::as_str)
+            .map(str::to_owned);
+
+        let mut summary = ChannelSummary {
+            name: parse_path(channel.path())?.1.unwrap_or_default().to_owned(),
+            data_type: data_type.map(|data_type| data_type.to_string()),
+            length,
+            min: None,
+            max: None,
+            mean: None,
+            unit,
+            properties: text_properties(self.read_all_properties(channel.as_ref())),
+        };
+
+        let mut min = f64::INFINITY;
+        let mut max = f64::NEG_INFINITY;
+        let mut sum = 0.0;
+        let mut buffer = Vec::new();
+        let mut position = 0;
+        while position < length {
+            let chunk = (length - position).min(CHUNK_SAMPLES);
+            buffer.clear();
+            buffer.resize(chunk as usize, f64::NAN);
+            match read_range_as_f64(self, channel, position, &mut buffer) {
+                Ok(()) => {}
+                Err(TdmsError::UnsupportedType(_)) => return Ok(summary),
+                Err(error) => return Err(error),
+            }
+            for &value in &buffer {
+                min = min.min(value);
+                max = max.max(value);
+                sum += value;
+            }
+            position += chunk;
+        }
+
+        if length > 0 {
+            summary.min = Some(min);
+            summary.max = Some(max);
+            summary.mean = Some(sum / length as f64);
+        }
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "write")]
+    use crate::DataLayout;
+
+    #[cfg(feature = "write")]
+    #[test]
+    fn test_summary_of_mixed_channels() {
+        let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+        let group = GroupPath::new("group");
+        let counts = group.channel("counts");
+        let empty = group.channel("empty");
+        let names = group.channel("names");
+
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_properties(group.as_ref(), &[("operator", PropertyValue::from("A"))])
+            .unwrap();
+        writer
+            .write_channels(&[&counts], &[3i32, -1, 4], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .create_channel(&empty, crate::types::DataType::DoubleFloat, &[])
+            .unwrap();
+        writer.write_strings(&names, &["a", "b"]).unwrap();
+        drop(writer);
+
+        let summary = file.summarize(&group).unwrap();
+        assert_eq!(summary.name, "group");
+        assert_eq!(summary.properties["operator"], "A");
+
+        let channels: BTreeMap<_, _> = summary
+            .channels
+            .iter()
+            .map(|channel| (channel.name.as_str(), channel))
+            .collect();
+        let counts = channels["counts"];
+        assert_eq!(counts.length, 3);
+        assert_eq!((counts.min, counts.max), (Some(-1.0), Some(4.0)));
+        assert_eq!(counts.mean, Some(2.0));
+        assert_eq!(channels["empty"].length, 0);
+        assert_eq!(channels["empty"].mean, None);
+        assert_eq!(channels["names"].length, 2);
+        assert_eq!(channels["names"].min, None);
+    }
+
+    #[test]
+    fn test_missing_group_errors() {
+        let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+        let result = file.summarize(&GroupPath::new("missing"));
+        assert!(matches!(result, Err(TdmsError::MissingGroup { .. })));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_summary_serializes() {
+        let summary = GroupSummary {
+            name: "group".into(),
+            properties: BTreeMap::new(),
+            channels: vec![],
+        };
+        let json = serde_json::to_string(&summary).unwrap();
+        assert_eq!(json, r#"{"name":"group","properties":{},"channels":[]}"#);
+    }
+}
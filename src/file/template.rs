@@ -0,0 +1,285 @@
+//! Standard file structures for test labs.
+//!
+//! A [`FileTemplate`] lists the groups, channels, data types and properties
+//! every file of a kind must have. [`TdmsFileWriter::apply_template`] creates
+//! the structure in a new file and [`TdmsFile::validate_against_template`]
+//! reports how an existing file differs from it.
+
+use std::collections::BTreeMap;
+#[cfg(feature = "write")]
+use std::io::Write;
+use std::io::{Read, Seek};
+
+#[cfg(feature = "write")]
+use crate::error::TdmsError;
+use crate::io::data_types::DataType;
+#[cfg(feature = "write")]
+use crate::io::writer::TdmsWriter;
+use crate::paths::{path_group_name, ChannelPath, GroupPath, ObjectPath, PropertyPath};
+#[cfg(feature = "write")]
+use crate::TdmsFileWriter;
+use crate::{PropertyValue, TdmsFile};
+
+/// The groups, channels and properties a standard file must have.
+///
+/// # Example
+#[cfg_attr(feature = "write", doc = "```rust")]
+#[cfg_attr(not(feature = "write"), doc = "```ignore")]
+/// use tedium::types::DataType;
+/// use tedium::{ChannelPath, FileTemplate, PropertyPath, PropertyValue, TdmsFile};
+///
+/// let template = FileTemplate::new()
+///     .channel(&ChannelPath::new("rig", "force"), DataType::DoubleFloat)
+///     .property(&PropertyPath::file(), "operator", PropertyValue::from(""));
+///
+/// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+/// let mut writer = file.writer().unwrap();
+/// writer.apply_template(&template).unwrap();
+/// drop(writer);
+/// assert!(file.validate_against_template(&template).is_empty());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileTemplate {
+    /// The required objects by path, so they sort file, groups then channels.
+    objects: BTreeMap<String, TemplateObject>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TemplateObject {
+    data_type: Option<DataType>,
+    /// The required properties with the value written by the template.
+    properties: Vec<(String, PropertyValue)>,
+}
+
+/// A difference between a file and a [`FileTemplate`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateViolation {
+    /// The group is not in the file.
+    MissingGroup(GroupPath),
+    /// The channel is not in the file.
+    MissingChannel(ChannelPath),
+    /// The channel holds data of another type.
+    ChannelType {
+        channel: ChannelPath,
+        expected: DataType,
+        found: DataType,
+    },
+    /// The object doesn't have the property.
+    MissingProperty { object: ObjectPath, name: String },
+    /// The property has a value of another type.
+    PropertyType {
+        object: ObjectPath,
+        name: String,
+        expected: DataType,
+        found: DataType,
+    },
+}
+
+impl FileTemplate {
+    /// Start with an empty template.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn object(&mut self, path: &str) -> &mut TemplateObject {
+        self.objects.entry(path.to_string()).or_default()
+    }
+
+    /// Require the group.
+    pub fn group(mut self, group: &GroupPath) -> Self {
+        self.object(group.path());
+        self
+    }
+
+    /// Require the channel, and its group, with data of the type.
+    pub fn channel(mut self, channel: &ChannelPath, data_type: DataType) -> Self {
+        if let Some(group) = path_group_name(channel.path()) {
+            self.object(GroupPath::new(group).path());
+        }
+        self.object(channel.path()).data_type = Some(data_type);
+        self
+    }
+
+    /// Require the property on the object, which is also required.
+    ///
+    /// Files must have a value of the same type. [`TdmsFileWriter::apply_template`]
+    /// writes `value`, so use a placeholder to be filled in later.
+    pub fn property(mut self, path: &PropertyPath, name: &str, value: PropertyValue) -> Self {
+        let properties = &mut self.object(path.path()).properties;
+        properties.retain(|(existing, _)| existing != name);
+        properties.push((name.to_string(), value));
+        self
+    }
+}
+
+#[cfg(feature = "write")]
+impl<'a, F: Write, W: TdmsWriter<&'a mut F>> TdmsFileWriter<'a, F, W> {
+    /// Create every group and channel in the template with its properties.
+    ///
+    /// The channels have no data until it is written, and data types are only
+    /// checked against the template once they do.
+    pub fn apply_template(&mut self, template: &FileTemplate) -> Result<(), TdmsError> {
+        let objects: Vec<_> = template
+            .objects
+            .iter()
+            .map(|(path, object)| (path.clone(), object.properties.clone()))
+            .collect();
+        if objects.is_empty() {
+            return Ok(());
+        }
+        self.write_objects_properties(objects)
+    }
+}
+
+impl<F: Read + Seek> TdmsFile<F> {
+    /// Check the file has everything in the template.
+    ///
+    /// Returns every difference found, so an empty list means the file conforms.
+    /// Objects and properties not in the template are allowed.
+    pub fn validate_against_template(&self, template: &FileTemplate) -> Vec<TemplateViolation> {
+        let mut violations = Vec::new();
+        for (path, required) in &template.objects {
+            let Ok(object) = ObjectPath::parse(path) else {
+                continue;
+            };
+            let present = match &object {
+                ObjectPath::File => true,
+                ObjectPath::Group(group) => self.list_groups().any(|found| &found == group),
+                ObjectPath::Channel(channel) => self.index.channel_length(channel).is_some(),
+            };
+            if !present {
+                violations.push(match object {
+                    ObjectPath::Channel(channel) => TemplateViolation::MissingChannel(channel),
+                    ObjectPath::Group(group) => TemplateViolation::MissingGroup(group),
+                    ObjectPath::File => unreachable!("the file is always present"),
+                });
+                continue;
+            }
+
+            if let (ObjectPath::Channel(channel), Some(expected)) = (&object, required.data_type) {
+                match self.index.channel_data_type(channel) {
+                    Some(found) if found != expected => {
+                        violations.push(TemplateViolation::ChannelType {
+                            channel: channel.clone(),
+                            expected,
+                            found,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+
+            let property_path = match &object {
+                ObjectPath::File => PropertyPath::file(),
+                ObjectPath::Group(group) => AsRef::<PropertyPath>::as_ref(group).clone(),
+                ObjectPath::Channel(channel) => AsRef::<PropertyPath>::as_ref(channel).clone(),
+            };
+            for (name, value) in &required.properties {
+                let found = self
+                    .index
+                    .get_object_property(&property_path, name)
+                    .ok()
+                    .flatten();
+                match found {
+                    None => violations.push(TemplateViolation::MissingProperty {
+                        object: object.clone(),
+                        name: name.clone(),
+                    }),
+                    Some(found) if found.datatype() != value.datatype() => {
+                        violations.push(TemplateViolation::PropertyType {
+                            object: object.clone(),
+                            name: name.clone(),
+                            expected: value.datatype(),
+                            found: found.datatype(),
+                        })
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        violations
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::DataLayout;
+
+    fn template() -> FileTemplate {
+        FileTemplate::new()
+            .group(&GroupPath::new("notes"))
+            .channel(&ChannelPath::new("rig", "force"), DataType::DoubleFloat)
+            .property(
+                ChannelPath::new("rig", "force").as_ref(),
+                "unit_string",
+                PropertyValue::from("N"),
+            )
+            .property(&PropertyPath::file(), "serial", PropertyValue::U32(0))
+    }
+
+    #[test]
+    fn test_applied_template_conforms_after_data() {
+        let template = template();
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer.apply_template(&template).unwrap();
+        writer
+            .write_channels(
+                &[ChannelPath::new("rig", "force")],
+                &[1.0, 2.0],
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        drop(writer);
+
+        assert_eq!(file.validate_against_template(&template), vec![]);
+        assert_eq!(
+            file.read_property(&PropertyPath::file(), "serial").unwrap(),
+            Some(&PropertyValue::U32(0))
+        );
+        let reloaded = TdmsFile::new(file.file).unwrap();
+        assert_eq!(reloaded.validate_against_template(&template), vec![]);
+    }
+
+    #[test]
+    fn test_violations_are_reported() {
+        let force = ChannelPath::new("rig", "force");
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_properties(
+                &PropertyPath::file(),
+                &[("serial", PropertyValue::from("A1"))],
+            )
+            .unwrap();
+        writer
+            .write_channels(&[&force], &[1i32, 2], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+
+        assert_eq!(
+            file.validate_against_template(&template()),
+            vec![
+                TemplateViolation::PropertyType {
+                    object: ObjectPath::File,
+                    name: "serial".to_string(),
+                    expected: DataType::U32,
+                    found: DataType::TdmsString,
+                },
+                TemplateViolation::MissingGroup(GroupPath::new("notes")),
+                TemplateViolation::ChannelType {
+                    channel: force.clone(),
+                    expected: DataType::DoubleFloat,
+                    found: DataType::I32,
+                },
+                TemplateViolation::MissingProperty {
+                    object: ObjectPath::Channel(force),
+                    name: "unit_string".to_string(),
+                },
+            ]
+        );
+    }
+}
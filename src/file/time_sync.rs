@@ -0,0 +1,453 @@
+//! Iterating over several waveform channels aligned by time.
+//!
+//! The timing comes from the standard waveform properties `wf_start_time`,
+//! `wf_increment` and the optional `wf_start_offset`. The first channel acts
+//! as the reference clock and the other channels are sampled at its times.
+
+use std::fmt::Debug;
+use std::io::{Read, Seek, Write};
+
+use labview_interop::types::LVTime;
+
+use crate::error::TdmsError;
+use crate::io::data_types::DataType;
+use crate::paths::{ChannelPath, PropertyPath};
+use crate::{PropertyValue, TdmsFile};
+
+/// The most samples of each channel loaded from the file at a time.
+const CHUNK_SAMPLES: u64 = 4096;
+
+/// How to find the value of a channel at a time between its samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyncPolicy {
+    /// Use the sample closest in time.
+    #[default]
+    Nearest,
+    /// Linearly interpolate between the samples either side.
+    Linear,
+}
+
+/// The values of all channels at one time point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncedSample {
+    /// Seconds since the first sample of the reference channel.
+    pub time: f64,
+    /// The value of each channel in the order requested.
+    ///
+    /// This is NaN where a channel has no data at this time.
+    pub values: Vec<f64>,
+}
+
+#[derive(Debug)]
+struct ChannelTiming {
+    path: ChannelPath,
+    /// The time of the first sample relative to the reference channel.
+    start: f64,
+    increment: f64,
+    length: u64,
+    /// The first sample held in `buffer`.
+    buffer_start: u64,
+    buffer: Vec<f64>,
+}
+
+impl ChannelTiming {
+    fn sample(&self, position: u64) -> f64 {
+        position
+            .checked_sub(self.buffer_start)
+            .and_then(|offset| self.buffer.get(offset as usize))
+            .copied()
+            .unwrap_or(f64::NAN)
+    }
+
+    /// The fractional sample position of the time.
+    fn position(&self, time: f64) -> f64 {
+        let position = (time - self.start) / self.increment;
+        // Remove floating point noise so exact sample times don't interpolate.
+        let rounded = position.round();
+        if (position - rounded).abs() < 1e-9 {
+            rounded
+        } else {
+            position
+        }
+    }
+
+    /// Make sure the samples either side of `time` are in the buffer.
+    ///
+    /// The buffer is refilled from the sample before `time` to the sample after
+    /// `until`, the last time of the reference chunk, but never holds more than
+    /// [`CHUNK_SAMPLES`]. If a channel is so much faster than the reference that
+    /// the next reference time is beyond a full buffer, only the two samples
+    /// around `time` are read.
+    fn load_around<F: Read + Seek + Write + Debug>(
+        &mut self,
+        file: &mut TdmsFile<F>,
+        time: f64,
+        reference_increment: f64,
+        until: f64,
+    ) -> Result<(), TdmsError> {
+        let position = self.position(time);
+        if position < 0.0 || position > (self.length as f64 - 1.0) {
+            return Ok(());
+        }
+        let first = position.floor() as u64;
+        let needed = (first + 2).min(self.length);
+        let buffer_end = self.buffer_start + self.buffer.len() as u64;
+        if first >= self.buffer_start && needed <= buffer_end {
+            return Ok(());
+        }
+
+        let end = if reference_increment / self.increment >= CHUNK_SAMPLES as f64 {
+            needed
+        } else {
+            // Float to integer casts saturate so a far away `until` is safe.
+            let wanted = (self.position(until).ceil() + 2.0).max(0.0) as u64;
+            wanted
+                .max(needed)
+                .min(first + CHUNK_SAMPLES)
+                .min(self.length)
+        };
+        self.buffer_start = first;
+        self.buffer.clear();
+        self.buffer.resize((end - first) as usize, f64::NAN);
+        read_range_as_f64(file, &self.path, first, &mut self.buffer)
+    }
+
+    fn value_at(&self, time: f64, policy: SyncPolicy) -> f64 {
+        let position = self.position(time);
+        if position < 0.0 || position > (self.length as f64 - 1.0) {
+            return f64::NAN;
+        }
+        match policy {
+            SyncPolicy::Nearest => self.sample(position.round() as u64),
+            SyncPolicy::Linear => {
+                let before = position.floor();
+                let fraction = position - before;
+                let before_value = self.sample(before as u64);
+                if fraction == 0.0 {
+                    before_value
+                } else {
+                    let after_value = self.sample(before as u64 + 1);
+                    before_value + (after_value - before_value) * fraction
+                }
+            }
+        }
+    }
+}
+
+/// An iterator over several channels aligned to the samples of the first channel.
+///
+/// Data is read from the file in chunks of at most [`CHUNK_SAMPLES`] samples per
+/// channel so memory use is bounded regardless of the channel lengths and rates.
+///
+/// Created by [`TdmsFile::time_synced`].
+pub struct TimeSyncedIter<'f, F: Read + Seek + Write + Debug> {
+    file: &'f mut TdmsFile<F>,
+    channels: Vec<ChannelTiming>,
+    policy: SyncPolicy,
+    next_sample: u64,
+    chunk_end: u64,
+    failed: bool,
+}
+
+impl<F: Read + Seek + Write + Debug> TimeSyncedIter<'_, F> {
+    fn reference(&self) -> &ChannelTiming {
+        &self.channels[0]
+    }
+
+    /// Load the samples of every channel needed at `time`.
+    fn load(&mut self, time: f64) -> Result<(), TdmsError> {
+        if self.next_sample >= self.chunk_end {
+            self.chunk_end = (self.next_sample + CHUNK_SAMPLES).min(self.reference().length);
+        }
+        let increment = self.reference().increment;
+        let until = (self.chunk_end - 1) as f64 * increment;
+        for channel in self.channels.iter_mut() {
+            channel.load_around(self.file, time, increment, until)?;
+        }
+        Ok(())
+    }
+}
+
+impl<F: Read + Seek + Write + Debug> Iterator for TimeSyncedIter<'_, F> {
+    type Item = Result<SyncedSample, TdmsError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.channels.is_empty() || self.next_sample >= self.reference().length {
+            return None;
+        }
+
+        let time = self.next_sample as f64 * self.reference().increment;
+        if let Err(e) = self.load(time) {
+            self.failed = true;
+            return Some(Err(e));
+        }
+        let values = self
+            .channels
+            .iter()
+            .map(|channel| channel.value_at(time, self.policy))
+            .collect();
+        self.next_sample += 1;
+
+        Some(Ok(SyncedSample { time, values }))
+    }
+}
+
+/// Read the range of a numeric channel converting the values to f64.
+pub(super) fn read_range_as_f64<F: Read + Seek + Write + Debug>(
+    file: &mut TdmsFile<F>,
+    channel: &ChannelPath,
+    start: u64,
+    output: &mut [f64],
+) -> Result<(), TdmsError> {
+    macro_rules! read_as {
+        ($type:ty) => {{
+            let mut buffer = vec![<$type>::default(); output.len()];
+            let outcome = file.read_range(channel, start, &mut buffer[..])?;
+            for (out, value) in output.iter_mut().zip(&buffer[..outcome.samples_read]) {
+                *out = *value as f64;
+            }
+        }};
+    }
+
+    let data_type = file
+        .index
+        .channel_data_type(channel)
+        .ok_or_else(|| file.index.missing_channel(channel))?;
+
+    match data_type {
+        DataType::I8 => read_as!(i8),
+        DataType::I16 => read_as!(i16),
+        DataType::I32 => read_as!(i32),
+        DataType::I64 => read_as!(i64),
+        DataType::U8 => read_as!(u8),
+        DataType::U16 => read_as!(u16),
+        DataType::U32 => read_as!(u32),
+        DataType::U64 => read_as!(u64),
+        DataType::SingleFloat | DataType::SingleFloatWithUnit => read_as!(f32),
+        DataType::DoubleFloat | DataType::DoubleFloatWithUnit => {
+            file.read_range(channel, start, output)?;
+        }
+        other => return Err(TdmsError::UnsupportedType(other)),
+    }
+    Ok(())
+}
+
+/// The difference between two timestamps in seconds, keeping the precision of the fractions.
+pub(super) fn seconds_between(time: &LVTime, reference: &LVTime) -> f64 {
+    (time.seconds() as f64 - reference.seconds() as f64)
+        + (time.sub_seconds() - reference.sub_seconds())
+}
+
+impl<F: Read + Seek + Write + Debug> TdmsFile<F> {
+    /// Iterate over the channels aligned by their waveform timing.
+    ///
+    /// The first channel is the reference. One [`SyncedSample`] is produced for
+    /// each of its samples, with the other channels sampled at the same time
+    /// according to `policy`. Channels may have different rates and start times.
+    ///
+    /// Errors with [`TdmsError::MissingWaveformTiming`] if a channel doesn't have
+    /// `wf_start_time` and `wf_increment` properties.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use labview_interop::types::LVTime;
+    /// use tedium::{ChannelPath, DataLayout, PropertyPath, PropertyValue, SyncPolicy, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let mut writer = file.writer().unwrap();
+    /// for (name, increment, data) in [("fast", 0.5, vec![0.0, 1.0, 2.0, 3.0]), ("slow", 1.0, vec![10.0, 20.0])] {
+    ///     writer.write_properties(&PropertyPath::channel("group", name), &[
+    ///         ("wf_start_time", PropertyValue::Timestamp(LVTime::from_lv_epoch(0.0))),
+    ///         ("wf_increment", PropertyValue::DoubleFloat(increment)),
+    ///     ]).unwrap();
+    ///     writer.write_channels(&[ChannelPath::new("group", name)], &data[..], DataLayout::Contigious).unwrap();
+    /// }
+    /// drop(writer);
+    ///
+    /// let channels = [ChannelPath::new("group", "fast"), ChannelPath::new("group", "slow")];
+    /// let samples: Vec<_> = file
+    ///     .time_synced(&channels, SyncPolicy::Linear)
+    ///     .unwrap()
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    /// assert_eq!(samples[1].values, vec![1.0, 15.0]);
+    /// ```
+    pub fn time_synced(
+        &mut self,
+        channels: &[ChannelPath],
+        policy: SyncPolicy,
+    ) -> Result<TimeSyncedIter<'_, F>, TdmsError> {
+        let mut reference_start = None;
+        let mut timings = Vec::with_capacity(channels.len());
+
+        for channel in channels {
+            let property_path: &PropertyPath = channel.as_ref();
+            let missing_timing = || TdmsError::MissingWaveformTiming(channel.clone());
+
+            let start_time = match self.read_property(property_path, "wf_start_time")? {
+                Some(PropertyValue::Timestamp(time)) => *time,
+                _ => return Err(missing_timing()),
+            };
+            let increment = match self.read_property(property_path, "wf_increment")? {
+                Some(PropertyValue::DoubleFloat(increment)) if *increment > 0.0 => *increment,
+                _ => return Err(missing_timing()),
+            };
+            let start_offset = match self.read_property(property_path, "wf_start_offset")? {
+                Some(PropertyValue::DoubleFloat(offset)) => *offset,
+                _ => 0.0,
+            };
+
+            let reference_start = *reference_start.get_or_insert((start_time, start_offset));
+            let start =
+                seconds_between(&start_time, &reference_start.0) + start_offset - reference_start.1;
+
+            timings.push(ChannelTiming {
+                path: channel.clone(),
+                start,
+                increment,
+                length: self.channel_length(channel).unwrap_or(0),
+                buffer_start: 0,
+                buffer: Vec::new(),
+            });
+        }
+
+        Ok(TimeSyncedIter {
+            file: self,
+            channels: timings,
+            policy,
+            next_sample: 0,
+            chunk_end: 0,
+            failed: false,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::DataLayout;
+
+    fn write_waveform(
+        file: &mut TdmsFile<Cursor<Vec<u8>>>,
+        name: &str,
+        start: f64,
+        increment: f64,
+        data: &[f64],
+    ) {
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_properties(
+                &PropertyPath::channel("group", name),
+                &[
+                    (
+                        "wf_start_time",
+                        PropertyValue::Timestamp(LVTime::from_lv_epoch(3_000_000_000.0 + start)),
+                    ),
+                    ("wf_increment", PropertyValue::DoubleFloat(increment)),
+                ],
+            )
+            .unwrap();
+        writer
+            .write_channels(
+                &[ChannelPath::new("group", name)],
+                data,
+                DataLayout::Contigious,
+            )
+            .unwrap();
+    }
+
+    fn collect(
+        file: &mut TdmsFile<Cursor<Vec<u8>>>,
+        names: &[&str],
+        policy: SyncPolicy,
+    ) -> Vec<SyncedSample> {
+        let channels: Vec<_> = names
+            .iter()
+            .map(|name| ChannelPath::new("group", name))
+            .collect();
+        file.time_synced(&channels, policy)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_nearest_with_offset_start() {
+        let mut file = TdmsFile::new(Cursor::new(Vec::new())).unwrap();
+        write_waveform(&mut file, "reference", 0.0, 1.0, &[0.0, 1.0, 2.0, 3.0]);
+        write_waveform(&mut file, "late", 1.25, 0.5, &[10.0, 11.0, 12.0]);
+
+        let samples = collect(&mut file, &["reference", "late"], SyncPolicy::Nearest);
+        assert_eq!(samples.len(), 4);
+        assert_eq!(samples[2].time, 2.0);
+        assert!(samples[0].values[1].is_nan());
+        // 2.0s is 1.5 samples into "late" which rounds to 12.0.
+        assert_eq!(samples[2].values, vec![2.0, 12.0]);
+        assert!(samples[3].values[1].is_nan());
+    }
+
+    #[test]
+    fn test_linear_across_chunks() {
+        let mut file = TdmsFile::new(Cursor::new(Vec::new())).unwrap();
+        let length = CHUNK_SAMPLES as usize * 2 + 10;
+        let fast: Vec<f64> = (0..length).map(|i| i as f64).collect();
+        let slow: Vec<f64> = (0..length / 4 + 2).map(|i| i as f64 * 4.0).collect();
+        write_waveform(&mut file, "fast", 0.0, 0.25, &fast);
+        write_waveform(&mut file, "slow", 0.0, 1.0, &slow);
+
+        let samples = collect(&mut file, &["fast", "slow"], SyncPolicy::Linear);
+        assert_eq!(samples.len(), length);
+        for sample in samples {
+            assert_eq!(sample.values[0], sample.values[1]);
+        }
+    }
+
+    #[test]
+    fn test_buffers_bounded_for_very_different_rates() {
+        let mut file = TdmsFile::new(Cursor::new(Vec::new())).unwrap();
+        let rate = 2.0 * CHUNK_SAMPLES as f64;
+        let fast: Vec<f64> = (0..4 * rate as usize + 1).map(|i| i as f64).collect();
+        write_waveform(&mut file, "slow", 0.0, 1.0, &[0.0, 1.0, 2.0, 3.0, 4.0]);
+        write_waveform(&mut file, "fast", 0.0, 1.0 / rate, &fast);
+        write_waveform(&mut file, "double", 0.0, 0.5, &[0.0, 1.0, 2.0, 3.0, 4.0]);
+
+        let channels = ["slow", "fast", "double"].map(|name| ChannelPath::new("group", name));
+        let mut iter = file.time_synced(&channels, SyncPolicy::Linear).unwrap();
+        let mut count = 0;
+        while let Some(sample) = iter.next() {
+            let sample = sample.unwrap();
+            let expected_double = if sample.time <= 2.0 {
+                sample.time * 2.0
+            } else {
+                f64::NAN
+            };
+            assert_eq!(sample.values[..2], [sample.time, sample.time * rate]);
+            assert!(
+                sample.values[2] == expected_double
+                    || (sample.values[2].is_nan() && expected_double.is_nan())
+            );
+            for channel in &iter.channels {
+                assert!(channel.buffer.len() as u64 <= CHUNK_SAMPLES);
+            }
+            count += 1;
+        }
+        assert_eq!(count, 5);
+    }
+
+    #[test]
+    fn test_missing_timing() {
+        let mut file = TdmsFile::new(Cursor::new(Vec::new())).unwrap();
+        let channel = ChannelPath::new("group", "untimed");
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&channel], &[1.0], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+
+        let result = file.time_synced(&[channel], SyncPolicy::Nearest);
+        assert!(matches!(result, Err(TdmsError::MissingWaveformTiming(_))));
+    }
+}
@@ -0,0 +1,296 @@
+//! Time tracks for waveform channels whose acquisition restarted.
+//!
+//! A waveform channel normally has one `wf_start_time` and the time of any
+//! sample is the start time plus a multiple of `wf_increment`. When an
+//! acquisition is stopped and restarted into the same file, the writer updates
+//! the timing properties before the new data, so a single time vector built
+//! from the final properties is wrong for every sample before the restart.
+//!
+//! The index remembers the timing in force when each data block was added.
+//! [`TdmsFile::waveform_sections`] splits the channel into sections of
+//! continuous timing and [`TdmsFile::time_track`] builds times which jump at
+//! each restart.
+
+use std::fmt::Debug;
+use std::io::{Read, Seek, Write};
+
+use labview_interop::types::LVTime;
+
+use crate::error::TdmsError;
+use crate::index::WaveformTiming;
+use crate::paths::ChannelPath;
+use crate::TdmsFile;
+
+use super::time_sync::seconds_between;
+
+/// A run of samples in a channel with continuous waveform timing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeTrackSection {
+    /// The first sample of the channel in the section.
+    pub first_sample: u64,
+    /// The number of samples in the section.
+    pub length: u64,
+    /// The timing of the samples in the section.
+    pub timing: WaveformTiming,
+}
+
+impl TimeTrackSection {
+    /// Seconds from `reference` to the sample at `position` within the section.
+    pub fn time_since(&self, reference: &LVTime, position: u64) -> f64 {
+        seconds_between(&self.timing.start_time, reference)
+            + self.timing.start_offset
+            + position as f64 * self.timing.increment
+    }
+}
+
+impl<F: Read + Seek + Write + Debug> TdmsFile<F> {
+    /// Split the channel into sections of continuous waveform timing.
+    ///
+    /// A new section starts wherever the timing properties changed between
+    /// data blocks. A channel with a single acquisition has one section and
+    /// an empty channel has none.
+    ///
+    /// Errors with [`TdmsError::MissingChannel`] or [`TdmsError::MissingGroup`]
+    /// if the channel doesn't exist or [`TdmsError::MissingWaveformTiming`] if
+    /// it has no timing properties.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use labview_interop::types::LVTime;
+    /// use tedium::{ChannelPath, DataLayout, PropertyValue, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "voltage");
+    /// let mut writer = file.writer().unwrap();
+    /// for start in [0.0, 100.0] {
+    ///     writer.write_properties(channel.as_ref(), &[
+    ///         ("wf_start_time", PropertyValue::Timestamp(LVTime::from_lv_epoch(start))),
+    ///         ("wf_increment", PropertyValue::DoubleFloat(0.5)),
+    ///     ]).unwrap();
+    ///     writer.write_channels(&[&channel], &[1.0, 2.0, 3.0], DataLayout::Contigious).unwrap();
+    /// }
+    /// drop(writer);
+    ///
+    /// let sections = file.waveform_sections(&channel).unwrap();
+    /// assert_eq!(sections.len(), 2);
+    /// assert_eq!(sections[1].first_sample, 3);
+    ///
+    /// let mut times = [0.0; 6];
+    /// file.time_track(&channel, 0, &mut times).unwrap();
+    /// assert_eq!(times, [0.0, 0.5, 1.0, 100.0, 100.5, 101.0]);
+    /// ```
+    pub fn waveform_sections(
+        &self,
+        channel: &ChannelPath,
+    ) -> Result<Vec<TimeTrackSection>, TdmsError> {
+        let length = self
+            .channel_length(channel)
+            .ok_or_else(|| self.index.missing_channel(channel))?;
+        let changes = self
+            .index
+            .channel_timing_changes(channel)
+            .unwrap_or_default();
+        if changes.is_empty() {
+            return Err(TdmsError::MissingWaveformTiming(channel.clone()));
+        }
+
+        let ends = changes
+            .iter()
+            .skip(1)
+            .map(|(start, _)| *start)
+            .chain(std::iter::once(length));
+        Ok(changes
+            .iter()
+            .zip(ends)
+            .map(|((start, timing), end)| TimeTrackSection {
+                first_sample: *start,
+                length: end - start,
+                timing: *timing,
+            })
+            .filter(|section| section.length > 0)
+            .collect())
+    }
+
+    /// Fill `times` with the time of each sample from `start` onwards.
+    ///
+    /// Times are seconds since the first sample of the channel, including its
+    /// start offset, and follow the timing of each section so they jump where
+    /// the acquisition restarted. See [`Self::waveform_sections`] for the errors,
+    /// and this also errors with [`TdmsError::ReadPastEnd`] if `times` extends
+    /// beyond the end of the channel.
+    pub fn time_track(
+        &self,
+        channel: &ChannelPath,
+        start: u64,
+        times: &mut [f64],
+    ) -> Result<(), TdmsError> {
+        let sections = self.waveform_sections(channel)?;
+        let length = sections
+            .last()
+            .map_or(0, |section| section.first_sample + section.length);
+        if start.saturating_add(times.len() as u64) > length {
+            return Err(TdmsError::ReadPastEnd {
+                channel: channel.clone(),
+                start,
+                requested: times.len(),
+                length,
+            });
+        }
+        let Some(first) = sections.first() else {
+            return Ok(());
+        };
+        let reference = first.timing.start_time;
+        let reference_offset = first.timing.start_offset;
+
+        let mut section_index =
+            sections.partition_point(|section| section.first_sample + section.length <= start);
+        for (sample, time) in (start..).zip(times.iter_mut()) {
+            let mut section = &sections[section_index];
+            if sample >= section.first_sample + section.length {
+                section_index += 1;
+                section = &sections[section_index];
+            }
+            *time =
+                section.time_since(&reference, sample - section.first_sample) - reference_offset;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use super::*;
+    use crate::{DataLayout, PropertyValue};
+
+    fn timing(start: f64, increment: f64) -> [(&'static str, PropertyValue); 2] {
+        [
+            (
+                "wf_start_time",
+                PropertyValue::Timestamp(LVTime::from_lv_epoch(start)),
+            ),
+            ("wf_increment", PropertyValue::DoubleFloat(increment)),
+        ]
+    }
+
+    #[test]
+    fn test_unchanged_timing_is_one_section() {
+        let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+        let channel = ChannelPath::new("group", "channel");
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_properties(channel.as_ref(), &timing(10.0, 1.0))
+            .unwrap();
+        writer
+            .write_channels(&[&channel], &[1.0, 2.0], DataLayout::Contigious)
+            .unwrap();
+        // Rewriting the same values isn't a restart.
+        writer
+            .write_properties(channel.as_ref(), &timing(10.0, 1.0))
+            .unwrap();
+        writer
+            .write_channels(&[&channel], &[3.0], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+
+        let sections = file.waveform_sections(&channel).unwrap();
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].length, 3);
+
+        let mut times = [0.0; 2];
+        file.time_track(&channel, 1, &mut times).unwrap();
+        assert_eq!(times, [1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_restart_with_new_rate_and_offset() {
+        let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+        let channel = ChannelPath::new("group", "channel");
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&channel], &[1.0, 2.0], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_properties(channel.as_ref(), &timing(0.0, 1.0))
+            .unwrap();
+        writer
+            .write_channels(&[&channel], &[3.0], DataLayout::Contigious)
+            .unwrap();
+        let mut restart = timing(50.0, 0.25).to_vec();
+        restart.push(("wf_start_offset", PropertyValue::DoubleFloat(2.0)));
+        writer.write_properties(channel.as_ref(), &restart).unwrap();
+        writer
+            .write_channels(&[&channel], &[4.0, 5.0], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+
+        let sections = file.waveform_sections(&channel).unwrap();
+        let starts: Vec<_> = sections
+            .iter()
+            .map(|section| (section.first_sample, section.length))
+            .collect();
+        // Data before the first timing properties takes the first timing.
+        assert_eq!(starts, vec![(0, 3), (3, 2)]);
+        assert_eq!(sections[1].timing.start_offset, 2.0);
+
+        let mut times = [0.0; 4];
+        file.time_track(&channel, 1, &mut times).unwrap();
+        assert_eq!(times, [1.0, 2.0, 52.0, 52.25]);
+    }
+
+    #[test]
+    fn test_timing_written_after_data() {
+        let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+        let channel = ChannelPath::new("group", "channel");
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&channel], &[1.0, 2.0], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_properties(channel.as_ref(), &timing(0.0, 0.1))
+            .unwrap();
+        drop(writer);
+
+        let sections = file.waveform_sections(&channel).unwrap();
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].length, 2);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+        let channel = ChannelPath::new("group", "channel");
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&channel], &[1.0, 2.0], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+
+        assert!(matches!(
+            file.waveform_sections(&channel),
+            Err(TdmsError::MissingWaveformTiming(_))
+        ));
+        assert!(matches!(
+            file.waveform_sections(&ChannelPath::new("group", "missing")),
+            Err(TdmsError::MissingChannel { .. })
+        ));
+    }
+
+    #[test]
+    fn test_track_past_end_errors() {
+        let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+        let channel = ChannelPath::new("group", "channel");
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_properties(channel.as_ref(), &timing(0.0, 1.0))
+            .unwrap();
+        writer
+            .write_channels(&[&channel], &[1.0, 2.0], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+
+        let mut times = [0.0; 2];
+        let result = file.time_track(&channel, 1, &mut times);
+        assert!(matches!(result, Err(TdmsError::ReadPastEnd { .. })));
+    }
+}
@@ -0,0 +1,191 @@
+//! Reading into buffers which haven't been initialised.
+//!
+//! Zeroing a buffer of hundreds of megabytes before reading into it costs a
+//! full extra pass over memory. [`TdmsFile::read_into_uninit`] decodes each
+//! sample straight into its slot, so the buffer is only written once and just
+//! the slots past the end of the channel are filled with a default value.
+
+use std::io::{Read, Seek, SeekFrom};
+use std::mem::MaybeUninit;
+
+use crate::error::{usize_from, TdmsError};
+use crate::io::data_types::TdmsStorageType;
+use crate::paths::ChannelPath;
+use crate::raw_data::Endianess;
+use crate::{RawBlock, TdmsFile};
+
+use super::block_decode::block_layout;
+
+impl<F: Read + Seek> TdmsFile<F> {
+    /// Read the channel into a buffer which may not be initialised.
+    ///
+    /// Returns the samples read, which start at the beginning of `output`.
+    ///
+    /// # Safety contract
+    ///
+    /// This function is safe to call. On success every element of `output` is
+    /// initialised, with any slots after the samples read set to the default
+    /// value, so the caller may then treat the whole buffer as initialised.
+    /// On error the contents are unspecified and must not be assumed to be
+    /// initialised.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use std::mem::MaybeUninit;
+    /// use tedium::{ChannelPath, DataLayout, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[1.0, 2.0, 3.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let mut buffer: Vec<MaybeUninit<f64>> = Vec::with_capacity(3);
+    /// buffer.resize_with(3, MaybeUninit::uninit);
+    /// let data = file.read_into_uninit(&channel, &mut buffer).unwrap();
+    /// assert_eq!(data, [1.0, 2.0, 3.0]);
+    /// ```
+    pub fn read_into_uninit<'o, D: TdmsStorageType + Default>(
+        &mut self,
+        channel: &ChannelPath,
+        output: &'o mut [MaybeUninit<D>],
+    ) -> Result<&'o mut [D], TdmsError> {
+        let locations = self
+            .index
+            .get_channel_data_positions(channel)
+            .ok_or_else(|| self.index.missing_channel(channel))?;
+        let first_location = self
+            .index
+            .find_sample_location(channel, 0)
+            .map_or(locations.len(), |(position, _)| position);
+
+        let mut bytes = Vec::new();
+        let mut channels = Vec::new();
+        let mut initialised = 0;
+        for location in &locations[first_location..] {
+            if initialised == output.len() {
+                break;
+            }
+            let block = self
+                .index
+                .get_data_block(location.data_block)
+                .ok_or_else(|| {
+                    TdmsError::DataBlockNotFound(channel.clone(), location.data_block)
+                })?;
+            let chunk_bytes = block_layout(block, &mut channels)?;
+            let data_type = channels[location.channel_index].data_type;
+            if !D::supports_data_type(&data_type) {
+                return Err(
+                    TdmsError::DataTypeMismatch(data_type, D::NATURAL_TYPE).with_channel(channel)
+                );
+            }
+
+            let block_bytes = usize_from(block.length)?;
+            bytes.resize(block_bytes, 0);
+            self.retry.run(|| {
+                self.file.seek(SeekFrom::Start(block.start))?;
+                self.file.read_exact(&mut bytes)?;
+                Ok(())
+            })?;
+            let raw_block = RawBlock {
+                data_block: location.data_block,
+                layout: block.layout,
+                big_endian: block.byte_order == Endianess::Big,
+                channels: &channels,
+                chunks: block_bytes.checked_div(chunk_bytes).unwrap_or(0),
+                chunk_bytes,
+                bytes: &bytes,
+            };
+
+            let samples = raw_block.spans(location.channel_index).flat_map(|span| {
+                (0..span.samples).map(move |sample| span.start + sample * span.stride)
+            });
+            for (slot, start) in output[initialised..].iter_mut().zip(samples) {
+                let mut value = bytes.get(start..).unwrap_or_default();
+                slot.write(match block.byte_order {
+                    Endianess::Big => D::read_be(&mut value)?,
+                    Endianess::Little => D::read_le(&mut value)?,
+                });
+                initialised += 1;
+            }
+        }
+
+        for slot in &mut output[initialised..] {
+            slot.write(D::default());
+        }
+        // SAFETY: The slots up to `initialised` were decoded into and the rest
+        // were written just above. MaybeUninit<D> has the same layout as D.
+        let output = unsafe { &mut *(output as *mut [MaybeUninit<D>] as *mut [D]) };
+        Ok(&mut output[..initialised])
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{DataLayout, TdmsError};
+
+    #[test]
+    fn test_uninit_read_matches_read_all_and_fills_the_rest() {
+        let channel = ChannelPath::new("group", "channel");
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = file.writer().unwrap();
+        for block in 0..3i32 {
+            let data: Vec<i32> = (0..5).map(|sample| block * 5 + sample).collect();
+            writer
+                .write_channels(&[&channel], &data, DataLayout::Contigious)
+                .unwrap();
+        }
+        drop(writer);
+
+        let mut buffer = vec![MaybeUninit::<i32>::uninit(); 18];
+        let data = file.read_into_uninit(&channel, &mut buffer).unwrap();
+        assert_eq!(data, (0..15).collect::<Vec<_>>());
+        // SAFETY: The read succeeded so every slot is initialised.
+        let whole: Vec<i32> = buffer
+            .into_iter()
+            .map(|slot| unsafe { slot.assume_init() })
+            .collect();
+        assert_eq!(&whole[15..], &[0, 0, 0]);
+
+        let mut short = vec![MaybeUninit::<i32>::uninit(); 7];
+        let data = file.read_into_uninit(&channel, &mut short).unwrap();
+        assert_eq!(data, [0, 1, 2, 3, 4, 5, 6]);
+
+        assert!(matches!(
+            file.read_into_uninit(&ChannelPath::new("group", "missing"), &mut short),
+            Err(TdmsError::MissingChannel { .. })
+        ));
+    }
+
+    #[test]
+    fn test_uninit_read_of_interleaved_channels() {
+        let first = ChannelPath::new("group", "first");
+        let second = ChannelPath::new("group", "second");
+        let mut file = TdmsFile::new(Cursor::new(vec![])).unwrap();
+        let mut writer = file.writer().unwrap();
+        for _ in 0..2 {
+            writer
+                .write_channels(
+                    &[&first, &second],
+                    &[1u16, 10, 2, 20],
+                    DataLayout::Interleaved,
+                )
+                .unwrap();
+        }
+        drop(writer);
+
+        let mut buffer = vec![MaybeUninit::<u16>::uninit(); 3];
+        let data = file.read_into_uninit(&second, &mut buffer).unwrap();
+        assert_eq!(data, [10, 20, 10]);
+
+        let mut wrong_type = vec![MaybeUninit::<f64>::uninit(); 4];
+        assert!(matches!(
+            file.read_into_uninit(&first, &mut wrong_type),
+            Err(TdmsError::ChannelTypeMismatch { .. })
+        ));
+    }
+}
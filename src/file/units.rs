@@ -0,0 +1,190 @@
+//! Unit conversion of channel data using the `unit_string` property.
+//!
+//! The conversion is applied as the data is read, block by block, and
+//! [`crate::CsvFollower`] uses it to export columns in the requested unit.
+
+use std::fmt::Debug;
+use std::io::{Read, Seek, Write};
+
+use crate::error::TdmsError;
+use crate::paths::{ChannelPath, PropertyPath};
+use crate::{PropertyValue, ReadOutcome, TdmsFile};
+
+use super::time_sync::read_range_as_f64;
+
+/// The number of samples converted at a time.
+const CHUNK_SAMPLES: usize = 4096;
+
+/// A linear conversion between two units: `value * scale + offset`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitConversion {
+    pub scale: f64,
+    pub offset: f64,
+}
+
+impl UnitConversion {
+    /// Find the conversion between two units in the built in table.
+    ///
+    /// Units are matched on the symbols commonly used in `unit_string`, such as
+    /// `psi`, `kPa`, `degC` or `mm`. Returns None if either unit is unknown or
+    /// they measure different quantities.
+    pub fn between(from: &str, to: &str) -> Option<Self> {
+        let (from_quantity, from_scale, from_offset) = unit_to_base(from)?;
+        let (to_quantity, to_scale, to_offset) = unit_to_base(to)?;
+        if from_quantity != to_quantity {
+            return None;
+        }
+        // Convert to the base unit and then back out to the target.
+        Some(Self {
+            scale: from_scale / to_scale,
+            offset: (from_offset - to_offset) / to_scale,
+        })
+    }
+
+    pub fn apply(&self, value: f64) -> f64 {
+        value * self.scale + self.offset
+    }
+}
+
+/// The quantity, scale and offset to convert the unit to the SI base unit.
+fn unit_to_base(unit: &str) -> Option<(&'static str, f64, f64)> {
+    let conversion = match unit.trim() {
+        // Pressure, base Pa.
+        "Pa" => ("pressure", 1.0, 0.0),
+        "kPa" => ("pressure", 1e3, 0.0),
+        "MPa" => ("pressure", 1e6, 0.0),
+        "bar" => ("pressure", 1e5, 0.0),
+        "mbar" => ("pressure", 1e2, 0.0),
+        "psi" => ("pressure", 6_894.757_293_168, 0.0),
+        "atm" => ("pressure", 101_325.0, 0.0),
+        // Temperature, base K.
+        "K" => ("temperature", 1.0, 0.0),
+        // Bare C and F are the coulomb and farad so only the explicit forms are accepted.
+        "degC" | "°C" => ("temperature", 1.0, 273.15),
+        "degF" | "°F" => ("temperature", 5.0 / 9.0, 273.15 - 32.0 * 5.0 / 9.0),
+        // Length, base m.
+        "m" => ("length", 1.0, 0.0),
+        "mm" => ("length", 1e-3, 0.0),
+        "cm" => ("length", 1e-2, 0.0),
+        "km" => ("length", 1e3, 0.0),
+        "in" => ("length", 0.0254, 0.0),
+        "ft" => ("length", 0.3048, 0.0),
+        // Electrical potential, base V.
+        "V" => ("voltage", 1.0, 0.0),
+        "mV" => ("voltage", 1e-3, 0.0),
+        "uV" | "µV" => ("voltage", 1e-6, 0.0),
+        // Current, base A.
+        "A" => ("current", 1.0, 0.0),
+        "mA" => ("current", 1e-3, 0.0),
+        // Force, base N.
+        "N" => ("force", 1.0, 0.0),
+        "kN" => ("force", 1e3, 0.0),
+        "lbf" => ("force", 4.448_221_615_260_5, 0.0),
+        // Time, base s.
+        "s" => ("time", 1.0, 0.0),
+        "ms" => ("time", 1e-3, 0.0),
+        "us" | "µs" => ("time", 1e-6, 0.0),
+        _ => return None,
+    };
+    Some(conversion)
+}
+
+impl<F: Read + Seek + Write + Debug> TdmsFile<F> {
+    /// Read a numeric channel converted from its `unit_string` to `unit`.
+    ///
+    /// The conversion is applied block by block as the data is read.
+    /// Errors with [`TdmsError::UnitConversionUnavailable`] if the channel has no
+    /// unit or there is no conversion to the requested unit.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, PropertyPath, PropertyValue, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "pressure");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_properties(
+    ///     &PropertyPath::channel("group", "pressure"),
+    ///     &[("unit_string", PropertyValue::String("bar".to_string()))],
+    /// ).unwrap();
+    /// writer.write_channels(&[&channel], &[1.0, 2.5], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let mut output = [0.0; 2];
+    /// file.read_channel_in_unit(&channel, "kPa", &mut output).unwrap();
+    /// assert_eq!(output, [100.0, 250.0]);
+    /// ```
+    pub fn read_channel_in_unit(
+        &mut self,
+        channel: &ChannelPath,
+        unit: &str,
+        output: &mut [f64],
+    ) -> Result<ReadOutcome, TdmsError> {
+        let property_path: &PropertyPath = channel.as_ref();
+        let source_unit = match self.read_property(property_path, "unit_string")? {
+            Some(PropertyValue::String(source_unit)) => Some(source_unit.clone()),
+            _ => None,
+        };
+        let conversion = source_unit
+            .as_deref()
+            .and_then(|source_unit| UnitConversion::between(source_unit, unit))
+            .ok_or_else(|| {
+                TdmsError::UnitConversionUnavailable(
+                    channel.clone(),
+                    source_unit.clone(),
+                    unit.to_string(),
+                )
+            })?;
+
+        let length = self.channel_length(channel).unwrap_or(0);
+        let samples_read = length.min(output.len() as u64) as usize;
+
+        for (chunk_index, chunk) in output[..samples_read].chunks_mut(CHUNK_SAMPLES).enumerate() {
+            let start = (chunk_index * CHUNK_SAMPLES) as u64;
+            read_range_as_f64(self, channel, start, chunk)?;
+            for value in chunk.iter_mut() {
+                *value = conversion.apply(*value);
+            }
+        }
+
+        Ok(ReadOutcome {
+            samples_read,
+            truncated: samples_read < output.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(left: f64, right: f64) {
+        assert!((left - right).abs() < 1e-9, "{left} != {right}");
+    }
+
+    #[test]
+    fn test_pressure_conversion() {
+        let conversion = UnitConversion::between("psi", "kPa").unwrap();
+        assert_close(conversion.apply(1.0), 6.894_757_293_168);
+    }
+
+    #[test]
+    fn test_temperature_conversion_with_offset() {
+        let conversion = UnitConversion::between("degC", "degF").unwrap();
+        assert_close(conversion.apply(100.0), 212.0);
+        assert_close(conversion.apply(-40.0), -40.0);
+
+        let conversion = UnitConversion::between("degF", "K").unwrap();
+        assert_close(conversion.apply(32.0), 273.15);
+    }
+
+    #[test]
+    fn test_incompatible_units() {
+        assert!(UnitConversion::between("psi", "degC").is_none());
+        assert!(UnitConversion::between("furlong", "m").is_none());
+        // Coulomb and farad, not degrees.
+        assert!(UnitConversion::between("F", "K").is_none());
+        assert!(UnitConversion::between("C", "degF").is_none());
+    }
+}
@@ -0,0 +1,127 @@
+//! Visiting a channel one data block at a time.
+//!
+//! [`TdmsFile::visit_channel_blocks`] decodes each block of a channel into a
+//! reused buffer and passes it to a closure, so streaming calculations such
+//! as an RMS or FFT run over the whole channel without holding it in memory.
+
+use std::io::{Read, Seek};
+
+use crate::error::{usize_from, TdmsError};
+use crate::io::data_types::TdmsStorageType;
+use crate::paths::ChannelPath;
+use crate::TdmsFile;
+
+/// Where a block passed to [`TdmsFile::visit_channel_blocks`] sits in the channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockInfo {
+    /// The number of the data block in the file.
+    pub data_block: usize,
+    /// The index in the channel of the first sample in the block.
+    pub first_sample: u64,
+}
+
+impl<F: Read + Seek> TdmsFile<F> {
+    /// Decode the channel block by block, passing the samples of each to `visit`.
+    ///
+    /// Blocks are visited in sample order and the buffer is reused between
+    /// blocks, so only the largest block is held in memory.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use tedium::{ChannelPath, DataLayout, TdmsFile};
+    ///
+    /// let mut file = TdmsFile::new(std::io::Cursor::new(vec![])).unwrap();
+    /// let channel = ChannelPath::new("group", "channel");
+    /// let mut writer = file.writer().unwrap();
+    /// writer.write_channels(&[&channel], &[3.0, 4.0], DataLayout::Contigious).unwrap();
+    /// writer.write_channels(&[&channel], &[0.0, 0.0], DataLayout::Contigious).unwrap();
+    /// drop(writer);
+    ///
+    /// let mut sum_of_squares = 0.0;
+    /// let mut count = 0;
+    /// file.visit_channel_blocks(&channel, |_, samples: &[f64]| {
+    ///     sum_of_squares += samples.iter().map(|sample| sample * sample).sum::<f64>();
+    ///     count += samples.len();
+    /// })
+    /// .unwrap();
+    /// assert_eq!((sum_of_squares / count as f64).sqrt(), 2.5);
+    /// ```
+    pub fn visit_channel_blocks<D: TdmsStorageType + Default + Clone>(
+        &mut self,
+        channel: &ChannelPath,
+        mut visit: impl FnMut(BlockInfo, &[D]),
+    ) -> Result<(), TdmsError> {
+        let locations = self
+            .index
+            .get_channel_data_positions(channel)
+            .ok_or_else(|| self.index.missing_channel(channel))?;
+
+        let mut buffer = Vec::new();
+        let mut first_sample = 0;
+        for location in locations {
+            let block = self
+                .index
+                .get_data_block(location.data_block)
+                .ok_or_else(|| {
+                    TdmsError::DataBlockNotFound(channel.clone(), location.data_block)
+                })?;
+            buffer.resize(usize_from(location.number_of_samples)?, D::default());
+            let read = self
+                .retry
+                .run(|| {
+                    block.read_from(
+                        &mut self.file,
+                        0,
+                        &mut [(location.channel_index, &mut buffer[..])],
+                    )
+                })
+                .map_err(|error| error.with_channel(channel))?
+                .min(buffer.len());
+
+            let info = BlockInfo {
+                data_block: location.data_block,
+                first_sample,
+            };
+            visit(info, &buffer[..read]);
+            first_sample += read as u64;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use super::*;
+    use crate::io::data_types::DataType;
+    use crate::raw_data::DataLayout;
+    use crate::testgen::TestFileSpec;
+
+    #[test]
+    fn test_visited_blocks_match_read_all() {
+        for layout in [DataLayout::Contigious, DataLayout::Interleaved] {
+            let spec = TestFileSpec {
+                channels_per_group: 2,
+                segments: 4,
+                samples_per_segment: 6,
+                layout,
+                data_type: DataType::I32,
+                ..Default::default()
+            };
+            let mut file = TdmsFile::new(std::io::Cursor::new(spec.build().unwrap())).unwrap();
+            let channel = spec.channel_path(0, 1);
+
+            let mut visited = Vec::new();
+            let mut starts = Vec::new();
+            file.visit_channel_blocks(&channel, |info, samples: &[i32]| {
+                starts.push(info.first_sample);
+                visited.extend_from_slice(samples);
+            })
+            .unwrap();
+
+            let expected: Vec<i32> = file.read_all(&channel).unwrap();
+            assert_eq!(visited, expected, "{layout:?}");
+            assert_eq!(starts, [0, 6, 12, 18]);
+        }
+    }
+}
@@ -0,0 +1,513 @@
+//! Watch growing files using filesystem notifications.
+//!
+//! [`TdmsWatcher`] follows a single file or every TDMS file in a directory,
+//! such as a logger rotating through files. When the filesystem reports a
+//! change the affected files are refreshed and an event is produced for every
+//! channel with new samples.
+//!
+//! Files which are deleted or renamed away, for example by a retention policy,
+//! are dropped so their handles are closed and they are no longer refreshed.
+//!
+//! A file which fails to refresh, or an error from the notifications, is
+//! reported as an event rather than an error so the other files keep being
+//! followed and the events already found aren't lost.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
+
+use notify::event::ModifyKind;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::error::{ErrorKind, TdmsError};
+use crate::index::Index;
+use crate::paths::ChannelPath;
+use crate::TdmsFile;
+
+use super::os_path::os_path;
+
+/// A change found by a [`TdmsWatcher`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchEvent {
+    /// A new TDMS file appeared in the watched directory.
+    NewFile(PathBuf),
+    /// A followed file was deleted or renamed away and is no longer followed.
+    RemovedFile(PathBuf),
+    /// New samples are available in a channel.
+    NewData {
+        file: PathBuf,
+        channel: ChannelPath,
+        /// The range of the new samples in the channel.
+        samples: Range<u64>,
+    },
+    /// A followed file couldn't be refreshed.
+    ///
+    /// The file is still followed and is refreshed again on each poll. The
+    /// error is only reported again after a refresh has succeeded.
+    FileError {
+        file: PathBuf,
+        kind: ErrorKind,
+        message: String,
+    },
+    /// The filesystem notifications reported an error.
+    ///
+    /// Every followed file is still refreshed, so their changes are found.
+    NotificationError(String),
+}
+
+/// A file being followed with the channel lengths already reported.
+struct WatchedFile {
+    file: TdmsFile<File>,
+    lengths: BTreeMap<String, u64>,
+    /// True if the last refresh failed and the error has been reported.
+    failed: bool,
+}
+
+impl WatchedFile {
+    fn open(path: &Path) -> Result<Self, TdmsError> {
+        // Index with refresh so a segment still being written is left for later.
+        let mut file = TdmsFile {
+            index: Arc::new(Index::new()),
+            generation: 0,
+            file: File::open(os_path(path))?,
+            retry: Default::default(),
+            parallelism: Default::default(),
+        };
+        file.refresh()?;
+        Ok(Self {
+            file,
+            lengths: BTreeMap::new(),
+            failed: false,
+        })
+    }
+
+    /// Refresh the index and produce events for channels that have grown.
+    fn update(&mut self, path: &Path, events: &mut Vec<WatchEvent>) -> Result<(), TdmsError> {
+        self.file.refresh()?;
+        for (object_path, length) in self.file.index.data_object_lengths() {
+            let previous = self.lengths.get(object_path).copied().unwrap_or(0);
+            if length <= previous {
+                continue;
+            }
+            self.lengths.insert(object_path.to_string(), length);
+            if let Ok(channel) = ChannelPath::try_from(object_path) {
+                events.push(WatchEvent::NewData {
+                    file: path.to_path_buf(),
+                    channel,
+                    samples: previous..length,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Follows TDMS files as they are written using filesystem notifications.
+///
+/// # Example
+/// ```no_run
+/// use std::time::Duration;
+/// use tedium::{TdmsWatcher, WatchEvent};
+///
+/// let mut watcher = TdmsWatcher::watch_directory("logs".as_ref()).unwrap();
+/// loop {
+///     for event in watcher.wait(Duration::from_secs(1)).unwrap() {
+///         if let WatchEvent::NewData { file, channel, samples } = event {
+///             let file = watcher.file(&file).unwrap();
+///             let mut data = vec![0.0f64; (samples.end - samples.start) as usize];
+///             file.read_range(&channel, samples.start, &mut data).unwrap();
+///         }
+///     }
+/// }
+/// ```
+pub struct TdmsWatcher {
+    files: BTreeMap<PathBuf, WatchedFile>,
+    directory: Option<PathBuf>,
+    receiver: Receiver<notify::Result<notify::Event>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl TdmsWatcher {
+    /// Watch a single file.
+    ///
+    /// The data already in the file is reported by the first call to [`Self::wait`]
+    /// or [`Self::poll`].
+    pub fn watch_file(path: &Path) -> Result<Self, TdmsError> {
+        let mut watcher = Self::watch(path, None)?;
+        watcher
+            .files
+            .insert(path.to_path_buf(), WatchedFile::open(path)?);
+        Ok(watcher)
+    }
+
+    /// Watch every file with a `tdms` extension in the directory, including new files.
+    ///
+    /// A file which can't be opened yet is picked up on its next notification.
+    pub fn watch_directory(directory: &Path) -> Result<Self, TdmsError> {
+        let mut watcher = Self::watch(directory, Some(directory.to_path_buf()))?;
+        for entry in std::fs::read_dir(os_path(directory))? {
+            // Keep the path as given rather than with the extended length prefix.
+            let path = directory.join(entry?.file_name());
+            if is_tdms_file(&path) {
+                if let Ok(watched) = WatchedFile::open(&path) {
+                    watcher.files.insert(path, watched);
+                }
+            }
+        }
+        Ok(watcher)
+    }
+
+    fn watch(path: &Path, directory: Option<PathBuf>) -> Result<Self, TdmsError> {
+        let (sender, receiver) = channel();
+        let mut watcher = notify::recommended_watcher(sender).map_err(watch_error)?;
+        watcher
+            .watch(&os_path(path), RecursiveMode::NonRecursive)
+            .map_err(watch_error)?;
+        Ok(Self {
+            files: BTreeMap::new(),
+            directory,
+            receiver,
+            _watcher: watcher,
+        })
+    }
+
+    /// Access a watched file to read the new data.
+    pub fn file(&mut self, path: &Path) -> Option<&mut TdmsFile<File>> {
+        self.files.get_mut(path).map(|watched| &mut watched.file)
+    }
+
+    /// The paths of the files being followed.
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.files.keys().map(PathBuf::as_path)
+    }
+
+    /// Wait up to `timeout` for a change and return the new data.
+    ///
+    /// Returns an empty list if nothing changed in that time.
+    pub fn wait(&mut self, timeout: Duration) -> Result<Vec<WatchEvent>, TdmsError> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(event) => {
+                let mut events = Vec::new();
+                self.handle_notification(event, &mut events);
+                Ok(self.process(events))
+            }
+            Err(RecvTimeoutError::Timeout) => Ok(Vec::new()),
+            Err(RecvTimeoutError::Disconnected) => Err(watch_error("watcher stopped")),
+        }
+    }
+
+    /// Process any pending changes without waiting.
+    ///
+    /// Every file is refreshed so this can also be called on a timer where
+    /// notifications are unreliable, such as network drives.
+    pub fn poll(&mut self) -> Result<Vec<WatchEvent>, TdmsError> {
+        Ok(self.process(Vec::new()))
+    }
+
+    /// Handle any queued notifications then refresh every file.
+    ///
+    /// Files which no longer exist are dropped first, in case a notification
+    /// for them was missed.
+    fn process(&mut self, mut events: Vec<WatchEvent>) -> Vec<WatchEvent> {
+        while let Ok(event) = self.receiver.try_recv() {
+            self.handle_notification(event, &mut events);
+        }
+
+        let removed: Vec<PathBuf> = self
+            .files
+            .keys()
+            .filter(|path| !os_path(path).exists())
+            .cloned()
+            .collect();
+        for path in removed {
+            self.remove_file(path, &mut events);
+        }
+
+        for (path, watched) in self.files.iter_mut() {
+            match watched.update(path, &mut events) {
+                Ok(()) => watched.failed = false,
+                Err(error) => {
+                    if !watched.failed {
+                        events.push(WatchEvent::FileError {
+                            file: path.clone(),
+                            kind: error.kind(),
+                            message: error.to_string(),
+                        });
+                    }
+                    watched.failed = true;
+                }
+            }
+        }
+        events
+    }
+
+    /// Stop following files which were removed and start following any new
+    /// TDMS files named in the notification.
+    ///
+    /// A new file which can't be opened yet, such as one still being created,
+    /// is skipped and tried again on its next notification.
+    fn handle_notification(
+        &mut self,
+        event: notify::Result<notify::Event>,
+        events: &mut Vec<WatchEvent>,
+    ) {
+        let event = match event {
+            Ok(event) => event,
+            Err(error) => {
+                events.push(WatchEvent::NotificationError(error.to_string()));
+                return;
+            }
+        };
+        let paths: Vec<PathBuf> = event
+            .paths
+            .iter()
+            .filter_map(|path| self.followed_path(path))
+            .collect();
+        if matches!(
+            event.kind,
+            EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(_))
+        ) {
+            for path in paths.iter().filter(|path| !os_path(path).exists()) {
+                self.remove_file(path.clone(), events);
+            }
+        }
+
+        if self.directory.is_none() {
+            return;
+        }
+        for path in paths {
+            if is_tdms_file(&path) && !self.files.contains_key(&path) && os_path(&path).is_file() {
+                if let Ok(watched) = WatchedFile::open(&path) {
+                    self.files.insert(path.clone(), watched);
+                    events.push(WatchEvent::NewFile(path));
+                }
+            }
+        }
+    }
+
+    /// The path a notification refers to in the form the files are keyed by.
+    ///
+    /// Notifications name paths under the watched path, which has the extended
+    /// length prefix on Windows.
+    fn followed_path(&self, path: &Path) -> Option<PathBuf> {
+        let name = path.file_name()?;
+        match &self.directory {
+            Some(directory) => Some(directory.join(name)),
+            None => self
+                .files
+                .keys()
+                .find(|file| file.file_name() == Some(name))
+                .cloned(),
+        }
+    }
+
+    /// Stop following the file, closing its handle.
+    fn remove_file(&mut self, path: PathBuf, events: &mut Vec<WatchEvent>) {
+        if self.files.remove(&path).is_some() {
+            events.push(WatchEvent::RemovedFile(path));
+        }
+    }
+}
+
+fn is_tdms_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("tdms"))
+}
+
+fn watch_error(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> TdmsError {
+    TdmsError::IoError(std::io::Error::other(error))
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+    use crate::testgen::TestFileSpec;
+
+    fn temp_directory(name: &str) -> PathBuf {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("target");
+        path.push(format!("watch_{name}_{}", std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_directory_watch_reports_new_files_and_data() {
+        let directory = temp_directory("directory");
+        let spec = TestFileSpec {
+            channels_per_group: 1,
+            segments: 2,
+            samples_per_segment: 5,
+            ..Default::default()
+        };
+        let bytes = spec.build().unwrap();
+        let complete = TdmsFile::new(std::io::Cursor::new(bytes.clone())).unwrap();
+        let second_segment = complete.data_block_debug(1).unwrap().segment_start as usize;
+
+        let mut watcher = TdmsWatcher::watch_directory(&directory).unwrap();
+        assert!(watcher.poll().unwrap().is_empty());
+
+        let path = directory.join("log_0.tdms");
+        let mut output = File::create(&path).unwrap();
+        output.write_all(&bytes[..second_segment]).unwrap();
+        output.flush().unwrap();
+
+        let channel = spec.channel_path(0, 0);
+        let mut events = Vec::new();
+        for _ in 0..50 {
+            events.extend(watcher.wait(Duration::from_millis(100)).unwrap());
+            if events.len() >= 2 {
+                break;
+            }
+        }
+        assert_eq!(
+            events,
+            vec![
+                WatchEvent::NewFile(path.clone()),
+                WatchEvent::NewData {
+                    file: path.clone(),
+                    channel: channel.clone(),
+                    samples: 0..5
+                }
+            ]
+        );
+
+        output.write_all(&bytes[second_segment..]).unwrap();
+        output.flush().unwrap();
+        let mut events = Vec::new();
+        for _ in 0..50 {
+            events.extend(watcher.wait(Duration::from_millis(100)).unwrap());
+            if !events.is_empty() {
+                break;
+            }
+        }
+        assert_eq!(
+            events,
+            vec![WatchEvent::NewData {
+                file: path.clone(),
+                channel: channel.clone(),
+                samples: 5..10
+            }]
+        );
+
+        let mut data = [0.0; 5];
+        watcher
+            .file(&path)
+            .unwrap()
+            .read_range(&channel, 5, &mut data)
+            .unwrap();
+        assert_eq!(data[0], spec.expected_value(0, 5));
+
+        drop(watcher);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    /// Wait until the events satisfy `done`, or give up after `attempts` waits.
+    fn wait_for(
+        watcher: &mut TdmsWatcher,
+        attempts: usize,
+        done: impl Fn(&[WatchEvent]) -> bool,
+    ) -> Vec<WatchEvent> {
+        let mut events = Vec::new();
+        for _ in 0..attempts {
+            events.extend(watcher.wait(Duration::from_millis(100)).unwrap());
+            if done(&events) {
+                break;
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn test_removed_files_are_dropped() {
+        let directory = temp_directory("removed");
+        let bytes = TestFileSpec::default().build().unwrap();
+        let path = directory.join("log_0.tdms");
+        std::fs::write(&path, &bytes).unwrap();
+
+        let mut watcher = TdmsWatcher::watch_directory(&directory).unwrap();
+        assert_eq!(watcher.files().count(), 1);
+        std::fs::remove_file(&path).unwrap();
+
+        let events = wait_for(&mut watcher, 50, |events| !events.is_empty());
+        assert_eq!(events, vec![WatchEvent::RemovedFile(path.clone())]);
+        assert_eq!(watcher.files().count(), 0);
+        assert!(watcher.poll().unwrap().is_empty());
+
+        drop(watcher);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_unreadable_new_file_is_retried() {
+        let directory = temp_directory("retried");
+        let mut watcher = TdmsWatcher::watch_directory(&directory).unwrap();
+
+        let path = directory.join("log_0.tdms");
+        // Long enough to hold a lead in, but without the TDMS tag.
+        std::fs::write(&path, [b'x'; 64]).unwrap();
+        let events = wait_for(&mut watcher, 5, |_| false);
+        assert!(events.is_empty());
+        assert_eq!(watcher.files().count(), 0);
+
+        std::fs::write(&path, TestFileSpec::default().build().unwrap()).unwrap();
+        let events = wait_for(&mut watcher, 50, |events| !events.is_empty());
+        assert_eq!(events.first(), Some(&WatchEvent::NewFile(path.clone())));
+
+        drop(watcher);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_file_is_reported_and_others_still_followed() {
+        let directory = temp_directory("corrupt");
+        let bytes = TestFileSpec::default().build().unwrap();
+        let corrupt = directory.join("log_0.tdms");
+        std::fs::write(&corrupt, &bytes).unwrap();
+
+        let mut watcher = TdmsWatcher::watch_directory(&directory).unwrap();
+        assert!(!watcher.poll().unwrap().is_empty());
+
+        // A segment without the TDMS tag can never be read.
+        let mut output = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&corrupt)
+            .unwrap();
+        output.write_all(&[b'x'; 64]).unwrap();
+        drop(output);
+        let next = directory.join("log_1.tdms");
+        std::fs::write(&next, &bytes).unwrap();
+
+        let events = wait_for(&mut watcher, 50, |events| {
+            events.contains(&WatchEvent::NewFile(next.clone()))
+                && events
+                    .iter()
+                    .any(|event| matches!(event, WatchEvent::FileError { .. }))
+        });
+        let errors: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                WatchEvent::FileError { file, kind, .. } => Some((file.clone(), *kind)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(errors, vec![(corrupt.clone(), ErrorKind::Corrupt)]);
+        assert!(events.iter().any(|event| matches!(
+            event,
+            WatchEvent::NewData { file, .. } if *file == next
+        )));
+
+        // The error isn't repeated while the file stays broken.
+        assert!(watcher.poll().unwrap().is_empty());
+        assert_eq!(watcher.files().count(), 2);
+
+        drop(watcher);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,187 @@
+//! Reading a TDMS file embedded in a larger container.
+//!
+//! A [`ByteWindow`] exposes a range of bytes from another reader as if it
+//! were a whole file, so TDMS data inside a custom archive or a network frame
+//! can be opened without copying it out first. Offsets in the TDMS file are
+//! relative to the start of the window.
+
+use std::io::{Read, Result as IoResult, Seek, SeekFrom, Write};
+
+use crate::error::TdmsError;
+use crate::TdmsFile;
+
+/// A read only view of `length` bytes of the inner reader starting at `offset`.
+#[derive(Debug)]
+pub struct ByteWindow<F> {
+    inner: F,
+    offset: u64,
+    length: u64,
+    position: u64,
+}
+
+impl<F: Read + Seek> ByteWindow<F> {
+    /// Create the window, checking the inner reader contains the whole range.
+    pub fn new(mut inner: F, offset: u64, length: u64) -> Result<Self, TdmsError> {
+        let inner_length = inner.seek(SeekFrom::End(0))?;
+        if offset
+            .checked_add(length)
+            .is_none_or(|end| end > inner_length)
+        {
+            return Err(TdmsError::IoError(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                format!(
+                    "byte window {offset}+{length} extends past the end of the {inner_length} byte source"
+                ),
+            )));
+        }
+        inner.seek(SeekFrom::Start(offset))?;
+        Ok(Self {
+            inner,
+            offset,
+            length,
+            position: 0,
+        })
+    }
+}
+
+impl<F> ByteWindow<F> {
+    /// The offset of the window in the inner reader.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// The length of the window in bytes.
+    pub fn len(&self) -> u64 {
+        self.length
+    }
+
+    /// True if the window has no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Return the inner reader.
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+}
+
+impl<F: Read> Read for ByteWindow<F> {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        let remaining = self.length.saturating_sub(self.position);
+        let limit = (buf.len() as u64).min(remaining) as usize;
+        let read = self.inner.read(&mut buf[..limit])?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl<F: Seek> Seek for ByteWindow<F> {
+    fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+        let target = match pos {
+            SeekFrom::Start(position) => Some(position),
+            SeekFrom::End(delta) => self.length.checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.position.checked_add_signed(delta),
+        };
+        let Some(target) = target else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "seek to a negative position in the byte window",
+            ));
+        };
+        self.inner.seek(SeekFrom::Start(self.offset + target))?;
+        self.position = target;
+        Ok(target)
+    }
+}
+
+/// Writing is not supported as it could overwrite the rest of the container.
+///
+/// This exists so the read API, which is shared with writable files, is available.
+impl<F> Write for ByteWindow<F> {
+    fn write(&mut self, _buf: &[u8]) -> IoResult<usize> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "byte windows are read only",
+        ))
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+impl<F: Read + Seek> TdmsFile<ByteWindow<F>> {
+    /// Open the TDMS file stored in `length` bytes of `inner` starting at `offset`.
+    ///
+    /// # Example
+    #[cfg_attr(feature = "write", doc = "```rust")]
+    #[cfg_attr(not(feature = "write"), doc = "```ignore")]
+    /// use std::io::Cursor;
+    /// use tedium::testgen::TestFileSpec;
+    /// use tedium::TdmsFile;
+    ///
+    /// let spec = TestFileSpec::default();
+    /// let tdms_bytes = spec.build().unwrap();
+    ///
+    /// // Wrap the file in a container with a header and trailer.
+    /// let mut container = b"HEADER".to_vec();
+    /// container.extend_from_slice(&tdms_bytes);
+    /// container.extend_from_slice(b"TRAILER");
+    ///
+    /// let mut file = TdmsFile::open_window(Cursor::new(container), 6, tdms_bytes.len() as u64).unwrap();
+    /// let data: Vec<f64> = file.read_all(&spec.channel_path(0, 0)).unwrap();
+    /// assert_eq!(data.len(), spec.channel_length());
+    /// ```
+    pub fn open_window(inner: F, offset: u64, length: u64) -> Result<Self, TdmsError> {
+        TdmsFile::new(ByteWindow::new(inner, offset, length)?)
+    }
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::testgen::TestFileSpec;
+
+    #[test]
+    fn test_window_reads_are_bounded() {
+        let source = Cursor::new((0u8..20).collect::<Vec<u8>>());
+        let mut window = ByteWindow::new(source, 5, 10).unwrap();
+
+        let mut buf = [0u8; 20];
+        assert_eq!(window.read(&mut buf).unwrap(), 10);
+        assert_eq!(&buf[..10], &[5, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
+        assert_eq!(window.read(&mut buf).unwrap(), 0);
+
+        assert_eq!(window.seek(SeekFrom::End(-2)).unwrap(), 8);
+        assert_eq!(window.read(&mut buf).unwrap(), 2);
+        assert_eq!(&buf[..2], &[13, 14]);
+        assert!(window.seek(SeekFrom::Current(-20)).is_err());
+        assert!(window.write(&[1]).is_err());
+
+        let source = Cursor::new(vec![0u8; 20]);
+        assert!(ByteWindow::new(source, 15, 10).is_err());
+    }
+
+    #[test]
+    fn test_open_embedded_file() {
+        let spec = TestFileSpec {
+            segments: 3,
+            ..Default::default()
+        };
+        let tdms = spec.build().unwrap();
+        let mut container = vec![0xAA; 100];
+        container.extend_from_slice(&tdms);
+        container.extend_from_slice(&[0xBB; 50]);
+
+        let mut file =
+            TdmsFile::open_window(Cursor::new(container), 100, tdms.len() as u64).unwrap();
+        let mut expected = TdmsFile::new(Cursor::new(tdms)).unwrap();
+        let channel = spec.channel_path(0, 0);
+        let read: Vec<f64> = file.read_all(&channel).unwrap();
+        let expected: Vec<f64> = expected.read_all(&channel).unwrap();
+        assert_eq!(read, expected);
+    }
+}
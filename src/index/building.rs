@@ -4,31 +4,38 @@
 //! that need to be entered into the index.
 //!
 
+use std::sync::Arc;
+
 use crate::{
     error::TdmsError,
     meta_data::{ObjectMetaData, RawDataIndex, RawDataMeta, Segment},
-    paths::ObjectPath,
-    raw_data::DataBlock,
+    raw_data::{ChunkSize, DataBlock},
 };
 
-use super::{DataFormat, DataLocation, ObjectData, ObjectIndex};
+use super::{
+    DataBlockDebug, DataFormat, DataLocation, ObjectData, ObjectId, ObjectIndex, SegmentRecord,
+    UnsupportedChannel,
+};
 
 /// Data cached for the current "active" objects which are the objects
 /// that we are expecting data in the next data block.
 #[derive(Debug, Clone)]
 pub struct ActiveObject {
-    pub path: String,
+    /// The ID of the object in the registry.
+    pub id: ObjectId,
+    /// The path, shared with the object registry.
+    pub path: Arc<str>,
     pub number_of_samples: u64,
 }
 
 impl ActiveObject {
-    fn new(path: &str, format: &DataFormat) -> Self {
-        let path = path.to_string();
+    fn new(id: ObjectId, path: Arc<str>, format: &DataFormat) -> Self {
         let number_of_samples = match format {
             DataFormat::RawData(raw) => raw.number_of_values,
         };
 
         Self {
+            id,
             path,
             number_of_samples,
         }
@@ -41,16 +48,12 @@ impl ActiveObject {
 
     /// Fetch the corresponding [`ObjectData`] for the active object.
     fn get_object_data<'c>(&self, index: &'c ObjectIndex) -> &'c ObjectData {
-        index
-            .get(&self.path)
-            .expect("Should always have a registered version of active object")
+        index.by_id(self.id)
     }
 
     /// Fetch the corresponding [`ObjectData`] for the active object in a mutable form.
     fn get_object_data_mut<'c>(&self, index: &'c mut ObjectIndex) -> &'c mut ObjectData {
-        index
-            .get_mut(&self.path)
-            .expect("Should always have a registered version of active object")
+        index.by_id_mut(self.id)
     }
 }
 
@@ -59,9 +62,13 @@ impl super::Index {
     ///
     /// Returns the start position of the next segment.
     ///
+    /// Segments flagged as containing raw data but with no data bytes, such as
+    /// heartbeat segments, don't create a data block.
+    ///
     /// Errors if:
     /// * The next segment address overflows.
-    pub fn add_segment(&mut self, segment: Segment) -> Result<u64, TdmsError> {
+    /// * The segment has raw data but no channels to assign it to.
+    pub fn add_segment(&mut self, segment: &Segment) -> Result<u64, TdmsError> {
         //Basic procedure.
         //1. If new object list is set, clear active objects.
         //2. Update the active object list - adding new objects or updating properties and data locations for existing objects.
@@ -71,33 +78,84 @@ impl super::Index {
         }
 
         if let Some(meta_data) = &segment.meta_data {
+            let latest_timestamp = meta_data
+                .objects
+                .iter()
+                .flat_map(|object| object.properties.iter())
+                .filter_map(|(_, value)| value.as_timestamp())
+                .next_back();
+            if let Some(time) = latest_timestamp {
+                self.latest_timestamp = Some(time);
+            }
             meta_data
                 .objects
                 .iter()
                 .for_each(|obj| match obj.raw_data_index {
-                    RawDataIndex::None => self.update_meta_object(obj),
+                    RawDataIndex::None => {
+                        self.update_meta_object(obj);
+                    }
+                    RawDataIndex::Unsupported(type_code) => {
+                        self.activate_unsupported_object(obj, Some(type_code))
+                    }
+                    RawDataIndex::MatchPrevious
+                        if self.unsupported_channels.contains_key(obj.path.as_str())
+                            && !self.has_data_format(&obj.path) =>
+                    {
+                        self.activate_unsupported_object(obj, None)
+                    }
                     _ => self.update_or_activate_data_object(obj),
                 });
         }
 
-        if segment.toc.contains_raw_data {
+        if segment.toc.contains_raw_data && !self.active_unsupported.is_empty() {
+            // We can't tell where any channel is in this block so skip it.
+            for path in &self.active_unsupported {
+                if let Some(unsupported) = self.unsupported_channels.get_mut(path) {
+                    unsupported.skipped_data_blocks += 1;
+                }
+            }
+        }
+        let mut channels = Vec::new();
+        if segment.toc.contains_raw_data && self.active_unsupported.is_empty() {
             let active_data_channels = self.get_active_raw_data_meta();
+            let data_block =
+                DataBlock::from_segment(segment, self.next_segment_start, active_data_channels);
 
-            if active_data_channels.is_empty() {
+            // Heartbeat segments can flag raw data without writing any, so these are not data blocks.
+            if data_block.length > 0 && data_block.channels.is_empty() {
                 return Err(TdmsError::SegmentTocDataBlockWithoutDataChannels);
             }
+            if data_block.length > 0 && data_block.chunk_size() != ChunkSize::Fixed(0) {
+                channels = self
+                    .active_objects
+                    .iter()
+                    .map(|object| object.path.clone())
+                    .collect();
+                self.push_data_block(segment, data_block);
+            }
+        }
 
-            let data_block =
-                DataBlock::from_segment(&segment, self.next_segment_start, active_data_channels);
-
-            self.insert_data_block(data_block);
+        self.segment_count += 1;
+        if segment.toc.big_endian {
+            self.big_endian_segment_count += 1;
         }
 
         let segment_size = segment.total_size_bytes()?;
+        let segment_start = self.next_segment_start;
         match self.next_segment_start.checked_add(segment_size) {
             Some(next_segment_start) => self.next_segment_start = next_segment_start,
             None => return Err(TdmsError::SegmentAddressOverflow),
         }
+        self.segment_records.push(SegmentRecord {
+            index: self.segment_count - 1,
+            offset: segment_start,
+            size: segment_size,
+            meta_data_bytes: segment.raw_data_offset,
+            data_bytes: segment
+                .next_segment_offset
+                .saturating_sub(segment.raw_data_offset),
+            channels,
+        });
         Ok(self.next_segment_start)
     }
 
@@ -117,6 +175,21 @@ impl super::Index {
             .collect()
     }
 
+    /// Record the data block for the segment being added.
+    fn push_data_block(&mut self, segment: &Segment, data_block: DataBlock) {
+        self.data_block_debug.push(DataBlockDebug {
+            segment_index: self.segment_count,
+            segment_start: self.next_segment_start,
+            toc: segment.toc,
+            next_segment_offset: segment.next_segment_offset,
+            raw_data_offset: segment.raw_data_offset,
+            data_start: data_block.start,
+            data_length: data_block.length,
+        });
+        self.data_block_written_at.push(self.latest_timestamp);
+        self.insert_data_block(data_block);
+    }
+
     fn insert_data_block(&mut self, block: DataBlock) {
         let data_index = self.data_blocks.len();
 
@@ -126,7 +199,7 @@ impl super::Index {
         self.data_blocks.push(block);
 
         for (channel_index, active_object) in self.active_objects.iter_mut().enumerate() {
-            let number_of_samples = active_object.number_of_samples * chunks as u64;
+            let number_of_samples = active_object.number_of_samples * chunks;
             let location = DataLocation {
                 data_block: data_index,
                 channel_index,
@@ -143,62 +216,83 @@ impl super::Index {
     /// Panics if the object was already listed as inactive.
     fn deactivate_all_objects(&mut self) {
         self.active_objects.clear();
+        self.active_unsupported.clear();
+    }
+
+    fn has_data_format(&self, path: &str) -> bool {
+        self.objects
+            .get(path)
+            .is_some_and(|object| object.latest_data_format.is_some())
+    }
+
+    /// Register an object with data we can't decode and add it to the data layout.
+    ///
+    /// `type_code` is None when the object matches its previous unsupported format.
+    fn activate_unsupported_object(&mut self, object: &ObjectMetaData, type_code: Option<u32>) {
+        let id = self.update_meta_object(object);
+        let object_data = self.objects.by_id_mut(id);
+        // The latest format is no longer one we can read.
+        object_data.latest_data_format = None;
+        let path = object_data.path.clone();
+
+        self.active_objects
+            .retain(|active| *active.path != *object.path);
+        if !self.active_unsupported.contains(&path) {
+            self.active_unsupported.push(path.clone());
+        }
+
+        let unsupported =
+            self.unsupported_channels
+                .entry(path)
+                .or_insert_with(|| UnsupportedChannel {
+                    path: object.path.clone(),
+                    type_code: type_code.unwrap_or_default(),
+                    skipped_data_blocks: 0,
+                });
+        if let Some(type_code) = type_code {
+            unsupported.type_code = type_code;
+        }
     }
 
     /// Activate Data Object
     ///
     /// Adds the object by path to the active objects. Creates it if it doesn't exist.
     fn update_or_activate_data_object(&mut self, object: &ObjectMetaData) {
+        let id = self.update_meta_object(object);
         let matching_active = self
             .active_objects
             .iter_mut()
-            .find(|active_object| active_object.path == object.path);
+            .find(|active_object| active_object.id == id);
 
         match matching_active {
-            Some(active_object) => {
-                active_object.update(object);
-                active_object
-                    .get_object_data_mut(&mut self.objects)
-                    .update(object);
-            }
+            Some(active_object) => active_object.update(object),
             None => {
-                self.update_meta_object(object);
+                let object_data = self.objects.by_id(id);
                 // Must fetch the latest format in case this is same as previous.
-                let format = self
-                    .channel_format(&object.path)
+                let format = object_data
+                    .latest_data_format
+                    .as_ref()
                     .expect("Should not reach this if there is no data with the object.");
 
                 self.active_objects
-                    .push(ActiveObject::new(&object.path, format));
+                    .push(ActiveObject::new(id, object_data.path.clone(), format));
             }
         }
     }
 
     /// Update Meta Only Object
     ///
-    /// Update an object which contains no data.
-    fn update_meta_object(&mut self, object: &ObjectMetaData) {
-        match self.objects.get_mut(&object.path) {
-            Some(found_object) => found_object.update(object),
-            None => {
-                let object_data = ObjectData::from_metadata(object);
-                let old = self.objects.insert(object_data.path.clone(), object_data);
-                assert!(
-                    old.is_none(),
-                    "Should not be possible to be replacing an existing object."
-                );
+    /// Update an object which contains no data, registering it if it is new.
+    /// Returns the ID of the object.
+    fn update_meta_object(&mut self, object: &ObjectMetaData) -> ObjectId {
+        match self.objects.id(object.path.as_str()) {
+            Some(id) => {
+                self.objects.by_id_mut(id).update(object);
+                id
             }
+            None => self.objects.insert(ObjectData::from_metadata(object)),
         }
     }
-
-    /// Get the current format for the channel.
-    ///
-    /// Returns none if we have no channel.
-    fn channel_format(&self, path: ObjectPath) -> Option<&DataFormat> {
-        self.objects
-            .get(path)
-            .and_then(|object| object.latest_data_format.as_ref())
-    }
 }
 
 #[cfg(test)]
@@ -252,7 +346,7 @@ mod tests {
         };
 
         let mut index = Index::new();
-        index.add_segment(segment).unwrap();
+        index.add_segment(&segment).unwrap();
 
         let group_properties = index
             .get_object_properties(&PropertyPath::group("group"))
@@ -262,14 +356,14 @@ mod tests {
             &[(&"Prop".to_string(), &PropertyValue::I32(-51))]
         );
         let ch1_properties = index
-            .get_object_properties(&ChannelPath::new("group", "ch1").as_ref())
+            .get_object_properties(ChannelPath::new("group", "ch1").as_ref())
             .unwrap();
         assert_eq!(
             ch1_properties,
             &[(&String::from("Prop1"), &PropertyValue::I32(-1))]
         );
         let ch2_properties = index
-            .get_object_properties(&ChannelPath::new("group", "ch2").as_ref())
+            .get_object_properties(ChannelPath::new("group", "ch2").as_ref())
             .unwrap();
         assert_eq!(
             ch2_properties,
@@ -345,7 +439,7 @@ mod tests {
         };
 
         let mut index = Index::new();
-        index.add_segment(segment).unwrap();
+        index.add_segment(&segment).unwrap();
 
         let group_properties = index
             .get_object_properties(&PropertyPath::group("group"))
@@ -355,14 +449,14 @@ mod tests {
             &[(&"Prop".to_string(), &PropertyValue::I32(-51))]
         );
         let ch1_properties = index
-            .get_object_properties(&ChannelPath::new("group", "ch1").as_ref())
+            .get_object_properties(ChannelPath::new("group", "ch1").as_ref())
             .unwrap();
         assert_eq!(
             ch1_properties,
             &[(&String::from("Prop1"), &PropertyValue::I32(-1))]
         );
         let ch2_properties = index
-            .get_object_properties(&ChannelPath::new("group", "ch2").as_ref())
+            .get_object_properties(ChannelPath::new("group", "ch2").as_ref())
             .unwrap();
         assert_eq!(
             ch2_properties,
@@ -437,7 +531,7 @@ mod tests {
         };
 
         let mut index = Index::new();
-        index.add_segment(segment).unwrap();
+        index.add_segment(&segment).unwrap();
 
         let expected_data_block = DataBlock {
             start: 48,
@@ -517,8 +611,8 @@ mod tests {
             }),
         };
         let mut index = Index::new();
-        index.add_segment(segment).unwrap();
-        index.add_segment(segment2).unwrap();
+        index.add_segment(&segment).unwrap();
+        index.add_segment(&segment2).unwrap();
 
         let expected_data_block = DataBlock {
             start: 576,
@@ -598,8 +692,8 @@ mod tests {
             }),
         };
         let mut index = Index::new();
-        index.add_segment(segment).unwrap();
-        index.add_segment(segment2).unwrap();
+        index.add_segment(&segment).unwrap();
+        index.add_segment(&segment2).unwrap();
 
         let expected_data_block = DataBlock {
             start: 576,
@@ -640,7 +734,7 @@ mod tests {
         };
 
         let mut index = Index::new();
-        index.add_segment(segment).unwrap();
+        index.add_segment(&segment).unwrap();
 
         let block = index.get_data_block(0);
         assert_eq!(block, None);
@@ -702,8 +796,8 @@ mod tests {
         };
 
         let mut index = Index::new();
-        index.add_segment(segment).unwrap();
-        index.add_segment(segment2).unwrap();
+        index.add_segment(&segment).unwrap();
+        index.add_segment(&segment2).unwrap();
 
         let group_properties = index
             .get_object_properties(&PropertyPath::group("group"))
@@ -770,8 +864,8 @@ mod tests {
         };
 
         let mut index = Index::new();
-        index.add_segment(segment).unwrap();
-        index.add_segment(segment2).unwrap();
+        index.add_segment(&segment).unwrap();
+        index.add_segment(&segment2).unwrap();
 
         let group_properties = index
             .get_object_properties(&PropertyPath::group("group"))
@@ -876,8 +970,8 @@ mod tests {
         };
 
         let mut index = Index::new();
-        index.add_segment(segment).unwrap();
-        index.add_segment(segment2).unwrap();
+        index.add_segment(&segment).unwrap();
+        index.add_segment(&segment2).unwrap();
 
         let ch1_data = index
             .get_channel_data_positions(&ChannelPath::new("group", "ch1"))
@@ -960,8 +1054,8 @@ mod tests {
         };
 
         let mut index = Index::new();
-        index.add_segment(segment).unwrap();
-        index.add_segment(segment2).unwrap();
+        index.add_segment(&segment).unwrap();
+        index.add_segment(&segment2).unwrap();
 
         let ch1_data = index
             .get_channel_data_positions(&ChannelPath::new("group", "ch1"))
@@ -1053,8 +1147,8 @@ mod tests {
         };
 
         let mut index = Index::new();
-        index.add_segment(segment).unwrap();
-        index.add_segment(segment2).unwrap();
+        index.add_segment(&segment).unwrap();
+        index.add_segment(&segment2).unwrap();
 
         let ch3_properties = index
             .get_object_properties(ChannelPath::new("group", "ch3").as_ref())
@@ -1165,8 +1259,8 @@ mod tests {
         };
 
         let mut index = Index::new();
-        index.add_segment(segment).unwrap();
-        index.add_segment(segment2).unwrap();
+        index.add_segment(&segment).unwrap();
+        index.add_segment(&segment2).unwrap();
 
         let ch3_properties = index
             .get_object_properties(ChannelPath::new("group", "ch3").as_ref())
@@ -1279,9 +1373,9 @@ mod tests {
         };
 
         let mut index = Index::new();
-        index.add_segment(segment).unwrap();
-        index.add_segment(segment2).unwrap();
-        index.add_segment(segment3).unwrap();
+        index.add_segment(&segment).unwrap();
+        index.add_segment(&segment2).unwrap();
+        index.add_segment(&segment3).unwrap();
 
         let ch1_data = index
             .get_channel_data_positions(&ChannelPath::new("group", "ch1"))
@@ -1332,6 +1426,90 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_unsupported_channel_skips_blocks_until_new_list() {
+        let daqmx_segment = Segment {
+            toc: ToC::from_u32(0xE),
+            next_segment_offset: 100,
+            raw_data_offset: 20,
+            meta_data: Some(MetaData {
+                objects: vec![
+                    ObjectMetaData {
+                        path: "/'group'/'daqmx'".to_string(),
+                        properties: vec![("Prop".to_string(), PropertyValue::I32(1))],
+                        raw_data_index: RawDataIndex::Unsupported(0x6912_0000),
+                    },
+                    ObjectMetaData {
+                        path: "/'group'/'ch1'".to_string(),
+                        properties: vec![],
+                        raw_data_index: RawDataIndex::RawData(RawDataMeta {
+                            data_type: DataType::DoubleFloat,
+                            number_of_values: 10,
+                            total_size_bytes: None,
+                        }),
+                    },
+                ],
+            }),
+        };
+        let data_only_segment = Segment {
+            toc: ToC::from_u32(0x8),
+            next_segment_offset: 80,
+            raw_data_offset: 0,
+            meta_data: None,
+        };
+        let match_previous_segment = Segment {
+            toc: ToC::from_u32(0xA),
+            next_segment_offset: 100,
+            raw_data_offset: 20,
+            meta_data: Some(MetaData {
+                objects: vec![ObjectMetaData {
+                    path: "/'group'/'daqmx'".to_string(),
+                    properties: vec![],
+                    raw_data_index: RawDataIndex::MatchPrevious,
+                }],
+            }),
+        };
+        let new_list_segment = Segment {
+            toc: ToC::from_u32(0xE),
+            next_segment_offset: 100,
+            raw_data_offset: 20,
+            meta_data: Some(MetaData {
+                objects: vec![ObjectMetaData {
+                    path: "/'group'/'ch1'".to_string(),
+                    properties: vec![],
+                    raw_data_index: RawDataIndex::MatchPrevious,
+                }],
+            }),
+        };
+
+        let mut index = Index::new();
+        index.add_segment(&daqmx_segment).unwrap();
+        index.add_segment(&data_only_segment).unwrap();
+        index.add_segment(&match_previous_segment).unwrap();
+        index.add_segment(&new_list_segment).unwrap();
+
+        let unsupported: Vec<_> = index.unsupported_channels().collect();
+        assert_eq!(
+            unsupported,
+            vec![&UnsupportedChannel {
+                path: "/'group'/'daqmx'".to_string(),
+                type_code: 0x6912_0000,
+                skipped_data_blocks: 3,
+            }]
+        );
+        assert_eq!(index.data_block_count(), 1);
+        assert_eq!(
+            index.channel_length(&ChannelPath::new("group", "ch1")),
+            Some(10)
+        );
+        assert_eq!(
+            index
+                .get_object_property(ChannelPath::new("group", "daqmx").as_ref(), "Prop")
+                .unwrap(),
+            Some(&PropertyValue::I32(1))
+        );
+    }
+
     #[test]
     fn test_toc_includes_data_but_no_active_channels() {
         let segment = Segment {
@@ -1348,7 +1526,7 @@ mod tests {
         };
 
         let mut index = Index::new();
-        let next_segment = index.add_segment(segment);
+        let next_segment = index.add_segment(&segment);
         assert!(matches!(
             next_segment,
             Err(TdmsError::SegmentTocDataBlockWithoutDataChannels)
@@ -1371,7 +1549,7 @@ mod tests {
         };
 
         let mut index = Index::new();
-        let next_segment = index.add_segment(segment);
+        let next_segment = index.add_segment(&segment);
         assert!(matches!(
             next_segment,
             Err(TdmsError::SegmentAddressOverflow)
@@ -1379,7 +1557,7 @@ mod tests {
     }
 
     #[test]
-    fn test_toc_includes_data_but_no_size() {
+    fn test_toc_includes_data_but_no_size_is_heartbeat() {
         let segment = Segment {
             toc: ToC::from_u32(0xE),
             next_segment_offset: 20,
@@ -1394,10 +1572,73 @@ mod tests {
         };
 
         let mut index = Index::new();
-        let next_segment = index.add_segment(segment);
-        assert!(matches!(
-            next_segment,
-            Err(TdmsError::SegmentTocDataBlockWithoutDataChannels)
-        ));
+        let next_segment = index.add_segment(&segment).unwrap();
+        assert_eq!(next_segment, 48);
+        assert_eq!(index.data_block_count(), 0);
+    }
+
+    #[test]
+    fn test_empty_blocks_for_active_channels_are_skipped() {
+        let channel = |values: u64| ObjectMetaData {
+            path: "/'group'/'ch1'".to_string(),
+            properties: vec![],
+            raw_data_index: RawDataIndex::RawData(RawDataMeta {
+                data_type: DataType::DoubleFloat,
+                number_of_values: values,
+                total_size_bytes: None,
+            }),
+        };
+        let segment = |values: u64, data_bytes: u64| Segment {
+            toc: ToC::from_u32(0xE),
+            next_segment_offset: 20 + data_bytes,
+            raw_data_offset: 20,
+            meta_data: Some(MetaData {
+                objects: vec![channel(values)],
+            }),
+        };
+
+        let mut index = Index::new();
+        index.add_segment(&segment(10, 80)).unwrap();
+        // A heartbeat with no data and a block where the channel has no values.
+        index.add_segment(&segment(10, 0)).unwrap();
+        index.add_segment(&segment(0, 80)).unwrap();
+        index.add_segment(&segment(10, 80)).unwrap();
+
+        assert_eq!(index.data_block_count(), 2);
+        assert_eq!(
+            index.channel_length(&ChannelPath::new("group", "ch1")),
+            Some(20)
+        );
+    }
+
+    #[test]
+    fn test_active_objects_share_registry_path() {
+        let segment = Segment {
+            toc: ToC::from_u32(0xE),
+            next_segment_offset: 8020,
+            raw_data_offset: 20,
+            meta_data: Some(MetaData {
+                objects: vec![ObjectMetaData {
+                    path: "/'group'/'ch1'".to_string(),
+                    properties: vec![],
+                    raw_data_index: RawDataIndex::RawData(RawDataMeta {
+                        data_type: DataType::DoubleFloat,
+                        number_of_values: 1000,
+                        total_size_bytes: None,
+                    }),
+                }],
+            }),
+        };
+
+        let mut index = Index::new();
+        index.add_segment(&segment).unwrap();
+        index.add_segment(&segment).unwrap();
+
+        let registry_path = index.objects.keys().next().unwrap();
+        assert!(Arc::ptr_eq(registry_path, &index.active_objects[0].path));
+        assert_eq!(
+            index.objects.id("/'group'/'ch1'"),
+            Some(index.active_objects[0].id)
+        );
     }
 }
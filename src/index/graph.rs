@@ -0,0 +1,96 @@
+//! Render the structure of the index as a Graphviz DOT graph.
+//!
+//! This is a debugging aid. Attaching the graph of an odd file to an issue
+//! shows how its objects and data blocks link together without sharing the
+//! data itself.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::paths::{parse_path, GroupPath};
+
+impl super::Index {
+    /// Render the objects, their property counts and the data blocks they have data in.
+    ///
+    /// Objects are boxes linked to their parent group or file. Data blocks are
+    /// ellipses, with an edge from each channel to every block holding its data.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph tdms {\n    rankdir=LR;\n");
+
+        let ids: BTreeMap<&str, usize> = self
+            .objects
+            .keys()
+            .enumerate()
+            .map(|(id, path)| (path.as_ref(), id))
+            .collect();
+
+        for (path, object) in self.objects.iter() {
+            let id = ids[path.as_ref()];
+            let _ = writeln!(
+                dot,
+                "    o{id} [shape=box, label=\"{}\\n{} properties\"];",
+                escape(path),
+                object.properties.len()
+            );
+            if let Some(parent) = parent_path(path).and_then(|parent| ids.get(parent.as_str())) {
+                let _ = writeln!(dot, "    o{parent} -> o{id};");
+            }
+        }
+
+        for (index, block) in self.data_blocks.iter().enumerate() {
+            let _ = writeln!(
+                dot,
+                "    b{index} [shape=ellipse, label=\"block {index}\\n{} bytes at {}\\n{}\"];",
+                block.length, block.start, block.layout
+            );
+        }
+
+        for (path, object) in self.objects.iter() {
+            let id = ids[path.as_ref()];
+            for location in &object.data_locations {
+                let _ = writeln!(
+                    dot,
+                    "    o{id} -> b{} [style=dashed, label=\"#{}: {} samples\"];",
+                    location.data_block, location.channel_index, location.number_of_samples
+                );
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// The path of the group or file containing the object.
+fn parent_path(path: &str) -> Option<String> {
+    match parse_path(path).ok()? {
+        (Some(group), Some(_)) => Some(GroupPath::new(group).path().to_owned()),
+        (Some(_), None) => Some("/".to_owned()),
+        _ => None,
+    }
+}
+
+/// Escape a label for a quoted DOT string.
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_quotes_and_backslashes() {
+        assert_eq!(escape(r#"/'a"b'/'c\d'"#), r#"/'a\"b'/'c\\d'"#);
+    }
+
+    #[test]
+    fn test_parent_paths() {
+        assert_eq!(parent_path("/"), None);
+        assert_eq!(parent_path("/'group'").as_deref(), Some("/"));
+        assert_eq!(
+            parent_path("/'group'/'channel'").as_deref(),
+            Some("/'group'")
+        );
+    }
+}
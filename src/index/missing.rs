@@ -0,0 +1,247 @@
+//! Errors for objects and properties which aren't in the file.
+//!
+//! A GUI needs to know whether it was the group, the channel or the property
+//! that was missing to show a useful message, and a typo is the most common
+//! cause, so each error lists the existing names closest to the one asked for.
+
+use std::collections::BTreeSet;
+
+use super::Index;
+use crate::error::TdmsError;
+use crate::paths::{parse_path, ChannelPath, GroupPath, ObjectPath, PropertyPath, RawPath};
+
+/// The most suggestions included in an error.
+const MAX_SUGGESTIONS: usize = 3;
+
+impl Index {
+    /// The error for a channel which isn't in the index.
+    ///
+    /// This is [`TdmsError::MissingGroup`] if the group isn't in the index
+    /// either, or otherwise [`TdmsError::MissingChannel`].
+    pub fn missing_channel(&self, channel: &ChannelPath) -> TdmsError {
+        missing_channel_error(channel, self.all_paths())
+    }
+
+    /// The error for a group which isn't in the index.
+    #[cfg_attr(not(feature = "analysis"), allow(dead_code))]
+    pub fn missing_group(&self, group: &GroupPath) -> TdmsError {
+        missing_group_error(group, self.all_paths())
+    }
+
+    /// The error for a property which isn't set, or the error for the object if that is missing.
+    pub fn missing_property(&self, path: &PropertyPath, property: &str) -> TdmsError {
+        let Some(object) = self.objects.get(path.path()) else {
+            if let Some(error) = self.missing_object(path) {
+                return error;
+            }
+            // The file object is implied so it just has no properties.
+            return TdmsError::MissingProperty {
+                path: path.path().to_owned(),
+                property: property.to_owned(),
+                suggestions: vec![],
+            };
+        };
+        TdmsError::MissingProperty {
+            path: path.path().to_owned(),
+            property: property.to_owned(),
+            suggestions: nearest(property, object.properties.keys().map(String::as_str))
+                .into_iter()
+                .map(str::to_owned)
+                .collect(),
+        }
+    }
+
+    /// The error for an object which isn't in the index.
+    ///
+    /// Returns None for the file object, which always exists even if it was never written.
+    pub fn missing_object(&self, path: &PropertyPath) -> Option<TdmsError> {
+        missing_object_error(path.path(), self.all_paths())
+    }
+}
+
+/// The error for the object at `path` given the paths which do exist.
+///
+/// Returns None for the file object. An invalid path returns its parse error.
+pub(crate) fn missing_object_error<'a>(
+    path: RawPath<'_>,
+    existing: impl Iterator<Item = RawPath<'a>>,
+) -> Option<TdmsError> {
+    match ObjectPath::parse(path) {
+        Ok(ObjectPath::File) => None,
+        Ok(ObjectPath::Group(group)) => Some(missing_group_error(&group, existing)),
+        Ok(ObjectPath::Channel(channel)) => Some(missing_channel_error(&channel, existing)),
+        Err(error) => Some(error),
+    }
+}
+
+fn missing_group_error<'a>(
+    group: &GroupPath,
+    existing: impl Iterator<Item = RawPath<'a>>,
+) -> TdmsError {
+    let name = group_name(group.path()).unwrap_or_default();
+    let groups: BTreeSet<&str> = existing.filter_map(group_name).collect();
+    TdmsError::MissingGroup {
+        group: group.clone(),
+        suggestions: nearest(name, groups.into_iter())
+            .into_iter()
+            .map(GroupPath::new)
+            .collect(),
+    }
+}
+
+fn missing_channel_error<'a>(
+    channel: &ChannelPath,
+    existing: impl Iterator<Item = RawPath<'a>>,
+) -> TdmsError {
+    let Ok((Some(group), Some(name))) = parse_path(channel.path()) else {
+        return TdmsError::InvalidChannelPath(channel.path().to_owned());
+    };
+
+    let mut group_exists = false;
+    let mut channels = BTreeSet::new();
+    let existing: Vec<RawPath<'a>> = existing.collect();
+    for path in &existing {
+        match parse_path(path) {
+            Ok((Some(existing_group), existing_channel)) if existing_group == group => {
+                group_exists = true;
+                channels.extend(existing_channel);
+            }
+            _ => {}
+        }
+    }
+    if !group_exists {
+        return missing_group_error(&GroupPath::new(group), existing.into_iter());
+    }
+
+    TdmsError::MissingChannel {
+        channel: channel.clone(),
+        suggestions: nearest(name, channels.into_iter())
+            .into_iter()
+            .map(|channel| ChannelPath::new(group, channel))
+            .collect(),
+    }
+}
+
+fn group_name(path: RawPath<'_>) -> Option<&str> {
+    parse_path(path).ok()?.0
+}
+
+/// The candidates close enough to `target` to be a likely typo, closest first.
+///
+/// Names are compared ignoring case. A candidate matches if it contains the
+/// target or the target contains it, or if a third of the characters or
+/// fewer need editing.
+fn nearest<'a>(target: &str, candidates: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let target_lower = target.to_lowercase();
+    let mut matches: Vec<(usize, &'a str)> = candidates
+        .filter_map(|candidate| {
+            let candidate_lower = candidate.to_lowercase();
+            let distance = edit_distance(&target_lower, &candidate_lower);
+            let limit = (target.chars().count().max(candidate.chars().count()) / 3).max(1);
+            let contains = !target_lower.is_empty()
+                && !candidate_lower.is_empty()
+                && (candidate_lower.contains(&target_lower)
+                    || target_lower.contains(&candidate_lower));
+            (distance <= limit || contains).then_some((distance, candidate))
+        })
+        .collect();
+    matches.sort();
+    matches.truncate(MAX_SUGGESTIONS);
+    matches
+        .into_iter()
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// The number of single character insertions, deletions or substitutions to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATHS: [&str; 5] = [
+        "/",
+        "/'Measurements'",
+        "/'Measurements'/'Voltage'",
+        "/'Measurements'/'Current'",
+        "/'Setup'/'Voltage'",
+    ];
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("voltage", "voltage"), 0);
+        assert_eq!(edit_distance("voltge", "voltage"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_nearest_orders_by_distance() {
+        let candidates = ["Voltage 2", "voltage", "Current", "Volts", "Voltage_1"];
+        assert_eq!(
+            nearest("Voltage", candidates.into_iter()),
+            vec!["voltage", "Voltage 2", "Voltage_1"]
+        );
+        assert!(nearest("Temperature", candidates.into_iter()).is_empty());
+    }
+
+    #[test]
+    fn test_missing_channel_in_existing_group() {
+        let error = missing_channel_error(
+            &ChannelPath::new("Measurements", "Votlage"),
+            PATHS.into_iter(),
+        );
+        let TdmsError::MissingChannel { suggestions, .. } = &error else {
+            panic!("unexpected error {error:?}");
+        };
+        assert_eq!(
+            suggestions,
+            &vec![ChannelPath::new("Measurements", "Voltage")]
+        );
+        assert!(error
+            .to_string()
+            .ends_with("Did you mean /'Measurements'/'Voltage'?"));
+    }
+
+    #[test]
+    fn test_missing_channel_in_missing_group() {
+        let error = missing_channel_error(
+            &ChannelPath::new("Measurement", "Voltage"),
+            PATHS.into_iter(),
+        );
+        let TdmsError::MissingGroup { group, suggestions } = error else {
+            panic!("unexpected error {error:?}");
+        };
+        assert_eq!(group, GroupPath::new("Measurement"));
+        assert_eq!(suggestions, vec![GroupPath::new("Measurements")]);
+    }
+
+    #[test]
+    fn test_group_implied_by_channel() {
+        let error = missing_channel_error(&ChannelPath::new("Setup", "Current"), PATHS.into_iter());
+        assert!(matches!(error, TdmsError::MissingChannel { .. }));
+    }
+
+    #[test]
+    fn test_file_object_is_never_missing() {
+        assert!(missing_object_error("/", PATHS.into_iter()).is_none());
+        assert!(matches!(
+            missing_object_error("/'Other'", PATHS.into_iter()),
+            Some(TdmsError::MissingGroup { .. })
+        ));
+    }
+}
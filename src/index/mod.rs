@@ -6,17 +6,32 @@
 //!
 //!
 mod building;
+mod graph;
+mod missing;
 mod querying;
+mod registry;
+mod stats;
+#[cfg(feature = "write")]
 mod writing;
 
 use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use labview_interop::types::LVTime;
 
 use crate::error::TdmsError;
-use crate::meta_data::{ObjectMetaData, RawDataIndex, RawDataMeta};
+use crate::io::data_types::DataType;
+use crate::meta_data::{ObjectMetaData, RawDataIndex, RawDataMeta, ToC};
 use crate::paths::{ChannelPath, PropertyPath};
 use crate::raw_data::DataBlock;
 use crate::PropertyValue;
 
+pub(crate) use missing::missing_object_error;
+use registry::{ObjectId, ObjectIndex};
+pub use stats::{FileStats, SegmentRecord};
+#[cfg(feature = "write")]
+pub use writing::ObjectListChange;
+
 /// A store for a given channel point to the data block with its data and the index within that.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DataLocation {
@@ -52,23 +67,72 @@ impl From<DataFormat> for RawDataIndex {
     }
 }
 
+/// The waveform timing properties of a channel.
+///
+/// These are the standard `wf_start_time`, `wf_increment` and optional
+/// `wf_start_offset` properties.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaveformTiming {
+    /// The time of the first sample, before the offset is added.
+    pub start_time: LVTime,
+    /// The seconds between samples.
+    pub increment: f64,
+    /// Seconds added to the start time. Zero if the property isn't set.
+    pub start_offset: f64,
+}
+
+impl WaveformTiming {
+    /// Read the timing from the properties of an object.
+    ///
+    /// Returns None if the start time or a positive increment is missing.
+    fn from_properties(properties: &BTreeMap<String, PropertyValue>) -> Option<Self> {
+        let start_time = match properties.get("wf_start_time")? {
+            PropertyValue::Timestamp(time) => *time,
+            _ => return None,
+        };
+        let increment = match properties.get("wf_increment")? {
+            PropertyValue::DoubleFloat(increment) if *increment > 0.0 => *increment,
+            _ => return None,
+        };
+        let start_offset = match properties.get("wf_start_offset") {
+            Some(PropertyValue::DoubleFloat(offset)) => *offset,
+            _ => 0.0,
+        };
+        Some(Self {
+            start_time,
+            increment,
+            start_offset,
+        })
+    }
+}
+
 /// Contains the data stored in the index for each object.
 #[derive(Clone, PartialEq, Debug)]
 struct ObjectData {
-    path: String,
+    path: Arc<str>,
     properties: BTreeMap<String, PropertyValue>,
     data_locations: Vec<DataLocation>,
+    /// The cumulative sample count at the end of each data location.
+    ///
+    /// This allows a binary search for the location of a sample.
+    location_ends: Vec<u64>,
     latest_data_format: Option<DataFormat>,
+    /// The waveform timing when the data location at each position was added.
+    ///
+    /// Only changes are stored so a restarted acquisition shows as a new entry.
+    timing_changes: Vec<(usize, WaveformTiming)>,
 }
 
 impl ObjectData {
     /// Create the object data from the file metadata.
     fn from_metadata(meta: &ObjectMetaData) -> Self {
         let mut new = Self {
-            path: meta.path.clone(),
+            path: Arc::from(meta.path.as_str()),
             properties: BTreeMap::new(),
             data_locations: vec![],
+            location_ends: vec![],
             latest_data_format: None,
+            timing_changes: vec![],
         };
 
         new.update(meta);
@@ -95,17 +159,77 @@ impl ObjectData {
 
     /// Add a new data location.
     fn add_data_location(&mut self, location: DataLocation) {
+        if let Some(timing) = WaveformTiming::from_properties(&self.properties) {
+            if self.timing_changes.last().map(|(_, last)| last) != Some(&timing) {
+                self.timing_changes
+                    .push((self.data_locations.len(), timing));
+            }
+        }
+        let end = self.length() + location.number_of_samples;
+        self.location_ends.push(end);
         self.data_locations.push(location);
     }
 
+    /// The total number of samples across all data locations.
+    fn length(&self) -> u64 {
+        self.location_ends.last().copied().unwrap_or(0)
+    }
+
+    /// Find the data location containing the sample.
+    ///
+    /// Returns the position in the data locations and the offset of the sample within that location.
+    fn find_sample(&self, sample: u64) -> Option<(usize, u64)> {
+        let position = self.location_ends.partition_point(|&end| end <= sample);
+        if position >= self.data_locations.len() {
+            return None;
+        }
+        let location_start = match position {
+            0 => 0,
+            _ => self.location_ends[position - 1],
+        };
+        Some((position, sample - location_start))
+    }
+
     /// Fetch all the properties as an array.
     fn get_all_properties(&self) -> Vec<(&String, &PropertyValue)> {
         self.properties.iter().collect()
     }
 }
 
-/// The inner format for registering the objects.
-type ObjectIndex = BTreeMap<String, ObjectData>;
+/// Details of the segment which a data block was read from.
+///
+/// This is intended for low level tools which need to explain exactly where data is in the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataBlockDebug {
+    /// The position of the segment in the file, counting from 0.
+    pub segment_index: u64,
+    /// The byte offset of the segment lead in.
+    pub segment_start: u64,
+    /// The table of contents from the lead in.
+    pub toc: ToC,
+    /// The next segment offset as recorded in the lead in.
+    pub next_segment_offset: u64,
+    /// The raw data offset as recorded in the lead in.
+    pub raw_data_offset: u64,
+    /// The byte offset of the raw data in the file.
+    pub data_start: u64,
+    /// The length of the raw data in bytes.
+    pub data_length: u64,
+}
+
+/// A channel with raw data that this library can't decode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedChannel {
+    /// The path of the channel.
+    pub path: String,
+    /// The raw data index tag for DAQmx data or the data type code.
+    pub type_code: u32,
+    /// The number of data blocks skipped because of this channel.
+    ///
+    /// The layout of a block containing an unsupported channel is unknown so
+    /// the data for every channel in the block is skipped.
+    pub skipped_data_blocks: u64,
+}
 
 #[derive(Default, Debug, Clone)]
 pub struct Index {
@@ -113,6 +237,23 @@ pub struct Index {
     objects: ObjectIndex,
     data_blocks: Vec<DataBlock>,
     next_segment_start: u64,
+    segment_count: u64,
+    big_endian_segment_count: u64,
+    /// Kept in step with `data_blocks` to explain where each block came from.
+    data_block_debug: Vec<DataBlockDebug>,
+    unsupported_channels: BTreeMap<Arc<str>, UnsupportedChannel>,
+    /// One record for every segment added.
+    segment_records: Vec<SegmentRecord>,
+    /// The end of the last segment known to be complete in the file.
+    ///
+    /// Data blocks past this are hidden until the snapshot is updated. `None` means unbounded.
+    snapshot_end: Option<u64>,
+    /// Unsupported channels in the current data layout.
+    active_unsupported: Vec<Arc<str>>,
+    /// The last timestamp property value written to any object.
+    latest_timestamp: Option<LVTime>,
+    /// Kept in step with `data_blocks` with the latest timestamp when each block was written.
+    data_block_written_at: Vec<Option<LVTime>>,
 }
 
 impl Index {
@@ -134,46 +275,228 @@ impl Index {
 
     /// Get the property value for the given object.
     ///
-    /// Errors if the object does not exist.
+    /// Errors if the group or channel does not exist.
     /// Will contain a None if the property does not exist.
     pub fn get_object_property(
         &self,
         path: &PropertyPath,
         property: &str,
     ) -> Result<Option<&PropertyValue>, TdmsError> {
-        let property = self
-            .objects
-            .get(path.path())
-            .ok_or_else(|| TdmsError::MissingObject(path.path().to_owned()))?
-            .properties
-            .get(property);
-
-        Ok(property)
+        match self.objects.get(path.path()) {
+            Some(object) => Ok(object.properties.get(property)),
+            None => match self.missing_object(path) {
+                Some(error) => Err(error),
+                None => Ok(None),
+            },
+        }
     }
 
+    /// Get the data locations of the channel inside the snapshot.
     pub fn get_channel_data_positions(&self, path: &ChannelPath) -> Option<&[DataLocation]> {
         self.objects
             .get(path.path())
-            .map(|object| &object.data_locations[..])
+            .map(|object| &object.data_locations[..self.snapshot_locations(object)])
     }
 
-    /// Get the length of the channel.
+    /// Get the length of the channel inside the snapshot.
     ///
     /// Returns None if the channel does not exist.
     pub fn channel_length(&self, path: &ChannelPath) -> Option<u64> {
+        self.objects
+            .get(path.path())
+            .map(|object| match self.snapshot_locations(object) {
+                0 => 0,
+                locations => object.location_ends[locations - 1],
+            })
+    }
+
+    /// Get the sample each data location of the channel inside the snapshot starts at.
+    ///
+    /// Returns None if the channel does not exist.
+    pub fn channel_block_starts(&self, path: &ChannelPath) -> Option<Vec<u64>> {
         self.objects.get(path.path()).map(|object| {
-            object
-                .data_locations
+            let locations = self.snapshot_locations(object);
+            std::iter::once(0)
+                .chain(object.location_ends.iter().copied())
+                .take(locations)
+                .collect()
+        })
+    }
+
+    /// Get the latest data type written for the channel.
+    ///
+    /// Returns None if the channel does not exist or has never had data.
+    pub fn channel_data_type(&self, path: &ChannelPath) -> Option<DataType> {
+        self.objects
+            .get(path.path())
+            .and_then(|object| object.latest_data_format.as_ref())
+            .map(|format| match format {
+                DataFormat::RawData(raw) => raw.data_type,
+            })
+    }
+
+    /// Get the waveform timing of the channel with the sample each timing starts at.
+    ///
+    /// A new entry starts wherever the timing properties changed between data
+    /// blocks, such as when an acquisition restarts. Only data inside the
+    /// snapshot is included. The first entry always starts at sample 0 so
+    /// data written before the timing properties takes the first timing, and
+    /// if the properties were only written after all the data the current
+    /// timing is used.
+    ///
+    /// Returns None if the channel does not exist.
+    #[cfg_attr(not(feature = "analysis"), allow(dead_code))]
+    pub fn channel_timing_changes(&self, path: &ChannelPath) -> Option<Vec<(u64, WaveformTiming)>> {
+        self.objects.get(path.path()).map(|object| {
+            let locations = self.snapshot_locations(object);
+            let mut changes: Vec<(u64, WaveformTiming)> = object
+                .timing_changes
                 .iter()
-                .map(|location| location.number_of_samples)
-                .sum()
+                .take_while(|(location, _)| *location < locations)
+                .map(|(location, timing)| {
+                    let start = match location {
+                        0 => 0,
+                        location => object.location_ends[location - 1],
+                    };
+                    (start, *timing)
+                })
+                .collect();
+            match changes.first_mut() {
+                Some((start, _)) => *start = 0,
+                None => changes.extend(
+                    WaveformTiming::from_properties(&object.properties).map(|timing| (0, timing)),
+                ),
+            }
+            changes
+        })
+    }
+
+    /// Find the data location containing the sample index for the channel.
+    ///
+    /// Returns the position in [`Self::get_channel_data_positions`] and the offset of the sample
+    /// within that location. Returns None if the channel does not exist or the sample is beyond the end.
+    pub fn find_sample_location(&self, path: &ChannelPath, sample: u64) -> Option<(usize, u64)> {
+        self.objects.get(path.path()).and_then(|object| {
+            object
+                .find_sample(sample)
+                .filter(|(position, _)| *position < self.snapshot_locations(object))
         })
     }
 
     pub fn get_data_block(&self, index: usize) -> Option<&DataBlock> {
         self.data_blocks.get(index)
     }
+
+    /// The end of the last segment which was complete when the file was scanned.
+    ///
+    /// Reads are bounded by this offset so data still being written by another
+    /// process is never read. None if the index isn't bounded.
+    pub fn snapshot_end(&self) -> Option<u64> {
+        self.snapshot_end
+    }
+
+    /// Set the snapshot to the end of the last segment which fits in `file_length` bytes.
+    pub fn update_snapshot(&mut self, file_length: u64) {
+        self.snapshot_end = Some(self.snapshot_end_within(file_length));
+    }
+
+    /// The end of the last segment which fits in `file_length` bytes.
+    pub fn snapshot_end_within(&self, file_length: u64) -> u64 {
+        self.segment_records
+            .iter()
+            .rev()
+            .map(|record| record.offset + record.size)
+            .find(|end| *end <= file_length)
+            .unwrap_or(0)
+    }
+
+    /// The number of data locations of the object in blocks inside the snapshot.
+    ///
+    /// Blocks are in file order so the hidden locations are always at the end.
+    fn snapshot_locations(&self, object: &ObjectData) -> usize {
+        let Some(snapshot_end) = self.snapshot_end else {
+            return object.data_locations.len();
+        };
+        object.data_locations.partition_point(|location| {
+            let block = &self.data_blocks[location.data_block];
+            block.start + block.length <= snapshot_end
+        })
+    }
+
+    /// The position in the file where the next segment will start.
+    pub fn next_segment_start(&self) -> u64 {
+        self.next_segment_start
+    }
+
+    /// The number of data blocks in the index.
+    pub fn data_block_count(&self) -> usize {
+        self.data_blocks.len()
+    }
+
+    /// Get the details of the segment that the data block was read from.
+    pub fn get_data_block_debug(&self, index: usize) -> Option<&DataBlockDebug> {
+        self.data_block_debug.get(index)
+    }
+
+    /// Get the latest timestamp property written at or before the data block.
+    pub fn get_data_block_written_at(&self, index: usize) -> Option<LVTime> {
+        self.data_block_written_at.get(index).copied().flatten()
+    }
+
+    /// The channels with raw data that couldn't be decoded, such as DAQmx raw data.
+    pub fn unsupported_channels(&self) -> impl Iterator<Item = &UnsupportedChannel> {
+        self.unsupported_channels.values()
+    }
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+
+    fn object_with_locations(lengths: &[u64]) -> ObjectData {
+        let mut object = ObjectData {
+            path: "/'group'/'ch1'".into(),
+            properties: BTreeMap::new(),
+            data_locations: vec![],
+            location_ends: vec![],
+            latest_data_format: None,
+            timing_changes: vec![],
+        };
+        for (data_block, &number_of_samples) in lengths.iter().enumerate() {
+            object.add_data_location(DataLocation {
+                data_block,
+                channel_index: 0,
+                number_of_samples,
+            });
+        }
+        object
+    }
+
+    #[test]
+    fn test_length_from_locations() {
+        let object = object_with_locations(&[100, 50, 25]);
+        assert_eq!(object.length(), 175);
+    }
+
+    #[test]
+    fn test_find_sample_at_boundaries() {
+        let object = object_with_locations(&[100, 50, 25]);
+        assert_eq!(object.find_sample(0), Some((0, 0)));
+        assert_eq!(object.find_sample(99), Some((0, 99)));
+        assert_eq!(object.find_sample(100), Some((1, 0)));
+        assert_eq!(object.find_sample(160), Some((2, 10)));
+        assert_eq!(object.find_sample(175), None);
+    }
+
+    #[test]
+    fn test_find_sample_skips_empty_locations() {
+        let object = object_with_locations(&[10, 0, 10]);
+        assert_eq!(object.find_sample(10), Some((2, 0)));
+    }
+
+    #[test]
+    fn test_find_sample_empty() {
+        let object = object_with_locations(&[]);
+        assert_eq!(object.find_sample(0), None);
+    }
+}
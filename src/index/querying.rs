@@ -7,7 +7,9 @@
 use std::ops::Bound;
 
 use super::Index;
-use crate::paths::ObjectPath;
+use crate::paths::{path_group_name, RawPath};
+use crate::PropertyValue;
+use crate::{ChannelPath, GroupPath, ObjectPath};
 
 /// Implement methods for getting the various objects from the index.
 ///
@@ -15,24 +17,76 @@ use crate::paths::ObjectPath;
 /// as that isn't a concept in the index.
 impl Index {
     /// Get all of the objects stored in the index.
-    pub fn all_paths(&self) -> impl Iterator<Item = ObjectPath> {
-        self.objects.keys().map(|path| path.as_str())
+    pub fn all_paths(&self) -> impl Iterator<Item = RawPath<'_>> {
+        self.objects.keys().map(|path| path.as_ref())
+    }
+
+    /// Get the path and length of every object which has a data format.
+    #[cfg_attr(not(any(feature = "write", feature = "watch")), allow(dead_code))]
+    pub fn data_object_lengths(&self) -> impl Iterator<Item = (RawPath<'_>, u64)> {
+        self.objects
+            .iter()
+            .filter(|(_, object)| object.latest_data_format.is_some())
+            .map(|(path, object)| (path.as_ref(), object.length()))
+    }
+
+    /// Get every object which has the property `name` with a value matching the predicate.
+    ///
+    /// Objects are returned in path order.
+    pub fn find_objects_with_property<'a>(
+        &'a self,
+        name: &'a str,
+        predicate: impl Fn(&PropertyValue) -> bool + 'a,
+    ) -> impl Iterator<Item = RawPath<'a>> + 'a {
+        self.objects
+            .iter()
+            .filter(move |(_, object)| object.properties.get(name).is_some_and(&predicate))
+            .map(|(path, _)| path.as_ref())
+    }
+
+    /// Get every object in the index as a typed path.
+    ///
+    /// Paths which can't be parsed are skipped.
+    pub fn objects(&self) -> impl Iterator<Item = ObjectPath> + '_ {
+        self.all_paths()
+            .filter_map(|path| ObjectPath::parse(path).ok())
+    }
+
+    /// Get the names of every group, including groups only implied by channel paths.
+    ///
+    /// Groups are returned in name order.
+    pub fn group_names(&self) -> impl Iterator<Item = &str> {
+        self.objects.group_names()
+    }
+
+    /// Get the channels in the group in the order they were first written.
+    ///
+    /// This only visits the channels in the group, using the group map in the registry.
+    pub fn channels_in_group<'a: 'b, 'b>(
+        &'a self,
+        group: &'b GroupPath,
+    ) -> impl Iterator<Item = ChannelPath> + 'b {
+        path_group_name(group.path())
+            .into_iter()
+            .flat_map(|name| self.objects.group_channels(name))
+            .filter_map(|object| ChannelPath::try_from(object.path.as_ref()).ok())
     }
 
     /// Get all of the objects that start with the given path.
     ///
     /// This is seperated as we may be able to use techiques in the index to speed this up.
+    #[cfg_attr(not(feature = "write"), allow(dead_code))]
     pub fn paths_starting_with<'a: 'b, 'b>(
         &'a self,
-        path: ObjectPath<'b>,
-    ) -> impl Iterator<Item = ObjectPath> + 'b {
+        path: RawPath<'b>,
+    ) -> impl Iterator<Item = RawPath<'a>> + 'b {
         // Since we use a BTree we can use ranges of strings to filter the interesting paths.
         // Lower range is our prefix.
         // An upper range is the prefix but with the last character incremented. This isn't trivial so we have
         // stuck with take_while as the performance benefit is likely to be low.
         self.objects
-            .range::<String, _>((Bound::Included(path.to_string()), Bound::Unbounded))
-            .map(|(path, _)| path.as_str())
+            .range((Bound::Included(path), Bound::Unbounded))
+            .map(|(path, _)| path.as_ref())
             .take_while(move |p| p.starts_with(path))
     }
 }
@@ -50,13 +104,13 @@ mod tests {
 
     /// Generate a test file with no data but a few objects.
     ///
-    /// group
+    /// - group
     ///   - ch1
     ///   - ch2
-    /// group2
+    /// - group2
     ///   - ch1
     ///   - ch2
-    /// group3
+    /// - group3
     fn generate_test_index() -> Index {
         let mut index = Index::new();
         let segment = Segment {
@@ -83,7 +137,7 @@ mod tests {
                 ],
             }),
         };
-        index.add_segment(segment).unwrap();
+        index.add_segment(&segment).unwrap();
 
         let segment = Segment {
             toc: ToC::from_u32(0x2),
@@ -114,7 +168,7 @@ mod tests {
                 ],
             }),
         };
-        index.add_segment(segment).unwrap();
+        index.add_segment(&segment).unwrap();
         index
     }
 
@@ -125,13 +179,13 @@ mod tests {
         assert_eq!(
             paths,
             vec![
-                ObjectPath::from("/'group'"),
-                ObjectPath::from("/'group'/'ch1'"),
-                ObjectPath::from("/'group'/'ch2'"),
-                ObjectPath::from("/'group2'"),
-                ObjectPath::from("/'group2'/'ch1'"),
-                ObjectPath::from("/'group2'/'ch2'"),
-                ObjectPath::from("/'group3'"),
+                RawPath::from("/'group'"),
+                RawPath::from("/'group'/'ch1'"),
+                RawPath::from("/'group'/'ch2'"),
+                RawPath::from("/'group2'"),
+                RawPath::from("/'group2'/'ch1'"),
+                RawPath::from("/'group2'/'ch2'"),
+                RawPath::from("/'group3'"),
             ]
         );
     }
@@ -140,23 +194,101 @@ mod tests {
     fn test_paths_starting_with() {
         let index = generate_test_index();
         let paths: Vec<_> = index
-            .paths_starting_with(&ObjectPath::from("/'group2'"))
+            .paths_starting_with(RawPath::from("/'group2'"))
             .collect();
         assert_eq!(
             paths,
             vec![
-                ObjectPath::from("/'group2'"),
-                ObjectPath::from("/'group2'/'ch1'"),
-                ObjectPath::from("/'group2'/'ch2'"),
+                RawPath::from("/'group2'"),
+                RawPath::from("/'group2'/'ch1'"),
+                RawPath::from("/'group2'/'ch2'"),
             ]
         );
     }
 
+    #[test]
+    fn test_find_objects_with_property() {
+        let index = generate_test_index();
+        let paths: Vec<_> = index
+            .find_objects_with_property("Prop", |value| *value == PropertyValue::I32(-51))
+            .collect();
+        assert_eq!(paths, vec!["/'group'", "/'group2'", "/'group3'"]);
+
+        let paths: Vec<_> = index
+            .find_objects_with_property("Prop2", |value| value.as_i64() == Some(-2))
+            .collect();
+        assert_eq!(paths, vec!["/'group'/'ch2'", "/'group2'/'ch2'"]);
+
+        assert_eq!(
+            index.find_objects_with_property("Prop", |_| false).count(),
+            0
+        );
+        assert_eq!(
+            index.find_objects_with_property("None", |_| true).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_channels_in_group() {
+        let index = generate_test_index();
+        let channels: Vec<_> = index.channels_in_group(&GroupPath::new("group")).collect();
+        assert_eq!(
+            channels,
+            vec![
+                ChannelPath::new("group", "ch1"),
+                ChannelPath::new("group", "ch2")
+            ]
+        );
+        assert_eq!(
+            index.channels_in_group(&GroupPath::new("group3")).count(),
+            0
+        );
+        assert_eq!(index.objects().count(), 7);
+    }
+
+    #[test]
+    fn test_group_map_keeps_write_order() {
+        let object = |path: &str| ObjectMetaData {
+            path: path.to_string(),
+            properties: vec![],
+            raw_data_index: RawDataIndex::None,
+        };
+        let segment = Segment {
+            toc: ToC::from_u32(0x2),
+            next_segment_offset: 0,
+            raw_data_offset: 0,
+            meta_data: Some(MetaData {
+                objects: vec![
+                    object("/'group'/'b'"),
+                    object("/'other'"),
+                    object("/'group'/'a'"),
+                ],
+            }),
+        };
+        let mut index = Index::new();
+        index.add_segment(&segment).unwrap();
+
+        let channels: Vec<_> = index.channels_in_group(&GroupPath::new("group")).collect();
+        assert_eq!(
+            channels,
+            vec![
+                ChannelPath::new("group", "b"),
+                ChannelPath::new("group", "a")
+            ]
+        );
+        assert_eq!(
+            index.group_names().collect::<Vec<_>>(),
+            vec!["group", "other"]
+        );
+        assert_eq!(index.channels_in_group(&GroupPath::new("other")).count(), 0);
+    }
+
     #[test]
     fn test_paths_starting_with_no_match() {
         let index = generate_test_index();
         let paths: Vec<_> = index
-            .paths_starting_with(&ObjectPath::from("/'group4'"))
+            .paths_starting_with(RawPath::from("/'group4'"))
             .collect();
         assert!(paths.is_empty());
     }
@@ -0,0 +1,114 @@
+//! The registry of every object in the index.
+//!
+//! Objects are stored in a vector and given an [`ObjectId`] when they are
+//! first seen. The path map is only used to find the ID from the metadata, so
+//! adding data blocks for the active objects is a direct lookup by ID rather
+//! than a string search through a map with tens of thousands of paths.
+//!
+//! A second map from each group to its channels lets group queries visit only
+//! the channels in the group.
+
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use std::sync::Arc;
+
+use super::ObjectData;
+use crate::paths::{path_group_name, ObjectPath};
+
+/// The position of an object in the registry.
+pub type ObjectId = usize;
+
+/// The objects of the index with lookups by path or by [`ObjectId`].
+#[derive(Debug, Clone, Default)]
+pub struct ObjectIndex {
+    /// The path map, kept in path order for the group and channel queries.
+    ///
+    /// The key shares its allocation with [`ObjectData::path`] and the active
+    /// objects so each path is stored once no matter how many segments reference it.
+    ids: BTreeMap<Arc<str>, ObjectId>,
+    objects: Vec<ObjectData>,
+    /// The channels of each group by group name, in the order they were added.
+    ///
+    /// Groups are included if they have their own object or any channels.
+    groups: BTreeMap<String, Vec<ObjectId>>,
+}
+
+impl ObjectIndex {
+    /// Find the ID of the object with the path.
+    pub fn id(&self, path: &str) -> Option<ObjectId> {
+        self.ids.get(path).copied()
+    }
+
+    pub fn get(&self, path: &str) -> Option<&ObjectData> {
+        self.id(path).map(|id| &self.objects[id])
+    }
+
+    /// Get the object by ID.
+    ///
+    /// Panics if the ID didn't come from this registry.
+    pub fn by_id(&self, id: ObjectId) -> &ObjectData {
+        &self.objects[id]
+    }
+
+    /// Get the object by ID in a mutable form.
+    ///
+    /// Panics if the ID didn't come from this registry.
+    pub fn by_id_mut(&mut self, id: ObjectId) -> &mut ObjectData {
+        &mut self.objects[id]
+    }
+
+    /// Add a new object and return its ID.
+    ///
+    /// Panics if an object with the same path is already registered.
+    pub fn insert(&mut self, object: ObjectData) -> ObjectId {
+        let id = self.objects.len();
+        let old = self.ids.insert(object.path.clone(), id);
+        assert!(
+            old.is_none(),
+            "Should not be possible to be replacing an existing object."
+        );
+        if let Some(group_name) = path_group_name(&object.path) {
+            let channels = self.groups.entry(group_name.to_string()).or_default();
+            if matches!(ObjectPath::parse(&object.path), Ok(ObjectPath::Channel(_))) {
+                channels.push(id);
+            }
+        }
+        self.objects.push(object);
+        id
+    }
+
+    /// The names of every group in name order.
+    pub fn group_names(&self) -> impl Iterator<Item = &str> {
+        self.groups.keys().map(String::as_str)
+    }
+
+    /// The channels in the group in the order they were added.
+    pub fn group_channels(&self, group_name: &str) -> impl Iterator<Item = &ObjectData> {
+        self.groups
+            .get(group_name)
+            .into_iter()
+            .flatten()
+            .map(|id| &self.objects[*id])
+    }
+
+    /// The paths of every object in path order.
+    pub fn keys(&self) -> impl Iterator<Item = &Arc<str>> {
+        self.ids.keys()
+    }
+
+    /// Every object with its path in path order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Arc<str>, &ObjectData)> {
+        self.ids.iter().map(|(path, id)| (path, &self.objects[*id]))
+    }
+
+    /// The objects with paths in the range, in path order.
+    #[cfg_attr(not(feature = "write"), allow(dead_code))]
+    pub fn range<'a: 'b, 'b>(
+        &'a self,
+        range: (Bound<&'b str>, Bound<&'b str>),
+    ) -> impl Iterator<Item = (&'a Arc<str>, &'a ObjectData)> + 'b {
+        self.ids
+            .range::<str, _>(range)
+            .map(|(path, id)| (path, &self.objects[*id]))
+    }
+}
@@ -0,0 +1,199 @@
+//! Summary statistics of the file structure.
+//!
+//! These help to decide whether a file would benefit from being defragmented
+//! and to triage performance issues with particular files.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::io::data_types::DataType;
+use crate::raw_data::DataLayout;
+
+/// Statistics about the structure of the file.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FileStats {
+    /// The total number of segments.
+    pub segments: u64,
+    /// The number of segments written in big endian format.
+    pub big_endian_segments: u64,
+    /// The number of segments written in little endian format.
+    pub little_endian_segments: u64,
+    /// The number of data blocks with interleaved data.
+    pub interleaved_blocks: u64,
+    /// The number of data blocks with contiguous data.
+    pub contiguous_blocks: u64,
+    /// The number of times each data type appears as a channel in a data block.
+    pub data_types: BTreeMap<DataType, u64>,
+    /// The total bytes of raw data.
+    pub data_bytes: u64,
+    /// The average size of a data block in bytes.
+    pub average_block_bytes: f64,
+    /// The proportion of the file used by lead ins and metadata rather than raw data.
+    ///
+    /// 0 is a file of only data. Heavily fragmented files approach 1.
+    pub fragmentation_ratio: f64,
+}
+
+/// Where a segment is in the file and how its bytes are split between metadata and data.
+///
+/// One record is kept for each segment so tools can plot fragmentation and
+/// metadata overhead through the file. With the `serde` feature this can be
+/// serialized to any serde format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SegmentRecord {
+    /// The position of the segment in the file, counting from 0.
+    pub index: u64,
+    /// The byte offset of the segment lead in.
+    pub offset: u64,
+    /// The total size of the segment including the lead in.
+    pub size: u64,
+    /// The bytes of metadata, excluding the lead in.
+    pub meta_data_bytes: u64,
+    /// The bytes of raw data.
+    pub data_bytes: u64,
+    /// The paths of the channels with data in the segment.
+    pub channels: Vec<Arc<str>>,
+}
+
+impl super::Index {
+    /// The record of every segment in the file in file order.
+    pub fn segment_records(&self) -> &[SegmentRecord] {
+        &self.segment_records
+    }
+
+    /// Calculate statistics for the structure of the file.
+    pub fn file_stats(&self) -> FileStats {
+        let mut stats = FileStats {
+            segments: self.segment_count,
+            big_endian_segments: self.big_endian_segment_count,
+            little_endian_segments: self.segment_count - self.big_endian_segment_count,
+            ..Default::default()
+        };
+
+        for block in &self.data_blocks {
+            match block.layout {
+                DataLayout::Interleaved => stats.interleaved_blocks += 1,
+                DataLayout::Contigious => stats.contiguous_blocks += 1,
+            }
+            for channel in &block.channels {
+                *stats.data_types.entry(channel.data_type).or_default() += 1;
+            }
+            stats.data_bytes += block.length;
+        }
+
+        if !self.data_blocks.is_empty() {
+            stats.average_block_bytes = stats.data_bytes as f64 / self.data_blocks.len() as f64;
+        }
+
+        if self.next_segment_start > 0 {
+            let overhead = self.next_segment_start.saturating_sub(stats.data_bytes);
+            stats.fragmentation_ratio = overhead as f64 / self.next_segment_start as f64;
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::Index;
+    use crate::meta_data::{MetaData, ObjectMetaData, RawDataIndex, RawDataMeta, Segment, ToC};
+
+    #[test]
+    fn test_file_stats() {
+        let mut index = Index::new();
+        let segment = Segment {
+            toc: ToC {
+                contains_meta_data: true,
+                contains_raw_data: true,
+                contains_new_object_list: true,
+                ..Default::default()
+            },
+            next_segment_offset: 820,
+            raw_data_offset: 20,
+            meta_data: Some(MetaData {
+                objects: vec![ObjectMetaData {
+                    path: "/'group'/'ch1'".to_string(),
+                    properties: vec![],
+                    raw_data_index: RawDataIndex::RawData(RawDataMeta {
+                        data_type: DataType::DoubleFloat,
+                        number_of_values: 100,
+                        total_size_bytes: None,
+                    }),
+                }],
+            }),
+        };
+        index.add_segment(&segment).unwrap();
+
+        let segment = Segment {
+            toc: ToC {
+                contains_raw_data: true,
+                data_is_interleaved: true,
+                big_endian: true,
+                ..Default::default()
+            },
+            next_segment_offset: 800,
+            raw_data_offset: 0,
+            meta_data: None,
+        };
+        index.add_segment(&segment).unwrap();
+
+        let stats = index.file_stats();
+        assert_eq!(stats.segments, 2);
+        assert_eq!(stats.big_endian_segments, 1);
+        assert_eq!(stats.little_endian_segments, 1);
+        assert_eq!(stats.interleaved_blocks, 1);
+        assert_eq!(stats.contiguous_blocks, 1);
+        assert_eq!(stats.data_types.get(&DataType::DoubleFloat), Some(&2));
+        assert_eq!(stats.data_bytes, 1600);
+        assert_eq!(stats.average_block_bytes, 800.0);
+        assert_eq!(stats.fragmentation_ratio, 76.0 / 1676.0);
+
+        let records = index.segment_records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[0],
+            SegmentRecord {
+                index: 0,
+                offset: 0,
+                size: 848,
+                meta_data_bytes: 20,
+                data_bytes: 800,
+                channels: vec![Arc::from("/'group'/'ch1'")],
+            }
+        );
+        assert_eq!(records[1].offset, 848);
+        assert_eq!(records[1].meta_data_bytes, 0);
+        assert_eq!(records[1].data_bytes, 800);
+        assert_eq!(records[1].channels, records[0].channels);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_segment_record_serializes() {
+        let record = SegmentRecord {
+            index: 1,
+            offset: 100,
+            size: 60,
+            meta_data_bytes: 12,
+            data_bytes: 20,
+            channels: vec![Arc::from("/'group'/'ch1'")],
+        };
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(
+            json,
+            r#"{"index":1,"offset":100,"size":60,"meta_data_bytes":12,"data_bytes":20,"channels":["/'group'/'ch1'"]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<SegmentRecord>(&json).unwrap(),
+            record
+        );
+    }
+
+    #[test]
+    fn test_empty_file_stats() {
+        let stats = Index::new().file_stats();
+        assert_eq!(stats, FileStats::default());
+    }
+}
@@ -8,41 +8,95 @@ use crate::meta_data::RawDataIndex;
 use super::{DataFormat, Index};
 
 impl Index {
-    /// Validates the data formats for the objects to include
-    /// in the next segment.
+    /// Check whether the object is currently expecting data in each data block.
+    ///
+    /// Metadata for these objects must keep the raw data index so they stay in the data layout.
+    pub fn is_active_data_object(&self, path: &str) -> bool {
+        self.active_objects
+            .iter()
+            .any(|active| *active.path == *path)
+    }
+
+    /// Choose how the next data segment lists its objects.
+    ///
+    /// Returns how the object list changes and the objects which must be
+    /// written in the segment metadata, in order:
+    /// * [`ObjectListChange::Unchanged`] only lists channels with a new data format.
+    /// * [`ObjectListChange::Appended`] also lists the channels added after the current ones.
+    /// * [`ObjectListChange::NewList`] lists every channel, as channels were
+    ///   removed or reordered and the data layout must be replaced.
+    ///
+    /// The active objects and formats come from the index so this works the same
+    /// whether they were written in this session or scanned from an existing file,
+    /// allowing appends to an existing file to continue with data only segments.
     pub fn check_write_values<'b>(
         &self,
         objects: Vec<(&'b str, DataFormat)>,
-    ) -> (bool, Vec<(&'b str, RawDataIndex)>) {
-        let live_matches = if !self.active_objects.is_empty() {
-            self.active_objects
-                .iter()
-                .zip(objects.iter())
-                .fold(true, |matches, (active, new)| {
-                    matches && active.path == new.0
-                })
-        } else {
-            //empty
-            false
-        };
+    ) -> (ObjectListChange, Vec<(&'b str, RawDataIndex)>) {
+        let change = self.object_list_change(&objects);
 
         let raw_data_formats = objects
             .into_iter()
-            .map(|(path, format)| {
+            .enumerate()
+            .filter_map(|(position, (path, format))| {
                 let found_format = self
                     .objects
                     .get(path)
                     .and_then(|object_data| object_data.latest_data_format.as_ref());
-                match found_format {
-                    Some(last_format) if last_format == &format => {
-                        (path, RawDataIndex::MatchPrevious)
-                    }
-                    _ => (path, format.into()),
-                }
+                let raw_index = match found_format {
+                    Some(last_format) if last_format == &format => RawDataIndex::MatchPrevious,
+                    _ => format.into(),
+                };
+                let listed = change == ObjectListChange::NewList
+                    || position >= self.active_objects.len()
+                    || raw_index != RawDataIndex::MatchPrevious;
+                listed.then_some((path, raw_index))
             })
             .collect();
-        (live_matches, raw_data_formats)
+        (change, raw_data_formats)
     }
+
+    /// Compare the channels for the next segment to the current data layout.
+    fn object_list_change(&self, objects: &[(&str, DataFormat)]) -> ObjectListChange {
+        // Unsupported channels can't be carried into a new layout.
+        if self.active_objects.is_empty() || !self.active_unsupported.is_empty() {
+            return ObjectListChange::NewList;
+        }
+        if objects.len() < self.active_objects.len() {
+            return ObjectListChange::NewList;
+        }
+        let prefix_matches = self
+            .active_objects
+            .iter()
+            .zip(objects)
+            .all(|(active, (path, _))| *active.path == **path);
+        if !prefix_matches {
+            return ObjectListChange::NewList;
+        }
+
+        // Appended channels must be new to the layout.
+        let appended = &objects[self.active_objects.len()..];
+        let repeated = appended.iter().enumerate().any(|(position, (path, _))| {
+            self.is_active_data_object(path)
+                || appended[..position].iter().any(|(other, _)| other == path)
+        });
+        match (appended.is_empty(), repeated) {
+            (true, _) => ObjectListChange::Unchanged,
+            (false, false) => ObjectListChange::Appended,
+            (false, true) => ObjectListChange::NewList,
+        }
+    }
+}
+
+/// How the channels of a new data segment relate to the current data layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectListChange {
+    /// The same channels in the same order.
+    Unchanged,
+    /// The current channels in order followed by new channels.
+    Appended,
+    /// Channels were removed or reordered so a new object list is required.
+    NewList,
 }
 
 #[cfg(test)]
@@ -78,8 +132,8 @@ mod tests {
                 }),
             ),
         ];
-        let (matches, data_format) = index.check_write_values(channels);
-        assert_eq!(matches, false);
+        let (change, data_format) = index.check_write_values(channels);
+        assert_eq!(change, ObjectListChange::NewList);
 
         let expected_format = vec![
             (
@@ -139,7 +193,7 @@ mod tests {
         };
 
         let mut index = Index::default();
-        index.add_segment(segment).unwrap();
+        index.add_segment(&segment).unwrap();
 
         let channels = vec![
             (
@@ -159,15 +213,9 @@ mod tests {
                 }),
             ),
         ];
-        let (matches, data_format) = index.check_write_values(channels);
-        assert_eq!(matches, true);
-
-        let expected_format = vec![
-            ("/'group'/'ch1'", RawDataIndex::MatchPrevious),
-            ("/'group'/'ch2'", RawDataIndex::MatchPrevious),
-        ];
-
-        assert_eq!(data_format, expected_format);
+        let (change, data_format) = index.check_write_values(channels);
+        assert_eq!(change, ObjectListChange::Unchanged);
+        assert!(data_format.is_empty());
     }
 
     #[test]
@@ -226,8 +274,8 @@ mod tests {
         };
 
         let mut index = Index::default();
-        index.add_segment(segment).unwrap();
-        index.add_segment(segment2).unwrap();
+        index.add_segment(&segment).unwrap();
+        index.add_segment(&segment2).unwrap();
 
         let channels = vec![
             (
@@ -247,15 +295,9 @@ mod tests {
                 }),
             ),
         ];
-        let (matches, data_format) = index.check_write_values(channels);
-        assert_eq!(matches, true);
-
-        let expected_format = vec![
-            ("/'group'/'ch1'", RawDataIndex::MatchPrevious),
-            ("/'group'/'ch2'", RawDataIndex::MatchPrevious),
-        ];
-
-        assert_eq!(data_format, expected_format);
+        let (change, data_format) = index.check_write_values(channels);
+        assert_eq!(change, ObjectListChange::Unchanged);
+        assert!(data_format.is_empty());
     }
 
     #[test]
@@ -294,7 +336,7 @@ mod tests {
         };
 
         let mut index = Index::default();
-        index.add_segment(segment).unwrap();
+        index.add_segment(&segment).unwrap();
 
         let channels = vec![
             (
@@ -314,20 +356,17 @@ mod tests {
                 }),
             ),
         ];
-        let (matches, data_format) = index.check_write_values(channels);
-        assert_eq!(matches, true);
-
-        let expected_format = vec![
-            ("/'group'/'ch1'", RawDataIndex::MatchPrevious),
-            (
-                "/'group'/'ch2'",
-                RawDataIndex::RawData(RawDataMeta {
-                    data_type: DataType::DoubleFloat,
-                    number_of_values: 2000,
-                    total_size_bytes: None,
-                }),
-            ),
-        ];
+        let (change, data_format) = index.check_write_values(channels);
+        assert_eq!(change, ObjectListChange::Unchanged);
+
+        let expected_format = vec![(
+            "/'group'/'ch2'",
+            RawDataIndex::RawData(RawDataMeta {
+                data_type: DataType::DoubleFloat,
+                number_of_values: 2000,
+                total_size_bytes: None,
+            }),
+        )];
 
         assert_eq!(data_format, expected_format);
     }
@@ -368,7 +407,7 @@ mod tests {
         };
 
         let mut index = Index::default();
-        index.add_segment(segment).unwrap();
+        index.add_segment(&segment).unwrap();
 
         let channels = vec![
             (
@@ -388,8 +427,8 @@ mod tests {
                 }),
             ),
         ];
-        let (matches, data_format) = index.check_write_values(channels);
-        assert_eq!(matches, false);
+        let (change, data_format) = index.check_write_values(channels);
+        assert_eq!(change, ObjectListChange::NewList);
 
         let expected_format = vec![
             (
@@ -482,8 +521,8 @@ mod tests {
         };
 
         let mut index = Index::default();
-        index.add_segment(segment).unwrap();
-        index.add_segment(segment2).unwrap();
+        index.add_segment(&segment).unwrap();
+        index.add_segment(&segment2).unwrap();
 
         let channels = vec![
             (
@@ -503,8 +542,8 @@ mod tests {
                 }),
             ),
         ];
-        let (matches, data_format) = index.check_write_values(channels);
-        assert_eq!(matches, false);
+        let (change, data_format) = index.check_write_values(channels);
+        assert_eq!(change, ObjectListChange::NewList);
 
         let expected_format = vec![
             ("/'group'/'ch1'", RawDataIndex::MatchPrevious),
@@ -513,4 +552,84 @@ mod tests {
 
         assert_eq!(data_format, expected_format);
     }
+
+    fn f64_format(number_of_values: u64) -> DataFormat {
+        DataFormat::RawData(RawDataMeta {
+            data_type: DataType::DoubleFloat,
+            number_of_values,
+            total_size_bytes: None,
+        })
+    }
+
+    /// An index with ch1 and ch2 active in that order.
+    fn two_channel_index() -> Index {
+        let channel = |path: &str| ObjectMetaData {
+            path: path.to_string(),
+            properties: vec![],
+            raw_data_index: f64_format(1000).into(),
+        };
+        let segment = Segment {
+            toc: ToC::from_u32(0xE),
+            next_segment_offset: 16020,
+            raw_data_offset: 20,
+            meta_data: Some(MetaData {
+                objects: vec![channel("/'group'/'ch1'"), channel("/'group'/'ch2'")],
+            }),
+        };
+        let mut index = Index::default();
+        index.add_segment(&segment).unwrap();
+        index
+    }
+
+    #[test]
+    fn appended_channels_extend_the_list() {
+        let index = two_channel_index();
+        let (change, data_format) = index.check_write_values(vec![
+            ("/'group'/'ch1'", f64_format(1000)),
+            ("/'group'/'ch2'", f64_format(1000)),
+            ("/'group'/'ch3'", f64_format(1000)),
+        ]);
+        assert_eq!(change, ObjectListChange::Appended);
+        assert_eq!(
+            data_format,
+            vec![("/'group'/'ch3'", f64_format(1000).into())]
+        );
+    }
+
+    #[test]
+    fn removed_channels_need_a_new_list() {
+        let index = two_channel_index();
+        let (change, data_format) =
+            index.check_write_values(vec![("/'group'/'ch1'", f64_format(1000))]);
+        assert_eq!(change, ObjectListChange::NewList);
+        assert_eq!(
+            data_format,
+            vec![("/'group'/'ch1'", RawDataIndex::MatchPrevious)]
+        );
+    }
+
+    #[test]
+    fn reordered_channels_need_a_new_list() {
+        let index = two_channel_index();
+        let (change, data_format) = index.check_write_values(vec![
+            ("/'group'/'ch2'", f64_format(1000)),
+            ("/'group'/'ch1'", f64_format(1000)),
+        ]);
+        assert_eq!(change, ObjectListChange::NewList);
+        assert_eq!(
+            data_format,
+            vec![
+                ("/'group'/'ch2'", RawDataIndex::MatchPrevious),
+                ("/'group'/'ch1'", RawDataIndex::MatchPrevious),
+            ]
+        );
+
+        // Repeating an active channel after the current list is also a reorder.
+        let (change, _) = index.check_write_values(vec![
+            ("/'group'/'ch1'", f64_format(1000)),
+            ("/'group'/'ch2'", f64_format(1000)),
+            ("/'group'/'ch1'", f64_format(1000)),
+        ]);
+        assert_eq!(change, ObjectListChange::NewList);
+    }
 }
@@ -35,9 +35,13 @@ impl TdmsStorageType for bool {
     fn size(&self) -> usize {
         1
     }
+
+    fn sample_buffer(values: &mut [Self]) -> Option<SampleBuffer<'_>> {
+        Some(SampleBuffer::Boolean(values))
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "write"))]
 mod tests {
     use super::*;
     use crate::io::reader::{BigEndianReader, LittleEndianReader, TdmsReader};
@@ -61,7 +61,7 @@ macro_rules! complex_type {
 complex_type!(f32, DataType::ComplexSingleFloat);
 complex_type!(f64, DataType::ComplexDoubleFloat);
 
-#[cfg(test)]
+#[cfg(all(test, feature = "write"))]
 mod tests {
     use super::*;
     use crate::io::reader::{BigEndianReader, LittleEndianReader, TdmsReader};
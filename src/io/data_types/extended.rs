@@ -21,6 +21,32 @@ impl ExtendedRaw {
     pub fn new(value: u128) -> Self {
         Self(value)
     }
+
+    /// Convert to the nearest double precision float.
+    ///
+    /// The extra precision is lost and values outside the range of an f64
+    /// become zero or infinity.
+    pub fn to_f64(&self) -> f64 {
+        let mantissa = self.0 as u64;
+        let exponent = ((self.0 >> 64) & 0x7FFF) as i32;
+        let sign = if (self.0 >> 79) & 1 == 1 { -1.0 } else { 1.0 };
+
+        if exponent == 0x7FFF {
+            // The integer bit is ignored for infinities and NaNs.
+            return if mantissa << 1 == 0 {
+                sign * f64::INFINITY
+            } else {
+                f64::NAN
+            };
+        }
+
+        // The mantissa has an explicit integer bit so is a fixed point value with 63 fraction bits.
+        let fraction = mantissa as f64 / 2f64.powi(63);
+        // Scale in two steps so the intermediate doesn't overflow before the fraction is applied.
+        let power = exponent.max(1) - 16383;
+        let half = power / 2;
+        sign * fraction * 2f64.powi(half) * 2f64.powi(power - half)
+    }
 }
 
 const EXT_SIZE: usize = 10;
@@ -67,16 +93,38 @@ impl TdmsStorageType for ExtendedRaw {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "write")]
     use crate::io::reader::{BigEndianReader, LittleEndianReader, TdmsReader};
+    #[cfg(feature = "write")]
     use crate::io::writer::{BigEndianWriter, LittleEndianWriter, TdmsWriter};
+    #[cfg(feature = "write")]
     use std::io::Cursor;
 
+    #[test]
+    fn test_to_f64() {
+        let one = ExtendedRaw(0x3FFF_8000_0000_0000_0000);
+        assert_eq!(one.to_f64(), 1.0);
+        let minus_three = ExtendedRaw(0xC000_C000_0000_0000_0000);
+        assert_eq!(minus_three.to_f64(), -3.0);
+        assert_eq!(ExtendedRaw(0).to_f64(), 0.0);
+        assert_eq!(
+            ExtendedRaw(0x7FFF_8000_0000_0000_0000).to_f64(),
+            f64::INFINITY
+        );
+        assert!(ExtendedRaw(0x7FFF_C000_0000_0000_0000).to_f64().is_nan());
+        assert_eq!(
+            ExtendedRaw(0x7FFE_8000_0000_0000_0000).to_f64(),
+            f64::INFINITY
+        );
+    }
+
     #[test]
     fn test_extended_size() {
         let value = ExtendedRaw(0);
         assert_eq!(value.size(), 10);
     }
 
+    #[cfg(feature = "write")]
     #[test]
     fn test_be_round_trip() {
         let mut buffer = Cursor::new(Vec::new());
@@ -91,6 +139,7 @@ mod tests {
         assert_eq!(read_value.0, 0x0008_000c);
     }
 
+    #[cfg(feature = "write")]
     #[test]
     fn test_le_round_trip() {
         let mut buffer = Cursor::new(Vec::new());
@@ -23,18 +23,17 @@ use std::io::{Read, Write};
 use num_derive::FromPrimitive;
 
 use crate::error::TdmsError;
+use crate::SampleBuffer;
 
 // Re-exports.
-pub use bool::*;
 pub use complex::*;
 pub use extended::*;
-pub use native_numerics::*;
-pub use timestamp::*;
+pub use timestamp::UnixNanos;
 
 /// The data types that can be encoded into TDMS data.
 ///
 /// The values are the codes used in the TDMS file.
-#[derive(Clone, Copy, Debug, FromPrimitive, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, FromPrimitive, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(u32)]
 pub enum DataType {
     Void = 0,
@@ -133,6 +132,20 @@ pub trait TdmsStorageType: Sized {
     fn write_be(&self, writer: &mut impl Write) -> StorageResult<()>;
     /// Report the size of the type to allow for planning of writes.
     fn size(&self) -> usize;
+    /// View the values as bytes in the host byte order, if their memory is exactly the stored format.
+    ///
+    /// This lets writers emit a whole slice with a single write when the file
+    /// and host byte orders match.
+    fn native_bytes(_values: &[Self]) -> Option<&[u8]> {
+        None
+    }
+    /// View the values as a [`SampleBuffer`], if it has a variant for this type.
+    ///
+    /// With the `small` feature single channel reads go through the
+    /// non-generic reader for these types.
+    fn sample_buffer(_values: &mut [Self]) -> Option<SampleBuffer<'_>> {
+        None
+    }
 
     fn supports_data_type(data_type: &DataType) -> bool {
         Self::SUPPORTED_TYPES.contains(data_type)
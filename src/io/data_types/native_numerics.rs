@@ -9,9 +9,10 @@ use super::*;
 ///
 /// Should provide the type which has a from_le_bytes and from_be_bytes
 /// Then the natural type for the storage type.
-/// and then a slice of supported [`DataType`] values.
+/// and then a slice of supported [`DataType`] values
+/// and the [`SampleBuffer`] variant for the type.
 macro_rules! numeric_type {
-    ($type:ty, $natural:expr, $supported:expr) => {
+    ($type:ty, $natural:expr, $supported:expr, $buffer:ident) => {
         impl TdmsStorageType for $type {
             const NATURAL_TYPE: DataType = $natural;
             const SUPPORTED_TYPES: &'static [DataType] = $supported;
@@ -36,27 +37,35 @@ macro_rules! numeric_type {
             fn size(&self) -> usize {
                 Self::SIZE_BYTES
             }
+            fn native_bytes(values: &[Self]) -> Option<&[u8]> {
+                Some(bytemuck::cast_slice(values))
+            }
+            fn sample_buffer(values: &mut [Self]) -> Option<SampleBuffer<'_>> {
+                Some(SampleBuffer::$buffer(values))
+            }
         }
     };
 }
 
-numeric_type!(i8, DataType::I8, &[DataType::I8]);
-numeric_type!(u8, DataType::U8, &[DataType::U8]);
-numeric_type!(i16, DataType::I16, &[DataType::I16]);
-numeric_type!(u16, DataType::U16, &[DataType::U16]);
-numeric_type!(i32, DataType::I32, &[DataType::I32]);
-numeric_type!(u32, DataType::U32, &[DataType::U32]);
-numeric_type!(i64, DataType::I64, &[DataType::I64]);
-numeric_type!(u64, DataType::U64, &[DataType::U64]);
+numeric_type!(i8, DataType::I8, &[DataType::I8], I8);
+numeric_type!(u8, DataType::U8, &[DataType::U8], U8);
+numeric_type!(i16, DataType::I16, &[DataType::I16], I16);
+numeric_type!(u16, DataType::U16, &[DataType::U16], U16);
+numeric_type!(i32, DataType::I32, &[DataType::I32], I32);
+numeric_type!(u32, DataType::U32, &[DataType::U32], U32);
+numeric_type!(i64, DataType::I64, &[DataType::I64], I64);
+numeric_type!(u64, DataType::U64, &[DataType::U64], U64);
 numeric_type!(
     f64,
     DataType::DoubleFloat,
-    &[DataType::DoubleFloat, DataType::DoubleFloatWithUnit]
+    &[DataType::DoubleFloat, DataType::DoubleFloatWithUnit],
+    F64
 );
 numeric_type!(
     f32,
     DataType::SingleFloat,
-    &[DataType::SingleFloat, DataType::SingleFloatWithUnit]
+    &[DataType::SingleFloat, DataType::SingleFloatWithUnit],
+    F32
 );
 
 fn read_string_with_length(reader: &mut impl Read, length: u32) -> Result<String, TdmsError> {
@@ -98,7 +107,7 @@ impl TdmsStorageType for String {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "write"))]
 mod tests {
     use crate::io::reader::{BigEndianReader, LittleEndianReader, TdmsReader};
     use crate::io::writer::{BigEndianWriter, LittleEndianWriter, TdmsWriter};
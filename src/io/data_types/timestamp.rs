@@ -7,6 +7,56 @@ use labview_interop::types::timestamp::LVTime;
 
 const LVTIME_SIZE: usize = 16;
 
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+
+/// The unix epoch in seconds since the 1904 LabVIEW epoch.
+const UNIX_EPOCH_IN_LV_SECONDS: i128 = 2_082_844_800;
+
+/// Conversion between timestamps and integer nanoseconds since the unix epoch.
+///
+/// This is exact to the nanosecond, unlike going through floating point seconds.
+///
+/// # Example
+/// ```rust
+/// use labview_interop::types::LVTime;
+/// use tedium::types::UnixNanos;
+///
+/// let time = LVTime::from_unix_nanos(1_700_000_000_123_456_789);
+/// assert_eq!(time.as_unix_nanos(), 1_700_000_000_123_456_789);
+/// ```
+pub trait UnixNanos: Sized {
+    /// The nanoseconds since the unix epoch, rounded down.
+    fn as_unix_nanos(&self) -> i128;
+    /// Create the timestamp from nanoseconds since the unix epoch.
+    ///
+    /// Times beyond the range of the timestamp are clamped to its limits.
+    fn from_unix_nanos(nanos: i128) -> Self;
+}
+
+impl UnixNanos for LVTime {
+    fn as_unix_nanos(&self) -> i128 {
+        let (seconds, fractions) = self.to_parts();
+        // The seconds are signed in the file format.
+        let seconds = seconds as i64 as i128 - UNIX_EPOCH_IN_LV_SECONDS;
+        let nanos = (fractions as i128 * NANOS_PER_SECOND) >> 64;
+        seconds * NANOS_PER_SECOND + nanos
+    }
+
+    fn from_unix_nanos(nanos: i128) -> Self {
+        let seconds = nanos.div_euclid(NANOS_PER_SECOND) + UNIX_EPOCH_IN_LV_SECONDS;
+        let nanos = nanos.rem_euclid(NANOS_PER_SECOND);
+        if seconds > i64::MAX as i128 {
+            return LVTime::from_parts(i64::MAX as u64, u64::MAX);
+        }
+        if seconds < i64::MIN as i128 {
+            return LVTime::from_parts(i64::MIN as u64, 0);
+        }
+        // Round the fraction up so converting back gives the same nanoseconds.
+        let fractions = ((nanos << 64) + NANOS_PER_SECOND - 1) / NANOS_PER_SECOND;
+        LVTime::from_parts(seconds as i64 as u64, fractions as u64)
+    }
+}
+
 impl TdmsStorageType for LVTime {
     const SUPPORTED_TYPES: &'static [DataType] = &[DataType::Timestamp];
     const NATURAL_TYPE: DataType = DataType::Timestamp;
@@ -36,9 +86,13 @@ impl TdmsStorageType for LVTime {
     fn size(&self) -> usize {
         LVTIME_SIZE
     }
+
+    fn sample_buffer(values: &mut [Self]) -> Option<SampleBuffer<'_>> {
+        Some(SampleBuffer::Timestamp(values))
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "write"))]
 mod tests {
     use super::*;
     use crate::io::reader::{BigEndianReader, LittleEndianReader, TdmsReader};
@@ -48,7 +102,7 @@ mod tests {
     #[test]
     fn test_timestamp_be() {
         //Will just test using a seconds timestamp.
-        let timestamp: f64 = 1234567890.123456789;
+        let timestamp: f64 = 1_234_567_890.123_456_7;
         let time = LVTime::from_unix_epoch(timestamp);
 
         let bytes = time.to_be_bytes();
@@ -67,7 +121,7 @@ mod tests {
     #[test]
     fn test_timestamp_le() {
         //Will just test using a seconds timestamp.
-        let timestamp: f64 = 1234567890.123456789;
+        let timestamp: f64 = 1_234_567_890.123_456_7;
         let time = LVTime::from_unix_epoch(timestamp);
 
         let bytes = time.to_le_bytes();
@@ -82,4 +136,31 @@ mod tests {
         drop(writer);
         assert_eq!(bytes, output_bytes);
     }
+
+    #[test]
+    fn test_unix_nanos_round_trip() {
+        for nanos in [
+            0,
+            1,
+            999_999_999,
+            1_234_567_890_123_456_789,
+            -1,
+            -2_082_844_800_000_000_000,
+            -3_000_000_000_000_000_000,
+        ] {
+            assert_eq!(LVTime::from_unix_nanos(nanos).as_unix_nanos(), nanos);
+        }
+    }
+
+    #[test]
+    fn test_unix_nanos_matches_epoch_seconds() {
+        let time = LVTime::from_unix_nanos(1_500_000_000_250_000_000);
+        assert_eq!(time.seconds(), 2_082_844_800 + 1_500_000_000);
+        assert_eq!(time.sub_seconds(), 0.25);
+        assert_eq!(LVTime::from_unix_epoch(0.0).as_unix_nanos(), 0);
+        assert_eq!(
+            LVTime::from_lv_epoch(1.5).as_unix_nanos(),
+            -2_082_844_798_500_000_000
+        );
+    }
 }
@@ -4,4 +4,5 @@
 
 pub mod data_types;
 pub mod reader;
+#[cfg(feature = "write")]
 pub mod writer;
@@ -9,10 +9,15 @@ use super::data_types::TdmsStorageType;
 
 pub trait TdmsReader<R: Read + Seek>: Sized {
     fn from_reader(reader: R) -> Self;
+    /// Create the reader from an existing buffered reader so the buffer can be reused.
+    fn from_buffered_reader(reader: BufReader<R>) -> Self;
+    /// Release the buffered reader so it can be reused.
+    fn into_buffered_reader(self) -> BufReader<R>;
     fn read_value<T: TdmsStorageType>(&mut self) -> Result<T, TdmsError>;
     fn read_meta<T: TdmsMetaData>(&mut self) -> Result<T, TdmsError> {
         T::read(self)
     }
+    #[cfg_attr(not(feature = "write"), allow(dead_code))]
     fn read_vec<T: TdmsMetaData>(&mut self, length: usize) -> Result<Vec<T>, TdmsError> {
         let mut vec = Vec::with_capacity(length);
         for _ in 0..length {
@@ -36,20 +41,29 @@ pub trait TdmsReader<R: Read + Seek>: Sized {
     }
 
     /// Called immediately after ToC has been read so we have determined the endianess.
+    #[cfg_attr(not(feature = "write"), allow(dead_code))]
     fn read_segment(&mut self, toc: ToC) -> Result<Segment, TdmsError> {
-        let _version: u32 = self.read_value()?;
-        let next_segment_offset = self.read_value()?;
-        let raw_data_offset = self.read_value()?;
+        let mut segment = Segment::default();
+        self.read_segment_into(toc, &mut segment)?;
+        Ok(segment)
+    }
 
-        //todo handle no meta data mode.
-        let meta_data = self.read_meta()?;
+    /// Read the segment into an existing segment, reusing the allocations in its metadata.
+    ///
+    /// Called immediately after ToC has been read so we have determined the endianess.
+    fn read_segment_into(&mut self, toc: ToC, segment: &mut Segment) -> Result<(), TdmsError> {
+        let _version: u32 = self.read_value()?;
+        segment.toc = toc;
+        segment.next_segment_offset = self.read_value()?;
+        segment.raw_data_offset = self.read_value()?;
+
+        let mut meta_data = segment.meta_data.take().unwrap_or_default();
+        if toc.contains_meta_data {
+            meta_data.read_into(self)?;
+            segment.meta_data = Some(meta_data);
+        }
 
-        Ok(Segment {
-            toc,
-            next_segment_offset,
-            raw_data_offset,
-            meta_data: Some(meta_data),
-        })
+        Ok(())
     }
 }
 
@@ -64,6 +78,14 @@ impl<R: Read + Seek> TdmsReader<R> for LittleEndianReader<R> {
         Self(BufReader::new(reader))
     }
 
+    fn from_buffered_reader(reader: BufReader<R>) -> Self {
+        Self(reader)
+    }
+
+    fn into_buffered_reader(self) -> BufReader<R> {
+        self.0
+    }
+
     fn buffered_reader(&mut self) -> &mut BufReader<R> {
         &mut self.0
     }
@@ -80,6 +102,14 @@ impl<R: Read + Seek> TdmsReader<R> for BigEndianReader<R> {
         Self(BufReader::new(reader))
     }
 
+    fn from_buffered_reader(reader: BufReader<R>) -> Self {
+        Self(reader)
+    }
+
+    fn into_buffered_reader(self) -> BufReader<R> {
+        self.0
+    }
+
     fn buffered_reader(&mut self) -> &mut BufReader<R> {
         &mut self.0
     }
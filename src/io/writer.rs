@@ -16,7 +16,18 @@ pub trait TdmsWriter<W: Write>: Sized {
     /// Marker to place in the big_endian part of the ToC.
     const BIG_ENDIAN_FLAG: bool;
     fn from_writer(writer: W) -> Self;
+    /// Create the writer with a buffer of `capacity` bytes.
+    fn with_capacity(capacity: usize, writer: W) -> Self;
     fn write_value<T: TdmsStorageType>(&mut self, value: &T) -> Result<()>;
+    /// Write a slice of values.
+    ///
+    /// Writers override this to write the bytes in one go where the byte order allows.
+    fn write_values<T: TdmsStorageType>(&mut self, values: &[T]) -> Result<()> {
+        for value in values {
+            self.write_value(value)?;
+        }
+        Ok(())
+    }
     fn write_meta<T: TdmsMetaData>(&mut self, value: &T) -> Result<()> {
         value.write(self)
     }
@@ -86,12 +97,27 @@ impl<W: Write> TdmsWriter<W> for LittleEndianWriter<W> {
     fn from_writer(writer: W) -> Self {
         Self(BufWriter::new(writer))
     }
+    fn with_capacity(capacity: usize, writer: W) -> Self {
+        Self(BufWriter::with_capacity(capacity, writer))
+    }
     fn write_value<T: TdmsStorageType>(&mut self, value: &T) -> Result<()> {
         value.write_le(&mut self.0)
     }
 
     const BIG_ENDIAN_FLAG: bool = false;
 
+    fn write_values<T: TdmsStorageType>(&mut self, values: &[T]) -> Result<()> {
+        match T::native_bytes(values) {
+            Some(bytes) if cfg!(target_endian = "little") => self.0.write_all(bytes)?,
+            _ => {
+                for value in values {
+                    self.write_value(value)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn sync(&mut self) -> Result<()> {
         self.0.flush()?;
         Ok(())
@@ -104,12 +130,27 @@ impl<W: Write> TdmsWriter<W> for BigEndianWriter<W> {
     fn from_writer(writer: W) -> Self {
         Self(BufWriter::new(writer))
     }
+    fn with_capacity(capacity: usize, writer: W) -> Self {
+        Self(BufWriter::with_capacity(capacity, writer))
+    }
     fn write_value<T: TdmsStorageType>(&mut self, value: &T) -> Result<()> {
         value.write_be(&mut self.0)
     }
 
     const BIG_ENDIAN_FLAG: bool = true;
 
+    fn write_values<T: TdmsStorageType>(&mut self, values: &[T]) -> Result<()> {
+        match T::native_bytes(values) {
+            Some(bytes) if cfg!(target_endian = "big") => self.0.write_all(bytes)?,
+            _ => {
+                for value in values {
+                    self.write_value(value)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn sync(&mut self) -> Result<()> {
         self.0.flush()?;
         Ok(())
@@ -147,6 +188,32 @@ mod tests {
         assert_eq!(output_buffer, expected_buffer);
     }
 
+    #[test]
+    fn test_write_values_matches_single_values() {
+        let values = [1.5f64, -2.25, 1e300];
+
+        let mut expected_le = vec![];
+        let mut expected_be = vec![];
+        for value in values {
+            expected_le.extend_from_slice(&value.to_le_bytes());
+            expected_be.extend_from_slice(&value.to_be_bytes());
+        }
+
+        let mut le = vec![];
+        let mut be = vec![];
+        {
+            LittleEndianWriter::from_writer(&mut le)
+                .write_values(&values)
+                .unwrap();
+            BigEndianWriter::from_writer(&mut be)
+                .write_values(&values)
+                .unwrap();
+        }
+
+        assert_eq!(le, expected_le);
+        assert_eq!(be, expected_be);
+    }
+
     #[test]
     fn test_write_segment() {
         //just going to use some arbitrary and tested data.
@@ -175,7 +242,7 @@ mod tests {
             ],
         };
 
-        let data = vec![0f64; 10];
+        let data = [0f64; 10];
 
         let meta_size = meta.size();
         let data_size = data.len() * size_of::<f64>();
@@ -192,8 +259,8 @@ mod tests {
         assert_eq!(&buffer[0..4], "TDSm".as_bytes());
 
         //check toc has data and meta bits set.
-        assert_eq!(segment.toc.contains_meta_data, true);
-        assert_eq!(segment.toc.contains_raw_data, true);
+        assert!(segment.toc.contains_meta_data);
+        assert!(segment.toc.contains_raw_data);
         let mut toc_buf = [0; 4];
         toc_buf.copy_from_slice(&buffer[4..8]);
         let read_back_toc = ToC::from_u32(u32::from_le_bytes(toc_buf));
@@ -222,7 +289,7 @@ mod tests {
 
         let toc = ToC::default();
 
-        let data = vec![0f64; 10];
+        let data = [0f64; 10];
 
         let meta_size = 0;
         let data_size = data.len() * size_of::<f64>();
@@ -237,8 +304,8 @@ mod tests {
         assert_eq!(&buffer[0..4], "TDSm".as_bytes());
 
         //check toc has data and meta bits set.
-        assert_eq!(segment.toc.contains_meta_data, false);
-        assert_eq!(segment.toc.contains_raw_data, true);
+        assert!(!segment.toc.contains_meta_data);
+        assert!(segment.toc.contains_raw_data);
         let mut toc_buf = [0; 4];
         toc_buf.copy_from_slice(&buffer[4..8]);
         let read_back_toc = ToC::from_u32(u32::from_le_bytes(toc_buf));
@@ -303,8 +370,8 @@ mod tests {
         assert_eq!(&buffer[0..4], "TDSm".as_bytes());
 
         //check toc has data and meta bits set.
-        assert_eq!(segment.toc.contains_meta_data, true);
-        assert_eq!(segment.toc.contains_raw_data, false);
+        assert!(segment.toc.contains_meta_data);
+        assert!(!segment.toc.contains_raw_data);
         let mut toc_buf = [0; 4];
         toc_buf.copy_from_slice(&buffer[4..8]);
         let read_back_toc = ToC::from_u32(u32::from_le_bytes(toc_buf));
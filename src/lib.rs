@@ -6,16 +6,100 @@ mod meta_data;
 mod paths;
 mod properties;
 mod raw_data;
+#[cfg(feature = "write")]
+pub mod testgen;
 
 // Re-exports.
-pub use error::TdmsError;
+pub use error::{ErrorKind, TdmsError};
+pub use file::peek_properties;
+pub use file::ByteWindow;
+pub use file::ChannelCursor;
+pub use file::ConcatenatedChannel;
+#[cfg(feature = "export")]
+pub use file::CsvFollower;
+#[cfg(feature = "direct-io")]
+pub use file::DirectFile;
+#[cfg(feature = "encryption")]
+pub use file::EncryptedFile;
+#[cfg(feature = "write")]
+pub use file::FileNameTemplate;
+#[cfg(feature = "http")]
+pub use file::HttpSource;
+pub use file::IndexEpoch;
+#[cfg(feature = "object-store")]
+pub use file::ObjectStoreSource;
+pub use file::Parallelism;
+#[cfg(feature = "parquet")]
+pub use file::ParquetFollower;
+#[cfg(feature = "write")]
+pub use file::RetentionPolicy;
+pub use file::RetryPolicy;
+#[cfg(feature = "write")]
+pub use file::SampleSource;
 pub use file::TdmsFile;
+#[cfg(feature = "write")]
 pub use file::TdmsFileWriter;
+#[cfg(feature = "write")]
+pub use file::TdmsStreamWriter;
+#[cfg(feature = "analysis")]
+pub use file::TimeTrackSection;
+pub use file::{compare_schema, ChannelChange, SchemaDiff};
+#[cfg(feature = "write")]
+pub use file::{extract, redact, Redaction};
+pub use file::{quality_channel, QUALITY_SUFFIX};
+#[cfg(feature = "write")]
+pub use file::{repack, RangePolicy, RepackOptions};
+pub use file::{AlignedGroup, GroupAlignment, ReadOutcome, ReadPastEnd};
+pub use file::{BlockDecoder, BlockInfo, RawBlock, RawChannel, SampleSpan};
+#[cfg(feature = "analysis")]
+pub use file::{ChannelOverview, MinMax, Overview, OverviewConfig};
+#[cfg(feature = "analysis")]
+pub use file::{ChannelSummary, GroupSummary};
+#[cfg(feature = "write")]
+pub use file::{
+    CompatibilityProfile, FlushPolicy, SegmentLimit, WriteBuffer, WriteObserver, WrittenSegment,
+    WALL_CLOCK_PROPERTY,
+};
+pub use file::{DiademChannelProperties, NI_CHANNEL_LENGTH, NI_DATA_TYPE};
+pub use file::{FileTemplate, TemplateViolation};
+#[cfg(feature = "write")]
+pub use file::{IndexView, OverflowPolicy, SharedTdmsWriter, TdmsProducer, WriterMetrics};
+pub use file::{PlanOutcome, PlannedBlock, ReadBudget, ReadPlan};
+pub use file::{RangeReader, RangeSource};
+#[cfg(feature = "analysis")]
+pub use file::{ResampleMethod, SyncPolicy, SyncedSample, TimeSyncedIter, UnitConversion};
+pub use file::{SampleBuffer, SampleVec};
+#[cfg(feature = "write")]
+pub use file::{SegmentBuilder, SegmentRawIndex};
+#[cfg(feature = "write")]
+pub use file::{SpillBuffer, SpillOutput};
+#[cfg(feature = "watch")]
+pub use file::{TdmsWatcher, WatchEvent};
+#[cfg(feature = "analysis")]
+pub use file::{WindowStats, WindowedStats};
+pub use index::{DataBlockDebug, FileStats, SegmentRecord, UnsupportedChannel, WaveformTiming};
 pub use io::data_types::TdmsStorageType;
-pub use paths::{ChannelPath, PropertyPath};
+pub use meta_data::ToC;
+pub use paths::{ChannelPath, GroupPath, ObjectPath, PropertyPath};
 pub use properties::PropertyValue;
 pub use raw_data::DataLayout;
 
+/// Instrumented entry points for comparing storage hardware and reporting performance regressions.
+///
+/// Each function opens a file, does one kind of work and returns the time taken
+/// along with the reads and seeks it needed.
+pub mod bench {
+    pub use crate::file::bench::*;
+}
+
+/// Streaming growing files out to other formats.
+#[cfg(feature = "export")]
+pub mod export {
+    pub use crate::file::CsvFollower;
+    #[cfg(feature = "parquet")]
+    pub use crate::file::{follow_to_parquet, ParquetFollower};
+}
+
 // Put the types in their own namespace.
 pub mod types {
     pub use crate::io::data_types::*;
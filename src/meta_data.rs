@@ -4,13 +4,16 @@
 //! for providing the basis of some of this.
 //!
 
-use std::io::{Read, Seek, Write};
+#[cfg(feature = "write")]
+use std::io::Write;
+use std::io::{BufReader, Cursor, Read, Seek};
 
 use num_traits::FromPrimitive;
 
 use crate::error::TdmsError;
 use crate::io::data_types::{DataType, TdmsStorageType};
 use crate::io::reader::{BigEndianReader, LittleEndianReader, TdmsReader};
+#[cfg(feature = "write")]
 use crate::io::writer::TdmsWriter;
 use crate::properties::PropertyValue;
 
@@ -21,8 +24,10 @@ pub const LEAD_IN_BYTES: u64 = 28;
 pub trait TdmsMetaData: Sized {
     fn read<R: Read + Seek>(reader: &mut impl TdmsReader<R>) -> Result<Self, TdmsError>;
     // Write the piece of meta-data, returning the total size.
+    #[cfg(feature = "write")]
     fn write<W: Write>(&self, writer: &mut impl TdmsWriter<W>) -> Result<(), TdmsError>;
     /// Report the size on disk so we can plan the write.
+    #[cfg_attr(not(feature = "write"), allow(dead_code))]
     fn size(&self) -> usize;
 }
 
@@ -34,6 +39,7 @@ impl TdmsMetaData for DataType {
         Ok(prop_type)
     }
 
+    #[cfg(feature = "write")]
     fn write<W: Write>(&self, writer: &mut impl TdmsWriter<W>) -> Result<(), TdmsError> {
         writer.write_value(&(*self as u32))?;
         Ok(())
@@ -97,6 +103,7 @@ impl TdmsMetaData for ToC {
         Ok(ToC::from_u32(toc_value))
     }
 
+    #[cfg(feature = "write")]
     fn write<W: Write>(&self, writer: &mut impl TdmsWriter<W>) -> Result<(), TdmsError> {
         let bytes = self.as_bytes();
         for byte in &bytes {
@@ -116,13 +123,180 @@ pub struct MetaData {
     pub objects: Vec<ObjectMetaData>,
 }
 
+impl MetaData {
+    /// Read the metadata into the existing structure, reusing the allocations of the previous contents.
+    pub fn read_into<R: Read + Seek>(
+        &mut self,
+        reader: &mut impl TdmsReader<R>,
+    ) -> Result<(), TdmsError> {
+        let object_length: u32 = reader.read_value()?;
+        let object_length = object_length as usize;
+        self.objects.truncate(object_length);
+        self.objects
+            .reserve(object_length.saturating_sub(self.objects.len()));
+
+        for index in 0..object_length {
+            match self.objects.get_mut(index) {
+                Some(object) => object.read_into(reader)?,
+                None => self.objects.push(reader.read_meta()?),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl MetaData {
+    /// Decode metadata which contains properties of types this library doesn't know.
+    ///
+    /// The size of these values isn't recorded in the file so each possible size
+    /// is tried in turn. The first size where the rest of the metadata decodes
+    /// and ends exactly at the end of the bytes is kept, with the value stored as
+    /// [`PropertyValue::Unknown`].
+    pub fn read_lenient(bytes: &[u8], big_endian: bool) -> Result<Self, TdmsError> {
+        let reader = BufReader::with_capacity(0, Cursor::new(bytes));
+        let end = bytes.len() as u64;
+        let objects = match big_endian {
+            true => decode_lenient(&mut BigEndianReader::from_buffered_reader(reader), end),
+            false => decode_lenient(&mut LittleEndianReader::from_buffered_reader(reader), end),
+        };
+        objects.map(|objects| MetaData { objects }).ok_or_else(|| {
+            TdmsError::IndexReaderError(
+                "metadata with unknown property types could not be decoded".into(),
+            )
+        })
+    }
+}
+
+/// True if the error means a property value couldn't be decoded because of its type.
+fn is_unknown_property_error(error: &TdmsError) -> bool {
+    matches!(
+        error,
+        TdmsError::UnknownPropertyType(_) | TdmsError::UnsupportedType(_)
+    )
+}
+
+fn decode_lenient<R: Read + Seek>(
+    reader: &mut impl TdmsReader<R>,
+    end: u64,
+) -> Option<Vec<ObjectMetaData>> {
+    let object_count: u32 = reader.read_value().ok()?;
+    let mut objects = Vec::new();
+    decode_rest_lenient(reader, end, &mut objects, object_count, 0).then_some(objects)
+}
+
+/// Decode the rest of the metadata into `objects`, returning true if it ends exactly at `end`.
+///
+/// `properties_left` is the number of properties still to read for the last object.
+fn decode_rest_lenient<R: Read + Seek>(
+    reader: &mut impl TdmsReader<R>,
+    end: u64,
+    objects: &mut Vec<ObjectMetaData>,
+    mut objects_left: u32,
+    mut properties_left: u32,
+) -> bool {
+    loop {
+        if properties_left == 0 {
+            if objects_left == 0 {
+                return position(reader) == Some(end);
+            }
+            let Some(path) = read_bounded_string(reader, end) else {
+                return false;
+            };
+            let Ok(raw_data_index) = reader.read_meta() else {
+                return false;
+            };
+            let Ok(property_count) = reader.read_value::<u32>() else {
+                return false;
+            };
+            objects.push(ObjectMetaData {
+                path,
+                properties: Vec::new(),
+                raw_data_index,
+            });
+            objects_left -= 1;
+            properties_left = property_count;
+            continue;
+        }
+
+        let Some(name) = read_bounded_string(reader, end) else {
+            return false;
+        };
+        let Some(value_start) = position(reader) else {
+            return false;
+        };
+        properties_left -= 1;
+        let Some(object) = objects.last_mut() else {
+            return false;
+        };
+        match reader.read_meta::<PropertyValue>() {
+            Ok(value) => {
+                object.properties.push((name, value));
+                continue;
+            }
+            Err(error) if is_unknown_property_error(&error) => {}
+            Err(_) => return false,
+        }
+
+        // Try every length for the unknown value until the rest of the metadata fits.
+        let Some(type_code) = reader
+            .to_file_position(value_start)
+            .and_then(|_| reader.read_value::<u32>())
+            .ok()
+        else {
+            return false;
+        };
+        let bytes_start = value_start + 4;
+        let object_count = objects.len();
+        for length in 0..=end.saturating_sub(bytes_start) {
+            let mut bytes = vec![0u8; length as usize];
+            let read = reader
+                .to_file_position(bytes_start)
+                .and_then(|_| Ok(reader.buffered_reader().read_exact(&mut bytes)?));
+            if read.is_err() {
+                return false;
+            }
+            let object = objects.last_mut().expect("The object was checked above");
+            object
+                .properties
+                .push((name.clone(), PropertyValue::Unknown { type_code, bytes }));
+            let property_count = object.properties.len();
+            if decode_rest_lenient(reader, end, objects, objects_left, properties_left) {
+                return true;
+            }
+            objects.truncate(object_count);
+            let object = objects.last_mut().expect("The object was checked above");
+            object.properties.truncate(property_count - 1);
+        }
+        return false;
+    }
+}
+
+fn position<R: Read + Seek>(reader: &mut impl TdmsReader<R>) -> Option<u64> {
+    reader.buffered_reader().stream_position().ok()
+}
+
+/// Read a string only if its length fits before `end`.
+fn read_bounded_string<R: Read + Seek>(
+    reader: &mut impl TdmsReader<R>,
+    end: u64,
+) -> Option<String> {
+    let start = position(reader)?;
+    let length: u32 = reader.read_value().ok()?;
+    if start + 4 + length as u64 > end {
+        return None;
+    }
+    reader.to_file_position(start).ok()?;
+    reader.read_value().ok()
+}
+
 impl TdmsMetaData for MetaData {
     fn read<R: Read + Seek>(reader: &mut impl TdmsReader<R>) -> Result<Self, TdmsError> {
-        let object_length: u32 = reader.read_value()?;
-        let objects = reader.read_vec(object_length as usize)?;
-        Ok(MetaData { objects })
+        let mut meta_data = MetaData::default();
+        meta_data.read_into(reader)?;
+        Ok(meta_data)
     }
 
+    #[cfg(feature = "write")]
     fn write<W: Write>(&self, writer: &mut impl TdmsWriter<W>) -> Result<(), TdmsError> {
         let objects_length: u32 = self.objects.len() as u32;
         writer.write_value(&objects_length)?;
@@ -172,8 +346,60 @@ impl Segment {
             None => Err(TdmsError::SegmentAddressOverflow),
         }
     }
+}
 
-    pub fn read(reader: &mut (impl Read + Seek)) -> Result<Segment, TdmsError> {
+/// Reads consecutive segments from a file.
+///
+/// The buffered reader and the segment metadata are reused between segments
+/// so scanning a heavily fragmented file doesn't allocate for every segment.
+pub struct SegmentScanner<R: Read + Seek> {
+    /// Always `Some` between calls. It is only taken while an endian specific reader owns it.
+    reader: Option<BufReader<R>>,
+    segment: Segment,
+    /// The start of the next segment, if it was set by [`Self::seek_to`].
+    position: Option<u64>,
+}
+
+impl<R: Read + Seek> SegmentScanner<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: Some(BufReader::new(reader)),
+            segment: Segment::default(),
+            position: None,
+        }
+    }
+
+    fn buffered_reader(&mut self) -> &mut BufReader<R> {
+        self.reader
+            .as_mut()
+            .expect("Reader is always restored after reading a segment")
+    }
+
+    /// Move to the absolute position in the file.
+    ///
+    /// Uses a relative seek so any data already buffered can still be used.
+    pub fn seek_to(&mut self, position: u64) -> Result<(), TdmsError> {
+        let reader = self.buffered_reader();
+        let current = reader.stream_position()?;
+        reader.seek_relative(position as i64 - current as i64)?;
+        self.position = Some(position);
+        Ok(())
+    }
+
+    /// Fill the buffer with the bytes at the absolute position in the file.
+    ///
+    /// Use [`Self::seek_to`] before reading the next segment.
+    #[cfg_attr(not(feature = "write"), allow(dead_code))]
+    pub fn read_bytes_at(&mut self, position: u64, buffer: &mut [u8]) -> Result<(), TdmsError> {
+        let reader = self.buffered_reader();
+        reader.seek(std::io::SeekFrom::Start(position))?;
+        reader.read_exact(buffer)?;
+        Ok(())
+    }
+
+    /// Read the tag and ToC which start every segment.
+    fn read_toc(&mut self) -> Result<ToC, TdmsError> {
+        let reader = self.buffered_reader();
         let mut tag = [0u8; 4];
         match reader.read_exact(&mut tag) {
             Ok(_) => {}
@@ -193,13 +419,163 @@ impl Segment {
         //ToC is always little endian.
         let mut buf = [0u8; 4];
         reader.read_exact(&mut buf)?;
-        let toc = ToC::from_u32(u32::from_le_bytes(buf));
+        Ok(ToC::from_u32(u32::from_le_bytes(buf)))
+    }
+
+    /// Read the segment at the current position.
+    ///
+    /// Returns [`TdmsError::EndOfFile`] if there are no more segments.
+    pub fn read_segment(&mut self) -> Result<&Segment, TdmsError> {
+        let segment_start = match self.position.take() {
+            Some(position) => position,
+            None => self.buffered_reader().stream_position()?,
+        };
+        let toc = self.read_toc()?;
+
+        let reader = self
+            .reader
+            .take()
+            .expect("Reader is always restored after reading a segment");
+
+        let result = match toc.big_endian {
+            true => {
+                let mut reader = BigEndianReader::from_buffered_reader(reader);
+                let result = reader.read_segment_into(toc, &mut self.segment);
+                self.reader = Some(reader.into_buffered_reader());
+                result
+            }
+            false => {
+                let mut reader = LittleEndianReader::from_buffered_reader(reader);
+                let result = reader.read_segment_into(toc, &mut self.segment);
+                self.reader = Some(reader.into_buffered_reader());
+                result
+            }
+        };
+        match result {
+            Err(error) if is_unknown_property_error(&error) => {
+                self.read_meta_data_lenient(segment_start)?
+            }
+            result => result?,
+        }
+
+        Ok(&self.segment)
+    }
+
+    /// Read the metadata of the current segment again, keeping unknown properties.
+    fn read_meta_data_lenient(&mut self, segment_start: u64) -> Result<(), TdmsError> {
+        let (raw_data_offset, next_segment_offset) = (
+            self.segment.raw_data_offset,
+            self.segment.next_segment_offset,
+        );
+        let reader = self.buffered_reader();
+        reader.seek(std::io::SeekFrom::Start(segment_start + LEAD_IN_BYTES))?;
+        let bytes = read_meta_data_block(reader, raw_data_offset, next_segment_offset)?;
+        self.segment.meta_data = Some(MetaData::read_lenient(&bytes, self.segment.toc.big_endian)?);
+        Ok(())
+    }
+
+    /// Read only the lead in at the current position, leaving the reader at the start of the metadata.
+    ///
+    /// Returns [`TdmsError::EndOfFile`] if there are no more segments.
+    #[cfg(feature = "parallel")]
+    pub fn read_lead_in(&mut self) -> Result<LeadIn, TdmsError> {
+        let toc = self.read_toc()?;
+        let mut buf = [0u8; 20];
+        self.buffered_reader().read_exact(&mut buf)?;
+
+        let (_version, offsets) = buf.split_at(4);
+        let (next_segment_offset, raw_data_offset) = offsets.split_at(8);
+        let read_u64 = |bytes: &[u8]| {
+            let bytes = bytes.try_into().expect("Split at 8 bytes");
+            match toc.big_endian {
+                true => u64::from_be_bytes(bytes),
+                false => u64::from_le_bytes(bytes),
+            }
+        };
+
+        Ok(LeadIn {
+            toc,
+            next_segment_offset: read_u64(next_segment_offset),
+            raw_data_offset: read_u64(raw_data_offset),
+        })
+    }
+
+    /// Read the metadata bytes which follow the lead in, without decoding them.
+    #[cfg(feature = "parallel")]
+    pub fn read_meta_data_bytes(&mut self, lead_in: &LeadIn) -> Result<Vec<u8>, TdmsError> {
+        if !lead_in.toc.contains_meta_data {
+            return Ok(Vec::new());
+        }
+        read_meta_data_block(
+            self.buffered_reader(),
+            lead_in.raw_data_offset,
+            lead_in.next_segment_offset,
+        )
+    }
+}
+
+/// Read the `raw_data_offset` bytes of metadata at the current position.
+///
+/// The length comes from the lead in so it can't be trusted for an allocation.
+/// It must fit in the segment and the buffer only grows as bytes are actually
+/// read, so a corrupt length fails at the end of the file instead.
+fn read_meta_data_block(
+    reader: &mut impl Read,
+    raw_data_offset: u64,
+    next_segment_offset: u64,
+) -> Result<Vec<u8>, TdmsError> {
+    if raw_data_offset > next_segment_offset {
+        return Err(TdmsError::InvalidMetaData(format!(
+            "the metadata length {raw_data_offset} is longer than the segment length {next_segment_offset}"
+        )));
+    }
+    let mut bytes = Vec::new();
+    reader.take(raw_data_offset).read_to_end(&mut bytes)?;
+    if (bytes.len() as u64) < raw_data_offset {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+    }
+    Ok(bytes)
+}
 
-        let segment = match toc.big_endian {
-            true => BigEndianReader::from_reader(reader).read_segment(toc)?,
-            false => LittleEndianReader::from_reader(reader).read_segment(toc)?,
+/// The fixed section at the start of a segment.
+///
+/// Used to split the metadata parsing from the sequential walk of the file.
+#[cfg(feature = "parallel")]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct LeadIn {
+    pub toc: ToC,
+    pub next_segment_offset: u64,
+    pub raw_data_offset: u64,
+}
+
+#[cfg(feature = "parallel")]
+impl LeadIn {
+    /// Decode the segment from the lead in and the metadata bytes read by [`SegmentScanner::read_meta_data_bytes`].
+    pub fn decode_segment(&self, meta_data_bytes: &[u8]) -> Result<Segment, TdmsError> {
+        let meta_data = match self.toc.contains_meta_data {
+            true => {
+                // The bytes are already in memory so there is no need for a buffer.
+                let reader = BufReader::with_capacity(0, std::io::Cursor::new(meta_data_bytes));
+                let meta_data = match self.toc.big_endian {
+                    true => BigEndianReader::from_buffered_reader(reader).read_meta(),
+                    false => LittleEndianReader::from_buffered_reader(reader).read_meta(),
+                };
+                match meta_data {
+                    Err(error) if is_unknown_property_error(&error) => Some(
+                        MetaData::read_lenient(meta_data_bytes, self.toc.big_endian)?,
+                    ),
+                    meta_data => Some(meta_data?),
+                }
+            }
+            false => None,
         };
-        Ok(segment)
+
+        Ok(Segment {
+            toc: self.toc,
+            next_segment_offset: self.next_segment_offset,
+            raw_data_offset: self.raw_data_offset,
+            meta_data,
+        })
     }
 }
 
@@ -213,29 +589,40 @@ pub struct ObjectMetaData {
     pub raw_data_index: RawDataIndex,
 }
 
-impl TdmsMetaData for ObjectMetaData {
-    fn read<R: Read + Seek>(reader: &mut impl TdmsReader<R>) -> Result<ObjectMetaData, TdmsError> {
-        let path: String = reader.read_value()?;
-
-        let raw_data: RawDataIndex = reader.read_meta()?;
+impl ObjectMetaData {
+    /// Read the object into the existing structure, reusing the property list allocation.
+    fn read_into<R: Read + Seek>(
+        &mut self,
+        reader: &mut impl TdmsReader<R>,
+    ) -> Result<(), TdmsError> {
+        self.path = reader.read_value()?;
+        self.raw_data_index = reader.read_meta()?;
 
         let property_count: u32 = reader.read_value()?;
-
-        let mut properties = Vec::with_capacity(property_count as usize);
+        self.properties.clear();
+        self.properties.reserve(property_count as usize);
 
         for _prop in 0..property_count {
             let name: String = reader.read_value()?;
             let value: PropertyValue = reader.read_meta()?;
-            properties.push((name, value));
+            self.properties.push((name, value));
         }
+        Ok(())
+    }
+}
 
-        Ok(ObjectMetaData {
-            path,
-            properties,
-            raw_data_index: raw_data,
-        })
+impl TdmsMetaData for ObjectMetaData {
+    fn read<R: Read + Seek>(reader: &mut impl TdmsReader<R>) -> Result<ObjectMetaData, TdmsError> {
+        let mut object = ObjectMetaData {
+            path: String::new(),
+            properties: Vec::new(),
+            raw_data_index: RawDataIndex::None,
+        };
+        object.read_into(reader)?;
+        Ok(object)
     }
 
+    #[cfg(feature = "write")]
     fn write<W: Write>(&self, writer: &mut impl TdmsWriter<W>) -> Result<(), TdmsError> {
         writer.write_value(&self.path)?;
         writer.write_meta(&self.raw_data_index)?;
@@ -265,6 +652,28 @@ pub enum RawDataIndex {
     None,
     MatchPrevious,
     RawData(RawDataMeta),
+    /// Raw data this library can't decode, such as DAQmx raw data or an unknown
+    /// data type. Holds the raw data index tag for DAQmx or the data type code.
+    Unsupported(u32),
+}
+
+/// The size of a DAQmx format changing scaler in the raw data index.
+const DAQMX_SCALER_BYTES: i64 = 20;
+/// The size of a DAQmx digital line scaler in the raw data index.
+const DAQMX_DIGITAL_SCALER_BYTES: i64 = 17;
+
+/// Skip over a DAQmx raw data index after its tag.
+fn skip_daqmx_index<R: Read + Seek>(
+    reader: &mut impl TdmsReader<R>,
+    scaler_bytes: i64,
+) -> Result<(), TdmsError> {
+    // Data type, array dimension and number of values.
+    reader.move_position(16)?;
+    let scaler_count: u32 = reader.read_value()?;
+    reader.move_position(scaler_count as i64 * scaler_bytes)?;
+    let width_count: u32 = reader.read_value()?;
+    reader.move_position(width_count as i64 * 4)?;
+    Ok(())
 }
 
 impl TdmsMetaData for RawDataIndex {
@@ -274,16 +683,38 @@ impl TdmsMetaData for RawDataIndex {
         let raw_data = match raw_index {
             0x0000_0000 => RawDataIndex::MatchPrevious,
             0xFFFF_FFFF => RawDataIndex::None,
-            0x69120000..=0x6912FFFF => todo!(), // daqmx 1
-            0x69130000..=0x6913FFFF => todo!(), //daqmx 2
+            0x69120000..=0x6912FFFF => {
+                skip_daqmx_index(reader, DAQMX_SCALER_BYTES)?;
+                RawDataIndex::Unsupported(raw_index)
+            }
+            0x69130000..=0x6913FFFF => {
+                skip_daqmx_index(reader, DAQMX_DIGITAL_SCALER_BYTES)?;
+                RawDataIndex::Unsupported(raw_index)
+            }
             _ => {
-                let data_type: DataType = reader.read_meta()?;
+                let type_code: u32 = reader.read_value()?;
+                let Some(data_type) = <DataType as FromPrimitive>::from_u32(type_code) else {
+                    // The index length includes the length and type we have read.
+                    if raw_index < 8 {
+                        return Err(TdmsError::InvalidMetaData(format!(
+                            "the raw data index length {raw_index} is shorter than its header"
+                        )));
+                    }
+                    reader.move_position(raw_index as i64 - 8)?;
+                    return Ok(RawDataIndex::Unsupported(type_code));
+                };
                 let _array_dims: u32 = reader.read_value()?; //always 1.
                 let number_of_values: u64 = reader.read_value()?;
+                // Strings have a variable size so the index also has their total size.
+                let total_size_bytes = if data_type == DataType::TdmsString {
+                    Some(reader.read_value()?)
+                } else {
+                    None
+                };
                 let meta = RawDataMeta {
                     data_type,
                     number_of_values,
-                    total_size_bytes: None,
+                    total_size_bytes,
                 };
                 RawDataIndex::RawData(meta)
             }
@@ -292,18 +723,22 @@ impl TdmsMetaData for RawDataIndex {
         Ok(raw_data)
     }
 
+    #[cfg(feature = "write")]
     fn write<W: Write>(&self, writer: &mut impl TdmsWriter<W>) -> Result<(), TdmsError> {
         match self {
             RawDataIndex::None => writer.write_value(&0xFFFF_FFFFu32)?,
             RawDataIndex::MatchPrevious => writer.write_value(&0u32)?,
             RawDataIndex::RawData(raw_meta) => {
-                //size: until we add string support it is 20 bytes.
-                writer.write_value(&20u32)?;
+                writer.write_value(&(self.size() as u32))?;
                 writer.write_meta(&raw_meta.data_type)?;
                 //array dim is alway 1 in TDMS v2.0.
                 writer.write_value(&1u32)?;
-                writer.write_value(&raw_meta.number_of_values)?
+                writer.write_value(&raw_meta.number_of_values)?;
+                if let Some(total_size_bytes) = raw_meta.total_size_bytes {
+                    writer.write_value(&total_size_bytes)?;
+                }
             }
+            RawDataIndex::Unsupported(code) => return Err(TdmsError::UnknownDataType(*code)),
         }
         Ok(())
     }
@@ -312,9 +747,14 @@ impl TdmsMetaData for RawDataIndex {
         match self {
             RawDataIndex::None => std::mem::size_of::<u32>(),
             RawDataIndex::MatchPrevious => std::mem::size_of::<u32>(),
-            RawDataIndex::RawData(_raw_meta) => {
-                3 * std::mem::size_of::<u32>() + std::mem::size_of::<u64>()
+            RawDataIndex::RawData(raw_meta) => {
+                let total_size = raw_meta
+                    .total_size_bytes
+                    .map_or(0, |_| std::mem::size_of::<u64>());
+                3 * std::mem::size_of::<u32>() + std::mem::size_of::<u64>() + total_size
             }
+            // Never written.
+            RawDataIndex::Unsupported(_) => std::mem::size_of::<u32>(),
         }
     }
 }
@@ -331,6 +771,7 @@ pub struct RawDataMeta {
 mod tests {
     use std::io::Cursor;
 
+    #[cfg(feature = "write")]
     use crate::io::writer::LittleEndianWriter;
 
     use super::*;
@@ -341,12 +782,12 @@ mod tests {
         let toc = ToC::from_u32(toc_int);
         println!("{toc:?}");
 
-        assert_eq!(toc.contains_meta_data, true);
-        assert_eq!(toc.contains_raw_data, true);
-        assert_eq!(toc.contains_daqmx_raw_data, false);
-        assert_eq!(toc.data_is_interleaved, false);
-        assert_eq!(toc.big_endian, false);
-        assert_eq!(toc.contains_new_object_list, true);
+        assert!(toc.contains_meta_data);
+        assert!(toc.contains_raw_data);
+        assert!(!toc.contains_daqmx_raw_data);
+        assert!(!toc.data_is_interleaved);
+        assert!(!toc.big_endian);
+        assert!(toc.contains_new_object_list);
     }
 
     #[test]
@@ -370,6 +811,27 @@ mod tests {
         assert!(matches!(segment.total_size_bytes(), Ok(528)));
     }
 
+    #[test]
+    fn test_meta_data_length_beyond_segment_is_invalid() {
+        let mut cursor = Cursor::new(vec![0u8; 16]);
+        let result = read_meta_data_block(&mut cursor, 12, 8);
+        assert!(matches!(result, Err(TdmsError::InvalidMetaData(_))));
+    }
+
+    #[test]
+    fn test_huge_meta_data_length_fails_at_end_of_file() {
+        // An incomplete final segment has no next segment offset to check against.
+        let mut cursor = Cursor::new(vec![0u8; 16]);
+        let result = read_meta_data_block(&mut cursor, u64::MAX - 1, u64::MAX);
+        assert!(
+            matches!(result, Err(TdmsError::IoError(error)) if error.kind() == std::io::ErrorKind::UnexpectedEof)
+        );
+
+        let mut cursor = Cursor::new(vec![1u8; 16]);
+        let bytes = read_meta_data_block(&mut cursor, 4, 10).unwrap();
+        assert_eq!(bytes, vec![1u8; 4]);
+    }
+
     #[test]
     fn test_unknown_property_type() {
         //example from NI site
@@ -430,9 +892,84 @@ mod tests {
         assert_eq!(objects, expected);
     }
 
+    #[test]
+    fn test_unsupported_raw_data_indexes_are_skipped() {
+        let mut buffer = vec![];
+        // Unknown data type 0x99 with a 20 byte index.
+        buffer.extend_from_slice(&20u32.to_le_bytes());
+        buffer.extend_from_slice(&0x99u32.to_le_bytes());
+        buffer.extend_from_slice(&1u32.to_le_bytes());
+        buffer.extend_from_slice(&5u64.to_le_bytes());
+        // DAQmx format changing scaler index with 2 scalers and 1 width.
+        buffer.extend_from_slice(&0x6912_0000u32.to_le_bytes());
+        buffer.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+        buffer.extend_from_slice(&1u32.to_le_bytes());
+        buffer.extend_from_slice(&5u64.to_le_bytes());
+        buffer.extend_from_slice(&2u32.to_le_bytes());
+        buffer.extend_from_slice(&[0u8; 40]);
+        buffer.extend_from_slice(&1u32.to_le_bytes());
+        buffer.extend_from_slice(&4u32.to_le_bytes());
+        // DAQmx digital line scaler index with 1 scaler and 1 width.
+        buffer.extend_from_slice(&0x6913_0000u32.to_le_bytes());
+        buffer.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+        buffer.extend_from_slice(&1u32.to_le_bytes());
+        buffer.extend_from_slice(&5u64.to_le_bytes());
+        buffer.extend_from_slice(&1u32.to_le_bytes());
+        buffer.extend_from_slice(&[0u8; 17]);
+        buffer.extend_from_slice(&1u32.to_le_bytes());
+        buffer.extend_from_slice(&1u32.to_le_bytes());
+        // A following normal index.
+        buffer.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+
+        let mut cursor = Cursor::new(buffer);
+        let mut reader = LittleEndianReader::from_reader(&mut cursor);
+        let indexes: Vec<RawDataIndex> = reader.read_vec(4).unwrap();
+        assert_eq!(
+            indexes,
+            vec![
+                RawDataIndex::Unsupported(0x99),
+                RawDataIndex::Unsupported(0x6912_0000),
+                RawDataIndex::Unsupported(0x6913_0000),
+                RawDataIndex::None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_raw_data_index_shorter_than_header_is_invalid() {
+        let mut buffer = vec![];
+        buffer.extend_from_slice(&4u32.to_le_bytes());
+        buffer.extend_from_slice(&0x99u32.to_le_bytes());
+        buffer.extend_from_slice(&[0u8; 8]);
+
+        let mut cursor = Cursor::new(buffer);
+        let mut reader = LittleEndianReader::from_reader(&mut cursor);
+        let result: Result<RawDataIndex, TdmsError> = reader.read_meta();
+        assert!(matches!(result, Err(TdmsError::InvalidMetaData(_))));
+    }
+
+    #[cfg(feature = "write")]
+    #[test]
+    fn test_string_raw_data_index_has_total_size() {
+        let index = RawDataIndex::RawData(RawDataMeta {
+            data_type: DataType::TdmsString,
+            number_of_values: 3,
+            total_size_bytes: Some(40),
+        });
+        let buffer = write_meta_to_buffer(index.clone(), 28);
+        assert_eq!(&buffer[..4], &28u32.to_le_bytes());
+        assert_eq!(&buffer[20..], &40u64.to_le_bytes());
+
+        let mut cursor = Cursor::new(buffer);
+        let mut reader = LittleEndianReader::from_reader(&mut cursor);
+        let read: RawDataIndex = reader.read_meta().unwrap();
+        assert_eq!(read, index);
+    }
+
     /// Will write the value to an array and return it for comparison.
     ///
     /// The second returned value is the reported written size.
+    #[cfg(feature = "write")]
     fn write_meta_to_buffer<T: TdmsMetaData>(value: T, expected_size: usize) -> Vec<u8> {
         let mut output_buffer = vec![0u8; expected_size];
         {
@@ -444,6 +981,7 @@ mod tests {
         output_buffer
     }
 
+    #[cfg(feature = "write")]
     #[test]
     fn test_properties_standard_data_write() {
         //example from NI "TDMS internal file format"
@@ -528,6 +1066,7 @@ mod tests {
         assert_eq!(objects, expected);
     }
 
+    #[cfg(feature = "write")]
     #[test]
     fn test_properties_raw_data_matches_write() {
         //example from NI "TDMS internal file format"
@@ -565,4 +1104,95 @@ mod tests {
         let output = write_meta_to_buffer(meta, expected_buffer.len());
         assert_eq!(output, expected_buffer);
     }
+
+    #[cfg(feature = "write")]
+    fn vendor_meta_data() -> MetaData {
+        MetaData {
+            objects: vec![
+                ObjectMetaData {
+                    path: String::from("/'Group'"),
+                    properties: vec![
+                        (
+                            String::from("vendor"),
+                            PropertyValue::Unknown {
+                                type_code: 0x23,
+                                bytes: vec![1, 2, 3, 4, 5, 6],
+                            },
+                        ),
+                        (String::from("num"), PropertyValue::I32(10)),
+                    ],
+                    raw_data_index: RawDataIndex::None,
+                },
+                ObjectMetaData {
+                    path: String::from("/'Group'/'Channel1'"),
+                    properties: vec![(
+                        String::from("trailer"),
+                        PropertyValue::Unknown {
+                            type_code: 0x1234,
+                            bytes: vec![0xAA; 3],
+                        },
+                    )],
+                    raw_data_index: RawDataIndex::None,
+                },
+            ],
+        }
+    }
+
+    #[cfg(feature = "write")]
+    #[test]
+    fn test_unknown_properties_round_trip() {
+        let meta = vendor_meta_data();
+        let bytes = write_meta_to_buffer(meta.clone(), meta.size());
+
+        let mut reader = LittleEndianReader::from_reader(Cursor::new(&bytes));
+        let strict: Result<MetaData, TdmsError> = reader.read_meta();
+        assert!(matches!(strict, Err(TdmsError::UnknownPropertyType(0x23))));
+
+        let lenient = MetaData::read_lenient(&bytes, false).unwrap();
+        assert_eq!(lenient, meta);
+        assert_eq!(write_meta_to_buffer(lenient, bytes.len()), bytes);
+    }
+
+    #[cfg(feature = "write")]
+    #[test]
+    fn test_unknown_properties_read_from_file() {
+        use crate::{PropertyPath, SegmentBuilder, SegmentRawIndex, TdmsFile};
+
+        let meta = vendor_meta_data();
+        let builder = SegmentBuilder::new()
+            .object(
+                &PropertyPath::group("Group"),
+                SegmentRawIndex::NoData,
+                &[
+                    ("vendor", meta.objects[0].properties[0].1.clone()),
+                    ("num", PropertyValue::I32(10)),
+                ],
+            )
+            .object(
+                &PropertyPath::channel("Group", "Channel1"),
+                SegmentRawIndex::NoData,
+                &[("trailer", meta.objects[1].properties[0].1.clone())],
+            );
+
+        let mut buffer = Cursor::new(vec![]);
+        let mut file = TdmsFile::new(&mut buffer).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer.write_built_segment(&builder).unwrap();
+        writer.write_built_segment(&builder).unwrap();
+        drop(writer);
+        drop(file);
+
+        let bytes = buffer.into_inner();
+        let file = TdmsFile::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(
+            file.read_property(&PropertyPath::group("Group"), "vendor")
+                .unwrap(),
+            Some(&meta.objects[0].properties[0].1)
+        );
+        assert_eq!(
+            file.read_property(&PropertyPath::group("Group"), "num")
+                .unwrap(),
+            Some(&PropertyValue::I32(10))
+        );
+    }
 }
@@ -5,8 +5,8 @@ use std::fmt::{Debug, Display};
 use crate::error::TdmsError;
 
 /// The internal type of paths into the TDMS file.
-pub type ObjectPath<'a> = &'a str;
-pub type ObjectPathOwned = String;
+pub type RawPath<'a> = &'a str;
+pub type RawPathOwned = String;
 
 /// Names in the path must be escaped.
 ///
@@ -22,7 +22,7 @@ fn escape_name(name: &str) -> String {
 /// - Ok(None, None): The path is the root of the file.
 /// - Ok(Some(group), None): The path is to a group.
 /// - Ok(Some(group), Some(channel)): The path is to a channel.
-fn parse_path(path: ObjectPath) -> Result<(Option<&str>, Option<&str>), TdmsError> {
+pub(crate) fn parse_path(path: RawPath<'_>) -> Result<(Option<&str>, Option<&str>), TdmsError> {
     //Simple filter.
     if !path.starts_with('/') {
         return Err(TdmsError::InvalidObjectPath(path.to_string()));
@@ -58,7 +58,7 @@ fn parse_name(name: &str) -> Option<&str> {
 }
 
 /// Get the group name for the path, if one exists.
-pub fn path_group_name(path: ObjectPath) -> Option<&str> {
+pub fn path_group_name(path: RawPath<'_>) -> Option<&str> {
     parse_path(path).ok()?.0
 }
 
@@ -112,7 +112,7 @@ impl PropertyPath {
     }
 
     /// Get the path in the internal format.
-    pub fn path(&self) -> ObjectPath {
+    pub fn path(&self) -> RawPath<'_> {
         self.0.as_ref()
     }
 
@@ -137,10 +137,10 @@ impl Display for PropertyPath {
     }
 }
 
-impl<'a> TryFrom<ObjectPath<'a>> for PropertyPath {
+impl<'a> TryFrom<RawPath<'a>> for PropertyPath {
     type Error = TdmsError;
 
-    fn try_from(value: ObjectPath) -> Result<Self, Self::Error> {
+    fn try_from(value: RawPath) -> Result<Self, Self::Error> {
         let parsed = parse_path(value)?;
         match parsed {
             (None, None) => Ok(Self::file()),
@@ -166,7 +166,7 @@ pub struct ChannelPath(PropertyPath);
 
 impl ChannelPath {
     /// Get the path in the internal format.
-    pub fn path(&self) -> ObjectPath {
+    pub fn path(&self) -> RawPath<'_> {
         self.0.path()
     }
 
@@ -198,10 +198,10 @@ impl std::fmt::Display for ChannelPath {
     }
 }
 
-impl<'a> TryFrom<ObjectPath<'a>> for ChannelPath {
+impl<'a> TryFrom<RawPath<'a>> for ChannelPath {
     type Error = TdmsError;
 
-    fn try_from(value: ObjectPath) -> Result<Self, Self::Error> {
+    fn try_from(value: RawPath) -> Result<Self, Self::Error> {
         let path = PropertyPath::try_from(value)?;
         path.try_into()
     }
@@ -217,6 +217,162 @@ impl TryFrom<PropertyPath> for ChannelPath {
     }
 }
 
+/// Path for a group in the TDMS file.
+///
+/// ```rust
+/// use tedium::GroupPath;
+///
+/// let path = GroupPath::new("group");
+/// assert_eq!(path.path(), "/'group'");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GroupPath(PropertyPath);
+
+impl GroupPath {
+    /// Create a new path to the named group.
+    pub fn new(group: &str) -> Self {
+        Self(PropertyPath::group(group))
+    }
+
+    /// Get the path in the internal format.
+    pub fn path(&self) -> RawPath<'_> {
+        self.0.path()
+    }
+
+    /// Get the path to a channel in this group.
+    pub fn channel(&self, channel: &str) -> ChannelPath {
+        ChannelPath(PropertyPath(format!(
+            "{}/'{}'",
+            self.path(),
+            escape_name(channel)
+        )))
+    }
+}
+
+impl AsRef<GroupPath> for GroupPath {
+    fn as_ref(&self) -> &GroupPath {
+        self
+    }
+}
+
+impl AsRef<PropertyPath> for GroupPath {
+    fn as_ref(&self) -> &PropertyPath {
+        &self.0
+    }
+}
+
+impl Display for GroupPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<'a> TryFrom<RawPath<'a>> for GroupPath {
+    type Error = TdmsError;
+
+    fn try_from(value: RawPath) -> Result<Self, Self::Error> {
+        PropertyPath::try_from(value)?.try_into()
+    }
+}
+
+impl TryFrom<PropertyPath> for GroupPath {
+    type Error = TdmsError;
+
+    fn try_from(path: PropertyPath) -> Result<Self, Self::Error> {
+        if path.path_depth() != 1 || path.path() == "/" {
+            return Err(TdmsError::InvalidGroupPath(path.path().to_string()));
+        }
+        Ok(Self(path))
+    }
+}
+
+/// A path to any object in a TDMS file: the file itself, a group or a channel.
+///
+/// ```rust
+/// use tedium::{ChannelPath, ObjectPath};
+///
+/// let path = ObjectPath::parse("/'group'/'channel'").unwrap();
+/// assert_eq!(path, ObjectPath::Channel(ChannelPath::new("group", "channel")));
+/// assert!(ObjectPath::parse("/'group'/'channel'/'extra'").is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ObjectPath {
+    File,
+    Group(GroupPath),
+    Channel(ChannelPath),
+}
+
+impl ObjectPath {
+    /// Parse a path in the internal TDMS format, such as `/'group'/'channel'`.
+    ///
+    /// Errors with [`TdmsError::InvalidObjectPath`] if the path is malformed.
+    pub fn parse(path: &str) -> Result<Self, TdmsError> {
+        match parse_path(path)? {
+            (None, None) => Ok(ObjectPath::File),
+            (Some(group), None) => Ok(ObjectPath::Group(GroupPath::new(group))),
+            (Some(group), Some(channel)) => {
+                Ok(ObjectPath::Channel(ChannelPath::new(group, channel)))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Get the path in the internal format.
+    pub fn path(&self) -> RawPath<'_> {
+        match self {
+            ObjectPath::File => "/",
+            ObjectPath::Group(group) => group.path(),
+            ObjectPath::Channel(channel) => channel.path(),
+        }
+    }
+}
+
+impl Display for ObjectPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path())
+    }
+}
+
+impl<'a> TryFrom<RawPath<'a>> for ObjectPath {
+    type Error = TdmsError;
+
+    fn try_from(value: RawPath) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+}
+
+impl From<GroupPath> for ObjectPath {
+    fn from(value: GroupPath) -> Self {
+        ObjectPath::Group(value)
+    }
+}
+
+impl From<ChannelPath> for ObjectPath {
+    fn from(value: ChannelPath) -> Self {
+        ObjectPath::Channel(value)
+    }
+}
+
+impl From<PropertyPath> for ObjectPath {
+    fn from(value: PropertyPath) -> Self {
+        match value.path_depth() {
+            _ if value.path() == "/" => ObjectPath::File,
+            1 => ObjectPath::Group(GroupPath(value)),
+            _ => ObjectPath::Channel(ChannelPath(value)),
+        }
+    }
+}
+
+impl From<ObjectPath> for PropertyPath {
+    fn from(value: ObjectPath) -> Self {
+        match value {
+            ObjectPath::File => PropertyPath::file(),
+            ObjectPath::Group(group) => group.0,
+            ObjectPath::Channel(channel) => channel.0,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -331,4 +487,53 @@ mod tests {
         assert_eq!(path_group_name("/"), None);
         assert_eq!(path_group_name("invalid"), None);
     }
+
+    #[test]
+    fn test_group_path_try_from_object_path() {
+        let path = GroupPath::try_from("/'group'").unwrap();
+        assert_eq!(path, GroupPath::new("group"));
+        assert!(matches!(
+            GroupPath::try_from("/'group'/'channel'"),
+            Err(TdmsError::InvalidGroupPath(_))
+        ));
+        assert!(matches!(
+            GroupPath::try_from("/"),
+            Err(TdmsError::InvalidGroupPath(_))
+        ));
+        assert!(matches!(
+            GroupPath::try_from("group"),
+            Err(TdmsError::InvalidObjectPath(_))
+        ));
+    }
+
+    #[test]
+    fn test_group_path_channel() {
+        let group = GroupPath::new("group'1");
+        assert_eq!(group.channel("ch'1"), ChannelPath::new("group'1", "ch'1"));
+    }
+
+    #[test]
+    fn test_object_path_parse() {
+        assert_eq!(ObjectPath::parse("/").unwrap(), ObjectPath::File);
+        assert_eq!(
+            ObjectPath::parse("/'group'").unwrap(),
+            ObjectPath::Group(GroupPath::new("group"))
+        );
+        assert_eq!(
+            ObjectPath::parse("/'group'/'channel'").unwrap().path(),
+            "/'group'/'channel'"
+        );
+        assert!(ObjectPath::parse("/group").is_err());
+        assert!(ObjectPath::parse("").is_err());
+    }
+
+    #[test]
+    fn test_object_path_property_path_conversion() {
+        for path in ["/", "/'group'", "/'group'/'channel'"] {
+            let property_path = PropertyPath::try_from(path).unwrap();
+            let object_path = ObjectPath::from(property_path.clone());
+            assert_eq!(object_path, ObjectPath::parse(path).unwrap());
+            assert_eq!(PropertyPath::from(object_path), property_path);
+        }
+    }
 }
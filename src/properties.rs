@@ -6,11 +6,15 @@
 use labview_interop::types::LVTime;
 
 use crate::error::TdmsError;
-use crate::io::data_types::{Complex, DataType, ExtendedRaw, TdmsStorageType};
+use crate::io::data_types::{Complex, DataType, ExtendedRaw, TdmsStorageType, UnixNanos};
 use crate::io::reader::TdmsReader;
+#[cfg(feature = "write")]
 use crate::io::writer::TdmsWriter;
 use crate::meta_data::TdmsMetaData;
-use std::io::{Read, Seek, Write};
+use std::fmt::{Display, Formatter};
+#[cfg(feature = "write")]
+use std::io::Write;
+use std::io::{Read, Seek};
 
 /// A wrapper type for data types found in tdms files
 #[derive(Debug, Clone, PartialEq)]
@@ -32,9 +36,21 @@ pub enum PropertyValue {
     ComplexSingleFloat(Complex<f32>),
     ComplexDoubleFloat(Complex<f64>),
     Timestamp(LVTime),
+    /// A property with a type code this library doesn't understand.
+    ///
+    /// The value bytes are kept exactly as read so they can be written back
+    /// unchanged.
+    Unknown {
+        type_code: u32,
+        bytes: Vec<u8>,
+    },
 }
 
 impl PropertyValue {
+    /// The data type of the value.
+    ///
+    /// [`PropertyValue::Unknown`] reports [`DataType::Void`] as its type code
+    /// is not a known data type.
     pub const fn datatype(&self) -> DataType {
         match self {
             PropertyValue::Boolean(_) => DataType::Boolean,
@@ -54,10 +70,174 @@ impl PropertyValue {
             PropertyValue::Void => DataType::Void,
             PropertyValue::ComplexSingleFloat(_) => DataType::ComplexSingleFloat,
             PropertyValue::ComplexDoubleFloat(_) => DataType::ComplexDoubleFloat,
+            // The type code isn't a known data type.
+            PropertyValue::Unknown { .. } => DataType::Void,
+        }
+    }
+
+    /// The value as a float if it is any float or integer type.
+    ///
+    /// 64 bit integers beyond 2^53 are rounded to the nearest float.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            PropertyValue::I8(value) => Some(*value as f64),
+            PropertyValue::I16(value) => Some(*value as f64),
+            PropertyValue::I32(value) => Some(*value as f64),
+            PropertyValue::I64(value) => Some(*value as f64),
+            PropertyValue::U8(value) => Some(*value as f64),
+            PropertyValue::U16(value) => Some(*value as f64),
+            PropertyValue::U32(value) => Some(*value as f64),
+            PropertyValue::U64(value) => Some(*value as f64),
+            PropertyValue::SingleFloat(value) => Some(*value as f64),
+            PropertyValue::DoubleFloat(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The value as an integer if it is an integer type that fits in an i64.
+    ///
+    /// Floats are not converted as that would lose the fraction.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            PropertyValue::I8(value) => Some(*value as i64),
+            PropertyValue::I16(value) => Some(*value as i64),
+            PropertyValue::I32(value) => Some(*value as i64),
+            PropertyValue::I64(value) => Some(*value),
+            PropertyValue::U8(value) => Some(*value as i64),
+            PropertyValue::U16(value) => Some(*value as i64),
+            PropertyValue::U32(value) => Some(*value as i64),
+            PropertyValue::U64(value) => i64::try_from(*value).ok(),
+            _ => None,
+        }
+    }
+
+    /// The value if it is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            PropertyValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The value if it is a timestamp.
+    pub fn as_timestamp(&self) -> Option<LVTime> {
+        match self {
+            PropertyValue::Timestamp(value) => Some(*value),
+            _ => None,
         }
     }
 }
 
+/// Formats the value for display, independent of the system locale.
+///
+/// Floats use the shortest representation that reads back as the same value,
+/// unless a precision is given such as `{:.3}`. Timestamps are shown in UTC in
+/// RFC 3339 format and complex numbers as `1+2i`. Void is empty.
+///
+/// # Example
+/// ```rust
+/// use tedium::PropertyValue;
+///
+/// assert_eq!(PropertyValue::DoubleFloat(0.1).to_string(), "0.1");
+/// assert_eq!(format!("{:.2}", PropertyValue::DoubleFloat(1.0 / 3.0)), "0.33");
+/// ```
+impl Display for PropertyValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PropertyValue::Void => Ok(()),
+            PropertyValue::Boolean(value) => write!(f, "{value}"),
+            PropertyValue::I8(value) => write!(f, "{value}"),
+            PropertyValue::I16(value) => write!(f, "{value}"),
+            PropertyValue::I32(value) => write!(f, "{value}"),
+            PropertyValue::I64(value) => write!(f, "{value}"),
+            PropertyValue::U8(value) => write!(f, "{value}"),
+            PropertyValue::U16(value) => write!(f, "{value}"),
+            PropertyValue::U32(value) => write!(f, "{value}"),
+            PropertyValue::U64(value) => write!(f, "{value}"),
+            PropertyValue::SingleFloat(value) => write_float(f, *value),
+            PropertyValue::DoubleFloat(value) => write_float(f, *value),
+            PropertyValue::Extended(value) => write_float(f, value.to_f64()),
+            PropertyValue::String(value) => write!(f, "{value}"),
+            PropertyValue::ComplexSingleFloat(value) => {
+                write_complex(f, value.real, value.imaginary)
+            }
+            PropertyValue::ComplexDoubleFloat(value) => {
+                write_complex(f, value.real, value.imaginary)
+            }
+            PropertyValue::Timestamp(value) => write_timestamp(f, value.as_unix_nanos()),
+            PropertyValue::Unknown { type_code, bytes } => {
+                write!(f, "<unknown type {type_code:#X}, {} bytes>", bytes.len())
+            }
+        }
+    }
+}
+
+fn write_float(f: &mut Formatter<'_>, value: impl Display) -> std::fmt::Result {
+    match f.precision() {
+        Some(precision) => write!(f, "{value:.precision$}"),
+        None => write!(f, "{value}"),
+    }
+}
+
+fn write_complex<T>(f: &mut Formatter<'_>, real: T, imaginary: T) -> std::fmt::Result
+where
+    T: Display + Copy + Into<f64> + std::ops::Neg<Output = T>,
+{
+    write_float(f, real)?;
+    if imaginary.into().is_sign_negative() {
+        write!(f, "-")?;
+        write_float(f, -imaginary)?;
+    } else {
+        write!(f, "+")?;
+        write_float(f, imaginary)?;
+    }
+    write!(f, "i")
+}
+
+/// Write the time as `YYYY-MM-DDTHH:MM:SS.fffffffffZ`, leaving out a zero fraction.
+fn write_timestamp(f: &mut Formatter<'_>, unix_nanos: i128) -> std::fmt::Result {
+    const NANOS_PER_DAY: i128 = 86_400 * 1_000_000_000;
+    let days = unix_nanos.div_euclid(NANOS_PER_DAY);
+    let nanos_of_day = unix_nanos.rem_euclid(NANOS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+
+    let seconds_of_day = nanos_of_day / 1_000_000_000;
+    let nanos = nanos_of_day % 1_000_000_000;
+    write!(
+        f,
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}",
+        seconds_of_day / 3600,
+        seconds_of_day / 60 % 60,
+        seconds_of_day % 60
+    )?;
+    if nanos != 0 {
+        write!(f, ".{nanos:09}")?;
+    }
+    write!(f, "Z")
+}
+
+/// Convert days since the unix epoch to a proleptic Gregorian calendar date.
+///
+/// From Howard Hinnant's `civil_from_days` algorithm.
+fn civil_from_days(days: i128) -> (i128, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i128::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(feature = "write")]
 fn write_property_components<W: Write, T: TdmsStorageType>(
     writer: &mut impl TdmsWriter<W>,
     data_type: DataType,
@@ -104,6 +284,7 @@ impl TdmsMetaData for PropertyValue {
         }
     }
 
+    #[cfg(feature = "write")]
     fn write<W: std::io::Write>(
         &self,
         writer: &mut impl crate::io::writer::TdmsWriter<W>,
@@ -145,6 +326,10 @@ impl TdmsMetaData for PropertyValue {
             PropertyValue::Timestamp(value) => {
                 write_property_components(writer, self.datatype(), value)
             }
+            PropertyValue::Unknown { type_code, bytes } => {
+                writer.write_value(type_code)?;
+                writer.write_values(&bytes[..])
+            }
         }
     }
 
@@ -167,6 +352,7 @@ impl TdmsMetaData for PropertyValue {
             PropertyValue::U8(value) => value.size(),
             PropertyValue::U16(value) => value.size(),
             PropertyValue::Timestamp(value) => value.size(),
+            PropertyValue::Unknown { bytes, .. } => bytes.len(),
         };
         internal_size + std::mem::size_of::<u32>()
     }
@@ -254,14 +440,18 @@ impl<'a> TryFrom<&'a PropertyValue> for &'a str {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "write")]
     use crate::io::reader::LittleEndianReader;
+    #[cfg(feature = "write")]
     use crate::io::writer::LittleEndianWriter;
+    #[cfg(feature = "write")]
     use std::io::Cursor;
 
     macro_rules! test_property_type {
         ($name:literal, $type:ty, $value:expr, $prop_value:expr) => {
             paste::item! {
                 #[allow(non_snake_case)]
+                #[cfg(feature = "write")]
                 #[test]
                 fn [<$name _read_write>]() {
                     let mut buffer = vec![];
@@ -274,6 +464,7 @@ mod tests {
                 }
 
                 #[allow(non_snake_case)]
+                #[cfg(feature = "write")]
                 #[test]
                 fn [< $name _size >]() {
                     let mut buffer = vec![];
@@ -328,8 +519,76 @@ mod tests {
         PropertyValue::ComplexDoubleFloat(Complex::new(1.0, 2.0))
     );
 
+    #[test]
+    fn test_accessors_widen_numbers() {
+        assert_eq!(PropertyValue::U8(5).as_f64(), Some(5.0));
+        assert_eq!(PropertyValue::SingleFloat(0.5).as_f64(), Some(0.5));
+        assert_eq!(PropertyValue::String("5".into()).as_f64(), None);
+
+        assert_eq!(PropertyValue::I16(-5).as_i64(), Some(-5));
+        assert_eq!(PropertyValue::U32(u32::MAX).as_i64(), Some(u32::MAX as i64));
+        assert_eq!(PropertyValue::U64(u64::MAX).as_i64(), None);
+        assert_eq!(PropertyValue::DoubleFloat(1.0).as_i64(), None);
+
+        assert_eq!(PropertyValue::String("name".into()).as_str(), Some("name"));
+        assert_eq!(PropertyValue::Boolean(true).as_str(), None);
+
+        let time = LVTime::from_unix_epoch(100.0);
+        assert_eq!(PropertyValue::Timestamp(time).as_timestamp(), Some(time));
+        assert_eq!(PropertyValue::I64(100).as_timestamp(), None);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(PropertyValue::Void.to_string(), "");
+        assert_eq!(PropertyValue::Boolean(true).to_string(), "true");
+        assert_eq!(PropertyValue::I64(-1_000_000).to_string(), "-1000000");
+        assert_eq!(PropertyValue::SingleFloat(0.1).to_string(), "0.1");
+        assert_eq!(
+            PropertyValue::DoubleFloat(1e21).to_string(),
+            "1000000000000000000000"
+        );
+        assert_eq!(format!("{:.3}", PropertyValue::DoubleFloat(2.0)), "2.000");
+        assert_eq!(format!("{:.1}", PropertyValue::I32(2)), "2");
+        assert_eq!(
+            PropertyValue::Extended(ExtendedRaw::new(0x3FFF_8000_0000_0000_0000)).to_string(),
+            "1"
+        );
+        assert_eq!(
+            PropertyValue::String("Volts, DC".to_string()).to_string(),
+            "Volts, DC"
+        );
+        assert_eq!(
+            PropertyValue::ComplexDoubleFloat(Complex::new(1.5, -2.0)).to_string(),
+            "1.5-2i"
+        );
+        assert_eq!(
+            format!(
+                "{:.1}",
+                PropertyValue::ComplexSingleFloat(Complex::new(1.0, 2.0))
+            ),
+            "1.0+2.0i"
+        );
+    }
+
+    #[test]
+    fn test_display_timestamp() {
+        let display = |nanos| PropertyValue::Timestamp(LVTime::from_unix_nanos(nanos)).to_string();
+        assert_eq!(display(0), "1970-01-01T00:00:00Z");
+        assert_eq!(
+            display(1_709_210_096_123_456_789),
+            "2024-02-29T12:34:56.123456789Z"
+        );
+        assert_eq!(display(-1), "1969-12-31T23:59:59.999999999Z");
+        assert_eq!(
+            PropertyValue::Timestamp(LVTime::from_parts(0, 0)).to_string(),
+            "1904-01-01T00:00:00Z"
+        );
+    }
+
     /// As properties can't directly link to units, united types are loaded
     /// as plain numbers.
+    #[cfg(feature = "write")]
     #[test]
     fn test_float_with_units_treated_as_float() {
         let mut buffer = vec![];
@@ -345,6 +604,7 @@ mod tests {
 
     /// As properties can't directly link to units, united types are loaded
     /// as plain numbers.
+    #[cfg(feature = "write")]
     #[test]
     fn test_double_float_with_units_treated_as_float() {
         let mut buffer = vec![];
@@ -360,6 +620,7 @@ mod tests {
 
     /// As properties can't directly link to units, united types are loaded
     /// as plain numbers.
+    #[cfg(feature = "write")]
     #[test]
     fn test_extended_float_with_units_treated_as_float() {
         let mut buffer = vec![];
@@ -375,6 +636,7 @@ mod tests {
         assert_eq!(value, PropertyValue::Extended(prop_value));
     }
 
+    #[cfg(feature = "write")]
     #[test]
     fn test_void_read() {
         let mut buffer = vec![];
@@ -387,6 +649,7 @@ mod tests {
         assert_eq!(value, PropertyValue::Void);
     }
 
+    #[cfg(feature = "write")]
     #[test]
     fn test_void_roundtrip() {
         let mut buffer = vec![];
@@ -33,42 +33,70 @@ impl<R: Read + Seek, T: TdmsReader<R>> MultiChannelContigousReader<R, T> {
 
     /// Read the data from the block for the channels specified into the output slices.
     ///
-    /// Returns the number of values read from the last read channel.
-    /// *ASSUMPTION*: All channels have the same number of values available. The spec
-    /// doesn't enforce this but all clients have I have seen do.
+    /// The first `skip_values` values of each channel are skipped to support reading from an offset.
     ///
+    /// Channels may have a different number of values in each sub-block, as the spec allows.
+    /// Returns the most values read into any channel after the skip.
     pub fn read<D: TdmsStorageType>(
         &mut self,
         mut channels: RecordStructure<D>,
+        skip_values: usize,
     ) -> Result<usize, TdmsError> {
-        self.reader.to_file_position(self.block_start)?;
+        let sub_block_size = channels.block_size() as u64;
+        if sub_block_size == 0 {
+            return Ok(0);
+        }
+        let total_sub_blocks = self.block_size / sub_block_size;
+
+        // Whole sub-blocks can be jumped over while every channel still has values to skip.
+        // Any remainder is skipped inside the sub-blocks read.
+        let skip_sub_blocks = channels
+            .read_instructions()
+            .iter()
+            .filter(|entry| matches!(entry.plan, RecordEntryPlan::Read(_)) && entry.length > 0)
+            .map(|entry| (skip_values / entry.length) as u64)
+            .min()
+            .unwrap_or(0)
+            .min(total_sub_blocks);
+        let mut remaining_skips: Vec<usize> = channels
+            .read_instructions()
+            .iter()
+            .map(|entry| skip_values - (skip_sub_blocks as usize * entry.length).min(skip_values))
+            .collect();
 
-        let total_sub_blocks = self.block_size / channels.block_size() as u64;
+        self.reader
+            .to_file_position(self.block_start + skip_sub_blocks * sub_block_size)?;
 
-        let mut length = 0;
+        let mut lengths = vec![0; remaining_skips.len()];
 
-        for _ in 0..total_sub_blocks {
-            length += self.read_sub_block(&mut channels)?;
+        for _ in skip_sub_blocks..total_sub_blocks {
+            self.read_sub_block(&mut channels, &mut remaining_skips, &mut lengths)?;
         }
 
-        Ok(length)
+        Ok(lengths.into_iter().max().unwrap_or(0))
     }
 
+    /// Read a single sub-block, skipping and counting the values for each entry.
     fn read_sub_block<D: TdmsStorageType>(
         &mut self,
         channels: &mut RecordStructure<'_, D>,
-    ) -> Result<usize, TdmsError> {
-        let mut length = 0;
-        for read_instruction in channels.read_instructions().iter_mut() {
+        remaining_skips: &mut [usize],
+        lengths: &mut [usize],
+    ) -> Result<(), TdmsError> {
+        for (index, read_instruction) in channels.read_instructions().iter_mut().enumerate() {
             match &mut read_instruction.plan {
                 RecordEntryPlan::Read(output) => {
-                    for _ in 0..read_instruction.length {
+                    let skip_values = remaining_skips[index].min(read_instruction.length);
+                    remaining_skips[index] -= skip_values;
+                    self.reader
+                        .move_position((skip_values * D::SIZE_BYTES) as i64)?;
+                    for _ in skip_values..read_instruction.length {
                         let read_value = self.reader.read_value()?;
                         if let Some(value) = output.next() {
                             *value = read_value;
                         }
                     }
-                    length = read_instruction.length;
+                    lengths[index] += read_instruction.length - skip_values;
                 }
                 RecordEntryPlan::Skip(bytes) => {
                     let skip_bytes = *bytes * read_instruction.length as i64;
@@ -77,7 +105,7 @@ impl<R: Read + Seek, T: TdmsReader<R>> MultiChannelContigousReader<R, T> {
             };
         }
 
-        Ok(length)
+        Ok(())
     }
 }
 
@@ -95,7 +123,7 @@ mod tests {
         let mut cursor = Cursor::new(buffer);
         for index in 0..TEST_BUFFER_SIZE {
             let value = index as f64;
-            cursor.write(&value.to_be_bytes()).unwrap();
+            cursor.write_all(&value.to_be_bytes()).unwrap();
         }
         cursor
     }
@@ -124,10 +152,10 @@ mod tests {
             800,
         );
         let mut output: Vec<f64> = vec![0.0; 3];
-        let mut channels = vec![(0usize, &mut output[..])];
+        let mut channels = [(0usize, &mut output[..])];
         let read_plan =
             RecordStructure::<f64>::build_record_plan(&meta, &mut channels[..]).unwrap();
-        reader.read(read_plan).unwrap();
+        reader.read(read_plan, 0).unwrap();
         assert_eq!(output, vec![0.0, 1.0, 2.0]);
     }
 
@@ -144,12 +172,12 @@ mod tests {
         );
         let mut output_1: Vec<f64> = vec![0.0; 3];
         let mut output_2: Vec<f64> = vec![0.0; 3];
-        let mut channels = vec![(0usize, &mut output_1[..]), (2usize, &mut output_2[..])];
+        let mut channels = [(0usize, &mut output_1[..]), (2usize, &mut output_2[..])];
         let read_plan =
             RecordStructure::<f64>::build_record_plan(&meta, &mut channels[..]).unwrap();
 
         let output_2_start = length * 2.0;
-        reader.read(read_plan).unwrap();
+        reader.read(read_plan, 0).unwrap();
         assert_eq!(output_1, vec![0.0, 1.0, 2.0]);
         assert_eq!(
             output_2,
@@ -180,11 +208,11 @@ mod tests {
         );
         let mut output_1: Vec<f64> = vec![0.0; 3];
         let mut output_2: Vec<f64> = vec![0.0; 3];
-        let mut channels = vec![(0usize, &mut output_1[..]), (2usize, &mut output_2[..])];
+        let mut channels = [(0usize, &mut output_1[..]), (2usize, &mut output_2[..])];
         let read_plan =
             RecordStructure::<f64>::build_record_plan(&meta, &mut channels[..]).unwrap();
 
-        reader.read(read_plan).unwrap();
+        reader.read(read_plan, 0).unwrap();
         assert_eq!(output_1, vec![0.0, 1.0, 8.0]);
         assert_eq!(output_2, vec![4.0, 5.0, 12.0]);
     }
@@ -202,14 +230,55 @@ mod tests {
         );
         let mut output_1: Vec<f64> = vec![0.0; 3];
         let mut output_2: Vec<f64> = vec![0.0; 2];
-        let mut channels = vec![(0usize, &mut output_1[..]), (2usize, &mut output_2[..])];
+        let mut channels = [(0usize, &mut output_1[..]), (2usize, &mut output_2[..])];
         let read_plan =
             RecordStructure::<f64>::build_record_plan(&meta, &mut channels[..]).unwrap();
 
-        reader.read(read_plan).unwrap();
+        reader.read(read_plan, 0).unwrap();
 
         let output2_start = length * 2.0;
         assert_eq!(output_1, vec![0.0, 1.0, 2.0]);
         assert_eq!(output_2, vec![output2_start, output2_start + 1.0]);
     }
+
+    #[test]
+    fn read_data_contigous_skipping_values() {
+        let mut buffer = create_test_buffer();
+        let meta = create_test_meta_data(4);
+
+        let mut reader = MultiChannelContigousReader::<_, _>::new(
+            BigEndianReader::from_reader(&mut buffer),
+            0,
+            800,
+        );
+        let mut output: Vec<f64> = vec![0.0; 3];
+        let mut channels = [(1usize, &mut output[..])];
+        let read_plan =
+            RecordStructure::<f64>::build_record_plan(&meta, &mut channels[..]).unwrap();
+        let read = reader.read(read_plan, 5).unwrap();
+        assert_eq!(read, 20);
+        assert_eq!(output, vec![30.0, 31.0, 32.0]);
+    }
+
+    #[test]
+    fn read_data_contigous_skipping_across_repeated_writes() {
+        let mut buffer = create_test_buffer();
+        let mut meta = create_test_meta_data(4);
+        for channel in meta.iter_mut() {
+            channel.number_of_values = 2;
+        }
+        // ch2: 2, 3, 10, 11, 18, 19...
+
+        let mut reader = MultiChannelContigousReader::<_, _>::new(
+            BigEndianReader::from_reader(&mut buffer),
+            0,
+            800,
+        );
+        let mut output: Vec<f64> = vec![0.0; 3];
+        let mut channels = [(1usize, &mut output[..])];
+        let read_plan =
+            RecordStructure::<f64>::build_record_plan(&meta, &mut channels[..]).unwrap();
+        reader.read(read_plan, 3).unwrap();
+        assert_eq!(output, vec![11.0, 18.0, 19.0]);
+    }
 }
@@ -33,16 +33,38 @@ impl<R: Read + Seek, T: TdmsReader<R>> MultiChannelInterleavedReader<R, T> {
 
     /// Read the data from the block for the channels specified into the output slices.
     ///
-    /// Returns the number of values read in this block.
+    /// The first `skip_rows` rows are skipped to support reading from an offset.
+    ///
+    /// Returns the number of values read in this block after the skip.
+    ///
+    /// Errors with [`TdmsError::MisalignedInterleavedBlock`] if the block isn't a whole
+    /// number of rows, as the samples can't be located reliably.
     ///
     /// *ASSUMPTION*: All channels have the same number of values available. The spec
     /// allows for different lengths but all clients have I have seen do not.
     pub fn read<D: TdmsStorageType>(
         &mut self,
         mut channels: RecordStructure<D>,
+        skip_rows: usize,
     ) -> Result<usize, TdmsError> {
-        self.reader.to_file_position(self.block_start)?;
-        let row_count = self.block_size as usize / channels.row_size();
+        let row_size = channels.row_size();
+        if row_size == 0 {
+            return Ok(0);
+        }
+        let residual = self.block_size % row_size as u64;
+        if residual != 0 {
+            return Err(TdmsError::MisalignedInterleavedBlock {
+                block_bytes: self.block_size,
+                stride: row_size as u64,
+                residual,
+            });
+        }
+        // Row counts stay in u64 as blocks can hold more rows than a 32 bit usize.
+        let total_rows = self.block_size / row_size as u64;
+        let skip_rows = (skip_rows as u64).min(total_rows);
+        let row_count = total_rows - skip_rows;
+        self.reader
+            .to_file_position(self.block_start + skip_rows * row_size as u64)?;
 
         for _ in 0..row_count {
             for read_instruction in channels.read_instructions().iter_mut() {
@@ -60,7 +82,8 @@ impl<R: Read + Seek, T: TdmsReader<R>> MultiChannelInterleavedReader<R, T> {
             }
         }
 
-        Ok(row_count)
+        // Callers cap this at their output length so saturating is safe.
+        Ok(usize::try_from(row_count).unwrap_or(usize::MAX))
     }
 }
 
@@ -76,7 +99,7 @@ mod tests {
         let mut cursor = Cursor::new(buffer);
         for index in 0..100 {
             let value = index as f64;
-            cursor.write(&value.to_be_bytes()).unwrap();
+            cursor.write_all(&value.to_be_bytes()).unwrap();
         }
         cursor
     }
@@ -105,10 +128,10 @@ mod tests {
             800,
         );
         let mut output: Vec<f64> = vec![0.0; 3];
-        let mut channels = vec![(0usize, &mut output[..])];
+        let mut channels = [(0usize, &mut output[..])];
         let read_plan =
             RecordStructure::<f64>::build_record_plan(&meta, &mut channels[..]).unwrap();
-        reader.read(read_plan).unwrap();
+        reader.read(read_plan, 0).unwrap();
         assert_eq!(output, vec![0.0, 2.0, 4.0]);
     }
 
@@ -124,10 +147,10 @@ mod tests {
         );
         let mut output_1: Vec<f64> = vec![0.0; 3];
         let mut output_2: Vec<f64> = vec![0.0; 3];
-        let mut channels = vec![(0usize, &mut output_1[..]), (2usize, &mut output_2[..])];
+        let mut channels = [(0usize, &mut output_1[..]), (2usize, &mut output_2[..])];
         let read_plan =
             RecordStructure::<f64>::build_record_plan(&meta, &mut channels[..]).unwrap();
-        reader.read(read_plan).unwrap();
+        reader.read(read_plan, 0).unwrap();
         assert_eq!(output_1, vec![0.0, 4.0, 8.0]);
         assert_eq!(output_2, vec![2.0, 6.0, 10.0]);
     }
@@ -144,11 +167,55 @@ mod tests {
         );
         let mut output_1: Vec<f64> = vec![0.0; 3];
         let mut output_2: Vec<f64> = vec![0.0; 2];
-        let mut channels = vec![(0usize, &mut output_1[..]), (2usize, &mut output_2[..])];
+        let mut channels = [(0usize, &mut output_1[..]), (2usize, &mut output_2[..])];
         let read_plan =
             RecordStructure::<f64>::build_record_plan(&meta, &mut channels[..]).unwrap();
-        reader.read(read_plan).unwrap();
+        reader.read(read_plan, 0).unwrap();
         assert_eq!(output_1, vec![0.0, 4.0, 8.0]);
         assert_eq!(output_2, vec![2.0, 6.0]);
     }
+
+    #[test]
+    fn read_data_interleaved_skipping_rows() {
+        let mut buffer = create_test_buffer();
+        let meta = create_test_meta_data(4);
+
+        let mut reader = MultiChannelInterleavedReader::<_, _>::new(
+            BigEndianReader::from_reader(&mut buffer),
+            0,
+            800,
+        );
+        let mut output: Vec<f64> = vec![0.0; 3];
+        let mut channels = [(1usize, &mut output[..])];
+        let read_plan =
+            RecordStructure::<f64>::build_record_plan(&meta, &mut channels[..]).unwrap();
+        let read = reader.read(read_plan, 2).unwrap();
+        assert_eq!(read, 23);
+        assert_eq!(output, vec![9.0, 13.0, 17.0]);
+    }
+
+    #[test]
+    fn read_data_interleaved_errors_on_partial_row() {
+        let mut buffer = create_test_buffer();
+        let meta = create_test_meta_data(3);
+
+        let mut reader = MultiChannelInterleavedReader::<_, _>::new(
+            BigEndianReader::from_reader(&mut buffer),
+            0,
+            800,
+        );
+        let mut output: Vec<f64> = vec![0.0; 3];
+        let mut channels = [(0usize, &mut output[..])];
+        let read_plan =
+            RecordStructure::<f64>::build_record_plan(&meta, &mut channels[..]).unwrap();
+        let result = reader.read(read_plan, 0);
+        assert!(matches!(
+            result,
+            Err(TdmsError::MisalignedInterleavedBlock {
+                block_bytes: 800,
+                stride: 24,
+                residual: 8
+            })
+        ));
+    }
 }
@@ -5,9 +5,15 @@
 mod contigious_multi_channel_read;
 mod interleaved_multi_channel_read;
 mod records;
+mod strings;
+#[cfg(feature = "write")]
 mod write;
 
 use records::RecordStructure;
+pub(crate) use strings::decode_strings;
+#[cfg(feature = "write")]
+pub(crate) use strings::encode_strings;
+#[cfg(feature = "write")]
 pub use write::{MultiChannelSlice, WriteBlock};
 
 use std::{
@@ -16,7 +22,7 @@ use std::{
 };
 
 use crate::{
-    error::TdmsError,
+    error::{usize_from, TdmsError},
     io::{
         data_types::TdmsStorageType,
         reader::{BigEndianReader, LittleEndianReader, TdmsReader},
@@ -140,20 +146,19 @@ impl DataBlock {
 
     ///Calculate the number of data chunks written to this data block.
     /// This is th number of repeated writes that have occured without new metadata.
-    pub fn number_of_chunks(&self) -> usize {
+    pub fn number_of_chunks(&self) -> u64 {
         let size = self.chunk_size();
 
         match size {
-            ChunkSize::Fixed(size) => (self.length / size) as usize,
+            ChunkSize::Fixed(size) => self.length.checked_div(size).unwrap_or(0),
             ChunkSize::Variable(_) => 1,
         }
     }
 
     /// Read the data from the block for the channels specified into the output slices.
     ///
-    /// We assume all channels in the block have the same length and so return the maximum
-    /// samples read in a given channel. The spec allows this assumption to be broken
-    /// but no clients I have seen do.
+    /// Channels in contigious blocks may have different lengths so this returns the
+    /// maximum samples read in a given channel.
     ///
     /// If an output slice for a channel has a length less than the number of samples it will stop
     /// reading once the end of the slice is reached.
@@ -161,8 +166,21 @@ impl DataBlock {
         &self,
         reader: &mut (impl Read + Seek),
         channels_to_read: &'b mut [(usize, &'b mut [D])],
+    ) -> Result<usize, TdmsError> {
+        self.read_from(reader, 0, channels_to_read)
+    }
+
+    /// Read the data from the block, skipping the first `skip_samples` samples of each channel.
+    ///
+    /// Returns the number of samples read after the skip.
+    pub fn read_from<'b, D: TdmsStorageType>(
+        &self,
+        reader: &mut (impl Read + Seek),
+        skip_samples: u64,
+        channels_to_read: &'b mut [(usize, &'b mut [D])],
     ) -> Result<usize, TdmsError> {
         let record_plan = RecordStructure::build_record_plan(&self.channels, channels_to_read)?;
+        let skip_samples = usize_from(skip_samples)?;
 
         match (self.layout, self.byte_order) {
             // No multichannel implementation for contiguous data yet.
@@ -171,14 +189,14 @@ impl DataBlock {
                 self.start,
                 self.length,
             )
-            .read(record_plan),
+            .read(record_plan, skip_samples),
             (DataLayout::Contigious, Endianess::Little) => {
                 MultiChannelContigousReader::<_, _>::new(
                     LittleEndianReader::from_reader(reader),
                     self.start,
                     self.length,
                 )
-                .read(record_plan)
+                .read(record_plan, skip_samples)
             }
             (DataLayout::Interleaved, Endianess::Big) => {
                 MultiChannelInterleavedReader::<_, _>::new(
@@ -186,7 +204,7 @@ impl DataBlock {
                     self.start,
                     self.length,
                 )
-                .read(record_plan)
+                .read(record_plan, skip_samples)
             }
             (DataLayout::Interleaved, Endianess::Little) => {
                 MultiChannelInterleavedReader::<_, _>::new(
@@ -194,7 +212,7 @@ impl DataBlock {
                     self.start,
                     self.length,
                 )
-                .read(record_plan)
+                .read(record_plan, skip_samples)
             }
         }
     }
@@ -202,6 +220,7 @@ impl DataBlock {
     /// Read a single channel from the block.
     ///
     /// This is a simple wrapper around the `read` function for the common case of reading a single channel.
+    #[cfg_attr(not(feature = "analysis"), allow(dead_code))]
     pub fn read_single<D: TdmsStorageType>(
         &self,
         channel_index: usize,
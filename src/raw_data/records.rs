@@ -249,7 +249,7 @@ mod tests {
         let read_plan_result =
             RecordStructure::<f64>::build_record_plan(&channels, &mut outputs[..]);
 
-        assert!(matches!(read_plan_result, Ok(_)));
+        assert!(read_plan_result.is_ok());
     }
 
     #[ignore = "Not yet implemented"]
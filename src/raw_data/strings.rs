@@ -0,0 +1,81 @@
+//! The raw data format of string channels.
+//!
+//! Strings are variable length so a block of `n` strings starts with `n`
+//! unsigned 32 bit offsets, each marking the end of a string, followed by the
+//! UTF-8 bytes of all the strings.
+
+/// Encode the strings as raw data in the given byte order.
+#[cfg(feature = "write")]
+pub(crate) fn encode_strings(values: &[impl AsRef<str>], big_endian: bool) -> Vec<u8> {
+    let text_bytes: usize = values.iter().map(|value| value.as_ref().len()).sum();
+    let mut bytes = Vec::with_capacity(values.len() * 4 + text_bytes);
+    let mut end = 0u32;
+    for value in values {
+        end += value.as_ref().len() as u32;
+        let offset = if big_endian {
+            end.to_be_bytes()
+        } else {
+            end.to_le_bytes()
+        };
+        bytes.extend_from_slice(&offset);
+    }
+    for value in values {
+        bytes.extend_from_slice(value.as_ref().as_bytes());
+    }
+    bytes
+}
+
+/// Decode `count` strings from the raw data.
+///
+/// Returns None if the offsets don't fit the data. Invalid UTF-8 is replaced
+/// rather than failing the whole read, as LabVIEW may write local code pages.
+pub(crate) fn decode_strings(bytes: &[u8], count: usize, big_endian: bool) -> Option<Vec<String>> {
+    let offsets = bytes.get(..count.checked_mul(4)?)?;
+    let text = &bytes[offsets.len()..];
+    let mut start = 0;
+    let mut strings = Vec::with_capacity(count);
+    for offset in offsets.chunks_exact(4) {
+        let offset = offset.try_into().expect("chunks are 4 bytes");
+        let end = if big_endian {
+            u32::from_be_bytes(offset)
+        } else {
+            u32::from_le_bytes(offset)
+        } as usize;
+        let value = text.get(start..end)?;
+        strings.push(String::from_utf8_lossy(value).into_owned());
+        start = end;
+    }
+    Some(strings)
+}
+
+#[cfg(all(test, feature = "write"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strings_round_trip_in_both_byte_orders() {
+        let values = ["start", "", "stop ✓"];
+        for big_endian in [false, true] {
+            let bytes = encode_strings(&values, big_endian);
+            assert_eq!(bytes.len(), 12 + 13);
+            assert_eq!(
+                decode_strings(&bytes, values.len(), big_endian).unwrap(),
+                values
+            );
+        }
+        let little = encode_strings(&values, false);
+        assert_eq!(&little[..4], &[5, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_bad_offsets_are_rejected() {
+        let mut bytes = encode_strings(&["a", "b"], false);
+        // The second string ends beyond the data.
+        bytes[4] = 9;
+        assert_eq!(decode_strings(&bytes, 2, false), None);
+        // Offsets going backwards.
+        bytes[4] = 0;
+        assert_eq!(decode_strings(&bytes, 2, false), None);
+        assert_eq!(decode_strings(&bytes[..6], 2, false), None);
+    }
+}
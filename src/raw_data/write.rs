@@ -3,6 +3,7 @@
 use crate::error::TdmsError;
 use crate::io::data_types::TdmsStorageType;
 use crate::meta_data::RawDataMeta;
+use crate::raw_data::DataLayout;
 // This is a circular reference - can we remove it?
 use crate::io::writer::TdmsWriter;
 use std::io::Write;
@@ -25,10 +26,7 @@ impl<D: TdmsStorageType> WriteBlock for &[D] {
     }
 
     fn write<W: Write, T: TdmsWriter<W>>(&self, writer: &mut T) -> Result<(), TdmsError> {
-        for item in *self {
-            writer.write_value(item)?;
-        }
-        Ok(())
+        writer.write_values(self)
     }
 
     fn size(&self) -> usize {
@@ -40,13 +38,31 @@ impl<D: TdmsStorageType> WriteBlock for &[D] {
 pub struct MultiChannelSlice<'a, D: TdmsStorageType>(&'a [D], usize);
 
 impl<'a, D: TdmsStorageType> MultiChannelSlice<'a, D> {
+    /// Wrap values for `channel_count` channels.
+    ///
+    /// Errors with [`TdmsError::BadDataBlockLength`] if the length isn't a multiple of the channel count.
     pub fn from_slice(slice: &'a [D], channel_count: usize) -> Result<Self, TdmsError> {
-        if (slice.len() % channel_count) == 0 {
+        if channel_count > 0 && slice.len().is_multiple_of(channel_count) {
             Ok(Self(slice, channel_count))
         } else {
             Err(TdmsError::BadDataBlockLength(slice.len(), channel_count))
         }
     }
+
+    /// Wrap the complete records of interleaved values and return the values of a ragged final record.
+    ///
+    /// The final record has fewer values than there are channels, for the first channels only.
+    pub fn from_slice_ragged(
+        slice: &'a [D],
+        channel_count: usize,
+    ) -> Result<(Self, &'a [D]), TdmsError> {
+        if channel_count == 0 {
+            return Err(TdmsError::BadDataBlockLength(slice.len(), channel_count));
+        }
+        let complete = slice.len() - slice.len() % channel_count;
+        let (records, ragged) = slice.split_at(complete);
+        Ok((Self(records, channel_count), ragged))
+    }
 }
 
 impl<'a, D: TdmsStorageType> WriteBlock for MultiChannelSlice<'a, D> {
@@ -54,7 +70,7 @@ impl<'a, D: TdmsStorageType> WriteBlock for MultiChannelSlice<'a, D> {
         let basic_meta = self
             .0
             .data_structure()
-            .get(0)
+            .first()
             .expect("Should always/only have 1 entry")
             .clone();
 
@@ -78,6 +94,192 @@ impl<'a, D: TdmsStorageType> WriteBlock for MultiChannelSlice<'a, D> {
     }
 }
 
+/// A range of samples taken from a multi-channel slice.
+///
+/// This allows a large write to be split into several segments without copying the data.
+pub struct MultiChannelChunk<'a, D: TdmsStorageType> {
+    values: &'a [D],
+    channel_count: usize,
+    layout: DataLayout,
+    start: usize,
+    length: usize,
+}
+
+impl<'a, D: TdmsStorageType> MultiChannelSlice<'a, D> {
+    /// The number of samples for each channel.
+    pub fn samples_per_channel(&self) -> usize {
+        self.0.len() / self.1
+    }
+
+    /// The values for all of the channels.
+    pub fn values(&self) -> &'a [D] {
+        self.0
+    }
+
+    /// Take `length` samples per channel starting at `start` from the slice in the given layout.
+    pub fn chunk(
+        &self,
+        layout: DataLayout,
+        start: usize,
+        length: usize,
+    ) -> MultiChannelChunk<'a, D> {
+        MultiChannelChunk {
+            values: self.0,
+            channel_count: self.1,
+            layout,
+            start,
+            length,
+        }
+    }
+}
+
+impl<D: TdmsStorageType> WriteBlock for MultiChannelChunk<'_, D> {
+    fn data_structure(&self) -> Vec<RawDataMeta> {
+        (0..self.channel_count)
+            .map(|_| RawDataMeta {
+                data_type: D::NATURAL_TYPE,
+                number_of_values: self.length as u64,
+                total_size_bytes: None,
+            })
+            .collect()
+    }
+
+    fn write<W: Write, T: TdmsWriter<W>>(&self, writer: &mut T) -> Result<(), TdmsError> {
+        match self.layout {
+            DataLayout::Interleaved => {
+                let start = self.start * self.channel_count;
+                let end = (self.start + self.length) * self.channel_count;
+                (&self.values[start..end]).write(writer)
+            }
+            DataLayout::Contigious => {
+                let samples_per_channel = self.values.len() / self.channel_count;
+                for channel in 0..self.channel_count {
+                    let start = channel * samples_per_channel + self.start;
+                    (&self.values[start..start + self.length]).write(writer)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.length * self.channel_count * std::mem::size_of::<D>()
+    }
+}
+
+/// The most values gathered at once when transposing channel-major data.
+const TRANSPOSE_SCRATCH_VALUES: usize = 4096;
+
+/// A range of samples from channel-major data written in interleaved order.
+///
+/// The values are gathered a tile at a time into a bounded scratch buffer so
+/// the caller never needs to hold an interleaved copy of the data.
+pub struct TransposedChunk<'a, D: TdmsStorageType> {
+    values: &'a [D],
+    channel_count: usize,
+    start: usize,
+    length: usize,
+}
+
+impl<'a, D: TdmsStorageType> MultiChannelSlice<'a, D> {
+    /// Take `length` samples per channel starting at `start` from contigious data to write interleaved.
+    pub fn transposed_chunk(&self, start: usize, length: usize) -> TransposedChunk<'a, D> {
+        TransposedChunk {
+            values: self.0,
+            channel_count: self.1,
+            start,
+            length,
+        }
+    }
+}
+
+impl<D: TdmsStorageType> WriteBlock for TransposedChunk<'_, D> {
+    fn data_structure(&self) -> Vec<RawDataMeta> {
+        (0..self.channel_count)
+            .map(|_| RawDataMeta {
+                data_type: D::NATURAL_TYPE,
+                number_of_values: self.length as u64,
+                total_size_bytes: None,
+            })
+            .collect()
+    }
+
+    fn write<W: Write, T: TdmsWriter<W>>(&self, writer: &mut T) -> Result<(), TdmsError> {
+        let samples_per_channel = self.values.len() / self.channel_count;
+        let tile_samples = (TRANSPOSE_SCRATCH_VALUES / self.channel_count).max(1);
+        let mut scratch = Vec::with_capacity(tile_samples.min(self.length) * self.channel_count);
+
+        let end = self.start + self.length;
+        let mut tile_start = self.start;
+        while tile_start < end {
+            let tile_end = (tile_start + tile_samples).min(end);
+            scratch.clear();
+            for sample in tile_start..tile_end {
+                for channel in 0..self.channel_count {
+                    scratch.push(&self.values[channel * samples_per_channel + sample]);
+                }
+            }
+            for value in &scratch {
+                writer.write_value(*value)?;
+            }
+            tile_start = tile_end;
+        }
+        Ok(())
+    }
+
+    fn size(&self) -> usize {
+        self.length * self.channel_count * std::mem::size_of::<D>()
+    }
+}
+
+/// A range of samples from interleaved data written one channel after another.
+///
+/// Used where the output must be contiguous but the caller has records of
+/// every channel. Values are written straight from the slice without a copy.
+pub struct DeinterleavedChunk<'a, D: TdmsStorageType> {
+    values: &'a [D],
+    channel_count: usize,
+    start: usize,
+    length: usize,
+}
+
+impl<'a, D: TdmsStorageType> MultiChannelSlice<'a, D> {
+    /// Take `length` samples per channel starting at `start` from interleaved data to write contigious.
+    pub fn deinterleaved_chunk(&self, start: usize, length: usize) -> DeinterleavedChunk<'a, D> {
+        DeinterleavedChunk {
+            values: self.0,
+            channel_count: self.1,
+            start,
+            length,
+        }
+    }
+}
+
+impl<D: TdmsStorageType> WriteBlock for DeinterleavedChunk<'_, D> {
+    fn data_structure(&self) -> Vec<RawDataMeta> {
+        (0..self.channel_count)
+            .map(|_| RawDataMeta {
+                data_type: D::NATURAL_TYPE,
+                number_of_values: self.length as u64,
+                total_size_bytes: None,
+            })
+            .collect()
+    }
+
+    fn write<W: Write, T: TdmsWriter<W>>(&self, writer: &mut T) -> Result<(), TdmsError> {
+        for channel in 0..self.channel_count {
+            for sample in self.start..self.start + self.length {
+                writer.write_value(&self.values[sample * self.channel_count + channel])?;
+            }
+        }
+        Ok(())
+    }
+
+    fn size(&self) -> usize {
+        self.length * self.channel_count * std::mem::size_of::<D>()
+    }
+}
+
 #[cfg(test)]
 mod write_tests {
     use crate::{io::data_types::DataType, io::writer::LittleEndianWriter};
@@ -86,7 +288,7 @@ mod write_tests {
 
     #[test]
     fn single_channel_writer_generates_meta_data() {
-        let data = vec![0u32; 20];
+        let data = [0u32; 20];
         let meta = (&data[..]).data_structure();
 
         // Although total size isi calculable this is only used for strings.
@@ -101,7 +303,7 @@ mod write_tests {
 
     #[test]
     fn single_channel_writer_writes_with_endianess() {
-        let data = vec![0u32, 1, 2, 3];
+        let data = [0u32, 1, 2, 3];
 
         let mut buf = vec![];
         {
@@ -120,7 +322,7 @@ mod write_tests {
 
     #[test]
     fn multi_channel_writer_generates_meta_data() {
-        let data = vec![0u32; 20];
+        let data = [0u32; 20];
         let multi_channel = MultiChannelSlice::from_slice(&data[..], 4).unwrap();
         let meta = multi_channel.data_structure();
 
@@ -145,11 +347,104 @@ mod write_tests {
     /// In this case it is bad because 20 isn't divisible by 3.
     #[test]
     fn multi_channel_writer_errors_bad_channel_length() {
-        let data = vec![0u32; 20];
+        let data = [0u32; 20];
         let multi_channel_result = MultiChannelSlice::from_slice(&data[..], 3);
         assert!(matches!(
             multi_channel_result,
             Err(TdmsError::BadDataBlockLength(20, 3))
         ))
     }
+
+    #[test]
+    fn multi_channel_ragged_splits_final_record() {
+        let data = [0u32; 20];
+        let (records, ragged) = MultiChannelSlice::from_slice_ragged(&data[..], 3).unwrap();
+        assert_eq!(records.samples_per_channel(), 6);
+        assert_eq!(ragged.len(), 2);
+        assert!(MultiChannelSlice::from_slice(&data[..], 0).is_err());
+        assert!(MultiChannelSlice::from_slice_ragged(&data[..], 0).is_err());
+    }
+
+    #[test]
+    fn multi_channel_chunk_writes_each_layout() {
+        let data = [1u8, 2, 3, 4, 5, 6];
+        let multi_channel = MultiChannelSlice::from_slice(&data[..], 2).unwrap();
+
+        let mut buf = vec![];
+        {
+            let mut writer = LittleEndianWriter::from_writer(&mut buf);
+            let chunk = multi_channel.chunk(DataLayout::Contigious, 1, 2);
+            assert_eq!(chunk.size(), 4);
+            assert_eq!(chunk.data_structure()[0].number_of_values, 2);
+            chunk.write(&mut writer).unwrap();
+            multi_channel
+                .chunk(DataLayout::Interleaved, 1, 2)
+                .write(&mut writer)
+                .unwrap();
+        }
+
+        assert_eq!(&buf[..], &[2, 3, 5, 6, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn transposed_chunk_interleaves_channel_major_data() {
+        let data = [1u8, 2, 3, 4, 5, 6];
+        let multi_channel = MultiChannelSlice::from_slice(&data[..], 2).unwrap();
+
+        let mut buf = vec![];
+        {
+            let mut writer = LittleEndianWriter::from_writer(&mut buf);
+            let chunk = multi_channel.transposed_chunk(1, 2);
+            assert_eq!(chunk.size(), 4);
+            assert_eq!(chunk.data_structure()[1].number_of_values, 2);
+            chunk.write(&mut writer).unwrap();
+        }
+
+        assert_eq!(&buf[..], &[2, 5, 3, 6]);
+    }
+
+    #[test]
+    fn transposed_chunk_spans_scratch_tiles() {
+        let channels = 3;
+        let samples = TRANSPOSE_SCRATCH_VALUES;
+        let data: Vec<u32> = (0..(channels * samples) as u32).collect();
+        let multi_channel = MultiChannelSlice::from_slice(&data[..], channels).unwrap();
+
+        let mut buf = vec![];
+        {
+            let mut writer = LittleEndianWriter::from_writer(&mut buf);
+            multi_channel
+                .transposed_chunk(0, samples)
+                .write(&mut writer)
+                .unwrap();
+        }
+
+        let written: Vec<u32> = buf
+            .chunks_exact(4)
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+            .collect();
+        let expected: Vec<u32> = (0..samples)
+            .flat_map(|sample| {
+                (0..channels).map(move |channel| (channel * samples + sample) as u32)
+            })
+            .collect();
+        assert_eq!(written, expected);
+    }
+
+    #[test]
+    fn deinterleaved_chunk_writes_channels_contigiously() {
+        let data = [1u8, 4, 2, 5, 3, 6];
+        let multi_channel = MultiChannelSlice::from_slice(&data[..], 2).unwrap();
+
+        let mut buf = vec![];
+        {
+            let mut writer = LittleEndianWriter::from_writer(&mut buf);
+            let chunk = multi_channel.deinterleaved_chunk(1, 2);
+            assert_eq!(chunk.size(), 4);
+            assert_eq!(chunk.data_structure()[1].number_of_values, 2);
+            chunk.write(&mut writer).unwrap();
+        }
+
+        assert_eq!(&buf[..], &[2, 3, 5, 6]);
+    }
 }
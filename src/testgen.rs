@@ -0,0 +1,258 @@
+//! Generation of synthetic TDMS files.
+//!
+//! This produces reproducible fixtures with a configurable structure for
+//! testing tools built on top of this library.
+//!
+//! Every sample has a known value from [`TestFileSpec::expected_value`] so
+//! the output of a reader can be checked without a reference file.
+//!
+//! # Example
+//! ```rust
+//! use tedium::testgen::TestFileSpec;
+//! use tedium::types::DataType;
+//! use tedium::{ChannelPath, TdmsFile};
+//!
+//! let spec = TestFileSpec {
+//!     segments: 4,
+//!     data_type: DataType::I32,
+//!     big_endian: true,
+//!     ..Default::default()
+//! };
+//! let bytes = spec.build().unwrap();
+//!
+//! let mut file = TdmsFile::new(std::io::Cursor::new(bytes)).unwrap();
+//! let data: Vec<i32> = file.read_all(&spec.channel_path(0, 1)).unwrap();
+//! assert_eq!(data[5], spec.expected_value(1, 5) as i32);
+//! ```
+
+use std::io::Write;
+
+use crate::error::TdmsError;
+use crate::file::TdmsFileWriter;
+use crate::index::Index;
+use crate::io::data_types::{DataType, TdmsStorageType};
+use crate::io::writer::{BigEndianWriter, LittleEndianWriter, TdmsWriter};
+use crate::paths::ChannelPath;
+use crate::raw_data::DataLayout;
+
+/// The structure of a generated file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestFileSpec {
+    /// The number of groups. Groups are named `group0`, `group1`...
+    pub groups: usize,
+    /// The number of channels in each group. Channels are named `channel0`, `channel1`...
+    pub channels_per_group: usize,
+    /// The number of data segments written for each group.
+    pub segments: usize,
+    /// The number of samples for each channel in every segment.
+    pub samples_per_segment: usize,
+    /// The layout of the data in each segment.
+    pub layout: DataLayout,
+    /// Write the file in big endian format.
+    pub big_endian: bool,
+    /// The data type of every channel. Only numeric types are supported.
+    pub data_type: DataType,
+}
+
+impl Default for TestFileSpec {
+    fn default() -> Self {
+        Self {
+            groups: 1,
+            channels_per_group: 2,
+            segments: 1,
+            samples_per_segment: 10,
+            layout: DataLayout::Contigious,
+            big_endian: false,
+            data_type: DataType::DoubleFloat,
+        }
+    }
+}
+
+impl TestFileSpec {
+    /// The path of a channel in the generated file.
+    pub fn channel_path(&self, group: usize, channel: usize) -> ChannelPath {
+        ChannelPath::new(&format!("group{group}"), &format!("channel{channel}"))
+    }
+
+    /// The total number of samples written to each channel.
+    pub fn channel_length(&self) -> usize {
+        self.segments * self.samples_per_segment
+    }
+
+    /// The value of a sample in a channel.
+    ///
+    /// Channels are numbered across the whole file, so channel `c` of group `g`
+    /// is `g * channels_per_group + c`. The values are kept below 100 so they
+    /// can be represented exactly in every numeric type.
+    pub fn expected_value(&self, channel_number: usize, sample: usize) -> f64 {
+        ((channel_number * 31 + sample) % 100) as f64
+    }
+
+    /// Generate the file in memory.
+    ///
+    /// Errors with [`TdmsError::UnsupportedType`] if the data type is not numeric.
+    pub fn build(&self) -> Result<Vec<u8>, TdmsError> {
+        let mut output = Vec::new();
+        self.write_to(&mut output)?;
+        Ok(output)
+    }
+
+    /// Generate the file into the output.
+    pub fn write_to<W: Write>(&self, output: &mut W) -> Result<(), TdmsError> {
+        let mut index = Index::new();
+        if self.big_endian {
+            let writer = BigEndianWriter::from_writer(output);
+            self.write_segments(TdmsFileWriter::new(&mut index, writer))
+        } else {
+            let writer = LittleEndianWriter::from_writer(output);
+            self.write_segments(TdmsFileWriter::new(&mut index, writer))
+        }
+    }
+
+    fn write_segments<'a, F: Write, W: TdmsWriter<&'a mut F>>(
+        &self,
+        mut writer: TdmsFileWriter<'a, F, W>,
+    ) -> Result<(), TdmsError> {
+        macro_rules! write_as {
+            ($type:ty) => {
+                self.write_typed(&mut writer, |value| value as $type)
+            };
+        }
+
+        match self.data_type {
+            DataType::I8 => write_as!(i8),
+            DataType::I16 => write_as!(i16),
+            DataType::I32 => write_as!(i32),
+            DataType::I64 => write_as!(i64),
+            DataType::U8 => write_as!(u8),
+            DataType::U16 => write_as!(u16),
+            DataType::U32 => write_as!(u32),
+            DataType::U64 => write_as!(u64),
+            DataType::SingleFloat => write_as!(f32),
+            DataType::DoubleFloat => write_as!(f64),
+            other => Err(TdmsError::UnsupportedType(other)),
+        }?;
+        writer.sync()
+    }
+
+    fn write_typed<'a, F: Write, W: TdmsWriter<&'a mut F>, D: TdmsStorageType>(
+        &self,
+        writer: &mut TdmsFileWriter<'a, F, W>,
+        convert: impl Fn(f64) -> D,
+    ) -> Result<(), TdmsError> {
+        let channels_per_group = self.channels_per_group;
+        let samples = self.samples_per_segment;
+        let mut values = Vec::with_capacity(channels_per_group * samples);
+
+        for segment in 0..self.segments {
+            for group in 0..self.groups {
+                let channels: Vec<ChannelPath> = (0..channels_per_group)
+                    .map(|channel| self.channel_path(group, channel))
+                    .collect();
+                let first_sample = segment * samples;
+                let value = |channel: usize, sample: usize| {
+                    let channel_number = group * channels_per_group + channel;
+                    convert(self.expected_value(channel_number, first_sample + sample))
+                };
+
+                values.clear();
+                match self.layout {
+                    DataLayout::Contigious => {
+                        for channel in 0..channels_per_group {
+                            values.extend((0..samples).map(|sample| value(channel, sample)));
+                        }
+                    }
+                    DataLayout::Interleaved => {
+                        for sample in 0..samples {
+                            values.extend(
+                                (0..channels_per_group).map(|channel| value(channel, sample)),
+                            );
+                        }
+                    }
+                }
+
+                writer.write_channels(&channels, &values[..], self.layout)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::TdmsFile;
+
+    fn check_file(spec: &TestFileSpec) {
+        let bytes = spec.build().unwrap();
+        let mut file = TdmsFile::new(Cursor::new(bytes)).unwrap();
+
+        assert_eq!(
+            file.file_stats().big_endian_segments,
+            if spec.big_endian {
+                (spec.segments * spec.groups) as u64
+            } else {
+                0
+            }
+        );
+
+        for group in 0..spec.groups {
+            for channel in 0..spec.channels_per_group {
+                let path = spec.channel_path(group, channel);
+                let data: Vec<f64> = file.read_all(&path).unwrap();
+                let channel_number = group * spec.channels_per_group + channel;
+                let expected: Vec<f64> = (0..spec.channel_length())
+                    .map(|sample| spec.expected_value(channel_number, sample))
+                    .collect();
+                assert_eq!(data, expected, "{spec:?} {path}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_layouts_and_endianness() {
+        for layout in [DataLayout::Contigious, DataLayout::Interleaved] {
+            for big_endian in [false, true] {
+                check_file(&TestFileSpec {
+                    groups: 2,
+                    channels_per_group: 3,
+                    segments: 4,
+                    samples_per_segment: 7,
+                    layout,
+                    big_endian,
+                    data_type: DataType::DoubleFloat,
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn test_numeric_types() {
+        let spec = TestFileSpec {
+            data_type: DataType::U8,
+            segments: 3,
+            ..Default::default()
+        };
+        let bytes = spec.build().unwrap();
+        let mut file = TdmsFile::new(Cursor::new(bytes)).unwrap();
+        let data: Vec<u8> = file.read_all(&spec.channel_path(0, 1)).unwrap();
+        let expected: Vec<u8> = (0..spec.channel_length())
+            .map(|sample| spec.expected_value(1, sample) as u8)
+            .collect();
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_unsupported_type() {
+        let spec = TestFileSpec {
+            data_type: DataType::TdmsString,
+            ..Default::default()
+        };
+        assert!(matches!(
+            spec.build(),
+            Err(TdmsError::UnsupportedType(DataType::TdmsString))
+        ));
+    }
+}
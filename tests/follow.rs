@@ -0,0 +1,95 @@
+//! Follow a file while it is being written.
+
+use std::io::Write;
+
+use tedium::testgen::TestFileSpec;
+use tedium::TdmsFile;
+
+#[test]
+fn test_refresh_and_read_new_follow_growing_file() {
+    let spec = TestFileSpec {
+        channels_per_group: 2,
+        segments: 3,
+        samples_per_segment: 4,
+        ..Default::default()
+    };
+    let bytes = spec.build().unwrap();
+    let complete = TdmsFile::new(std::io::Cursor::new(bytes.clone())).unwrap();
+    let second_segment = complete.data_block_debug(1).unwrap().segment_start as usize;
+    let third_segment = complete.data_block_debug(2).unwrap().segment_start as usize;
+
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("follow.tdms");
+    let mut output = std::fs::File::create(&path).unwrap();
+    output.write_all(&bytes[..second_segment]).unwrap();
+    output.flush().unwrap();
+
+    let mut file = TdmsFile::load(&path).unwrap();
+    let channel = spec.channel_path(0, 1);
+    let expected: Vec<f64> = (0..spec.channel_length())
+        .map(|sample| spec.expected_value(1, sample))
+        .collect();
+    let mut cursor = file.append_cursor(&channel);
+    assert_eq!(file.read_new::<f64>(&mut cursor).unwrap(), expected[..4]);
+
+    // Nothing new yet.
+    assert_eq!(file.refresh().unwrap(), 0);
+    assert!(file.read_new::<f64>(&mut cursor).unwrap().is_empty());
+
+    // A complete segment and a partial one. Only the complete one is added.
+    output
+        .write_all(&bytes[second_segment..third_segment + 40])
+        .unwrap();
+    output.flush().unwrap();
+    assert_eq!(file.refresh().unwrap(), 1);
+    assert_eq!(file.read_new::<f64>(&mut cursor).unwrap(), expected[4..8]);
+
+    output.write_all(&bytes[third_segment + 40..]).unwrap();
+    output.flush().unwrap();
+    assert_eq!(file.refresh().unwrap(), 1);
+    assert_eq!(file.read_new::<f64>(&mut cursor).unwrap(), expected[8..]);
+    assert_eq!(cursor.position(), 12);
+
+    drop(file);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_reads_are_bounded_by_snapshot() {
+    let spec = TestFileSpec {
+        channels_per_group: 2,
+        segments: 3,
+        samples_per_segment: 4,
+        ..Default::default()
+    };
+    let bytes = spec.build().unwrap();
+    let complete = TdmsFile::new(std::io::Cursor::new(bytes.clone())).unwrap();
+    let third_segment = complete.data_block_debug(2).unwrap();
+
+    // The last segment has all of its metadata but only part of its data.
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("snapshot.tdms");
+    let partial_length = third_segment.data_start as usize + 8;
+    let mut output = std::fs::File::create(&path).unwrap();
+    output.write_all(&bytes[..partial_length]).unwrap();
+    output.flush().unwrap();
+
+    let mut file = TdmsFile::load(&path).unwrap();
+    let channel = spec.channel_path(0, 1);
+    let expected: Vec<f64> = (0..spec.channel_length())
+        .map(|sample| spec.expected_value(1, sample))
+        .collect();
+    assert_eq!(file.snapshot_end(), third_segment.segment_start);
+    assert_eq!(file.channel_length(&channel), Some(8));
+    assert_eq!(file.read_all::<f64>(&channel).unwrap(), expected[..8]);
+
+    // Completing the segment has no effect until the file is refreshed.
+    output.write_all(&bytes[partial_length..]).unwrap();
+    output.flush().unwrap();
+    assert_eq!(file.read_all::<f64>(&channel).unwrap(), expected[..8]);
+
+    file.refresh().unwrap();
+    assert_eq!(file.snapshot_end(), bytes.len() as u64);
+    assert_eq!(file.read_all::<f64>(&channel).unwrap(), expected);
+
+    drop(file);
+    std::fs::remove_file(&path).unwrap();
+}
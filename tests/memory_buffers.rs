@@ -1,5 +1,8 @@
 use std::io::Cursor;
-use tedium::{ChannelPath, DataLayout, TdmsFile};
+use tedium::{
+    ChannelPath, DataLayout, ErrorKind, PropertyPath, PropertyValue, TdmsError, TdmsFile,
+    TdmsStreamWriter,
+};
 
 #[test]
 fn test_can_write_and_read_from_buffer() {
@@ -39,3 +42,163 @@ fn test_can_write_and_read_from_buffer() {
         .unwrap();
     assert_eq!(output_buffer, data_to_write);
 }
+
+#[test]
+fn test_reload_buffer_with_data_only_segments() {
+    let mut buffer = Cursor::new(Vec::new());
+    let channel = ChannelPath::new("group", "channel");
+
+    {
+        let mut file = TdmsFile::new(&mut buffer).unwrap();
+        let mut writer = file.writer().unwrap();
+        // Repeat writes of the same channel generate segments without metadata.
+        for block in 0..4 {
+            let data = [1.5e300 * block as f64, -2.5, 3.25];
+            writer
+                .write_channels(&[&channel], &data[..], DataLayout::Contigious)
+                .unwrap();
+        }
+    }
+
+    let mut file = TdmsFile::new(&mut buffer).unwrap();
+    assert_eq!(file.channel_length(&channel), Some(12));
+
+    let mut output = vec![0.0; 12];
+    file.read_channel(&channel, &mut output[..]).unwrap();
+    let expected: Vec<f64> = (0..4)
+        .flat_map(|block| [1.5e300 * block as f64, -2.5, 3.25])
+        .collect();
+    assert_eq!(output, expected);
+}
+
+/// An output which can only be written, like a pipe.
+struct WriteOnly(Vec<u8>);
+
+impl std::io::Write for WriteOnly {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_stream_to_write_only_output() {
+    let channel = ChannelPath::new("group", "channel");
+    let mut stream = TdmsStreamWriter::new(WriteOnly(Vec::new()));
+
+    {
+        let mut writer = stream.writer();
+        writer
+            .write_properties(
+                &PropertyPath::channel("group", "channel"),
+                &[("unit_string", PropertyValue::String("V".to_string()))],
+            )
+            .unwrap();
+        writer
+            .write_channels(&[&channel], &[1.0, 2.0][..], DataLayout::Contigious)
+            .unwrap();
+    }
+    {
+        let mut writer = stream.writer();
+        writer
+            .write_channels(&[&channel], &[3.0, 4.0][..], DataLayout::Contigious)
+            .unwrap();
+    }
+
+    let bytes = stream.into_inner().0;
+    let mut file = TdmsFile::new(Cursor::new(bytes)).unwrap();
+    let mut output = vec![0.0; 4];
+    file.read_channel(&channel, &mut output[..]).unwrap();
+    assert_eq!(output, vec![1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(
+        file.read_property(&PropertyPath::channel("group", "channel"), "unit_string")
+            .unwrap(),
+        Some(&PropertyValue::String("V".to_string()))
+    );
+}
+
+#[test]
+fn test_data_block_debug_locates_segments() {
+    let channel = ChannelPath::new("group", "channel");
+    let mut file = TdmsFile::new(Cursor::new(Vec::new())).unwrap();
+    {
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&channel], &[1.0, 2.0][..], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_properties(
+                &PropertyPath::group("group"),
+                &[("a", PropertyValue::I32(1))],
+            )
+            .unwrap();
+        writer
+            .write_channels(&[&channel], &[3.0, 4.0][..], DataLayout::Interleaved)
+            .unwrap();
+    }
+
+    assert_eq!(file.data_block_count(), 2);
+
+    let first = file.data_block_debug(0).unwrap();
+    assert_eq!(first.segment_index, 0);
+    assert_eq!(first.segment_start, 0);
+    assert!(first.toc.contains_meta_data);
+    assert_eq!(first.data_start, 28 + first.raw_data_offset);
+    assert_eq!(first.data_length, 16);
+
+    let second = file.data_block_debug(1).unwrap();
+    assert_eq!(second.segment_index, 2);
+    assert!(!second.toc.contains_meta_data);
+    assert!(second.toc.data_is_interleaved);
+    assert_eq!(second.data_start, second.segment_start + 28);
+
+    assert!(file.data_block_debug(2).is_none());
+}
+
+#[test]
+fn test_corrupt_segment_error_has_location() {
+    let mut buffer = Cursor::new(Vec::new());
+    let channel = ChannelPath::new("group", "channel");
+    {
+        let mut file = TdmsFile::new(&mut buffer).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&channel], &[1.0, 2.0], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_channels(&[&channel], &[1u8, 2], DataLayout::Contigious)
+            .unwrap();
+    }
+
+    let mut bytes = buffer.into_inner();
+    let second_segment = {
+        let file = TdmsFile::new(Cursor::new(bytes.clone())).unwrap();
+        file.data_block_debug(1).unwrap().segment_start
+    };
+    bytes[second_segment as usize] = b'X';
+
+    let error = TdmsFile::new(Cursor::new(bytes)).unwrap_err();
+    assert_eq!(error.segment(), Some(1));
+    assert_eq!(error.offset(), Some(second_segment));
+    assert_eq!(error.kind(), ErrorKind::Corrupt);
+    assert_eq!(error.code(), 8);
+    assert!(matches!(
+        error,
+        TdmsError::InSegment { source, .. } if matches!(*source, TdmsError::HeaderPatternNotMatched(_))
+    ));
+}
+
+#[test]
+fn test_error_kind_and_path() {
+    let file = TdmsFile::new(Cursor::new(Vec::new())).unwrap();
+    let error = file
+        .read_property(&PropertyPath::group("missing"), "name")
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::NotFound);
+    assert_eq!(error.kind().as_str(), "not_found");
+    assert_eq!(error.path(), Some("/'missing'"));
+    assert_eq!(error.segment(), None);
+}
@@ -35,7 +35,7 @@ fn test_properties<F: Write + Read + Seek + Debug>(file: TdmsFile<F>, path: Prop
     for (name, expected) in TEST_PROPERTIES {
         let actual = file
             .read_property(&path, name)
-            .expect(&format!("Failed to read property {}", name));
+            .unwrap_or_else(|_| panic!("Failed to read property {}", name));
         assert_eq!(actual, Some(expected));
     }
 
@@ -1,7 +1,8 @@
 mod common;
 
 use labview_interop::types::LVTime;
-use tedium::{types::Complex, ChannelPath};
+use tedium::types::DataType;
+use tedium::{types::Complex, ChannelPath, ReadPastEnd, TdmsError};
 
 fn test_data(channel_index: usize) -> Vec<f64> {
     let samples = match channel_index {
@@ -68,6 +69,35 @@ fn test_multi_channel_read_shorter() {
     assert_eq!(buffer4, expected4[0..read_length]);
 }
 
+#[test]
+fn test_read_range_mid_segment() {
+    let mut file = common::open_test_file();
+    let path = ChannelPath::new("structure", "ch2");
+    let expected = test_data(1);
+    let start = 3456;
+
+    let mut buffer = vec![0.0; 2000];
+    file.read_range(&path, start as u64, &mut buffer[..])
+        .unwrap();
+
+    assert_eq!(buffer, expected[start..start + 2000]);
+}
+
+#[test]
+fn test_read_range_sub_blocks() {
+    let mut file = common::open_test_file();
+    let expected = test_data(0);
+    let start = 1300;
+    let mut buffer = vec![0.0; 1000];
+    file.read_range(
+        &ChannelPath::new("subblock", "ch1"),
+        start as u64,
+        &mut buffer[..],
+    )
+    .unwrap();
+    assert_eq!(buffer, expected[start..start + 1000]);
+}
+
 #[test]
 fn test_read_sub_blocks() {
     let mut file = common::open_test_file();
@@ -137,7 +167,7 @@ fn test_complex_type_read() {
 #[test]
 fn test_boolean_data_types() {
     let mut file = common::open_test_file();
-    let mut buffer = vec![false; 100];
+    let mut buffer = [false; 100];
     file.read_channel(&ChannelPath::new("datatypes", "bool"), &mut buffer[..])
         .unwrap();
     assert_eq!(&buffer[..4], &[true, false, true, false]);
@@ -157,3 +187,119 @@ fn test_timestamp_data_types() {
         .unwrap();
     assert_eq!(&buffer[..3], &expected);
 }
+
+#[test]
+fn test_read_all_exact_length() {
+    let mut file = common::open_test_file();
+    let path = ChannelPath::new("structure", "ch4");
+
+    let data: Vec<f64> = file.read_all(&path).unwrap();
+    assert_eq!(data, test_data(3));
+}
+
+#[test]
+fn test_read_all_into_reuses_vector() {
+    let mut file = common::open_test_file();
+    let mut buffer: Vec<f64> = Vec::with_capacity(20000);
+    let capacity = buffer.capacity();
+
+    file.read_all_into(&ChannelPath::new("structure", "ch1"), &mut buffer)
+        .unwrap();
+    assert_eq!(buffer, test_data(0));
+
+    file.read_all_into(&ChannelPath::new("structure", "ch4"), &mut buffer)
+        .unwrap();
+    assert_eq!(buffer, test_data(3));
+    assert_eq!(buffer.capacity(), capacity);
+}
+
+#[test]
+fn test_read_all_missing_channel() {
+    let mut file = common::open_test_file();
+    let result = file.read_all::<f64>(&ChannelPath::new("structure", "missing"));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_read_outcome_reports_truncation() {
+    let mut file = common::open_test_file();
+    let path = ChannelPath::new("structure", "ch4");
+
+    let mut output = vec![0.0f64; 6000];
+    let outcome = file.read_channel(&path, &mut output[..]).unwrap();
+    assert_eq!(outcome.samples_read, 5000);
+    assert!(outcome.truncated);
+
+    let mut output = vec![0.0f64; 100];
+    let outcome = file.read_range(&path, 4900, &mut output[..]).unwrap();
+    assert_eq!(outcome.samples_read, 100);
+    assert!(!outcome.truncated);
+}
+
+#[test]
+fn test_read_past_end_policies() {
+    let mut file = common::open_test_file();
+    let path = ChannelPath::new("structure", "ch4");
+    let expected = test_data(3);
+
+    let mut output = vec![-1.0f64; 10];
+    let result = file.read_range_with_policy(&path, 4995, &mut output[..], ReadPastEnd::Error);
+    assert!(matches!(
+        result,
+        Err(TdmsError::ReadPastEnd {
+            start: 4995,
+            requested: 10,
+            length: 5000,
+            ..
+        })
+    ));
+    assert_eq!(output, vec![-1.0; 10]);
+
+    let outcome = file
+        .read_range_with_policy(&path, 4995, &mut output[..], ReadPastEnd::Truncate)
+        .unwrap();
+    assert_eq!(outcome.samples_read, 5);
+    assert_eq!(output[..5], expected[4995..]);
+    assert_eq!(output[5..], [-1.0; 5]);
+
+    let outcome = file
+        .read_range_with_policy(&path, 4995, &mut output[..], ReadPastEnd::Fill(0.0))
+        .unwrap();
+    assert!(outcome.truncated);
+    assert_eq!(output[..5], expected[4995..]);
+    assert_eq!(output[5..], [0.0; 5]);
+}
+
+#[test]
+fn test_type_mismatch_names_channel_and_types() {
+    let mut file = common::open_test_file();
+    let path = ChannelPath::new("structure", "ch2");
+    let mut output = [0i16; 10];
+
+    let error = file.read_channel(&path, &mut output[..]).unwrap_err();
+    match &error {
+        TdmsError::ChannelTypeMismatch {
+            channel,
+            stored,
+            requested,
+        } => {
+            assert_eq!(channel, &path);
+            assert_eq!(*stored, DataType::DoubleFloat);
+            assert_eq!(*requested, DataType::I16);
+        }
+        other => panic!("Unexpected error {other:?}"),
+    }
+    let message = error.to_string();
+    assert!(message.contains("ch2"), "{message}");
+
+    let other = ChannelPath::new("structure", "ch1");
+    let mut other_output = [0i16; 10];
+    let result = file.read_channels(
+        &[&other, &path],
+        &mut [&mut other_output[..], &mut output[..]],
+    );
+    assert!(matches!(
+        result,
+        Err(TdmsError::ChannelTypeMismatch { channel, .. }) if channel == other
+    ));
+}
@@ -0,0 +1,40 @@
+//! Files in directories deeper than the Windows path limit.
+#![cfg(windows)]
+
+use std::path::PathBuf;
+
+use tedium::{ChannelPath, DataLayout, TdmsFile};
+
+#[test]
+fn test_create_and_load_beyond_max_path() {
+    let mut directory = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("long_paths");
+    while directory.as_os_str().len() < 300 {
+        directory.push("a_deeply_nested_log_directory");
+    }
+    // Creating the directories needs the prefix too, which the crate adds
+    // when opening files but not here.
+    let extended = PathBuf::from(format!(r"\\?\{}", directory.display()));
+    std::fs::create_dir_all(&extended).unwrap();
+    let path = directory.join("run.tdms");
+    let channel = ChannelPath::new("group", "channel");
+
+    let mut file = TdmsFile::create(&path).unwrap();
+    let mut writer = file.writer().unwrap();
+    writer
+        .write_channels(&[&channel], &[1.0, 2.0], DataLayout::Contigious)
+        .unwrap();
+    drop(writer);
+    drop(file);
+
+    let mut file = TdmsFile::load(&path).unwrap();
+    assert_eq!(file.read_all::<f64>(&channel).unwrap(), vec![1.0, 2.0]);
+}
+
+#[test]
+fn test_relative_paths_with_forward_slashes() {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("windows_paths/../forward.tdms");
+    std::fs::create_dir_all(PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("windows_paths"))
+        .unwrap();
+    TdmsFile::create(&path).unwrap();
+    assert!(TdmsFile::load(&path).is_ok());
+}
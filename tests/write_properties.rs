@@ -2,7 +2,7 @@ mod common;
 use labview_interop::types::LVTime;
 use std::{fmt::Debug, io::Read, io::Seek, io::Write};
 use tedium::types::Complex;
-use tedium::{PropertyPath, PropertyValue, TdmsFile};
+use tedium::{ChannelPath, DataLayout, PropertyPath, PropertyValue, TdmsFile};
 
 const TEST_PROPERTIES: &[(&str, PropertyValue)] = &[
     ("i8", PropertyValue::I8(-5)),
@@ -38,7 +38,7 @@ fn write_properties<F: Write + Read + Seek + Debug>(file: &mut TdmsFile<F>, path
     //this one wont exist as a constant.
     writer
         .write_properties(
-            &path,
+            path,
             &[(
                 "timestamp",
                 PropertyValue::Timestamp(LVTime::from_lv_epoch(3780807561.0)),
@@ -51,7 +51,7 @@ fn test_properties<F: Write + Read + Seek + Debug>(file: TdmsFile<F>, path: Prop
     for (name, expected) in TEST_PROPERTIES {
         let actual = file
             .read_property(&path, name)
-            .expect(&format!("Failed to read property {}", name));
+            .unwrap_or_else(|_| panic!("Failed to read property {}", name));
         assert_eq!(actual, Some(expected));
     }
 
@@ -92,3 +92,94 @@ fn test_channel_properties() {
     write_properties(&mut file, &path);
     test_properties(file, path);
 }
+
+#[test]
+fn test_update_property_on_active_channel() {
+    let mut buffer = std::io::Cursor::new(Vec::new());
+    let channel = ChannelPath::new("group", "channel");
+    let channel_properties = PropertyPath::channel("group", "channel");
+
+    {
+        let mut file = TdmsFile::new(&mut buffer).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&channel], &[1.0, 2.0][..], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .update_property(&channel_properties, "state", PropertyValue::I32(1))
+            .unwrap();
+        writer
+            .write_channels(&[&channel], &[3.0, 4.0][..], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .update_property(&channel_properties, "state", PropertyValue::I32(2))
+            .unwrap();
+        drop(writer);
+
+        // Read after write in the same process.
+        assert_eq!(
+            file.read_property(&channel_properties, "state").unwrap(),
+            Some(&PropertyValue::I32(2))
+        );
+    }
+
+    let mut file = TdmsFile::new(&mut buffer).unwrap();
+    assert_eq!(
+        file.read_property(&channel_properties, "state").unwrap(),
+        Some(&PropertyValue::I32(2))
+    );
+    let data: Vec<f64> = file.read_all(&channel).unwrap();
+    assert_eq!(data, vec![1.0, 2.0, 3.0, 4.0]);
+}
+
+#[test]
+fn test_finalize_writes_sample_counts() {
+    let mut buffer = std::io::Cursor::new(Vec::new());
+    let first = ChannelPath::new("group", "first");
+    let second = ChannelPath::new("group", "second");
+    let other = ChannelPath::new("other", "channel");
+
+    {
+        let mut file = TdmsFile::new(&mut buffer).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(
+                &[&first, &second],
+                &[1.0, 2.0, 3.0, 4.0][..],
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        writer
+            .write_channels(&[&other], &[1u8, 2, 3][..], DataLayout::Contigious)
+            .unwrap();
+    }
+
+    // Append in a new session so the counts include the existing data.
+    {
+        let mut file = TdmsFile::new(&mut buffer).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&first, &second], &[5.0, 6.0][..], DataLayout::Contigious)
+            .unwrap();
+        writer.finalize_with(&["wf_samples", "total"]).unwrap();
+        // Data written after the metadata segment is still in the same layout.
+        writer
+            .write_channels(&[&first, &second], &[7.0, 8.0][..], DataLayout::Contigious)
+            .unwrap();
+    }
+
+    let mut file = TdmsFile::new(&mut buffer).unwrap();
+    for (channel, expected) in [(&first, 3), (&second, 3), (&other, 3)] {
+        for name in ["wf_samples", "total"] {
+            assert_eq!(
+                file.read_property(channel.as_ref(), name).unwrap(),
+                Some(&PropertyValue::I32(expected)),
+                "{channel} {name}"
+            );
+        }
+    }
+    let data: Vec<f64> = file.read_all(&first).unwrap();
+    assert_eq!(data, vec![1.0, 2.0, 5.0, 7.0]);
+    let data: Vec<f64> = file.read_all(&second).unwrap();
+    assert_eq!(data, vec![3.0, 4.0, 6.0, 8.0]);
+}
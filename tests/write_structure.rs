@@ -3,15 +3,21 @@
 mod common;
 
 use common::get_empty_file;
+use std::io::Cursor;
 use tedium::types::Complex;
-use tedium::{ChannelPath, DataLayout};
+use tedium::types::DataType;
+use tedium::{
+    ChannelPath, CompatibilityProfile, DataLayout, ErrorKind, FlushPolicy, PropertyPath,
+    PropertyValue, SegmentLimit, TdmsError, TdmsFile, TdmsStreamWriter, WriteBuffer,
+    WrittenSegment,
+};
 
 #[test]
 fn test_multi_channel_write_interleaved() {
     let mut file = get_empty_file();
     let mut writer = file.writer().unwrap();
 
-    let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+    let data = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
     writer
         .write_channels(
             &[
@@ -39,7 +45,7 @@ fn test_multi_channel_write_contigious() {
     let mut file = get_empty_file();
     let mut writer = file.writer().unwrap();
 
-    let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+    let data = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
     writer
         .write_channels(
             &[
@@ -71,7 +77,7 @@ fn test_fragmented_write() {
     let mut writer = file.writer().unwrap();
 
     let data1 = vec![1.0, 2.0, 3.0, 4.0, 5.0];
-    let data2 = vec![6.0, 7.0, 8.0, 9.0, 10.0];
+    let data2 = [6.0, 7.0, 8.0, 9.0, 10.0];
 
     writer
         .write_channels(&[&channel1], &data1[..3], DataLayout::Contigious)
@@ -98,8 +104,8 @@ fn test_repeated_writes() {
     let mut file = get_empty_file();
     let mut writer = file.writer().unwrap();
 
-    let data1 = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
-    let data2 = vec![7.0, 8.0, 9.0, 10.0, 11.0, 12.0];
+    let data1 = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+    let data2 = [7.0, 8.0, 9.0, 10.0, 11.0, 12.0];
 
     writer
         .write_channels(&[&channel1, &channel2], &data1[..], DataLayout::Contigious)
@@ -187,3 +193,515 @@ fn test_write_complex_types() {
     write_complex_datatype_test!(file, f32);
     write_complex_datatype_test!(file, f64);
 }
+
+#[test]
+fn test_write_observer_sees_each_segment() {
+    let mut file = get_empty_file();
+    let mut log: Vec<(u64, bool, Vec<String>, u64)> = Vec::new();
+
+    let mut writer = file.writer().unwrap();
+    writer.set_observer(|segment: &WrittenSegment| {
+        log.push((
+            segment.segment_start,
+            segment.toc.contains_meta_data,
+            segment.paths.iter().map(|path| path.to_string()).collect(),
+            segment.data_bytes,
+        ))
+    });
+
+    let channels = [
+        ChannelPath::new("structure", "ch1"),
+        ChannelPath::new("structure", "ch2"),
+    ];
+    writer
+        .write_channels(&channels, &[1.0, 2.0, 3.0, 4.0], DataLayout::Contigious)
+        .unwrap();
+    writer
+        .write_properties(
+            &PropertyPath::group("structure"),
+            &[("name", PropertyValue::I32(1))],
+        )
+        .unwrap();
+    writer
+        .write_channels(&channels, &[1.0, 2.0, 3.0, 4.0], DataLayout::Contigious)
+        .unwrap();
+    drop(writer);
+
+    assert_eq!(log.len(), 3);
+    let channel_paths = vec![
+        "/'structure'/'ch1'".to_string(),
+        "/'structure'/'ch2'".to_string(),
+    ];
+    assert_eq!(log[0].0, 0);
+    assert!(log[0].1);
+    assert_eq!(log[0].2, channel_paths);
+    assert_eq!(log[0].3, 32);
+    assert_eq!(log[1].2, vec!["/'structure'".to_string()]);
+    assert_eq!(log[1].3, 0);
+    assert!(log[1].0 > log[0].0);
+    assert!(!log[2].1);
+    assert_eq!(log[2].2, channel_paths);
+}
+
+#[test]
+fn test_changing_write_length_keeps_all_data() {
+    let mut buffer = Cursor::new(Vec::new());
+    let channel = ChannelPath::new("structure", "ch1");
+    {
+        let mut file = TdmsFile::new(&mut buffer).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&channel], &[1.0, 2.0], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_channels(&[&channel], &[3.0, 4.0, 5.0], DataLayout::Contigious)
+            .unwrap();
+    }
+
+    let mut file = TdmsFile::new(buffer).unwrap();
+    let data: Vec<f64> = file.read_all(&channel).unwrap();
+    assert_eq!(data, vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+}
+
+#[test]
+fn test_create_objects_before_data() {
+    let mut buffer = Cursor::new(Vec::new());
+    let ch1 = ChannelPath::new("structure", "ch1");
+    let ch2 = ChannelPath::new("structure", "ch2");
+    {
+        let mut file = TdmsFile::new(&mut buffer).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .create_group("structure", &[("rig", PropertyValue::I32(4))])
+            .unwrap();
+        writer
+            .create_channel(&ch1, DataType::DoubleFloat, &[])
+            .unwrap();
+        writer
+            .write_channels(&[&ch1], &[1.0, 2.0], DataLayout::Contigious)
+            .unwrap();
+        // Declare a second channel while the first is live.
+        writer
+            .create_channel(
+                &ch2,
+                DataType::DoubleFloat,
+                &[("unit_string", PropertyValue::String("V".to_string()))],
+            )
+            .unwrap();
+        writer
+            .write_channels(&[&ch1], &[3.0, 4.0], DataLayout::Contigious)
+            .unwrap();
+        writer
+            .write_channels(&[&ch1, &ch2], &[5.0, 6.0, 7.0, 8.0], DataLayout::Contigious)
+            .unwrap();
+    }
+
+    let mut file = TdmsFile::new(buffer).unwrap();
+    assert_eq!(
+        file.read_property(&PropertyPath::group("structure"), "rig")
+            .unwrap(),
+        Some(&PropertyValue::I32(4))
+    );
+    assert_eq!(
+        file.read_property(&PropertyPath::channel("structure", "ch2"), "unit_string")
+            .unwrap(),
+        Some(&PropertyValue::String("V".to_string()))
+    );
+    let data: Vec<f64> = file.read_all(&ch1).unwrap();
+    assert_eq!(data, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    let data: Vec<f64> = file.read_all(&ch2).unwrap();
+    assert_eq!(data, vec![7.0, 8.0]);
+}
+
+#[test]
+fn test_append_after_reopen_reuses_previous_layout() {
+    let mut buffer = Cursor::new(Vec::new());
+    let channels = [
+        ChannelPath::new("structure", "ch1"),
+        ChannelPath::new("structure", "ch2"),
+    ];
+    {
+        let mut file = TdmsFile::new(&mut buffer).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&channels, &[1.0, 2.0, 3.0, 4.0], DataLayout::Contigious)
+            .unwrap();
+    }
+
+    // Reopen as a new session so the writer only knows what was scanned from the file.
+    {
+        let mut file = TdmsFile::new(&mut buffer).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&channels, &[5.0, 6.0, 7.0, 8.0], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+
+        let appended = file.data_block_debug(1).unwrap();
+        assert!(!appended.toc.contains_meta_data);
+        assert!(!appended.toc.contains_new_object_list);
+        assert_eq!(appended.raw_data_offset, 0);
+    }
+
+    let mut file = TdmsFile::new(buffer).unwrap();
+    let data: Vec<f64> = file.read_all(&channels[0]).unwrap();
+    assert_eq!(data, vec![1.0, 2.0, 5.0, 6.0]);
+    let data: Vec<f64> = file.read_all(&channels[1]).unwrap();
+    assert_eq!(data, vec![3.0, 4.0, 7.0, 8.0]);
+}
+
+#[test]
+fn test_channel_list_changes_choose_minimal_toc() {
+    let mut buffer = Cursor::new(Vec::new());
+    let ch1 = ChannelPath::new("structure", "ch1");
+    let ch2 = ChannelPath::new("structure", "ch2");
+    let ch3 = ChannelPath::new("structure", "ch3");
+    {
+        let mut file = TdmsFile::new(&mut buffer).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&ch1, &ch2], &[1.0, 2.0], DataLayout::Contigious)
+            .unwrap();
+        // Add a channel to the end.
+        writer
+            .write_channels(
+                &[&ch1, &ch2, &ch3],
+                &[3.0, 4.0, 5.0],
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        // Reorder the channels.
+        writer
+            .write_channels(
+                &[&ch3, &ch1, &ch2],
+                &[6.0, 7.0, 8.0],
+                DataLayout::Contigious,
+            )
+            .unwrap();
+        // Remove a channel.
+        writer
+            .write_channels(&[&ch3, &ch1], &[9.0, 10.0], DataLayout::Contigious)
+            .unwrap();
+        drop(writer);
+
+        let appended = file.data_block_debug(1).unwrap();
+        assert!(appended.toc.contains_meta_data);
+        assert!(!appended.toc.contains_new_object_list);
+        let reordered = file.data_block_debug(2).unwrap();
+        assert!(reordered.toc.contains_new_object_list);
+        let removed = file.data_block_debug(3).unwrap();
+        assert!(removed.toc.contains_new_object_list);
+        // Only the new channel is listed when appending.
+        assert!(appended.raw_data_offset < reordered.raw_data_offset);
+    }
+
+    let mut file = TdmsFile::new(buffer).unwrap();
+    let data: Vec<f64> = file.read_all(&ch1).unwrap();
+    assert_eq!(data, vec![1.0, 3.0, 7.0, 10.0]);
+    let data: Vec<f64> = file.read_all(&ch2).unwrap();
+    assert_eq!(data, vec![2.0, 4.0, 8.0]);
+    let data: Vec<f64> = file.read_all(&ch3).unwrap();
+    assert_eq!(data, vec![5.0, 6.0, 9.0]);
+}
+
+#[test]
+fn test_identical_writes_are_raw_data_only() {
+    let channels = [
+        ChannelPath::new("structure", "ch1"),
+        ChannelPath::new("structure", "ch2"),
+    ];
+    let mut file = get_empty_file();
+    let mut writer = file.writer().unwrap();
+    let layouts = [
+        DataLayout::Contigious,
+        DataLayout::Contigious,
+        DataLayout::Interleaved,
+        DataLayout::Interleaved,
+    ];
+    for layout in layouts {
+        writer
+            .write_channels(&channels, &[1.0, 2.0, 3.0, 4.0], layout)
+            .unwrap();
+        // Property segments don't break the data layout.
+        writer
+            .write_properties(channels[0].as_ref(), &[("count", PropertyValue::I32(1))])
+            .unwrap();
+    }
+    drop(writer);
+
+    for block in 1..file.data_block_count() {
+        let debug = file.data_block_debug(block).unwrap();
+        assert!(!debug.toc.contains_meta_data);
+        assert!(!debug.toc.contains_new_object_list);
+        assert_eq!(debug.raw_data_offset, 0);
+    }
+    let data: Vec<f64> = file.read_all(&channels[1]).unwrap();
+    assert_eq!(data, vec![3.0, 4.0, 3.0, 4.0, 2.0, 4.0, 2.0, 4.0]);
+
+    // The stream writer produces the same compact segments.
+    let mut stream = TdmsStreamWriter::new(Vec::new());
+    let mut writer = stream.writer();
+    for _ in 0..3 {
+        writer
+            .write_channels(&channels, &[1.0, 2.0, 3.0, 4.0], DataLayout::Contigious)
+            .unwrap();
+    }
+    drop(writer);
+    let mut file = TdmsFile::new(Cursor::new(stream.into_inner())).unwrap();
+    assert!(!file.data_block_debug(2).unwrap().toc.contains_meta_data);
+    let data: Vec<f64> = file.read_all(&channels[0]).unwrap();
+    assert_eq!(data, vec![1.0, 2.0, 1.0, 2.0, 1.0, 2.0]);
+}
+
+#[test]
+fn test_duplicate_channels_are_rejected() {
+    let ch1 = ChannelPath::new("structure", "ch1");
+    let ch2 = ChannelPath::new("structure", "ch2");
+    let mut file = get_empty_file();
+    let mut writer = file.writer().unwrap();
+
+    let error = writer
+        .write_channels(
+            &[&ch1, &ch2, &ch1],
+            &[1.0, 2.0, 3.0],
+            DataLayout::Interleaved,
+        )
+        .unwrap_err();
+    assert!(matches!(error, TdmsError::DuplicateChannel(_)));
+    assert_eq!(error.path(), Some(ch1.path()));
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    assert!(writer
+        .write_channels_transposed(&[&ch2, &ch2], &[1.0, 2.0])
+        .is_err());
+    drop(writer);
+
+    assert_eq!(file.data_block_count(), 0);
+}
+
+#[test]
+fn test_values_must_fill_every_channel_unless_ragged() {
+    let channels = [
+        ChannelPath::new("structure", "ch1"),
+        ChannelPath::new("structure", "ch2"),
+        ChannelPath::new("structure", "ch3"),
+    ];
+    let mut file = get_empty_file();
+    let mut writer = file.writer().unwrap();
+
+    let error = writer
+        .write_channels(&channels, &[1.0; 8], DataLayout::Interleaved)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Expected a multiple of 3 values to write to 3 channels but got 8 values"
+    );
+
+    writer
+        .write_channels_ragged(&channels, &[1.0, 2.0, 3.0, 4.0, 5.0])
+        .unwrap();
+    writer
+        .write_channels(&channels, &[6.0, 7.0, 8.0], DataLayout::Interleaved)
+        .unwrap();
+    drop(writer);
+
+    assert_eq!(
+        file.read_all::<f64>(&channels[0]).unwrap(),
+        vec![1.0, 4.0, 6.0]
+    );
+    assert_eq!(
+        file.read_all::<f64>(&channels[1]).unwrap(),
+        vec![2.0, 5.0, 7.0]
+    );
+    assert_eq!(file.read_all::<f64>(&channels[2]).unwrap(), vec![3.0, 8.0]);
+}
+
+#[test]
+fn test_segment_limit_splits_large_writes() {
+    let channels = [
+        ChannelPath::new("structure", "ch1"),
+        ChannelPath::new("structure", "ch2"),
+    ];
+    let ch1: Vec<f64> = (0..25).map(|value| value as f64).collect();
+    let ch2: Vec<f64> = (100..125).map(|value| value as f64).collect();
+
+    for layout in [DataLayout::Contigious, DataLayout::Interleaved] {
+        let data: Vec<f64> = match layout {
+            DataLayout::Contigious => ch1.iter().chain(ch2.iter()).copied().collect(),
+            DataLayout::Interleaved => ch1
+                .iter()
+                .zip(ch2.iter())
+                .flat_map(|(first, second)| [*first, *second])
+                .collect(),
+        };
+
+        for (limit, expected_blocks) in [
+            (SegmentLimit::Samples(10), 3),
+            // 2 channels * 8 bytes * 5 samples.
+            (SegmentLimit::Bytes(80), 5),
+            (SegmentLimit::Bytes(1), 25),
+        ] {
+            let mut file = get_empty_file();
+            let mut writer = file.writer().unwrap();
+            writer.set_segment_limit(Some(limit));
+            writer.write_channels(&channels, &data, layout).unwrap();
+            drop(writer);
+
+            assert_eq!(file.data_block_count(), expected_blocks, "{limit:?}");
+            let read: Vec<f64> = file.read_all(&channels[0]).unwrap();
+            assert_eq!(read, ch1, "{layout:?} {limit:?}");
+            let read: Vec<f64> = file.read_all(&channels[1]).unwrap();
+            assert_eq!(read, ch2, "{layout:?} {limit:?}");
+        }
+    }
+}
+
+#[test]
+fn test_transposed_write_matches_interleaved_write() {
+    let channels = [
+        ChannelPath::new("structure", "ch1"),
+        ChannelPath::new("structure", "ch2"),
+    ];
+    let ch1: Vec<f64> = (0..25).map(|value| value as f64).collect();
+    let ch2: Vec<f64> = (100..125).map(|value| value as f64).collect();
+    let contigious: Vec<f64> = ch1.iter().chain(ch2.iter()).copied().collect();
+    let interleaved: Vec<f64> = ch1
+        .iter()
+        .zip(ch2.iter())
+        .flat_map(|(first, second)| [*first, *second])
+        .collect();
+
+    for limit in [None, Some(SegmentLimit::Samples(10))] {
+        let mut transposed = get_empty_file();
+        let mut writer = transposed.writer().unwrap();
+        writer.set_segment_limit(limit);
+        writer
+            .write_channels_transposed(&channels, &contigious)
+            .unwrap();
+        drop(writer);
+
+        let mut expected = get_empty_file();
+        let mut writer = expected.writer().unwrap();
+        writer.set_segment_limit(limit);
+        writer
+            .write_channels(&channels, &interleaved, DataLayout::Interleaved)
+            .unwrap();
+        drop(writer);
+
+        assert!(
+            transposed
+                .data_block_debug(0)
+                .unwrap()
+                .toc
+                .data_is_interleaved
+        );
+        assert_eq!(transposed.data_block_count(), expected.data_block_count());
+        for channel in &channels {
+            let read: Vec<f64> = transposed.read_all(channel).unwrap();
+            let expected: Vec<f64> = expected.read_all(channel).unwrap();
+            assert_eq!(read, expected, "{limit:?}");
+        }
+    }
+}
+
+/// An output which can be inspected while a writer holds it.
+#[derive(Clone, Default)]
+struct SharedOutput(std::rc::Rc<std::cell::RefCell<Vec<u8>>>);
+
+impl std::io::Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_write_buffer_flush_policy() {
+    let channel = ChannelPath::new("group", "channel");
+
+    for (flush, flushed) in [
+        (FlushPolicy::Threshold, false),
+        (FlushPolicy::EverySegment, true),
+    ] {
+        let output = SharedOutput::default();
+        let mut stream = TdmsStreamWriter::new(output.clone());
+        let mut writer = stream.writer_with_buffer(WriteBuffer {
+            capacity: 1024 * 1024,
+            flush,
+        });
+        writer
+            .write_channels(&[&channel], &[1.0; 100], DataLayout::Contigious)
+            .unwrap();
+        assert_eq!(!output.0.borrow().is_empty(), flushed, "{flush:?}");
+        drop(writer);
+        assert!(!output.0.borrow().is_empty());
+    }
+}
+
+#[test]
+fn test_heartbeat_segments_keep_channels_active() {
+    let mut buffer = Cursor::new(Vec::new());
+    let channel = ChannelPath::new("group", "channel");
+    {
+        let mut file = TdmsFile::new(&mut buffer).unwrap();
+        let mut writer = file.writer().unwrap();
+        writer
+            .write_channels(&[&channel], &[1.0, 2.0], DataLayout::Contigious)
+            .unwrap();
+        writer.write_heartbeat().unwrap();
+        writer
+            .write_channels(&[&channel], &[3.0], DataLayout::Contigious)
+            .unwrap();
+        writer.write_heartbeat().unwrap();
+    }
+
+    let mut file = TdmsFile::new(buffer).unwrap();
+    assert_eq!(file.data_block_count(), 2);
+    // The write after the heartbeat still only needs the new length.
+    assert!(
+        !file
+            .data_block_debug(1)
+            .unwrap()
+            .toc
+            .contains_new_object_list
+    );
+    let data: Vec<f64> = file.read_all(&channel).unwrap();
+    assert_eq!(data, vec![1.0, 2.0, 3.0]);
+}
+
+#[test]
+fn test_compatibility_profile_constrains_segments() {
+    let channels = [
+        ChannelPath::new("structure", "ch1"),
+        ChannelPath::new("structure", "ch2"),
+    ];
+    let mut file = get_empty_file();
+    let mut writer = file.writer().unwrap();
+    writer.set_compatibility_profile(CompatibilityProfile::maximum());
+    writer.set_segment_limit(Some(SegmentLimit::Samples(2)));
+    writer
+        .write_channels(
+            &channels,
+            &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+            DataLayout::Interleaved,
+        )
+        .unwrap();
+    writer
+        .write_channels_transposed(&channels, &[7.0, 8.0])
+        .unwrap();
+    drop(writer);
+
+    assert_eq!(file.data_block_count(), 3);
+    for block in 0..file.data_block_count() {
+        let toc = file.data_block_debug(block).unwrap().toc;
+        assert!(toc.contains_new_object_list);
+        assert!(!toc.data_is_interleaved);
+    }
+    let ch1: Vec<f64> = file.read_all(&channels[0]).unwrap();
+    assert_eq!(ch1, vec![1.0, 3.0, 5.0, 7.0]);
+    let ch2: Vec<f64> = file.read_all(&channels[1]).unwrap();
+    assert_eq!(ch2, vec![2.0, 4.0, 6.0, 8.0]);
+}